@@ -233,23 +233,29 @@ fn write_rules(out: &mut String) {
     out.push_str("- Only operate within the allowed directories listed below.\n");
 }
 
-fn write_allowed_directories(out: &mut String, settings: &AppSettings, jobs: &[Job]) {
+/// Directories the agent may operate in: every job's folder, work dir and
+/// extra `allowed_dirs`, plus the default work dir. Also scopes the agent's
+/// Claude Code file permissions.
+pub(crate) fn allowed_directories(settings: &AppSettings, jobs: &[Job]) -> Vec<String> {
     let mut dirs: Vec<String> = Vec::new();
-    for job in jobs {
-        if let Some(ref fp) = job.folder_path {
-            if !dirs.contains(fp) {
-                dirs.push(fp.clone());
-            }
-        }
-        if let Some(ref wd) = job.work_dir {
-            if !dirs.contains(wd) {
-                dirs.push(wd.clone());
-            }
+    let mut push = |d: &String| {
+        if !dirs.contains(d) {
+            dirs.push(d.clone());
         }
+    };
+    for job in jobs {
+        job.folder_path.iter().for_each(&mut push);
+        job.work_dir.iter().for_each(&mut push);
+        job.allowed_dirs.iter().for_each(&mut push);
     }
-    if !settings.default_work_dir.is_empty() && !dirs.contains(&settings.default_work_dir) {
-        dirs.push(settings.default_work_dir.clone());
+    if !settings.default_work_dir.is_empty() {
+        push(&settings.default_work_dir);
     }
+    dirs
+}
+
+fn write_allowed_directories(out: &mut String, settings: &AppSettings, jobs: &[Job]) {
+    let dirs = allowed_directories(settings, jobs);
 
     out.push_str("\n## Allowed Directories\n\n");
     for d in &dirs {
@@ -268,6 +274,7 @@ fn write_configured_jobs(out: &mut String, jobs: &[Job]) {
     for job in jobs {
         let jt = match job.job_type {
             JobType::Binary => "bin",
+            JobType::Shell => "sh",
             JobType::Claude => "claude",
             JobType::Job => "job",
         };
@@ -297,35 +304,19 @@ fn write_cli_help(out: &mut String) {
     out.push_str("```\n");
 }
 
-/// Build a synthetic `Job` for running Claude as an ad-hoc interactive agent.
-/// Writes enriched prompt to `~/.config/clawtab/agent/<group>/...`
-/// and returns a Job that can be passed to `execute_job`.
-///
-/// When `target_dir` is provided, the agent runs in that directory instead of the
-/// default agent dir. The job name/slug become `agent-<folder>` so multiple
-/// per-folder agents can coexist.
-pub fn build_agent_job(
-    prompt: &str,
-    chat_id: Option<i64>,
-    settings: &AppSettings,
-    jobs: &[Job],
+/// Name, slug, work dir and group of an agent job, derived from `target_dir`.
+/// The slug must be unique per spawn: executor.rs prunes panes by slug
+/// (list_panes_by_slug), so reusing an existing pane's slug would kill it
+/// when a new agent/shell is spawned in the same folder.
+fn agent_identity(
     target_dir: Option<&str>,
-    provider: Option<ProcessProvider>,
-    model: Option<String>,
-) -> Result<Job, String> {
-    let agent_dir = agent_dir_path();
-    std::fs::create_dir_all(&agent_dir)
-        .map_err(|e| format!("Failed to create agent dir: {}", e))?;
-
-    // Derive name/slug and work_dir from target_dir. Slug must be unique per
-    // spawn: executor.rs prunes panes by slug (list_panes_by_slug), so reusing
-    // an existing pane's slug would kill it when a new agent/shell is spawned
-    // in the same folder.
+    agent_dir: &std::path::Path,
+) -> (String, String, String, String) {
     let unique_suffix = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_millis())
         .unwrap_or(0);
-    let (job_id, job_slug, work_dir, agent_group) = if let Some(dir) = target_dir {
+    if let Some(dir) = target_dir {
         let project_dir = std::path::Path::new(dir);
         let folder = project_dir
             .file_name()
@@ -346,29 +337,64 @@ pub fn build_agent_job(
             agent_dir.display().to_string(),
             "default".to_string(),
         )
-    };
+    }
+}
 
-    let group_dir = agent_group_dir(&agent_group);
+/// Write the agent's prompt to a per-agent file in its group dir, to avoid
+/// collisions, and return its path. With `context` (the default agent), the
+/// context goes to the group's `cwt.md` and the prompt references it;
+/// group/folder agents just run claude in that folder.
+fn write_agent_prompt(
+    prompt: &str,
+    job_slug: &str,
+    agent_group: &str,
+    context: Option<String>,
+) -> Result<std::path::PathBuf, String> {
+    let group_dir = agent_group_dir(agent_group);
     std::fs::create_dir_all(&group_dir)
         .map_err(|e| format!("Failed to create agent group dir: {}", e))?;
 
-    // For group/folder agents, skip the shared context - just run claude in
-    // that folder. The default agent keeps its generated context in its group.
-    let enriched = if target_dir.is_some() {
-        prompt.to_string()
-    } else {
-        let context = generate_agent_cwt_context(settings, jobs, chat_id);
-        let cwt_md_path = group_dir.join("cwt.md");
-        std::fs::write(&cwt_md_path, &context)
-            .map_err(|e| format!("Failed to write agent cwt.md: {}", e))?;
-        format!("@{}\n\n{}", cwt_md_path.display(), prompt)
+    let enriched = match context {
+        Some(context) => {
+            let cwt_md_path = group_dir.join("cwt.md");
+            std::fs::write(&cwt_md_path, &context)
+                .map_err(|e| format!("Failed to write agent cwt.md: {}", e))?;
+            format!("@{}\n\n{}", cwt_md_path.display(), prompt)
+        }
+        None => prompt.to_string(),
     };
 
-    // Write prompt to a per-agent file to avoid collisions
-    let prompt_filename = format!(".agent-prompt-{}.md", job_slug);
-    let prompt_path = group_dir.join(&prompt_filename);
+    let prompt_path = group_dir.join(format!(".agent-prompt-{}.md", job_slug));
     std::fs::write(&prompt_path, &enriched)
         .map_err(|e| format!("Failed to write agent prompt: {}", e))?;
+    Ok(prompt_path)
+}
+
+/// Build a synthetic `Job` for running Claude as an ad-hoc interactive agent.
+/// Writes enriched prompt to `~/.config/clawtab/agent/<group>/...`
+/// and returns a Job that can be passed to `execute_job`.
+///
+/// When `target_dir` is provided, the agent runs in that directory instead of the
+/// default agent dir. The job name/slug become `agent-<folder>` so multiple
+/// per-folder agents can coexist.
+pub fn build_agent_job(
+    prompt: &str,
+    chat_id: Option<i64>,
+    settings: &AppSettings,
+    jobs: &[Job],
+    target_dir: Option<&str>,
+    provider: Option<ProcessProvider>,
+    model: Option<String>,
+) -> Result<Job, String> {
+    let agent_dir = agent_dir_path();
+    std::fs::create_dir_all(&agent_dir)
+        .map_err(|e| format!("Failed to create agent dir: {}", e))?;
+
+    let (job_id, job_slug, work_dir, agent_group) = agent_identity(target_dir, &agent_dir);
+    let context = target_dir
+        .is_none()
+        .then(|| generate_agent_cwt_context(settings, jobs, chat_id));
+    let prompt_path = write_agent_prompt(prompt, &job_slug, &agent_group, context)?;
 
     Ok(Job {
         name: job_id,
@@ -402,5 +428,34 @@ pub fn build_agent_job(
         agent_model: model,
         added_at: Some(chrono::Utc::now().to_rfc3339()),
         max_history: 3,
+        run_on_enable: false,
+        history_retention_days: None,
+        include_git_info: false,
+        notify_on_change_only: false,
+        allowed_dirs: Vec::new(),
+        skip_claude_settings: false,
+        timezone: None,
+        catch_up: false,
+        after_boot_secs: None,
+        manage_project_files: true,
+        retries: 0,
+        retry_delay_secs: 0,
+        min_interval_secs: 0,
+        max_output_bytes: None,
+        pty: false,
+        precheck_command: None,
+        run_if: None,
+        session_max_age_days: None,
+        login_check_url: None,
+        skip_on_stale_session: false,
+        post_command: None,
+        mutex_group: None,
+        auto_answer: Vec::new(),
+        items: Vec::new(),
+        items_file: None,
+        parallelism: None,
+        on_success: None,
+        on_failure: None,
+        triggers: Vec::new(),
     })
 }
@@ -0,0 +1,163 @@
+//! Reminders for questions left unanswered. A question stays pending while the
+//! same prompt (same `question_id`) is on screen; once the pane content
+//! changes it drops out and its timers reset.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use clawtab_protocol::ClaudeQuestion;
+
+use crate::config::settings::{AnswerTimeoutAction, AnswerTimeoutSettings};
+
+/// What a pending question is due for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Due {
+    Remind,
+    /// Apply the configured `AnswerTimeoutAction`.
+    Act,
+}
+
+struct Pending {
+    since: Instant,
+    reminded: bool,
+    acted: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct AnswerTimeouts {
+    pending: HashMap<String, Pending>,
+}
+
+impl AnswerTimeouts {
+    /// Track the questions currently on screen and return those whose
+    /// reminder or action is due. Each fires at most once per question. The
+    /// action only applies to job panes and comes one more `reminder_secs`
+    /// after the reminder.
+    pub(crate) fn due(
+        &mut self,
+        questions: &[ClaudeQuestion],
+        auto_yes_panes: &HashSet<String>,
+        settings: &AnswerTimeoutSettings,
+        now: Instant,
+    ) -> Vec<(ClaudeQuestion, Due)> {
+        if settings.reminder_secs == 0 {
+            self.pending.clear();
+            return Vec::new();
+        }
+        let present: HashSet<&str> = questions.iter().map(|q| q.question_id.as_str()).collect();
+        self.pending.retain(|id, _| present.contains(id.as_str()));
+
+        let reminder = Duration::from_secs(settings.reminder_secs);
+        let mut due = Vec::new();
+        for q in questions {
+            if auto_yes_panes.contains(&q.pane_id) {
+                continue;
+            }
+            let p = self
+                .pending
+                .entry(q.question_id.clone())
+                .or_insert(Pending {
+                    since: now,
+                    reminded: false,
+                    acted: false,
+                });
+            let waited = now.duration_since(p.since);
+            if !p.reminded && waited >= reminder {
+                p.reminded = true;
+                due.push((q.clone(), Due::Remind));
+            } else if p.reminded
+                && !p.acted
+                && waited >= reminder * 2
+                && settings.action != AnswerTimeoutAction::None
+                && q.matched_job.is_some()
+            {
+                p.acted = true;
+                due.push((q.clone(), Due::Act));
+            }
+        }
+        due
+    }
+}
+
+/// The question's last non-empty context line, for the reminder text.
+pub(crate) fn question_summary(q: &ClaudeQuestion) -> String {
+    let line = q
+        .context_lines
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("a question");
+    match line.char_indices().nth(120) {
+        Some((idx, _)) => format!("{}...", &line[..idx]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(id: &str, job: Option<&str>) -> ClaudeQuestion {
+        ClaudeQuestion {
+            pane_id: "%1".to_string(),
+            cwd: "/tmp".to_string(),
+            tmux_session: "s".to_string(),
+            window_name: "w".to_string(),
+            question_id: id.to_string(),
+            context_lines: "Do you want to proceed?\n".to_string(),
+            options: Vec::new(),
+            input_mode: String::new(),
+            button_row: 0,
+            matched_group: None,
+            matched_job: job.map(str::to_string),
+        }
+    }
+
+    fn settings(action: AnswerTimeoutAction) -> AnswerTimeoutSettings {
+        AnswerTimeoutSettings {
+            reminder_secs: 60,
+            action,
+        }
+    }
+
+    #[test]
+    fn reminds_once_then_acts_on_job_panes() {
+        let mut t = AnswerTimeouts::default();
+        let s = settings(AnswerTimeoutAction::Stop);
+        let qs = vec![question("a", Some("proj/job"))];
+        let none = HashSet::new();
+        let start = Instant::now();
+        assert!(t.due(&qs, &none, &s, start).is_empty());
+        let at = |secs| start + Duration::from_secs(secs);
+        assert_eq!(t.due(&qs, &none, &s, at(60))[0].1, Due::Remind);
+        assert!(t.due(&qs, &none, &s, at(90)).is_empty());
+        assert_eq!(t.due(&qs, &none, &s, at(120))[0].1, Due::Act);
+        assert!(t.due(&qs, &none, &s, at(500)).is_empty());
+    }
+
+    #[test]
+    fn answered_question_resets_and_interactive_panes_are_never_acted_on() {
+        let mut t = AnswerTimeouts::default();
+        let s = settings(AnswerTimeoutAction::Decline);
+        let none = HashSet::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let interactive = vec![question("a", None)];
+        t.due(&interactive, &none, &s, start);
+        assert_eq!(t.due(&interactive, &none, &s, at(60)).len(), 1);
+        assert!(t.due(&interactive, &none, &s, at(200)).is_empty());
+
+        // The prompt went away, then a new one with the same id showed up.
+        t.due(&[], &none, &s, at(201));
+        assert!(t.due(&interactive, &none, &s, at(202)).is_empty());
+        assert_eq!(t.due(&interactive, &none, &s, at(262)).len(), 1);
+    }
+
+    #[test]
+    fn summary_uses_last_context_line() {
+        let mut q = question("a", None);
+        q.context_lines = "Bash(rm -rf build)\n\nAllow this command?\n  ".to_string();
+        assert_eq!(question_summary(&q), "Allow this command?");
+    }
+}
@@ -30,7 +30,8 @@ fn print_usage() {
         "  secrets insert [--yes] <key>  Prompt for and store a secret; confirms before overwrite"
     );
     eprintln!("  secrets delete [--yes] <key>          Delete a secret; confirms first");
-    eprintln!("  telegram send <message>    Send a Telegram message via configured bot");
+    eprintln!("  telegram send [--parse-mode html|markdownv2|none] <message>");
+    eprintln!("                    Send a Telegram message via configured bot (default: html)");
     eprintln!();
     eprintln!("Agent:");
     eprintln!("  agent auto-yes [toggle|check] [pane_id]  Manage auto-yes for an agent pane");
@@ -61,7 +62,8 @@ fn print_jobs_usage() {
     eprintln!();
     eprintln!("Commands:");
     eprintln!("  jobs list | ls             List jobs grouped by group");
-    eprintln!("  jobs run <group>/<job>     Run a job and attach/follow its output");
+    eprintln!("  jobs run <group>/<job> [key=value...]");
+    eprintln!("                             Run a job and attach/follow its output");
     eprintln!("  jobs pause <group>/<job>   Pause a running job");
     eprintln!("  jobs resume <group>/<job>  Resume a paused job");
     eprintln!("  jobs restart <group>/<job> Restart a job");
@@ -422,11 +424,27 @@ async fn main() {
         }
         "telegram" => {
             if args.len() >= 3 && args[2] == "send" {
-                if args.len() < 4 {
+                let mut rest = &args[3..];
+                let mut parse_mode = clawtab_lib::telegram::ParseMode::Html;
+                if rest.first().map(String::as_str) == Some("--parse-mode") {
+                    let Some(mode) = rest.get(1) else {
+                        eprintln!("Error: --parse-mode requires html, markdownv2 or none");
+                        std::process::exit(1);
+                    };
+                    parse_mode = match mode.parse() {
+                        Ok(mode) => mode,
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    rest = &rest[2..];
+                }
+                if rest.is_empty() {
                     eprintln!("Error: 'telegram send' requires a message");
                     std::process::exit(1);
                 }
-                let message = args[3..].join(" ");
+                let message = rest.join(" ");
                 let settings = clawtab_lib::config::settings::AppSettings::load();
                 let tg = match settings.telegram {
                     Some(ref t) if t.is_configured() => t,
@@ -436,7 +454,14 @@ async fn main() {
                     }
                 };
                 let chat_id = tg.chat_ids[0];
-                match clawtab_lib::telegram::send_message(&tg.bot_token, chat_id, &message).await {
+                match clawtab_lib::telegram::send_message(
+                    &tg.bot_token,
+                    chat_id,
+                    &message,
+                    parse_mode,
+                )
+                .await
+                {
                     Ok(()) => {
                         println!("ok");
                     }
@@ -615,6 +640,24 @@ async fn main() {
                     serde_json::to_string_pretty(&status).unwrap_or_default()
                 );
             }
+            IpcResponse::RelayPing(result) => {
+                println!(
+                    "latency_ms={} mobile_clients={}",
+                    result.latency_ms, result.mobile_clients
+                );
+            }
+            IpcResponse::DroppedRelayMessages(messages) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&messages).unwrap_or_default()
+                );
+            }
+            IpcResponse::UsagePause(pause) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&pause).unwrap_or_default()
+                );
+            }
             IpcResponse::PaneCreated {
                 pane_id,
                 tmux_session,
@@ -648,9 +691,10 @@ async fn main() {
 }
 
 async fn run_job_command(args: &[String], command_prefix: &str) {
-    let reference = parse_run_reference(args, command_prefix);
+    let (reference, params) = parse_run_args(args, command_prefix);
     let response = ipc::send_command(IpcCommand::RunJobCli {
         name: reference.clone(),
+        params,
     })
     .await;
 
@@ -666,18 +710,31 @@ async fn run_job_command(args: &[String], command_prefix: &str) {
     }
 }
 
-fn parse_run_reference(args: &[String], command_prefix: &str) -> String {
-    match args.len() {
-        3 => args[2].clone(),
-        4 => format!("{}/{}", args[2], args[3]),
+/// Split `run` arguments into the job reference and `key=value` params for
+/// the job's `{placeholders}`.
+fn parse_run_args(
+    args: &[String],
+    command_prefix: &str,
+) -> (String, std::collections::HashMap<String, String>) {
+    let (params, positional): (Vec<&String>, Vec<&String>) =
+        args[2..].iter().partition(|arg| arg.contains('='));
+    let reference = match positional.as_slice() {
+        [reference] => reference.to_string(),
+        [group, job] => format!("{}/{}", group, job),
         _ => {
             let usage = format!(
-                "usage: {} run <group>/<job> (or: {} run <group> <job>)",
+                "usage: {} run <group>/<job> [key=value...] (or: {} run <group> <job> [key=value...])",
                 command_prefix, command_prefix
             );
             exit_error(&usage)
         }
-    }
+    };
+    let params = params
+        .into_iter()
+        .filter_map(|arg| arg.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    (reference, params)
 }
 
 async fn follow_started_job(reference: &str, slug: &str, run_id: &str, is_binary: bool) {
@@ -743,7 +800,10 @@ async fn follow_started_job(reference: &str, slug: &str, run_id: &str, is_binary
                     return;
                 }
             }
-            Some(JobStatus::Idle) | Some(JobStatus::Paused) | None => {}
+            Some(JobStatus::Idle)
+            | Some(JobStatus::Queued { .. })
+            | Some(JobStatus::Paused)
+            | None => {}
         }
 
         if let Some(path) = log_path.as_ref() {
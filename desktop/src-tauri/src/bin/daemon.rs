@@ -82,6 +82,7 @@ fn main() {
     let history = Arc::new(Mutex::new(
         HistoryStore::new().expect("failed to initialize history database"),
     ));
+    clawtab_lib::history::configure_encryption(&history, &secrets, settings.lock().encrypt_history);
 
     // Run startup migrations
     {
@@ -89,8 +90,13 @@ fn main() {
         clawtab_lib::config::jobs::migrate_job_md_to_central(&mut j.jobs);
         clawtab_lib::config::jobs::migrate_cwt_to_central(&j.jobs);
     }
+    prune_history(&history, &jobs_config, &settings);
 
-    let job_status: Arc<Mutex<HashMap<String, JobStatus>>> = Arc::new(Mutex::new(HashMap::new()));
+    // Restore statuses persisted before the last exit (or crash) so reattach
+    // reconciles against the real prior state instead of an empty map.
+    let job_status: Arc<Mutex<HashMap<String, JobStatus>>> = Arc::new(Mutex::new(
+        clawtab_lib::config::job_status_store::load_reconciled(),
+    ));
     {
         let restored =
             clawtab_lib::scheduler::executor::binary_runtime::reattach_running_binary_jobs(
@@ -104,6 +110,13 @@ fn main() {
             job_status.lock().extend(restored);
         }
     }
+    // Jobs missing from the status file start from their last recorded run.
+    // Tmux runs still alive are marked Running again by reattach below.
+    clawtab_lib::config::job_status_store::seed_from_history(
+        &mut job_status.lock(),
+        &history.lock(),
+        &jobs_config.lock().jobs,
+    );
     let active_agents: Arc<Mutex<HashMap<i64, telegram::ActiveAgent>>> =
         Arc::new(Mutex::new(HashMap::new()));
     let relay_handle: Arc<Mutex<Option<clawtab_lib::relay::RelayHandle>>> =
@@ -211,6 +224,25 @@ fn main() {
             });
         }
 
+        // Persist job statuses on change
+        tokio::spawn(persist_job_status_loop(Arc::clone(&job_status)));
+
+        // Keep pruning history while the daemon stays up
+        {
+            let history = Arc::clone(&history);
+            let jobs_config = Arc::clone(&jobs_config);
+            let settings = Arc::clone(&settings);
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(HISTORY_PRUNE_INTERVAL);
+                // The first tick is immediate; startup already pruned.
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    prune_history(&history, &jobs_config, &settings);
+                }
+            });
+        }
+
         // Question detection + auto-yes
         {
             let jobs_config = Arc::clone(&jobs_config);
@@ -224,6 +256,8 @@ fn main() {
             let settings = Arc::clone(&settings);
             let event_sink = Arc::clone(&event_sink);
             let hook_runtime = hook_runtime.clone();
+            let history = Arc::clone(&history);
+            let secrets = Arc::clone(&secrets);
             tokio::spawn(async move {
                 clawtab_lib::questions::question_detection_loop(
                     settings,
@@ -237,6 +271,8 @@ fn main() {
                     notification_state,
                     event_sink,
                     hook_runtime,
+                    history,
+                    secrets,
                 )
                 .await;
             });
@@ -284,11 +320,7 @@ fn main() {
                 };
 
                 if rs.enabled && !rs.server_url.is_empty() && !device_token.is_empty() {
-                    let ws_url = if rs.server_url.starts_with("http") {
-                        rs.server_url.replacen("http", "ws", 1) + "/ws"
-                    } else {
-                        rs.server_url.clone()
-                    };
+                    let ws_url = clawtab_lib::relay::ws_url(&rs.server_url);
                     let server_url = rs.server_url.clone();
                     let relay_sub = Arc::clone(&relay_sub_required);
                     let jobs_config = Arc::clone(&jobs_config);
@@ -446,7 +478,7 @@ async fn handle_ipc_command(
             });
             IpcResponse::Jobs(summaries)
         }
-        IpcCommand::RunJob { name } => {
+        IpcCommand::RunJob { name, params } => {
             let jobs = jobs_config.lock();
             let job = clawtab_lib::config::jobs::find_job(&jobs.jobs, &name);
             match job {
@@ -458,7 +490,7 @@ async fn handle_ipc_command(
                             &job,
                             &ctx,
                             "cli",
-                            &HashMap::new(),
+                            &params,
                             clawtab_lib::scheduler::executor::ExecuteOpts::default(),
                         )
                         .await;
@@ -495,7 +527,7 @@ async fn handle_ipc_command(
                 Err(error) => IpcResponse::Error(error),
             }
         }
-        IpcCommand::RunJobCli { name } => {
+        IpcCommand::RunJobCli { name, params } => {
             let job = {
                 let jobs = jobs_config.lock();
                 clawtab_lib::config::jobs::find_job(&jobs.jobs, &name).cloned()
@@ -506,7 +538,7 @@ async fn handle_ipc_command(
             };
             let run_id = uuid::Uuid::new_v4().to_string();
             let slug = job.slug.clone();
-            let is_binary = matches!(job.job_type, clawtab_lib::config::jobs::JobType::Binary);
+            let is_binary = job.job_type.is_process();
             let ctx = ctx.clone();
             let task_run_id = run_id.clone();
             tokio::spawn(async move {
@@ -514,7 +546,7 @@ async fn handle_ipc_command(
                     &job,
                     &ctx,
                     "cli",
-                    &HashMap::new(),
+                    &params,
                     clawtab_lib::scheduler::executor::ExecuteOpts {
                         run_id: Some(task_run_id),
                         ..Default::default()
@@ -588,6 +620,21 @@ async fn handle_ipc_command(
             let status = job_status.lock().clone();
             IpcResponse::Status(status)
         }
+        IpcCommand::ImportJobStatus { statuses } => {
+            if !status_import_allowed() {
+                return IpcResponse::Error(
+                    "Importing job status requires a debug build or CLAWTAB_DEV=1".to_string(),
+                );
+            }
+            log::warn!(
+                "Replacing job status map with {} imported entries",
+                statuses.len()
+            );
+            *job_status.lock() = statuses;
+            event_sink.emit_jobs_changed();
+            clawtab_lib::relay::push_full_state_if_connected(relay, jobs_config, job_status);
+            IpcResponse::Ok
+        }
         IpcCommand::OpenSettings => IpcResponse::Error("requires desktop app".to_string()),
         IpcCommand::GetAutoYesPanes => {
             let panes: Vec<String> = auto_yes_panes.lock().iter().cloned().collect();
@@ -748,10 +795,54 @@ async fn handle_ipc_command(
             }
             IpcResponse::Ok
         }
+        IpcCommand::RelayPing => {
+            let status = compute_relay_status(
+                settings,
+                secrets,
+                relay,
+                relay_sub_required,
+                relay_auth_expired,
+            );
+            if let Some(reason) = relay_ping_blocker(&status) {
+                return IpcResponse::Error(reason.to_string());
+            }
+            match clawtab_lib::relay::ping_roundtrip(relay).await {
+                Ok(result) => IpcResponse::RelayPing(result),
+                Err(e) => IpcResponse::Error(e),
+            }
+        }
+        IpcCommand::GetDroppedRelayMessages => {
+            IpcResponse::DroppedRelayMessages(clawtab_lib::relay::dropped_messages())
+        }
+        IpcCommand::GetUsagePause => {
+            IpcResponse::UsagePause(clawtab_lib::scheduler::usage_pause::current())
+        }
         IpcCommand::ReloadSettings => {
             *settings.lock() = AppSettings::load();
+            let encrypt = settings.lock().encrypt_history;
+            if encrypt {
+                // The app may have just created the key in the keychain.
+                secrets.lock().reload();
+            }
+            clawtab_lib::history::configure_encryption(&ctx.history, secrets, encrypt);
             IpcResponse::Ok
         }
+        IpcCommand::CancelQueuedRun { name } => {
+            let job_slug = {
+                let jobs = jobs_config.lock();
+                match clawtab_lib::config::jobs::find_job(&jobs.jobs, &name) {
+                    Ok(job) => job.slug.clone(),
+                    Err(error) => return IpcResponse::Error(error),
+                }
+            };
+            match clawtab_lib::scheduler::cancel_queued_run(&job_slug, ctx) {
+                Some(status) => {
+                    event_sink.emit_job_status_changed(job_slug, status);
+                    IpcResponse::Ok
+                }
+                None => IpcResponse::Error("Job has no queued run".to_string()),
+            }
+        }
         IpcCommand::StopJob { name } => {
             let mut status = job_status.lock();
             match status.get(&name).cloned() {
@@ -800,6 +891,9 @@ async fn handle_ipc_command(
                         *config = JobsConfig::load();
                         drop(config);
                         event_sink.emit_jobs_changed();
+                        if job.enabled && job.run_on_enable {
+                            spawn_enable_run(job, ctx);
+                        }
                         IpcResponse::Ok
                     }
                     Err(e) => IpcResponse::Error(e),
@@ -828,6 +922,18 @@ async fn handle_ipc_command(
             event_sink.emit_jobs_changed();
             IpcResponse::Ok
         }
+        IpcCommand::RenameJobStatus { old_slug, new_slug } => {
+            {
+                let mut statuses = job_status.lock();
+                if let Some(status) = statuses.remove(&old_slug) {
+                    statuses.insert(new_slug, status);
+                }
+            }
+            *jobs_config.lock() = JobsConfig::load();
+            clawtab_lib::relay::push_full_state_if_connected(relay, jobs_config, job_status);
+            event_sink.emit_jobs_changed();
+            IpcResponse::Ok
+        }
         IpcCommand::AnswerQuestion { pane_id, answer } => {
             // Remove question from active list, send answer via tmux send-keys
             let mut qs = active_questions.lock();
@@ -856,7 +962,11 @@ async fn handle_ipc_command(
             event_sink.emit_questions_changed();
             IpcResponse::Ok
         }
-        IpcCommand::RunJobNow { name, params } => {
+        IpcCommand::RunJobNow {
+            name,
+            params,
+            bypass_limit,
+        } => {
             let job_result = {
                 let cfg = jobs_config.lock();
                 clawtab_lib::config::jobs::find_job(&cfg.jobs, &name).cloned()
@@ -883,6 +993,7 @@ async fn handle_ipc_command(
                         clawtab_lib::scheduler::executor::ExecuteOpts {
                             use_auto_yes: true,
                             pane_tx: Some(pane_tx),
+                            bypass_concurrency_limit: bypass_limit,
                             ..Default::default()
                         },
                     )
@@ -909,6 +1020,7 @@ async fn handle_ipc_command(
                         clawtab_lib::scheduler::executor::ExecuteOpts {
                             use_auto_yes: true,
                             pane_tx: None,
+                            bypass_concurrency_limit: bypass_limit,
                             ..Default::default()
                         },
                     )
@@ -1043,6 +1155,82 @@ async fn handle_ipc_command(
     }
 }
 
+/// Start the test run for a job that was just toggled on with `run_on_enable`.
+/// Skipped while the job is already running so enabling never stacks a
+/// second concurrent run on top of one started by cron or by hand.
+fn spawn_enable_run(
+    job: clawtab_lib::config::jobs::Job,
+    ctx: &clawtab_lib::job_context::JobContext,
+) {
+    if matches!(
+        ctx.job_status.lock().get(&job.slug),
+        Some(JobStatus::Running { .. })
+    ) {
+        log::info!("Job '{}' already running, skipping run on enable", job.slug);
+        return;
+    }
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        clawtab_lib::scheduler::executor::execute_job(
+            &job,
+            &ctx,
+            "enable",
+            &HashMap::new(),
+            clawtab_lib::scheduler::executor::ExecuteOpts {
+                use_auto_yes: true,
+                ..Default::default()
+            },
+        )
+        .await;
+    });
+}
+
+const HISTORY_PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Apply the configured history retention.
+fn prune_history(
+    history: &Mutex<HistoryStore>,
+    jobs_config: &Mutex<JobsConfig>,
+    settings: &Mutex<AppSettings>,
+) {
+    let days = settings.lock().history_retention_days;
+    let jobs = jobs_config.lock().jobs.clone();
+    if let Err(e) = history.lock().prune_expired(&jobs, days) {
+        log::warn!("Failed to prune expired history: {}", e);
+    }
+}
+
+const JOB_STATUS_PERSIST_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Write the status map to disk whenever it changes.
+async fn persist_job_status_loop(job_status: Arc<Mutex<HashMap<String, JobStatus>>>) {
+    let mut last_saved = String::new();
+    let mut ticker = tokio::time::interval(JOB_STATUS_PERSIST_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let json = match clawtab_lib::config::job_status_store::to_json(&job_status.lock()) {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("Failed to serialize job status: {}", e);
+                continue;
+            }
+        };
+        if json == last_saved {
+            continue;
+        }
+        match clawtab_lib::config::job_status_store::save_json(&json) {
+            Ok(()) => last_saved = json,
+            Err(e) => log::warn!("Failed to persist job status: {}", e),
+        }
+    }
+}
+
+/// Status import is a debugging aid for the reattach/monitor logic and is
+/// not exposed in release builds unless explicitly enabled.
+fn status_import_allowed() -> bool {
+    cfg!(debug_assertions) || std::env::var_os("CLAWTAB_DEV").is_some_and(|v| v == "1")
+}
+
 fn compute_relay_status(
     settings: &Arc<Mutex<AppSettings>>,
     secrets: &Arc<Mutex<SecretsManager>>,
@@ -1051,7 +1239,10 @@ fn compute_relay_status(
     relay_auth_expired: &Arc<Mutex<bool>>,
 ) -> IpcRelayStatus {
     let relay_settings = settings.lock().relay.clone().unwrap_or_default();
-    let connected = relay.lock().is_some();
+    let (connected, update_required) = relay
+        .lock()
+        .as_ref()
+        .map_or((false, false), |h| (true, h.update_required()));
     let subscription_required = *relay_sub_required.lock();
     let auth_expired = *relay_auth_expired.lock();
 
@@ -1071,6 +1262,22 @@ fn compute_relay_status(
         configured,
         server_url: relay_settings.server_url,
         device_name: relay_settings.device_name,
+        update_required,
+    }
+}
+
+/// Explain why a relay ping cannot succeed, before trying one.
+fn relay_ping_blocker(status: &IpcRelayStatus) -> Option<&'static str> {
+    if !status.configured {
+        Some("Not authenticated: pair this device with the relay first")
+    } else if status.auth_expired {
+        Some("Not authenticated: relay session expired, sign in again")
+    } else if status.subscription_required {
+        Some("Subscription required to use the relay")
+    } else if !status.connected {
+        Some("Desktop is offline: not connected to the relay")
+    } else {
+        None
     }
 }
 
@@ -1091,11 +1298,7 @@ fn spawn_relay_connect(
     }
     let server_url = rs.server_url.clone();
     let yaml_token = rs.device_token.clone();
-    let ws_url = if rs.server_url.starts_with("http") {
-        rs.server_url.replacen("http", "ws", 1) + "/ws"
-    } else {
-        rs.server_url.clone()
-    };
+    let ws_url = clawtab_lib::relay::ws_url(&rs.server_url);
     drop(settings_guard);
 
     let device_token = if yaml_token.is_empty() {
@@ -243,6 +243,7 @@ async fn activate(app: &mut App) {
                 let display = format!("{}/{}", job.group, job.name);
                 match ipc::send_command(IpcCommand::RunJob {
                     name: job.slug.clone(),
+                    params: HashMap::new(),
                 })
                 .await
                 {
@@ -345,6 +346,7 @@ fn draw(f: &mut Frame, app: &mut App) {
         .map(|job| {
             let (mark, style) = match app.statuses.get(&job.slug) {
                 Some(JobStatus::Running { .. }) => (">>", Style::default().fg(Color::Yellow)),
+                Some(JobStatus::Queued { .. }) => ("..", Style::default().fg(Color::Yellow)),
                 Some(JobStatus::Success { .. }) => ("ok", Style::default().fg(Color::Green)),
                 Some(JobStatus::Failed { .. }) => ("!!", Style::default().fg(Color::Red)),
                 Some(JobStatus::Paused) => ("||", Style::default().fg(Color::Cyan)),
@@ -149,7 +149,12 @@ async fn handle_key(app: &mut App, key: KeyCode) {
             if let Some(job) = app.selected_job().cloned() {
                 let name = job.slug.clone();
                 let display = format!("{}/{}", job.group, job.name);
-                match ipc::send_command(IpcCommand::RunJob { name }).await {
+                match ipc::send_command(IpcCommand::RunJob {
+                    name,
+                    params: HashMap::new(),
+                })
+                .await
+                {
                     Ok(IpcResponse::Ok) => {
                         app.message = Some(format!("Started: {}", display));
                     }
@@ -1,4 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::settings::AppSettings;
 
 /// Get the browser session directory for a job.
 /// Sessions are stored at `~/.config/clawtab/browser-sessions/<job_id>/`.
@@ -14,6 +19,69 @@ pub fn has_session(job_id: &str) -> bool {
     session_dir(job_id).join("auth.json").exists()
 }
 
+/// How long ago the job's session was saved, from `auth.json`'s mtime. None
+/// when there is no session.
+pub fn session_age(job_id: &str) -> Option<Duration> {
+    let modified = std::fs::metadata(session_dir(job_id).join("auth.json"))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(modified.elapsed().unwrap_or_default())
+}
+
+/// `Cookie` header for a request to `url` with the job's saved session, or
+/// None when no saved cookie applies.
+pub fn session_cookie_header(job_id: &str, url: &reqwest::Url) -> Option<String> {
+    let auth = std::fs::read_to_string(session_dir(job_id).join("auth.json")).ok()?;
+    cookie_header(&auth, url, chrono::Utc::now().timestamp() as f64)
+}
+
+/// The parts of Playwright's `storageState` (`auth.json`) a login check needs.
+#[derive(Deserialize)]
+struct StorageState {
+    #[serde(default)]
+    cookies: Vec<StoredCookie>,
+}
+
+#[derive(Deserialize)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    #[serde(default = "default_cookie_path")]
+    path: String,
+    /// Unix seconds; -1 for session cookies.
+    #[serde(default = "session_cookie")]
+    expires: f64,
+    #[serde(default)]
+    secure: bool,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+fn session_cookie() -> f64 {
+    -1.0
+}
+
+fn cookie_header(storage_state: &str, url: &reqwest::Url, now: f64) -> Option<String> {
+    let state: StorageState = serde_json::from_str(storage_state).ok()?;
+    let host = url.host_str()?;
+    let pairs: Vec<String> = state
+        .cookies
+        .iter()
+        .filter(|c| {
+            let domain = c.domain.trim_start_matches('.');
+            (host == domain || host.ends_with(&format!(".{}", domain)))
+                && url.path().starts_with(&c.path)
+                && (!c.secure || url.scheme() == "https")
+                && (c.expires < 0.0 || c.expires > now)
+        })
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect();
+    (!pairs.is_empty()).then(|| pairs.join("; "))
+}
+
 /// Clear the saved auth session for a job.
 pub fn clear_session(job_id: &str) -> Result<(), String> {
     let auth_path = session_dir(job_id).join("auth.json");
@@ -24,6 +92,109 @@ pub fn clear_session(job_id: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Zip a job's session dir into `browser-sessions/exports/` and return the
+/// zip's path.
+pub fn export_session(job_id: &str) -> Result<PathBuf, String> {
+    let dir = session_dir(job_id);
+    if !dir.join("auth.json").exists() {
+        return Err(format!("No saved session for '{}'", job_id));
+    }
+    let exports = browser_sessions_root().join("exports");
+    std::fs::create_dir_all(&exports)
+        .map_err(|e| format!("Failed to create exports dir: {}", e))?;
+    let dest = exports.join(format!(
+        "{}-{}.zip",
+        job_id.replace('/', "-"),
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    zip_session(&dir, &dest)?;
+    Ok(dest)
+}
+
+/// Replace a job's session with the one in `zip_path`. The current session
+/// is kept unless the zip unpacks to a session with an `auth.json`.
+pub fn import_session(job_id: &str, zip_path: &Path) -> Result<(), String> {
+    let dir = session_dir(job_id);
+    let staging = dir.with_file_name(format!(
+        ".{}.import",
+        dir.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let _ = std::fs::remove_dir_all(&staging);
+    let result = unzip_session(zip_path, &staging).and_then(|()| {
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to remove old session: {}", e))?;
+        }
+        std::fs::rename(&staging, &dir).map_err(|e| format!("Failed to install session: {}", e))
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// Files written while a login window is open, not part of the session.
+fn is_launch_artifact(name: &str) -> bool {
+    name.starts_with("_auth_launch")
+}
+
+/// Zip the regular files under `dir`. Symlinks (the browser's lock files)
+/// are left out.
+fn zip_session(dir: &Path, dest: &Path) -> Result<(), String> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let file = std::fs::File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let Ok(rel) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let name = rel.to_string_lossy().replace('\\', "/");
+            if !file_type.is_file() || is_launch_artifact(&name) {
+                continue;
+            }
+            // Browsers drop and rewrite files while running; skip what vanished.
+            let Ok(content) = std::fs::read(&path) else {
+                continue;
+            };
+            zip.start_file(name.as_str(), options)
+                .and_then(|()| zip.write_all(&content).map_err(Into::into))
+                .map_err(|e| format!("Failed to add {} to zip: {}", name, e))?;
+        }
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish zip: {}", e))?;
+    Ok(())
+}
+
+/// Unpack a session zip into `dest`, which must end up with an `auth.json`.
+fn unzip_session(zip_path: &Path, dest: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(zip_path)
+        .map_err(|e| format!("Failed to open {}: {}", zip_path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a session zip: {}", e))?;
+    archive
+        .extract(dest)
+        .map_err(|e| format!("Failed to unpack session: {}", e))?;
+    if !dest.join("auth.json").is_file() {
+        return Err("The zip has no auth.json at its top level".to_string());
+    }
+    Ok(())
+}
+
 /// Get the root browser-sessions directory (shared node_modules live here).
 fn browser_sessions_root() -> PathBuf {
     crate::config::config_dir()
@@ -45,24 +216,101 @@ fn needs_browser_download(browser: &str) -> bool {
     matches!(browser, "chromium" | "firefox")
 }
 
-/// Ensure playwright node module is installed, and download browser binary if needed.
-fn ensure_playwright_installed(browser: &str) -> Result<(), String> {
+/// Playwright version and browser cache taken from the settings.
+#[derive(Debug, Clone)]
+pub struct PlaywrightConfig {
+    pub version: String,
+    /// Passed to every playwright process as `PLAYWRIGHT_BROWSERS_PATH`.
+    pub browsers_path: PathBuf,
+}
+
+impl PlaywrightConfig {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let browsers_path = match settings.playwright_cache_dir.as_deref() {
+            Some(dir) if !dir.trim().is_empty() => expand_home(dir.trim()),
+            _ => default_browsers_path(),
+        };
+        Self {
+            version: settings.playwright_version.trim().to_string(),
+            browsers_path,
+        }
+    }
+}
+
+/// Playwright's own default browser cache: `~/Library/Caches/ms-playwright`
+/// on macOS, `$XDG_CACHE_HOME/ms-playwright` (or `~/.cache/ms-playwright`)
+/// on Linux.
+fn default_browsers_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("ms-playwright")
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// `package.json` pinning playwright to exactly `version`. Ranges are
+/// rejected so npm never picks a newer release on reinstall.
+fn package_json(version: &str) -> Result<String, String> {
+    if !is_exact_version(version) {
+        return Err(format!(
+            "playwright_version must be an exact version like 1.50.0, got '{}'",
+            version
+        ));
+    }
+    Ok(serde_json::json!({
+        "private": true,
+        "dependencies": { "playwright": version },
+    })
+    .to_string())
+}
+
+/// MAJOR.MINOR.PATCH with an optional `-prerelease`; anything else is a range
+/// to npm.
+fn is_exact_version(version: &str) -> bool {
+    let (core, prerelease) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let numeric = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|p| numeric(p))
+        && prerelease.is_none_or(|pre| {
+            !pre.is_empty()
+                && pre
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        })
+}
+
+/// Version of the installed playwright module, if any.
+fn installed_playwright_version(root: &Path) -> Option<String> {
+    let pkg = root
+        .join("node_modules")
+        .join("playwright")
+        .join("package.json");
+    let pkg: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(pkg).ok()?).ok()?;
+    pkg["version"].as_str().map(str::to_string)
+}
+
+/// Ensure the pinned playwright module is installed, and download the browser
+/// binary into the configured cache if needed.
+fn ensure_playwright_installed(browser: &str, config: &PlaywrightConfig) -> Result<(), String> {
     let root = browser_sessions_root();
     std::fs::create_dir_all(&root)
         .map_err(|e| format!("Failed to create browser-sessions dir: {}", e))?;
 
-    let node_modules = root.join("node_modules").join("playwright");
-    if !node_modules.exists() {
-        let pkg_json = root.join("package.json");
-        if !pkg_json.exists() {
-            std::fs::write(
-                &pkg_json,
-                r#"{"private": true, "dependencies": {"playwright": "^1.50.0"}}"#,
-            )
+    let pkg_json = package_json(&config.version)?;
+    if installed_playwright_version(&root).as_deref() != Some(config.version.as_str()) {
+        std::fs::write(root.join("package.json"), pkg_json)
             .map_err(|e| format!("Failed to write package.json: {}", e))?;
-        }
 
-        log::info!("Installing playwright in {:?}...", root);
+        log::info!("Installing playwright {} in {:?}...", config.version, root);
         let output = std::process::Command::new("npm")
             .args(["install"])
             .current_dir(&root)
@@ -80,18 +328,14 @@ fn ensure_playwright_installed(browser: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    // Playwright stores browsers in ~/Library/Caches/ms-playwright/ on macOS
-    let cache_dir = dirs::home_dir()
-        .map(|h| h.join("Library/Caches/ms-playwright"))
-        .unwrap_or_default();
-
+    let cache_dir = &config.browsers_path;
     let browser_prefix = match browser {
         "firefox" => "firefox",
         _ => "chromium",
     };
 
     let has_binary = cache_dir.exists()
-        && std::fs::read_dir(&cache_dir)
+        && std::fs::read_dir(cache_dir)
             .ok()
             .map(|entries| {
                 entries
@@ -101,10 +345,15 @@ fn ensure_playwright_installed(browser: &str) -> Result<(), String> {
             .unwrap_or(false);
 
     if !has_binary {
-        log::info!("Downloading {} for playwright...", browser_prefix);
+        log::info!(
+            "Downloading {} for playwright into {:?}...",
+            browser_prefix,
+            cache_dir
+        );
         let output = std::process::Command::new("npx")
             .args(["playwright", "install", browser_prefix])
             .current_dir(&root)
+            .env("PLAYWRIGHT_BROWSERS_PATH", cache_dir)
             .output()
             .map_err(|e| format!("Failed to run playwright install: {}", e))?;
 
@@ -120,6 +369,15 @@ fn ensure_playwright_installed(browser: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Brave's binary, for the `brave` choice.
+fn brave_executable() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser"
+    } else {
+        "/usr/bin/brave-browser"
+    }
+}
+
 /// Build the playwright JS script based on browser choice.
 fn build_auth_script(browser: &str, user_data_dir: &str, url: &str, auth_path: &str) -> String {
     let (require_name, launch_opts) = match browser {
@@ -134,12 +392,14 @@ fn build_auth_script(browser: &str, user_data_dir: &str, url: &str, auth_path: &
         ),
         "brave" => (
             "chromium",
-            r#"{
-    executablePath: "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+            format!(
+                r#"{{
+    executablePath: {},
     headless: false,
-    viewport: { width: 1280, height: 900 },
-  }"#
-            .to_string(),
+    viewport: {{ width: 1280, height: 900 }},
+  }}"#,
+                serde_json::to_string(brave_executable()).unwrap_or_default()
+            ),
         ),
         "firefox" => (
             "firefox",
@@ -195,8 +455,13 @@ fn build_auth_script(browser: &str, user_data_dir: &str, url: &str, auth_path: &
 /// Launch an interactive browser session so the user can log in.
 /// Uses Playwright's persistent context with `headless: false`.
 /// Auth state (cookies, localStorage) is saved to `auth.json` in the session dir.
-pub fn launch_auth_session(url: &str, job_id: &str, browser: &str) -> Result<(), String> {
-    ensure_playwright_installed(browser)?;
+pub fn launch_auth_session(
+    url: &str,
+    job_id: &str,
+    browser: &str,
+    config: &PlaywrightConfig,
+) -> Result<(), String> {
+    ensure_playwright_installed(browser, config)?;
 
     let sess_dir = session_dir(job_id);
     std::fs::create_dir_all(&sess_dir)
@@ -229,6 +494,7 @@ pub fn launch_auth_session(url: &str, job_id: &str, browser: &str) -> Result<(),
     std::process::Command::new("node")
         .arg(&tmp_script)
         .current_dir(&root)
+        .env("PLAYWRIGHT_BROWSERS_PATH", &config.browsers_path)
         .stdout(log_file)
         .stderr(stderr_file)
         .spawn()
@@ -236,3 +502,65 @@ pub fn launch_auth_session(url: &str, job_id: &str, browser: &str) -> Result<(),
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_zip_round_trips_and_needs_auth_json() {
+        let tmp = tempfile::tempdir().unwrap();
+        let session = tmp.path().join("session");
+        std::fs::create_dir_all(session.join("user-data/Default")).unwrap();
+        std::fs::write(session.join("auth.json"), "{\"cookies\":[]}").unwrap();
+        std::fs::write(session.join("user-data/Default/Cookies"), "c").unwrap();
+        std::fs::write(session.join("_auth_launch.log"), "log").unwrap();
+
+        let zip = tmp.path().join("session.zip");
+        zip_session(&session, &zip).unwrap();
+        let restored = tmp.path().join("restored");
+        unzip_session(&zip, &restored).unwrap();
+        assert!(restored.join("user-data/Default/Cookies").is_file());
+        assert!(!restored.join("_auth_launch.log").exists());
+
+        std::fs::remove_file(session.join("auth.json")).unwrap();
+        zip_session(&session, &zip).unwrap();
+        assert!(unzip_session(&zip, &tmp.path().join("bad")).is_err());
+    }
+
+    #[test]
+    fn cookie_header_sends_only_matching_live_cookies() {
+        let state = r#"{"cookies": [
+            {"name": "sid", "value": "a", "domain": ".example.com", "path": "/", "expires": -1, "secure": true},
+            {"name": "pref", "value": "b", "domain": "app.example.com", "path": "/app", "expires": 2000, "secure": false},
+            {"name": "old", "value": "c", "domain": "app.example.com", "path": "/", "expires": 500, "secure": false},
+            {"name": "other", "value": "d", "domain": "example.org", "path": "/", "expires": -1, "secure": false}
+        ], "origins": []}"#;
+        let url = |u: &str| reqwest::Url::parse(u).unwrap();
+        assert_eq!(
+            cookie_header(state, &url("https://app.example.com/app/me"), 1000.0).as_deref(),
+            Some("sid=a; pref=b")
+        );
+        assert_eq!(
+            cookie_header(state, &url("http://app.example.com/"), 1000.0),
+            None
+        );
+        assert_eq!(
+            cookie_header("not json", &url("https://example.com/"), 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn package_json_pins_an_exact_version() {
+        let pkg: serde_json::Value =
+            serde_json::from_str(&package_json("1.50.1").unwrap()).unwrap();
+        assert_eq!(pkg["dependencies"]["playwright"], "1.50.1");
+        assert!(package_json("1.51.0-beta-1").is_ok());
+        for range in [
+            "^1.50.0", "~1.50.0", ">=1.50", "latest", "1.x", "1", "1.50", "1.50.0-", "",
+        ] {
+            assert!(package_json(range).is_err(), "{} accepted", range);
+        }
+    }
+}
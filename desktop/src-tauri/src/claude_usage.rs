@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::process::Command;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +44,183 @@ impl UsageBucket {
 pub struct UsageResponse {
     pub five_hour: Option<UsageBucket>,
     pub seven_day: Option<UsageBucket>,
+    /// Set (RFC3339) when the usage endpoint failed and this reading comes
+    /// from the last successful fetch instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<String>,
+}
+
+/// Cached readings older than this are too stale to show.
+const CACHE_MAX_AGE_HOURS: i64 = 12;
+
+/// The last successful usage response, kept on disk so a transient endpoint
+/// failure doesn't blank the usage display.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedUsage {
+    fetched_at: DateTime<Utc>,
+    usage: UsageResponse,
+}
+
+fn cache_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("claude_usage_cache.json"))
+}
+
+fn write_cache(usage: &UsageResponse) -> Result<(), String> {
+    let path = cache_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let payload = CachedUsage {
+        fetched_at: Utc::now(),
+        usage: usage.clone(),
+    };
+    let json =
+        serde_json::to_string(&payload).map_err(|e| format!("Failed to serialize: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to rename cache: {}", e))
+}
+
+fn read_cache(now: DateTime<Utc>) -> Option<UsageResponse> {
+    let contents = std::fs::read_to_string(cache_path()?).ok()?;
+    let cached: CachedUsage = serde_json::from_str(&contents).ok()?;
+    from_cache(cached, now)
+}
+
+/// Turn a cached reading into a response, or `None` when it is too old.
+/// Buckets whose reset time has passed since are reported as empty.
+fn from_cache(cached: CachedUsage, now: DateTime<Utc>) -> Option<UsageResponse> {
+    if now - cached.fetched_at > chrono::Duration::hours(CACHE_MAX_AGE_HOURS) {
+        return None;
+    }
+    let expire = |bucket: Option<UsageBucket>| {
+        bucket.map(|b| {
+            let passed = b
+                .resets_at
+                .as_ref()
+                .and_then(|r| r.parse::<DateTime<Utc>>().ok())
+                .is_some_and(|at| at <= now);
+            if passed {
+                UsageBucket {
+                    utilization: 0.0,
+                    resets_at: None,
+                }
+            } else {
+                b
+            }
+        })
+    };
+    Some(UsageResponse {
+        five_hour: expire(cached.usage.five_hour),
+        seven_day: expire(cached.usage.seven_day),
+        cached_at: Some(cached.fetched_at.to_rfc3339()),
+    })
+}
+
+/// Utilization (percent) at or below which a bucket counts as freshly reset.
+const RESET_NEAR_ZERO: f64 = 1.0;
+/// Minimum previous utilization for a drop to count as a reset, so small
+/// fluctuations around zero don't trigger notifications.
+const RESET_MIN_PREVIOUS: f64 = 5.0;
+
+/// Remembers the last observed utilization per bucket to detect the edge
+/// where a quota resets back to (near) zero.
+#[derive(Debug, Default)]
+pub struct UsageResetTracker {
+    five_hour: Option<f64>,
+    seven_day: Option<f64>,
+}
+
+impl UsageResetTracker {
+    /// Record a new reading and return the labels of buckets that just reset.
+    pub fn observe(&mut self, usage: &UsageResponse) -> Vec<&'static str> {
+        let mut reset = Vec::new();
+        if bucket_reset(&mut self.five_hour, usage.five_hour.as_ref()) {
+            reset.push("session");
+        }
+        if bucket_reset(&mut self.seven_day, usage.seven_day.as_ref()) {
+            reset.push("weekly");
+        }
+        reset
+    }
+}
+
+fn bucket_reset(previous: &mut Option<f64>, current: Option<&UsageBucket>) -> bool {
+    let Some(current) = current.map(|b| b.utilization) else {
+        return false;
+    };
+    let was_used = previous.is_some_and(|p| p >= RESET_MIN_PREVIOUS);
+    *previous = Some(current);
+    was_used && current <= RESET_NEAR_ZERO
+}
+
+/// How full a quota bucket is relative to the alert thresholds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UsageLevel {
+    #[default]
+    Normal,
+    Warn,
+    Critical,
+}
+
+impl UsageLevel {
+    fn of(utilization: f64, warn: f64, critical: f64) -> Self {
+        if critical > 0.0 && utilization >= critical {
+            UsageLevel::Critical
+        } else if warn > 0.0 && utilization >= warn {
+            UsageLevel::Warn
+        } else {
+            UsageLevel::Normal
+        }
+    }
+}
+
+/// A bucket that just reached a higher alert level.
+pub struct UsageAlert<'a> {
+    pub bucket: &'static str,
+    pub level: UsageLevel,
+    pub usage: &'a UsageBucket,
+}
+
+/// Remembers the level already alerted per bucket, so each threshold alerts
+/// once. Dropping back under the warning threshold (a quota reset) re-arms it.
+#[derive(Debug, Default)]
+pub struct UsageThresholdTracker {
+    five_hour: UsageLevel,
+    seven_day: UsageLevel,
+}
+
+impl UsageThresholdTracker {
+    pub fn observe<'a>(
+        &mut self,
+        usage: &'a UsageResponse,
+        warn: f64,
+        critical: f64,
+    ) -> Vec<UsageAlert<'a>> {
+        [
+            ("session", &mut self.five_hour, usage.five_hour.as_ref()),
+            ("weekly", &mut self.seven_day, usage.seven_day.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(bucket, alerted, current)| {
+            let current = current?;
+            let level = UsageLevel::of(current.utilization, warn, critical);
+            if level == UsageLevel::Normal {
+                *alerted = level;
+            }
+            if level <= *alerted {
+                return None;
+            }
+            *alerted = level;
+            Some(UsageAlert {
+                bucket,
+                level,
+                usage: current,
+            })
+        })
+        .collect()
+    }
 }
 
 fn read_oauth_token() -> Result<String, String> {
@@ -73,7 +251,27 @@ fn read_oauth_token() -> Result<String, String> {
         .ok_or_else(|| "accessToken not found in credentials".to_string())
 }
 
+/// Fetch current usage. When the endpoint fails, fall back to the last
+/// successful reading (marked with `cached_at`) if a recent one exists.
 pub async fn fetch_usage() -> Result<UsageResponse, String> {
+    match fetch_live_usage().await {
+        Ok(usage) => {
+            if let Err(e) = write_cache(&usage) {
+                log::debug!("claude_usage: cache write failed: {}", e);
+            }
+            Ok(usage)
+        }
+        Err(e) => match read_cache(Utc::now()) {
+            Some(cached) => {
+                log::warn!("claude_usage: {}; using cached reading", e);
+                Ok(cached)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn fetch_live_usage() -> Result<UsageResponse, String> {
     let token = read_oauth_token()?;
 
     let client = reqwest::Client::new();
@@ -93,3 +291,93 @@ pub async fn fetch_usage() -> Result<UsageResponse, String> {
         .await
         .map_err(|e| format!("failed to parse usage response: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        from_cache, CachedUsage, UsageBucket, UsageLevel, UsageResetTracker, UsageResponse,
+        UsageThresholdTracker,
+    };
+    use chrono::{DateTime, Duration, Utc};
+
+    fn usage(five_hour: f64) -> UsageResponse {
+        UsageResponse {
+            five_hour: Some(UsageBucket {
+                utilization: five_hour,
+                resets_at: None,
+            }),
+            seven_day: None,
+            cached_at: None,
+        }
+    }
+
+    #[test]
+    fn cached_reading_expires_passed_resets_and_old_entries() {
+        let now: DateTime<Utc> = "2026-01-01T12:00:00Z".parse().unwrap();
+        let cached = |age_hours: i64| CachedUsage {
+            fetched_at: now - Duration::hours(age_hours),
+            usage: UsageResponse {
+                five_hour: Some(UsageBucket {
+                    utilization: 70.0,
+                    resets_at: Some("2026-01-01T11:00:00Z".to_string()),
+                }),
+                seven_day: Some(UsageBucket {
+                    utilization: 40.0,
+                    resets_at: Some("2026-01-03T00:00:00Z".to_string()),
+                }),
+                cached_at: None,
+            },
+        };
+
+        let usage = from_cache(cached(2), now).unwrap();
+        assert!(usage.cached_at.is_some());
+        let session = usage.five_hour.unwrap();
+        assert_eq!(session.utilization, 0.0);
+        assert!(session.resets_at.is_none());
+        assert_eq!(usage.seven_day.unwrap().utilization, 40.0);
+
+        assert!(from_cache(cached(13), now).is_none());
+    }
+
+    #[test]
+    fn each_threshold_alerts_once_until_usage_drops() {
+        let mut tracker = UsageThresholdTracker::default();
+        let mut levels = |u: f64| -> Vec<UsageLevel> {
+            tracker
+                .observe(&usage(u), 80.0, 95.0)
+                .iter()
+                .map(|a| a.level)
+                .collect()
+        };
+        assert!(levels(50.0).is_empty());
+        assert_eq!(levels(82.0), vec![UsageLevel::Warn]);
+        assert!(levels(85.0).is_empty());
+        assert_eq!(levels(97.0), vec![UsageLevel::Critical]);
+        assert!(levels(90.0).is_empty());
+        assert!(levels(99.0).is_empty());
+        assert!(levels(2.0).is_empty());
+        assert_eq!(levels(96.0), vec![UsageLevel::Critical]);
+    }
+
+    #[test]
+    fn reset_reported_once_when_a_used_bucket_drops_to_zero() {
+        let mut tracker = UsageResetTracker::default();
+        let mut weekly = usage(0.0);
+        weekly.seven_day = Some(UsageBucket {
+            utilization: 60.0,
+            resets_at: None,
+        });
+        assert!(tracker.observe(&weekly).is_empty());
+
+        assert!(tracker.observe(&usage(3.0)).is_empty());
+        assert!(tracker.observe(&usage(40.0)).is_empty());
+        weekly.five_hour = None;
+        weekly.seven_day = Some(UsageBucket {
+            utilization: 0.5,
+            resets_at: None,
+        });
+        assert_eq!(tracker.observe(&weekly), vec!["weekly"]);
+        assert!(tracker.observe(&weekly).is_empty());
+        assert_eq!(tracker.observe(&usage(0.0)), vec!["session"]);
+    }
+}
@@ -1,14 +1,23 @@
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+
 use crate::browser;
+use crate::AppState;
 
 #[tauri::command]
 pub async fn launch_browser_auth(
+    state: State<'_, AppState>,
     job_id: String,
     url: String,
     browser: String,
 ) -> Result<(), String> {
-    tokio::task::spawn_blocking(move || browser::launch_auth_session(&url, &job_id, &browser))
-        .await
-        .map_err(|e| format!("Failed to launch auth: {}", e))?
+    let config = browser::PlaywrightConfig::from_settings(&state.settings.lock());
+    tokio::task::spawn_blocking(move || {
+        browser::launch_auth_session(&url, &job_id, &browser, &config)
+    })
+    .await
+    .map_err(|e| format!("Failed to launch auth: {}", e))?
 }
 
 #[tauri::command]
@@ -31,3 +40,41 @@ pub async fn check_playwright_installed() -> Result<bool, String> {
         .await
         .map_err(|e| format!("Failed to check playwright: {}", e))
 }
+
+/// Slug of the job named `job_name`; browser sessions are stored per slug.
+fn job_slug(state: &AppState, job_name: &str) -> Result<String, String> {
+    state
+        .jobs_config
+        .lock()
+        .jobs
+        .iter()
+        .find(|j| j.name == job_name)
+        .map(|j| j.slug.clone())
+        .ok_or_else(|| format!("Job '{}' not found", job_name))
+}
+
+/// Zip a job's browser session and return where it was written.
+#[tauri::command]
+pub async fn export_session(
+    state: State<'_, AppState>,
+    job_name: String,
+) -> Result<PathBuf, String> {
+    let slug = job_slug(&state, &job_name)?;
+    tokio::task::spawn_blocking(move || browser::export_session(&slug))
+        .await
+        .map_err(|e| format!("Failed to export session: {}", e))?
+}
+
+/// Replace a job's browser session with one written by `export_session`,
+/// possibly for another job.
+#[tauri::command]
+pub async fn import_session(
+    state: State<'_, AppState>,
+    job_name: String,
+    zip_path: String,
+) -> Result<(), String> {
+    let slug = job_slug(&state, &job_name)?;
+    tokio::task::spawn_blocking(move || browser::import_session(&slug, Path::new(&zip_path)))
+        .await
+        .map_err(|e| format!("Failed to import session: {}", e))?
+}
@@ -1,16 +1,134 @@
+use std::collections::HashMap;
+
 use tauri::State;
 
-use crate::history::RunRecord;
+use crate::config::jobs::{Job, JobType};
+use crate::history::bundle::BundleInputs;
+use crate::history::{
+    AutoAnswerRecord, HistoryPage, JobStats, LaunchStats, RunItemRecord, RunRecord,
+};
+use crate::scheduler::output_sections::OutputSection;
 use crate::AppState;
 
 #[tauri::command]
 pub fn get_history(state: State<AppState>) -> Result<Vec<RunRecord>, String> {
     let history = state.history.lock();
-    history.get_recent(100)
+    history.get_recent(100, 0)
 }
 
+/// A page of history, newest first, with the total run count for paging UI.
+/// `job_name` limits both the page and the count to one job.
 #[tauri::command]
-pub fn get_run_detail(state: State<AppState>, id: String) -> Result<Option<RunRecord>, String> {
+pub fn get_history_page(
+    state: State<AppState>,
+    limit: usize,
+    offset: usize,
+    job_name: Option<String>,
+) -> Result<HistoryPage, String> {
+    let history = state.history.lock();
+    let records = match job_name.as_deref() {
+        Some(job) => history.get_by_job_id(job, limit, offset)?,
+        None => history.get_recent(limit, offset)?,
+    };
+    let total = history.count(job_name.as_deref())?;
+    Ok(HistoryPage { records, total })
+}
+
+/// History restricted to the given triggers (e.g. `cron`, `manual`) and,
+/// optionally, a single job. An empty trigger list returns every run.
+#[tauri::command]
+pub fn get_history_filtered(
+    state: State<AppState>,
+    triggers: Vec<String>,
+    job_name: Option<String>,
+    limit: usize,
+) -> Result<Vec<RunRecord>, String> {
+    let history = state.history.lock();
+    history.get_filtered(&triggers, job_name.as_deref(), limit)
+}
+
+/// Runs whose job name or captured output contains `query`, newest first.
+#[tauri::command]
+pub fn search_history(
+    state: State<AppState>,
+    query: String,
+    limit: usize,
+) -> Result<Vec<RunRecord>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    state.history.lock().search(query.trim(), limit)
+}
+
+/// Days of run history kept for jobs without their own retention (0 = forever).
+#[tauri::command]
+pub fn get_retention(state: State<AppState>) -> u32 {
+    state.settings.lock().history_retention_days
+}
+
+#[tauri::command]
+pub fn set_retention(state: State<AppState>, days: u32) -> Result<(), String> {
+    {
+        let mut settings = state.settings.lock();
+        let on_disk = crate::config::settings::AppSettings::load();
+        if settings.telegram.is_none() {
+            settings.telegram = on_disk.telegram;
+        }
+        if settings.relay.is_none() {
+            settings.relay = on_disk.relay;
+        }
+        settings.history_retention_days = days;
+        settings.save()?;
+    }
+    let jobs = state.jobs_config.lock().jobs.clone();
+    state.history.lock().prune_expired(&jobs, days)?;
+    tauri::async_runtime::spawn(async {
+        let _ = crate::ipc::send_command(crate::ipc::IpcCommand::ReloadSettings).await;
+    });
+    Ok(())
+}
+
+/// Trigger-to-pane-ready latency across tmux runs, optionally for one job.
+#[tauri::command]
+pub fn get_launch_stats(
+    state: State<AppState>,
+    job_name: Option<String>,
+) -> Result<LaunchStats, String> {
+    state.history.lock().launch_stats(job_name.as_deref())
+}
+
+/// Run counts, outcomes and durations for one job.
+#[tauri::command]
+pub fn get_job_stats(state: State<AppState>, job_name: String) -> Result<JobStats, String> {
+    state.history.lock().job_stats(&job_name)
+}
+
+/// Prompts the job's `auto_answer` rules answered during a run.
+#[tauri::command]
+pub fn get_auto_answers(
+    state: State<AppState>,
+    run_id: String,
+) -> Result<Vec<AutoAnswerRecord>, String> {
+    state.history.lock().get_auto_answers(&run_id)
+}
+
+/// Per-item exit codes of a batch binary run.
+#[tauri::command]
+pub fn get_run_items(state: State<AppState>, run_id: String) -> Result<Vec<RunItemRecord>, String> {
+    state.history.lock().get_run_items(&run_id)
+}
+
+/// A run plus the parsed output sections saved next to its log, if any.
+#[derive(serde::Serialize)]
+pub struct RunDetail {
+    #[serde(flatten)]
+    pub record: RunRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sections: Option<Vec<OutputSection>>,
+}
+
+#[tauri::command]
+pub fn get_run_detail(state: State<AppState>, id: String) -> Result<Option<RunDetail>, String> {
     let history = state.history.lock();
     let mut record = match history.get_by_id(&id)? {
         Some(r) => r,
@@ -28,7 +146,11 @@ pub fn get_run_detail(state: State<AppState>, id: String) -> Result<Option<RunRe
         }
     }
 
-    Ok(Some(record))
+    let sections = record
+        .log_path
+        .as_deref()
+        .and_then(|p| crate::scheduler::output_sections::load(std::path::Path::new(p), &history));
+    Ok(Some(RunDetail { record, sections }))
 }
 
 /// Tail a run's on-disk log starting at `offset` bytes. Used for live viewing
@@ -90,7 +212,71 @@ pub struct TailChunk {
 #[tauri::command]
 pub fn get_job_runs(state: State<AppState>, job_id: String) -> Result<Vec<RunRecord>, String> {
     let history = state.history.lock();
-    history.get_by_job_id(&job_id, 10)
+    history.get_by_job_id(&job_id, 10, 0)
+}
+
+/// Full output of a single run, suitable for the clipboard.
+#[tauri::command]
+pub fn get_run_output(state: State<AppState>, id: String) -> Result<String, String> {
+    let history = state.history.lock();
+    let record = history
+        .get_by_id(&id)?
+        .ok_or_else(|| format!("Run '{}' not found", id))?;
+    Ok(record.full_output())
+}
+
+/// Send a run's output to Telegram as a `.log` document. Uses the job's chat
+/// override when set, otherwise the first configured chat.
+#[tauri::command]
+pub async fn share_run(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let record = {
+        let history = state.history.lock();
+        history
+            .get_by_id(&id)?
+            .ok_or_else(|| format!("Run '{}' not found", id))?
+    };
+    let config = state
+        .settings
+        .lock()
+        .telegram
+        .clone()
+        .filter(|c| c.is_configured())
+        .ok_or("Telegram is not configured")?;
+    let (group, name, job_chat_id) = {
+        let jobs = state.jobs_config.lock();
+        match jobs.jobs.iter().find(|j| j.slug == record.job_id) {
+            Some(job) => (
+                crate::config::jobs::job_group(job).to_string(),
+                job.name.clone(),
+                job.telegram_chat_id,
+            ),
+            None => ("default".to_string(), record.job_id.clone(), None),
+        }
+    };
+    let chat_id = config
+        .chat_for_job(job_chat_id, &group)
+        .ok_or("No Telegram chat configured")?;
+
+    let status = match record.exit_code {
+        None => "running",
+        Some(0) => "finished",
+        Some(_) => "failed",
+    };
+    let caption = crate::telegram::format_job_status_message(
+        &group,
+        &name,
+        status,
+        record.exit_code.filter(|c| *c != 0),
+    );
+    let file_name = format!("{}-{}.log", name.replace('/', "-"), record.id);
+    crate::telegram::send_document(
+        &config.bot_token,
+        chat_id,
+        &file_name,
+        record.full_output().into_bytes(),
+        Some(&caption),
+    )
+    .await
 }
 
 #[tauri::command]
@@ -169,6 +355,107 @@ pub fn open_run_log(state: State<AppState>, run_id: String) -> Result<(), String
     Ok(())
 }
 
+/// Write a zip of everything recorded about a run to `path`, chosen in the
+/// UI: its metadata, stored output, log file, the job's config and, for
+/// agent jobs, the prompt and `job.md`. Secret values are redacted in every
+/// file.
+#[tauri::command]
+pub async fn export_run_bundle(
+    state: State<'_, AppState>,
+    run_id: String,
+    path: String,
+) -> Result<(), String> {
+    let (record, auto_answers, items) = {
+        let h = state.history.lock();
+        let record = h
+            .get_by_id(&run_id)?
+            .ok_or_else(|| format!("Run '{}' not found", run_id))?;
+        (
+            record,
+            h.get_auto_answers(&run_id)?,
+            h.get_run_items(&run_id)?,
+        )
+    };
+    let job = state
+        .jobs_config
+        .lock()
+        .jobs
+        .iter()
+        .find(|j| j.slug == record.job_id)
+        .cloned();
+
+    let mut inputs = BundleInputs {
+        log: record
+            .log_path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok()),
+        auto_answers,
+        items,
+        ..Default::default()
+    };
+    if let Some(ref job) = job {
+        add_job_files(&mut inputs, job, &record.started_at, &state);
+    }
+
+    let secrets = {
+        let sm = state.secrets.lock();
+        let mut values: Vec<String> = sm
+            .list_keys()
+            .iter()
+            .filter_map(|k| sm.get(k).cloned())
+            .collect();
+        drop(sm);
+        if let Some(ref tg) = state.settings.lock().telegram {
+            values.push(tg.bot_token.clone());
+        }
+        values
+    };
+    tokio::task::spawn_blocking(move || {
+        let bytes = crate::history::bundle::build(&record, inputs, &secrets)?;
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))
+    })
+    .await
+    .map_err(|e| format!("Failed to export run bundle: {}", e))?
+}
+
+/// The job's config and, for agent jobs, its prompt as the job stands now.
+fn add_job_files(inputs: &mut BundleInputs, job: &Job, started_at: &str, state: &AppState) {
+    inputs.job_yaml = serde_yml::to_string(job).ok();
+    if !matches!(job.job_type, JobType::Claude | JobType::Job) {
+        return;
+    }
+    inputs.prompt = crate::scheduler::executor::preview_job(
+        job,
+        &state.secrets,
+        &state.settings,
+        &HashMap::new(),
+    )
+    .ok()
+    .and_then(|p| p.prompt);
+    let source = if job.job_type == JobType::Job {
+        crate::config::jobs::central_job_md_path(&job.slug)
+    } else {
+        Some(std::path::PathBuf::from(&job.path))
+    };
+    if let Some(source) = source {
+        inputs.prompt_changed_since_run = modified_after(&source, started_at);
+        if job.job_type == JobType::Job {
+            inputs.job_md = std::fs::read_to_string(&source).ok();
+        }
+    }
+}
+
+/// Whether the file at `path` was modified after the RFC 3339 time `since`.
+fn modified_after(path: &std::path::Path, since: &str) -> bool {
+    let Ok(since) = chrono::DateTime::parse_from_rfc3339(since) else {
+        return false;
+    };
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t) > since)
+        .unwrap_or(false)
+}
+
 #[tauri::command]
 pub fn delete_run(state: State<AppState>, run_id: String) -> Result<(), String> {
     let history = state.history.lock();
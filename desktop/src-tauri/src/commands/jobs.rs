@@ -6,7 +6,7 @@ use tauri::{Emitter, State};
 use crate::agent_session::ProcessProvider;
 use crate::config::jobs::{Job, JobStatus};
 use crate::config::settings::AppSettings;
-use crate::cwt::CwtFolder;
+use crate::cwt::{CwtFolder, DiscoveredJob};
 use crate::AppState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +39,47 @@ pub fn get_jobs(state: State<AppState>) -> Vec<Job> {
     state.jobs_config.lock().jobs.clone()
 }
 
+/// Next fire time (RFC3339) per job, keyed by slug like the status map.
+/// Disabled jobs and jobs with an empty or invalid cron map to `None`.
+#[tauri::command]
+pub fn get_next_runs(state: State<AppState>) -> HashMap<String, Option<String>> {
+    let default_tz = state.settings.lock().default_timezone.clone();
+    state
+        .jobs_config
+        .lock()
+        .jobs
+        .iter()
+        .map(|job| {
+            let zone = crate::scheduler::cron_zone(job, default_tz.as_deref());
+            let next = job
+                .enabled
+                .then(|| crate::scheduler::next_run(&job.cron, zone))
+                .flatten()
+                .map(|t| t.to_rfc3339());
+            (job.slug.clone(), next)
+        })
+        .collect()
+}
+
+/// Dry run of the scheduler: every fire time of the enabled jobs between
+/// `from` and `to` (RFC3339), merged into one timeline. Nothing is run.
+#[tauri::command]
+pub fn simulate_schedule(
+    state: State<AppState>,
+    from: String,
+    to: String,
+) -> Result<crate::scheduler::ScheduleSimulation, String> {
+    let parse = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .map_err(|e| format!("Invalid time '{}': {}", s, e))
+    };
+    let (from, to) = (parse(&from)?, parse(&to)?);
+    let default_tz = state.settings.lock().default_timezone.clone();
+    let jobs = state.jobs_config.lock().jobs.clone();
+    crate::scheduler::simulate_schedule(&jobs, default_tz.as_deref(), from, to)
+}
+
 #[tauri::command]
 pub fn get_cached_jobs_snapshot() -> Option<CachedJobsSnapshot> {
     let path = cached_jobs_snapshot_path()?;
@@ -56,6 +97,8 @@ pub fn save_cached_jobs_snapshot(
 
 #[tauri::command]
 pub fn save_job(app: tauri::AppHandle, state: State<AppState>, job: Job) -> Result<(), String> {
+    crate::scheduler::validate_cron(&job.cron)?;
+    crate::scheduler::validate_timezone(job.timezone.as_deref())?;
     let mut config = state.jobs_config.lock();
 
     let mut job = job;
@@ -86,67 +129,162 @@ pub fn save_job(app: tauri::AppHandle, state: State<AppState>, job: Job) -> Resu
 }
 
 #[tauri::command]
-pub fn rename_job(
+pub async fn rename_job(
     app: tauri::AppHandle,
-    state: State<AppState>,
+    state: State<'_, AppState>,
     old_name: String,
     job: Job,
 ) -> Result<(), String> {
+    let new_slug = move_renamed_job(&state, &old_name, job)?;
+
+    if new_slug != old_name {
+        migrate_job_state(&state, old_name, new_slug).await;
+    }
+
+    let settings = state.settings.lock().clone();
+    let jobs = state.jobs_config.lock().jobs.clone();
+    ensure_agent_dir(&settings, &jobs);
+    regenerate_all_cwt_contexts(&settings, &jobs);
+
+    let _ = app.emit("jobs-changed", ());
+
+    Ok(())
+}
+
+/// Point a moved job's run history and daemon status at its new slug.
+async fn migrate_job_state(state: &AppState, old_slug: String, new_slug: String) {
+    if let Err(e) = state.history.lock().rename_job(&old_slug, &new_slug) {
+        log::warn!("Failed to migrate history of '{}': {}", old_slug, e);
+    }
+    let cmd = crate::ipc::IpcCommand::RenameJobStatus { old_slug, new_slug };
+    match crate::ipc::send_command(cmd).await {
+        Ok(crate::ipc::IpcResponse::Ok) => {}
+        Ok(resp) => log::warn!("Unexpected IPC response to rename: {:?}", resp),
+        Err(e) => log::warn!("Daemon unavailable, job status not migrated: {}", e),
+    }
+}
+
+/// Replace malformed or duplicate job slugs with derived ones, moving each
+/// job's directory, logs, history and status along. Returns what changed.
+#[tauri::command]
+pub async fn normalize_job_slugs(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::config::jobs::SlugChange>, String> {
+    let changes = state.jobs_config.lock().normalize_slugs()?;
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+    for change in &changes {
+        // A duplicate's history and status can't be told apart from those of
+        // the job that kept the slug, so they stay there.
+        if change.reason == crate::config::jobs::SlugIssue::Malformed {
+            migrate_job_state(&state, change.old_slug.clone(), change.new_slug.clone()).await;
+        }
+    }
+
+    let settings = state.settings.lock().clone();
+    let jobs = state.jobs_config.lock().jobs.clone();
+    ensure_agent_dir(&settings, &jobs);
+    regenerate_all_cwt_contexts(&settings, &jobs);
+
+    let _ = app.emit("jobs-changed", ());
+    Ok(changes)
+}
+
+/// Find ClawTab-generated `CLAUDE.md` files and unmigrated `.cwt/` dirs in
+/// folder jobs' project dirs. Reports only, unless `apply` is set (after the
+/// user confirmed the report), in which case it also fixes them.
+#[tauri::command]
+pub fn migrate_cwt_layout(
+    state: State<AppState>,
+    apply: bool,
+) -> Result<Vec<crate::config::jobs::LayoutFix>, String> {
+    let jobs = state.jobs_config.lock().jobs.clone();
+    crate::config::jobs::repair_cwt_layout(&jobs, apply)
+}
+
+/// Save `job` in place of the job at `old_slug`, moving its directory (and
+/// logs) when the slug changes. Returns the new slug.
+fn move_renamed_job(state: &AppState, old_slug: &str, job: Job) -> Result<String, String> {
     let mut config = state.jobs_config.lock();
 
-    let old_job = config
+    let others: Vec<Job> = config
         .jobs
         .iter()
-        .find(|j| j.slug == old_name)
+        .filter(|j| j.slug != old_slug)
         .cloned()
-        .ok_or_else(|| format!("Job not found: {}", old_name))?;
-
-    // Rename in central location if the job_id changed
-    let old_jn = old_job.job_id.as_deref().unwrap_or("default");
-    let new_jn = job.job_id.as_deref().unwrap_or("default");
-    if old_jn != new_jn {
-        if let Some(ref fp) = old_job.folder_path {
-            if let Some(jobs_dir) = crate::config::config_dir().map(|p| p.join("jobs")) {
-                let old_central = jobs_dir.join(&old_job.slug);
-                let new_central_slug =
-                    crate::config::jobs::derive_slug(fp, Some(new_jn), &config.jobs);
-                let new_central = jobs_dir.join(&new_central_slug);
-                if old_central.is_dir() && !new_central.exists() {
-                    let _ = std::fs::create_dir_all(new_central.parent().unwrap_or(&jobs_dir));
-                    let _ = std::fs::rename(&old_central, &new_central);
-                }
-            }
-        }
+        .collect();
+    if others.len() == config.jobs.len() {
+        return Err(format!("Job not found: {}", old_slug));
+    }
+    let group = crate::config::jobs::job_group(&job);
+    if others
+        .iter()
+        .any(|j| j.name == job.name && crate::config::jobs::job_group(j) == group)
+    {
+        return Err(format!(
+            "A job named '{}/{}' already exists",
+            group, job.name
+        ));
     }
 
-    // Delete old config entry
-    config.delete_job(&old_job.slug)?;
-
-    // Save new job with fresh slug
     let mut new_job = job;
-    new_job.slug = String::new();
-    // Derive new slug
     new_job.slug = crate::config::jobs::derive_slug(
-        &new_job.folder_path.as_deref().unwrap_or(&new_job.name),
+        new_job.folder_path.as_deref().unwrap_or(&new_job.name),
         new_job.job_id.as_deref(),
-        &config.jobs,
+        &others,
     );
+    if new_job.slug != old_slug {
+        config.move_job_dir(old_slug, &new_job.slug)?;
+    }
     config.save_job(&new_job)?;
 
     // Refresh in-memory list
     *config = crate::config::jobs::JobsConfig::load();
+    Ok(new_job.slug)
+}
 
-    let settings = state.settings.lock().clone();
-    let jobs = config.jobs.clone();
-    drop(config);
-    ensure_agent_dir(&settings, &jobs);
-    regenerate_all_cwt_contexts(&settings, &jobs);
-
+/// Reorder jobs to match `ordered_names`, which must list every job exactly
+/// once. The order is persisted so it survives a reload.
+#[tauri::command]
+pub fn reorder_jobs(
+    app: tauri::AppHandle,
+    state: State<AppState>,
+    ordered_names: Vec<String>,
+) -> Result<(), String> {
+    state.jobs_config.lock().reorder(&ordered_names)?;
     let _ = app.emit("jobs-changed", ());
-
     Ok(())
 }
 
+/// Find `.cwt/<job>/job.md` folders under `root` that are not configured yet,
+/// so the UI can offer to import them with `import_job_folder`.
+#[tauri::command]
+pub async fn discover_cwt_jobs(
+    state: State<'_, AppState>,
+    root: String,
+) -> Result<Vec<DiscoveredJob>, String> {
+    let root_path = std::path::PathBuf::from(&root);
+    if !root_path.is_dir() {
+        return Err(format!("Not a directory: {}", root));
+    }
+    let found = tokio::task::spawn_blocking(move || crate::cwt::discover_jobs(&root_path))
+        .await
+        .map_err(|e| format!("Discovery failed: {}", e))?;
+
+    let config = state.jobs_config.lock();
+    Ok(found
+        .into_iter()
+        .filter(|d| {
+            !config.jobs.iter().any(|j| {
+                j.folder_path.as_deref() == Some(d.folder_path.as_str())
+                    && j.job_id.as_deref() == Some(d.job_name.as_str())
+            })
+        })
+        .collect())
+}
+
 /// Import a job folder (containing job.md) into central config.
 /// `source` is the folder with job.md.
 /// `dest_cwt` is the project root directory.
@@ -203,6 +341,35 @@ pub fn import_job_folder(
         agent_model: None,
         added_at: Some(chrono::Utc::now().to_rfc3339()),
         max_history: 3,
+        run_on_enable: false,
+        history_retention_days: None,
+        include_git_info: false,
+        notify_on_change_only: false,
+        allowed_dirs: Vec::new(),
+        skip_claude_settings: false,
+        timezone: None,
+        catch_up: false,
+        after_boot_secs: None,
+        manage_project_files: true,
+        retries: 0,
+        retry_delay_secs: 0,
+        min_interval_secs: 0,
+        max_output_bytes: None,
+        pty: false,
+        precheck_command: None,
+        run_if: None,
+        session_max_age_days: None,
+        login_check_url: None,
+        skip_on_stale_session: false,
+        post_command: None,
+        mutex_group: None,
+        auto_answer: Vec::new(),
+        items: Vec::new(),
+        items_file: None,
+        parallelism: None,
+        on_success: None,
+        on_failure: None,
+        triggers: Vec::new(),
     };
 
     // Copy job.md to central location
@@ -345,6 +512,35 @@ fn clone_job_with_overrides(
         agent_model: source.agent_model.clone(),
         added_at: Some(chrono::Utc::now().to_rfc3339()),
         max_history: source.max_history,
+        run_on_enable: source.run_on_enable,
+        history_retention_days: source.history_retention_days,
+        include_git_info: source.include_git_info,
+        notify_on_change_only: source.notify_on_change_only,
+        allowed_dirs: source.allowed_dirs.clone(),
+        skip_claude_settings: source.skip_claude_settings,
+        timezone: source.timezone.clone(),
+        catch_up: source.catch_up,
+        after_boot_secs: source.after_boot_secs,
+        manage_project_files: source.manage_project_files,
+        retries: source.retries,
+        retry_delay_secs: source.retry_delay_secs,
+        min_interval_secs: source.min_interval_secs,
+        max_output_bytes: source.max_output_bytes,
+        pty: source.pty,
+        precheck_command: source.precheck_command.clone(),
+        run_if: source.run_if.clone(),
+        session_max_age_days: source.session_max_age_days,
+        login_check_url: source.login_check_url.clone(),
+        skip_on_stale_session: source.skip_on_stale_session,
+        post_command: source.post_command.clone(),
+        mutex_group: source.mutex_group.clone(),
+        auto_answer: source.auto_answer.clone(),
+        items: source.items.clone(),
+        items_file: source.items_file.clone(),
+        parallelism: source.parallelism,
+        on_success: source.on_success.clone(),
+        on_failure: source.on_failure.clone(),
+        triggers: source.triggers.clone(),
     }
 }
 
@@ -389,22 +585,28 @@ pub async fn run_job_now(
     state: State<'_, AppState>,
     name: String,
     params: Option<std::collections::HashMap<String, String>>,
+    bypass_limit: Option<bool>,
 ) -> Result<Option<RunAgentResult>, String> {
     let params = params.unwrap_or_default();
-    let result =
-        match crate::ipc::send_command(crate::ipc::IpcCommand::RunJobNow { name, params }).await {
-            Ok(crate::ipc::IpcResponse::PaneCreated {
-                pane_id: Some(pane_id),
-                tmux_session: Some(tmux_session),
-            }) => Ok(Some(RunAgentResult {
-                pane_id,
-                tmux_session,
-            })),
-            Ok(crate::ipc::IpcResponse::PaneCreated { .. }) => Ok(None),
-            Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
-            Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
-            Err(e) => Err(format!("Daemon unavailable: {}", e)),
-        };
+    let result = match crate::ipc::send_command(crate::ipc::IpcCommand::RunJobNow {
+        name,
+        params,
+        bypass_limit: bypass_limit.unwrap_or(false),
+    })
+    .await
+    {
+        Ok(crate::ipc::IpcResponse::PaneCreated {
+            pane_id: Some(pane_id),
+            tmux_session: Some(tmux_session),
+        }) => Ok(Some(RunAgentResult {
+            pane_id,
+            tmux_session,
+        })),
+        Ok(crate::ipc::IpcResponse::PaneCreated { .. }) => Ok(None),
+        Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    };
     // The daemon's new-window dragged every grouped view session's active
     // window to the new pane. Restore each viewer's intended window so the
     // existing PTY readers stop streaming the new pane's output.
@@ -452,6 +654,16 @@ pub async fn stop_job(_state: State<'_, AppState>, name: String) -> Result<(), S
     }
 }
 
+#[tauri::command]
+pub async fn cancel_queued_run(_state: State<'_, AppState>, name: String) -> Result<(), String> {
+    match crate::ipc::send_command(crate::ipc::IpcCommand::CancelQueuedRun { name }).await {
+        Ok(crate::ipc::IpcResponse::Ok) => Ok(()),
+        Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    }
+}
+
 #[tauri::command]
 pub async fn restart_job(
     _app_handle: tauri::AppHandle,
@@ -820,35 +1032,162 @@ const CLAUDE_ALLOWED_BASH: &[&str] = &[
     "Bash(whoami)",
     "Bash(hostname)",
     "Bash(brew *)",
-    "Read(**)",
-    "Edit(**)",
-    "Write(**)",
     "WebSearch(*)",
     "WebFetch(*)",
 ];
 
-/// Write `.claude/settings.local.json` in the given directory with default
-/// permissions for automated Claude Code jobs (curl, cwtctl, kill, etc.).
-fn write_claude_settings(dir: &std::path::Path) {
-    let claude_dir = dir.join(".claude");
-    if let Err(e) = std::fs::create_dir_all(&claude_dir) {
-        log::warn!("Failed to create .claude dir in {}: {}", dir.display(), e);
-        return;
+/// File tools whose permissions are scoped to a job's allowed directories.
+const CLAUDE_FILE_TOOLS: &[&str] = &["Read", "Edit", "Write"];
+
+/// Claude Code permission path for everything under `dir`. Absolute paths
+/// need a leading `//`; a single `/` would be relative to the settings file.
+fn dir_glob(dir: &str) -> String {
+    let dir = dir.trim_end_matches('/');
+    if dir.is_empty() {
+        "//**".to_string()
+    } else if dir.starts_with('/') {
+        format!("/{}/**", dir)
+    } else {
+        format!("{}/**", dir)
     }
+}
 
-    let settings = serde_json::json!({
-        "permissions": { "allow": CLAUDE_ALLOWED_BASH }
-    });
+/// `Read`/`Edit`/`Write` rules granting access under each of `dirs`.
+fn file_permission_rules(dirs: &[String]) -> Vec<String> {
+    let mut rules = Vec::new();
+    for dir in dirs {
+        let glob = dir_glob(dir);
+        for tool in CLAUDE_FILE_TOOLS {
+            let rule = format!("{}({})", tool, glob);
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+        }
+    }
+    rules
+}
 
-    let path = claude_dir.join("settings.local.json");
-    match serde_json::to_string_pretty(&settings) {
-        Ok(json) => {
-            if let Err(e) = std::fs::write(&path, json) {
-                log::warn!("Failed to write {}: {}", path.display(), e);
+/// Credential directories whose file access is denied. A deny rule holds
+/// even when auto-yes answers every permission prompt, which the allow list
+/// alone can't ensure. Claude Code rules can't deny "everything outside
+/// these dirs", so only these are blocked outright.
+const CLAUDE_DENIED_DIRS: &[&str] = &[
+    "~/.ssh",
+    "~/.aws",
+    "~/.gnupg",
+    "~/.kube",
+    "~/.docker",
+    "~/.config/gcloud",
+];
+
+/// `Read`/`Edit`/`Write` deny rules for `CLAUDE_DENIED_DIRS`, skipping any
+/// that holds one of `allowed_dirs`: deny wins over allow, so denying it
+/// would block the allowed dir too.
+fn file_deny_rules(allowed_dirs: &[String]) -> Vec<String> {
+    let holds = |denied: &str, dir: &str| {
+        let dir = dir.trim_end_matches('/');
+        dir == denied || dir.starts_with(&format!("{}/", denied))
+    };
+    let denied: Vec<String> = CLAUDE_DENIED_DIRS
+        .iter()
+        .filter(|denied| !allowed_dirs.iter().any(|dir| holds(denied, dir)))
+        .map(|denied| denied.to_string())
+        .collect();
+    file_permission_rules(&denied)
+}
+
+/// A directory that gets a generated `.claude/settings.local.json`.
+struct SettingsScope {
+    dir: std::path::PathBuf,
+    allowed: Vec<String>,
+    /// A job in this directory opted out with `skip_claude_settings` or
+    /// `manage_project_files: false`.
+    skip: bool,
+}
+
+/// Directory a job's Claude Code settings are written to: the project root
+/// for folder jobs, the work dir (or the default) for Claude jobs.
+fn claude_settings_dir<'a>(job: &'a Job, settings: &'a AppSettings) -> Option<&'a str> {
+    let dir = match job.job_type {
+        crate::config::jobs::JobType::Job => job.folder_path.as_deref()?,
+        crate::config::jobs::JobType::Claude => job
+            .work_dir
+            .as_deref()
+            .unwrap_or(&settings.default_work_dir),
+        _ => return None,
+    };
+    (!dir.is_empty()).then_some(dir)
+}
+
+/// Group jobs by settings directory, merging their allowed dirs. Each
+/// directory is allowed itself.
+fn claude_settings_scopes(settings: &AppSettings, jobs: &[Job]) -> Vec<SettingsScope> {
+    let mut scopes: Vec<SettingsScope> = Vec::new();
+    for job in jobs {
+        let Some(dir) = claude_settings_dir(job, settings) else {
+            continue;
+        };
+        let path = std::path::PathBuf::from(dir);
+        let idx = match scopes.iter().position(|s| s.dir == path) {
+            Some(i) => i,
+            None => {
+                scopes.push(SettingsScope {
+                    dir: path,
+                    allowed: vec![dir.to_string()],
+                    skip: false,
+                });
+                scopes.len() - 1
             }
+        };
+        let scope = &mut scopes[idx];
+        scope.skip |= job.skip_claude_settings || !job.manage_project_files;
+        for d in &job.allowed_dirs {
+            if !scope.allowed.contains(d) {
+                scope.allowed.push(d.clone());
+            }
+        }
+    }
+    scopes
+}
+
+/// Contents of a generated `.claude/settings.local.json`. The allowed dirs
+/// are also Claude Code's `additionalDirectories`, so it treats them as the
+/// workspace.
+fn claude_settings_json(allowed_dirs: &[String]) -> serde_json::Value {
+    let mut allow: Vec<String> = CLAUDE_ALLOWED_BASH.iter().map(|s| s.to_string()).collect();
+    allow.extend(file_permission_rules(allowed_dirs));
+    serde_json::json!({
+        "permissions": {
+            "allow": allow,
+            "deny": file_deny_rules(allowed_dirs),
+            "additionalDirectories": allowed_dirs,
         }
-        Err(e) => log::warn!("Failed to serialize claude settings: {}", e),
+    })
+}
+
+/// Write `.claude/settings.local.json` in the given directory with default
+/// permissions for automated Claude Code jobs (curl, cwtctl, kill, etc.).
+/// File access is limited to `allowed_dirs`. Fails, writing nothing, when
+/// `dir` or an existing `.claude` in it is not a directory.
+fn write_claude_settings(dir: &std::path::Path, allowed_dirs: &[String]) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let claude_dir = dir.join(".claude");
+    if claude_dir.exists() && !claude_dir.is_dir() {
+        return Err(format!(
+            "{} exists but is not a directory",
+            claude_dir.display()
+        ));
     }
+    std::fs::create_dir_all(&claude_dir)
+        .map_err(|e| format!("Failed to create .claude dir in {}: {}", dir.display(), e))?;
+
+    let settings = claude_settings_json(allowed_dirs);
+    let path = claude_dir.join("settings.local.json");
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize claude settings: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
 }
 
 /// Ensure the agent directory exists with current config.
@@ -867,8 +1206,15 @@ pub fn ensure_agent_dir(settings: &AppSettings, jobs: &[Job]) {
         log::warn!("Failed to write agent cwt.md: {}", e);
     }
 
-    // Write Claude Code permissions
-    write_claude_settings(&agent_dir);
+    // Write Claude Code permissions, scoped to the listed allowed directories
+    let mut allowed = crate::agent::allowed_directories(settings, jobs);
+    allowed.push(agent_dir.display().to_string());
+    if let Some(config_dir) = crate::config::config_dir() {
+        allowed.push(config_dir.display().to_string());
+    }
+    if let Err(e) = write_claude_settings(&agent_dir, &allowed) {
+        log::warn!("Failed to write agent Claude settings: {}", e);
+    }
 
     // Clean up old files from previous formats
     for old in &["CLAUDE.md"] {
@@ -887,63 +1233,137 @@ pub fn ensure_agent_dir(settings: &AppSettings, jobs: &[Job]) {
 /// Regenerate context.md for every folder job in central config.
 /// Also writes `.claude/settings.local.json` in each project root / work_dir.
 pub fn regenerate_all_cwt_contexts(settings: &AppSettings, jobs: &[Job]) {
-    let mut settings_written: Vec<std::path::PathBuf> = Vec::new();
-
     for job in jobs {
-        match job.job_type {
-            crate::config::jobs::JobType::Job => {
-                if let Some(ref folder_path) = job.folder_path {
-                    // Write context.md to central: ~/.config/clawtab/jobs/{slug}/context.md
-                    if let Some(context_path) =
-                        crate::config::jobs::central_job_context_path(&job.slug)
-                    {
-                        if job.agent_provider == Some(ProcessProvider::Shell) {
-                            if context_path.exists() {
-                                let _ = std::fs::remove_file(&context_path);
-                            }
-                        } else {
-                            let content = generate_cwt_context(job, settings);
-                            if let Some(parent) = context_path.parent() {
-                                let _ = std::fs::create_dir_all(parent);
-                            }
-                            if let Err(e) = std::fs::write(&context_path, content) {
-                                log::warn!("Failed to write context.md for '{}': {}", job.name, e);
-                            }
-                        }
-                    }
-
-                    // Write Claude Code permissions in the project root
-                    let project_root = std::path::Path::new(folder_path);
-                    let pr = project_root.to_path_buf();
-                    if !settings_written.contains(&pr) {
-                        write_claude_settings(project_root);
-                        settings_written.push(pr);
-                    }
-                }
+        if job.job_type != crate::config::jobs::JobType::Job
+            || job.folder_path.is_none()
+            || !job.manage_project_files
+        {
+            continue;
+        }
+        // Write context.md to central: ~/.config/clawtab/jobs/{slug}/context.md
+        let Some(context_path) = crate::config::jobs::central_job_context_path(&job.slug) else {
+            continue;
+        };
+        if job.agent_provider == Some(ProcessProvider::Shell) {
+            if context_path.exists() {
+                let _ = std::fs::remove_file(&context_path);
+            }
+        } else {
+            let content = generate_cwt_context(job, settings);
+            if let Some(parent) = context_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
             }
-            crate::config::jobs::JobType::Claude => {
-                // Claude jobs run from work_dir; write permissions there
-                if let Some(ref wd) = job.work_dir {
-                    let dir = std::path::PathBuf::from(wd);
-                    if !settings_written.contains(&dir) {
-                        write_claude_settings(&dir);
-                        settings_written.push(dir);
-                    }
-                }
+            if let Err(e) = std::fs::write(&context_path, content) {
+                log::warn!("Failed to write context.md for '{}': {}", job.name, e);
             }
-            _ => {}
+        }
+    }
+
+    // Claude Code permissions go in each project root / work_dir
+    let scopes = claude_settings_scopes(settings, jobs);
+    for scope in scopes.iter().filter(|s| !s.skip) {
+        if let Err(e) = write_claude_settings(&scope.dir, &scope.allowed) {
+            log::warn!("Skipped Claude settings: {}", e);
         }
     }
 
     // Also write to default_work_dir if set
     if !settings.default_work_dir.is_empty() {
         let dir = std::path::PathBuf::from(&settings.default_work_dir);
-        if !settings_written.contains(&dir) && dir.is_dir() {
-            write_claude_settings(&dir);
+        if !scopes.iter().any(|s| s.dir == dir) && dir.is_dir() {
+            if let Err(e) =
+                write_claude_settings(&dir, std::slice::from_ref(&settings.default_work_dir))
+            {
+                log::warn!("Skipped Claude settings: {}", e);
+            }
         }
     }
 }
 
+/// The `.claude/settings.local.json` that would be written for a job's
+/// directory, or `None` if nothing is written there (opted out, or the job
+/// type doesn't use Claude Code settings).
+#[tauri::command]
+pub fn preview_claude_settings(
+    state: State<AppState>,
+    name: String,
+) -> Result<Option<String>, String> {
+    let jobs = state.jobs_config.lock().jobs.clone();
+    let settings = state.settings.lock().clone();
+    let job = jobs
+        .iter()
+        .find(|j| j.name == name)
+        .ok_or_else(|| format!("Job '{}' not found", name))?;
+    let Some(dir) = claude_settings_dir(job, &settings) else {
+        return Ok(None);
+    };
+    let dir = std::path::PathBuf::from(dir);
+    let Some(scope) = claude_settings_scopes(&settings, &jobs)
+        .into_iter()
+        .find(|s| s.dir == dir && !s.skip)
+    else {
+        return Ok(None);
+    };
+    serde_json::to_string_pretty(&claude_settings_json(&scope.allowed))
+        .map(Some)
+        .map_err(|e| format!("Failed to serialize claude settings: {}", e))
+}
+
+/// Assemble a folder job's prompt without running it and report which
+/// `@` references are missing and which `{params}` are left unfilled.
+#[tauri::command]
+pub fn validate_folder_job(
+    state: State<AppState>,
+    name: String,
+) -> Result<crate::scheduler::executor::FolderPromptReport, String> {
+    let job = state
+        .jobs_config
+        .lock()
+        .jobs
+        .iter()
+        .find(|j| j.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Job '{}' not found", name))?;
+    if job.job_type != crate::config::jobs::JobType::Job {
+        return Err(format!("Job '{}' is not a folder job", name));
+    }
+    crate::scheduler::executor::validate_folder_prompt(&job)
+}
+
+/// Resolve what running a job would do (work dir, env, command, prompt)
+/// without starting it. Secret values are left out.
+#[tauri::command]
+pub fn preview_job(
+    state: State<AppState>,
+    name: String,
+    params: Option<HashMap<String, String>>,
+) -> Result<crate::scheduler::executor::JobPreview, String> {
+    let job = {
+        let config = state.jobs_config.lock();
+        crate::config::jobs::find_job(&config.jobs, &name)?.clone()
+    };
+    crate::scheduler::executor::preview_job(
+        &job,
+        &state.secrets,
+        &state.settings,
+        &params.unwrap_or_default(),
+    )
+}
+
+/// Params a manual run of a job can take: its declared params plus any
+/// `{placeholder}` in its prompt file, for the run form.
+#[tauri::command]
+pub fn get_job_params(
+    state: State<AppState>,
+    name: String,
+) -> Result<Vec<clawtab_protocol::JobParam>, String> {
+    let job = {
+        let config = state.jobs_config.lock();
+        crate::config::jobs::find_job(&config.jobs, &name)?.clone()
+    };
+    crate::scheduler::executor::job_params(&job)
+}
+
 pub use crate::agent::agent_dir_path;
 
 /// Open an agent file (cwt.md) in the user's preferred editor.
@@ -1021,7 +1441,16 @@ fn generate_cwt_context(job: &Job, _settings: &AppSettings) -> String {
     out.push_str(&format!("Job name: `{}`\n", job.name));
 
     out.push_str("\n## Rules\n\n");
-    out.push_str("- Only edit and look for files in the current directory.\n");
+    if job.allowed_dirs.is_empty() {
+        out.push_str("- Only edit and look for files in the current directory.\n");
+    } else {
+        out.push_str(
+            "- Only edit and look for files in the current directory and these directories:\n",
+        );
+        for d in &job.allowed_dirs {
+            out.push_str(&format!("  - `{}`\n", d));
+        }
+    }
     out.push_str("- The job directions are managed by ClawTab (stored centrally).\n");
     out.push_str("- Shared project context is loaded automatically from central config.\n");
     out.push_str("- Notifications are handled by ClawTab. Do not send notifications directly.\n");
@@ -1081,3 +1510,63 @@ pub async fn run_agent(
     state.pty_manager.lock().restore_view_session_windows();
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{claude_settings_json, file_permission_rules, write_claude_settings};
+
+    #[test]
+    fn file_permissions_are_scoped_to_allowed_dirs() {
+        let rules = file_permission_rules(&["/work/proj/".to_string(), "~/notes".to_string()]);
+
+        assert_eq!(
+            rules,
+            vec![
+                "Read(//work/proj/**)",
+                "Edit(//work/proj/**)",
+                "Write(//work/proj/**)",
+                "Read(~/notes/**)",
+                "Edit(~/notes/**)",
+                "Write(~/notes/**)",
+            ]
+        );
+    }
+
+    #[test]
+    fn credential_dirs_are_denied_unless_allowed() {
+        let settings = claude_settings_json(&["/work/proj".to_string(), "~/.aws/".to_string()]);
+        let permissions = &settings["permissions"];
+        let deny: Vec<&str> = permissions["deny"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r.as_str())
+            .collect();
+
+        assert!(deny.contains(&"Read(~/.ssh/**)"));
+        assert!(deny.contains(&"Write(~/.config/gcloud/**)"));
+        assert!(!deny.iter().any(|r| r.contains("~/.aws")));
+        assert_eq!(
+            permissions["additionalDirectories"],
+            serde_json::json!(["/work/proj", "~/.aws/"])
+        );
+    }
+
+    #[test]
+    fn claude_settings_need_directories() {
+        let project = tempfile::tempdir().unwrap();
+        let allowed = vec![project.path().display().to_string()];
+
+        let missing = project.path().join("missing");
+        assert!(write_claude_settings(&missing, &allowed).is_err());
+        assert!(!missing.exists());
+
+        std::fs::write(project.path().join(".claude"), "").unwrap();
+        let err = write_claude_settings(project.path(), &allowed).unwrap_err();
+        assert!(err.contains("is not a directory"));
+
+        std::fs::remove_file(project.path().join(".claude")).unwrap();
+        write_claude_settings(project.path(), &allowed).unwrap();
+        assert!(project.path().join(".claude/settings.local.json").is_file());
+    }
+}
@@ -11,6 +11,7 @@ pub mod processes;
 pub mod pty;
 pub mod relay;
 pub mod secrets;
+pub mod self_test;
 pub mod settings;
 pub mod skills;
 pub mod status;
@@ -228,7 +228,7 @@ fn collect_history_panes(state: &State<'_, AppState>) -> HashMap<String, PaneJob
             })
             .collect()
     };
-    let recent = state.history.lock().get_recent(500).unwrap_or_default();
+    let recent = state.history.lock().get_recent(500, 0).unwrap_or_default();
     let mut panes = HashMap::new();
     for run in recent {
         let Some(pane_id) = run.pane_id else {
@@ -716,6 +716,56 @@ pub fn get_detected_process_logs(tmux_session: String, pane_id: String) -> Resul
     crate::tmux::capture_pane(&tmux_session, &pane_id, 200)
 }
 
+/// Save a detected pane's full scrollback as a finished run of `job_name`
+/// (trigger "detected") so ad-hoc sessions show up in history. Returns the
+/// new run id.
+#[tauri::command]
+pub fn archive_detected_process(
+    state: State<'_, AppState>,
+    pane_id: String,
+    job_name: String,
+) -> Result<String, String> {
+    let slug = {
+        let config = state.jobs_config.lock();
+        crate::config::jobs::find_job(&config.jobs, &job_name)?
+            .slug
+            .clone()
+    };
+    if !crate::tmux::pane_exists(&pane_id) {
+        return Err(format!("Pane {} no longer exists", pane_id));
+    }
+    let output = crate::tmux::capture_pane_full(&pane_id)?.trim().to_string();
+    // A pane ClawTab launched prints its exit marker; any other only reports
+    // a code once its process has exited.
+    let exit_code = crate::scheduler::monitor::parse_exit_marker(&output)
+        .or_else(|| crate::tmux::pane_dead_status(&pane_id));
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let log_path = crate::scheduler::monitor::save_log_file(&slug, &run_id, &output, None);
+    let record = crate::history::RunRecord {
+        id: run_id.clone(),
+        job_id: slug,
+        started_at: now.clone(),
+        finished_at: Some(now),
+        exit_code,
+        trigger: "detected".to_string(),
+        stdout: output,
+        stderr: String::new(),
+        // Not a clawtab-owned pane: leave pane_id unset so history pruning
+        // never closes the user's session.
+        pane_id: None,
+        log_path: log_path.map(|p| p.to_string_lossy().into_owned()),
+        git_branch: None,
+        git_commit: None,
+        launch_ms: None,
+        output_truncated: false,
+        skipped: false,
+    };
+    state.history.lock().insert(&record)?;
+    Ok(run_id)
+}
+
 #[tauri::command]
 pub fn send_detected_process_input(
     pane_id: String,
@@ -909,3 +959,14 @@ fn resolve_pane_group(
         .max_by_key(|(root, _)| root.len())
         .map(|(_, group)| group)
 }
+
+/// Run the numbered-prompt detector on pasted pane output with the current
+/// `prompt_detection` settings, so patterns can be tuned against real output.
+#[tauri::command]
+pub fn test_prompt_detection(
+    state: State<AppState>,
+    sample: String,
+) -> crate::questions::PromptDetection {
+    let patterns = state.settings.lock().prompt_detection.clone();
+    crate::questions::detect_numbered_prompt(&sample, &patterns)
+}
@@ -18,6 +18,7 @@ pub struct RelayStatus {
     pub configured: bool,
     pub server_url: String,
     pub device_name: String,
+    pub update_required: bool,
 }
 
 #[tauri::command]
@@ -68,6 +69,10 @@ pub async fn set_relay_settings(
 
 #[tauri::command]
 pub async fn get_relay_status(_state: State<'_, AppState>) -> Result<RelayStatus, String> {
+    relay_status().await
+}
+
+pub(crate) async fn relay_status() -> Result<RelayStatus, String> {
     match crate::ipc::send_command(crate::ipc::IpcCommand::GetRelayStatus).await {
         Ok(crate::ipc::IpcResponse::RelayStatus(s)) => Ok(RelayStatus {
             enabled: s.enabled,
@@ -77,12 +82,20 @@ pub async fn get_relay_status(_state: State<'_, AppState>) -> Result<RelayStatus
             configured: s.configured,
             server_url: s.server_url,
             device_name: s.device_name,
+            update_required: s.update_required,
         }),
         Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
         Err(e) => Err(format!("Daemon unavailable: {}", e)),
     }
 }
 
+/// Check that `server_url` points at a reachable relay with a valid
+/// certificate, before it is saved.
+#[tauri::command]
+pub async fn relay_probe(server_url: String) -> Result<crate::relay::RelayProbe, String> {
+    Ok(crate::relay::probe(&server_url).await)
+}
+
 #[derive(Deserialize)]
 pub struct LoginRequest {
     pub server_url: String,
@@ -244,6 +257,29 @@ pub async fn relay_connect(
     }
 }
 
+/// Send a ping through the relay server and report the round-trip latency.
+#[tauri::command]
+pub async fn relay_ping_roundtrip() -> Result<crate::relay::RelayPingResult, String> {
+    match crate::ipc::send_command(crate::ipc::IpcCommand::RelayPing).await {
+        Ok(crate::ipc::IpcResponse::RelayPing(result)) => Ok(result),
+        Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    }
+}
+
+/// Relay messages the daemon tried to send but could not deliver.
+#[tauri::command]
+pub async fn get_dropped_relay_messages() -> Result<Vec<crate::relay::DroppedRelayMessage>, String>
+{
+    match crate::ipc::send_command(crate::ipc::IpcCommand::GetDroppedRelayMessages).await {
+        Ok(crate::ipc::IpcResponse::DroppedRelayMessages(messages)) => Ok(messages),
+        Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    }
+}
+
 #[tauri::command]
 pub fn relay_save_tokens(
     state: State<AppState>,
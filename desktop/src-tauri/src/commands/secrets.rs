@@ -1,6 +1,6 @@
 use tauri::State;
 
-use crate::secrets::SecretEntry;
+use crate::secrets::{KeychainStatus, SecretEntry, SecretSource};
 use crate::AppState;
 
 #[tauri::command]
@@ -9,15 +9,25 @@ pub fn list_secrets(state: State<AppState>) -> Vec<SecretEntry> {
     secrets.list_entries()
 }
 
+/// Report whether the keychain could be read so the UI can ask the user to
+/// grant access instead of jobs silently running without their secrets.
+#[tauri::command]
+pub fn get_keychain_status(state: State<AppState>) -> KeychainStatus {
+    state.secrets.lock().keychain_status()
+}
+
+/// Store a secret. `source` defaults to the keychain; with `gopass` the
+/// value is also written to the gopass store at path `key`.
 #[tauri::command]
 pub async fn set_secret(
     state: State<'_, AppState>,
     key: String,
     value: String,
+    source: Option<SecretSource>,
 ) -> Result<(), String> {
     {
         let mut secrets = state.secrets.lock();
-        secrets.set(&key, &value)?;
+        secrets.set_in(source.unwrap_or(SecretSource::Keychain), &key, &value)?;
     }
     let _ = crate::ipc::send_command(crate::ipc::IpcCommand::ReloadSecrets).await;
     Ok(())
@@ -0,0 +1,315 @@
+use serde::Serialize;
+use tauri::State;
+
+use super::relay::RelayStatus;
+use crate::secrets::KeychainStatus;
+use crate::tools::ToolInfo;
+use crate::AppState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something optional is off or missing.
+    Warn,
+    Fail,
+}
+
+/// One line of the self-test checklist.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestCheck {
+    /// Stable identifier, e.g. `tmux` or `relay`.
+    pub id: &'static str,
+    pub label: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warn or fail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl SelfTestCheck {
+    fn new(id: &'static str, label: &'static str, status: CheckStatus, detail: String) -> Self {
+        Self {
+            id,
+            label,
+            status,
+            detail,
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    /// The worst status among the checks.
+    pub status: CheckStatus,
+    pub checks: Vec<SelfTestCheck>,
+    pub ran_at: String,
+}
+
+/// Run every setup check (daemon, tmux, agent CLIs, Telegram, relay,
+/// keychain, Playwright) and report each as pass, warn or fail.
+#[tauri::command]
+pub async fn self_test(state: State<'_, AppState>) -> Result<SelfTestReport, String> {
+    let custom_paths = state.settings.lock().tool_paths.clone();
+    let tools =
+        tokio::task::spawn_blocking(move || crate::tools::detect_tools_cached(&custom_paths, true))
+            .await
+            .map_err(|e| format!("Detection failed: {}", e))?;
+
+    let (running, installed) = tokio::task::spawn_blocking(|| {
+        (crate::daemon::is_running().0, crate::daemon::is_installed())
+    })
+    .await
+    .map_err(|e| format!("Failed to check the daemon: {}", e))?;
+    let mut checks = vec![daemon_check(running, installed)];
+    checks.push(tool_check(&tools, "tmux", "tmux", CheckStatus::Fail));
+    checks.push(tool_check(
+        &tools,
+        "claude",
+        "Claude Code",
+        CheckStatus::Warn,
+    ));
+    checks.push(tool_check(&tools, "codex", "Codex", CheckStatus::Warn));
+    checks.push(telegram_check(&state).await);
+    checks.push(relay_check(super::relay::relay_status().await));
+    checks.push(keychain_check(&state.secrets.lock().keychain_status()));
+    let (playwright, npm) = tokio::task::spawn_blocking(|| {
+        (
+            crate::browser::is_playwright_installed(),
+            crate::tools::which("npm").is_some(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to check playwright: {}", e))?;
+    checks.push(playwright_check(playwright, npm));
+
+    Ok(SelfTestReport {
+        status: checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass),
+        checks,
+        ran_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn daemon_check(running: bool, installed: bool) -> SelfTestCheck {
+    let check =
+        |status, detail: &str| SelfTestCheck::new("daemon", "Daemon", status, detail.into());
+    match (running, installed) {
+        (true, true) => check(CheckStatus::Pass, "Running"),
+        (true, false) => check(
+            CheckStatus::Warn,
+            "Running, but not installed as a login service",
+        )
+        .hint("Enable it in Settings > Daemon so jobs run after a restart"),
+        (false, true) => check(CheckStatus::Fail, "Installed but not running")
+            .hint("Restart it in Settings > Daemon and check its logs"),
+        (false, false) => check(CheckStatus::Fail, "Not installed")
+            .hint("Enable it in Settings > Daemon; scheduled jobs need it"),
+    }
+}
+
+/// `missing` is the status when the tool isn't found.
+fn tool_check(
+    tools: &[ToolInfo],
+    name: &'static str,
+    label: &'static str,
+    missing: CheckStatus,
+) -> SelfTestCheck {
+    match tools.iter().find(|t| t.name == name && t.available) {
+        Some(tool) => SelfTestCheck::new(
+            name,
+            label,
+            CheckStatus::Pass,
+            match (&tool.version, &tool.path) {
+                (Some(v), Some(p)) => format!("{} at {}", v, p),
+                (Some(v), None) => v.clone(),
+                (None, Some(p)) => p.clone(),
+                (None, None) => "Found".to_string(),
+            },
+        ),
+        None => {
+            let brew = tools
+                .iter()
+                .find(|t| t.name == name)
+                .and_then(|t| t.brew_formula.clone());
+            let hint = match brew {
+                Some(formula) => format!(
+                    "Install it (brew install {}) or set its path in Settings > Tools",
+                    formula
+                ),
+                None => "Install it or set its path in Settings > Tools".to_string(),
+            };
+            SelfTestCheck::new(name, label, missing, "Not found".to_string()).hint(hint)
+        }
+    }
+}
+
+async fn telegram_check(state: &AppState) -> SelfTestCheck {
+    let check = |status, detail: String| SelfTestCheck::new("telegram", "Telegram", status, detail);
+    let config = state.settings.lock().telegram.clone();
+    let Some(config) = config else {
+        return check(CheckStatus::Warn, "Not set up".to_string())
+            .hint("Set up a bot in Settings > Telegram to get job notifications");
+    };
+    if config.bot_token.is_empty() {
+        return check(CheckStatus::Warn, "No bot token".to_string())
+            .hint("Add the bot token in Settings > Telegram");
+    }
+    match super::telegram::validate_bot_token(config.bot_token).await {
+        Ok(_) if config.chat_ids.is_empty() => check(
+            CheckStatus::Warn,
+            "Bot token works, but no chat is linked".to_string(),
+        )
+        .hint("Send the bot a message during setup in Settings > Telegram"),
+        Ok(bot) => check(CheckStatus::Pass, format!("Bot @{}", bot.username)),
+        Err(e) => check(CheckStatus::Fail, e)
+            .hint("Check the bot token with @BotFather and your network connection"),
+    }
+}
+
+fn relay_check(status: Result<RelayStatus, String>) -> SelfTestCheck {
+    let check = |status, detail: String| SelfTestCheck::new("relay", "Relay", status, detail);
+    let s = match status {
+        Ok(s) => s,
+        Err(e) => {
+            return check(CheckStatus::Fail, e)
+                .hint("Start the daemon; it holds the relay connection")
+        }
+    };
+    if !s.enabled || !s.configured {
+        return check(CheckStatus::Warn, "Remote access is off".to_string())
+            .hint("Connect a relay in Settings > Remote to use the phone app");
+    }
+    if s.auth_expired {
+        return check(CheckStatus::Fail, "Sign-in expired".to_string())
+            .hint("Sign in again in Settings > Remote");
+    }
+    if s.subscription_required {
+        return check(CheckStatus::Fail, "Subscription required".to_string())
+            .hint("Renew the subscription, or use a self-hosted relay");
+    }
+    if s.update_required {
+        return check(CheckStatus::Fail, "The relay needs a newer app".to_string())
+            .hint("Update ClawTab");
+    }
+    if !s.connected {
+        return check(
+            CheckStatus::Fail,
+            format!("Not connected to {}", s.server_url),
+        )
+        .hint("Check the server URL in Settings > Remote and that the relay is reachable");
+    }
+    check(CheckStatus::Pass, format!("Connected to {}", s.server_url))
+}
+
+fn keychain_check(status: &KeychainStatus) -> SelfTestCheck {
+    let check = |status, detail: String| SelfTestCheck::new("keychain", "Keychain", status, detail);
+    if let Some(ref error) = status.error {
+        return check(CheckStatus::Fail, error.clone())
+            .hint("Allow ClawTab to access the keychain when asked, then reload secrets");
+    }
+    if !status.denied_keys.is_empty() {
+        return check(
+            CheckStatus::Warn,
+            format!("Access denied to {}", status.denied_keys.join(", ")),
+        )
+        .hint("Grant access to these items in Keychain Access");
+    }
+    check(CheckStatus::Pass, "Accessible".to_string())
+}
+
+fn playwright_check(installed: bool, npm_available: bool) -> SelfTestCheck {
+    let check = |status, detail: &str| {
+        SelfTestCheck::new("playwright", "Playwright", status, detail.into())
+    };
+    match (installed, npm_available) {
+        (true, _) => check(CheckStatus::Pass, "Installed"),
+        (false, true) => check(CheckStatus::Warn, "Not installed yet")
+            .hint("It is installed on the first browser login"),
+        (false, false) => check(CheckStatus::Warn, "Not installed, and npm is missing")
+            .hint("Install Node.js to use browser logins"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(connected: bool) -> RelayStatus {
+        RelayStatus {
+            enabled: true,
+            connected,
+            subscription_required: false,
+            auth_expired: false,
+            configured: true,
+            server_url: "https://relay.example.com".to_string(),
+            device_name: "mac".to_string(),
+            update_required: false,
+        }
+    }
+
+    #[test]
+    fn relay_states_map_to_pass_warn_and_fail() {
+        assert_eq!(relay_check(Ok(relay(true))).status, CheckStatus::Pass);
+        let down = relay_check(Ok(relay(false)));
+        assert_eq!(down.status, CheckStatus::Fail);
+        assert!(down.hint.is_some());
+        let off = RelayStatus {
+            enabled: false,
+            ..relay(false)
+        };
+        assert_eq!(relay_check(Ok(off)).status, CheckStatus::Warn);
+        assert_eq!(
+            relay_check(Err("Daemon unavailable".to_string())).status,
+            CheckStatus::Fail
+        );
+    }
+
+    #[test]
+    fn denied_keychain_items_warn_and_a_keychain_error_fails() {
+        let denied = KeychainStatus {
+            accessible: false,
+            error: None,
+            denied_keys: vec!["GITHUB_TOKEN".to_string()],
+        };
+        let check = keychain_check(&denied);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(check.detail, "Access denied to GITHUB_TOKEN");
+        let broken = KeychainStatus {
+            error: Some("Keychain locked".to_string()),
+            ..denied
+        };
+        assert_eq!(keychain_check(&broken).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn missing_required_tool_fails_with_install_hint() {
+        let tools = vec![ToolInfo {
+            name: "tmux".to_string(),
+            available: false,
+            version: None,
+            path: None,
+            category: "Required".to_string(),
+            required: true,
+            group: None,
+            brew_formula: Some("tmux".to_string()),
+        }];
+        let check = tool_check(&tools, "tmux", "tmux", CheckStatus::Fail);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.hint.unwrap().contains("brew install tmux"));
+        assert_eq!(
+            tool_check(&tools, "codex", "Codex", CheckStatus::Warn).status,
+            CheckStatus::Warn
+        );
+    }
+}
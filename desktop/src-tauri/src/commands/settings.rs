@@ -20,6 +20,12 @@ pub fn set_settings(
     state: State<AppState>,
     new_settings: AppSettings,
 ) -> Result<(), String> {
+    crate::scheduler::validate_timezone(new_settings.default_timezone.as_deref())?;
+    state
+        .history
+        .lock()
+        .configure_encryption(new_settings.encrypt_history, &mut state.secrets.lock())
+        .map_err(|e| format!("Failed to set up history encryption: {}", e))?;
     let mut settings = state.settings.lock();
     // Re-read from disk so fields the frontend doesn't manage (telegram, relay)
     // aren't clobbered when they were written by another process (CLI, daemon,
@@ -83,10 +89,7 @@ pub fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub fn get_hostname() -> String {
-    gethostname::gethostname()
-        .to_string_lossy()
-        .trim_end_matches(".local")
-        .to_string()
+    crate::config::settings::current_hostname()
 }
 
 #[tauri::command]
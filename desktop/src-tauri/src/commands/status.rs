@@ -1,10 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use serde::Serialize;
 use tauri::State;
 
-use crate::config::jobs::JobStatus;
+use crate::config::jobs::{job_group, Job, JobStatus};
 use crate::AppState;
 
+/// Rollup of the statuses of one group's jobs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GroupStatus {
+    pub group: String,
+    pub running: u32,
+    pub queued: u32,
+    pub idle: u32,
+    pub success: u32,
+    pub failed: u32,
+    pub paused: u32,
+    /// `started_at` of the most recent run of any job in the group.
+    pub last_run: Option<String>,
+}
+
 #[tauri::command]
 pub async fn get_job_statuses(
     _state: State<'_, AppState>,
@@ -16,6 +31,74 @@ pub async fn get_job_statuses(
     }
 }
 
+/// Per-group status counts, sorted by group name. Jobs without a status
+/// count as idle.
+#[tauri::command]
+pub async fn get_group_statuses(state: State<'_, AppState>) -> Result<Vec<GroupStatus>, String> {
+    let statuses = match crate::ipc::send_command(crate::ipc::IpcCommand::GetStatus).await {
+        Ok(crate::ipc::IpcResponse::Status(s)) => s,
+        Ok(resp) => return Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => return Err(format!("Daemon unavailable: {}", e)),
+    };
+    let last_runs = state.history.lock().last_started_runs()?;
+    let jobs = state.jobs_config.lock().jobs.clone();
+    Ok(group_statuses(&jobs, &statuses, &last_runs))
+}
+
+fn group_statuses(
+    jobs: &[Job],
+    statuses: &HashMap<String, JobStatus>,
+    last_runs: &HashMap<String, String>,
+) -> Vec<GroupStatus> {
+    let mut groups: BTreeMap<&str, GroupStatus> = BTreeMap::new();
+    for job in jobs {
+        let group = job_group(job);
+        let entry = groups.entry(group).or_insert_with(|| GroupStatus {
+            group: group.to_string(),
+            ..Default::default()
+        });
+        let count = match statuses.get(&job.slug) {
+            Some(JobStatus::Running { .. }) => &mut entry.running,
+            Some(JobStatus::Queued { .. }) => &mut entry.queued,
+            Some(JobStatus::Success { .. }) => &mut entry.success,
+            Some(JobStatus::Failed { .. }) => &mut entry.failed,
+            Some(JobStatus::Paused) => &mut entry.paused,
+            Some(JobStatus::Idle) | None => &mut entry.idle,
+        };
+        *count += 1;
+        if let Some(started) = last_runs.get(&job.slug) {
+            if entry.last_run.as_ref().is_none_or(|last| started > last) {
+                entry.last_run = Some(started.clone());
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// Snapshot the daemon's job status map as JSON, for debugging reattach and
+/// the pane monitor.
+#[tauri::command]
+pub async fn export_job_status() -> Result<String, String> {
+    match crate::ipc::send_command(crate::ipc::IpcCommand::GetStatus).await {
+        Ok(crate::ipc::IpcResponse::Status(s)) => crate::config::job_status_store::to_json(&s),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    }
+}
+
+/// Replace the daemon's job status map. Only honoured by debug builds or a
+/// daemon started with `CLAWTAB_DEV=1`.
+#[tauri::command]
+pub async fn import_job_status(map: HashMap<String, JobStatus>) -> Result<(), String> {
+    match crate::ipc::send_command(crate::ipc::IpcCommand::ImportJobStatus { statuses: map }).await
+    {
+        Ok(crate::ipc::IpcResponse::Ok) => Ok(()),
+        Ok(crate::ipc::IpcResponse::Error(e)) => Err(e),
+        Ok(resp) => Err(format!("Unexpected IPC response: {:?}", resp)),
+        Err(e) => Err(format!("Daemon unavailable: {}", e)),
+    }
+}
+
 async fn get_status_via_ipc(name: &str) -> Result<JobStatus, String> {
     match crate::ipc::send_command(crate::ipc::IpcCommand::GetStatus).await {
         Ok(crate::ipc::IpcResponse::Status(mut s)) => {
@@ -84,3 +167,64 @@ pub async fn send_job_input(
         _ => Err("Job is not running".to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(group: &str, slug: &str) -> Job {
+        let yaml = "name: t\njob_type: binary\nenabled: true\npath: /bin/true\ncron: ''\n";
+        let mut job: Job = serde_yml::from_str(yaml).unwrap();
+        job.group = group.to_string();
+        job.slug = slug.to_string();
+        job
+    }
+
+    #[test]
+    fn counts_statuses_and_latest_run_per_group() {
+        let jobs = vec![
+            job("deploy", "deploy/a"),
+            job("deploy", "deploy/b"),
+            job("deploy", "deploy/c"),
+            job("ops", "ops/a"),
+        ];
+        let statuses = HashMap::from([
+            (
+                "deploy/a".to_string(),
+                JobStatus::Running {
+                    run_id: "r".to_string(),
+                    started_at: "2026-01-02T00:00:00Z".to_string(),
+                    pane_id: None,
+                    tmux_session: None,
+                },
+            ),
+            (
+                "deploy/b".to_string(),
+                JobStatus::Failed {
+                    last_run: "2026-01-01T00:00:00Z".to_string(),
+                    exit_code: 1,
+                    consecutive_failures: 1,
+                },
+            ),
+        ]);
+        let last_runs = HashMap::from([
+            ("deploy/a".to_string(), "2026-01-02T00:00:00Z".to_string()),
+            ("deploy/b".to_string(), "2026-01-01T00:00:00Z".to_string()),
+        ]);
+        let groups = group_statuses(&jobs, &statuses, &last_runs);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[0],
+            GroupStatus {
+                group: "deploy".to_string(),
+                running: 1,
+                failed: 1,
+                idle: 1,
+                last_run: Some("2026-01-02T00:00:00Z".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!((groups[1].group.as_str(), groups[1].idle), ("ops", 1));
+        assert_eq!(groups[1].last_run, None);
+    }
+}
@@ -4,13 +4,18 @@ use crate::agent_session::ProcessProvider;
 use crate::tools;
 use crate::AppState;
 
+/// Detected tools, cached between calls. `force_refresh` skips the cache.
 #[tauri::command]
-pub async fn detect_tools(state: State<'_, AppState>) -> Result<Vec<tools::ToolInfo>, String> {
+pub async fn detect_tools(
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<Vec<tools::ToolInfo>, String> {
     let custom_paths = {
         let s = state.settings.lock();
         s.tool_paths.clone()
     };
-    tokio::task::spawn_blocking(move || tools::detect_tools(&custom_paths))
+    let force_refresh = force_refresh.unwrap_or(false);
+    tokio::task::spawn_blocking(move || tools::detect_tools_cached(&custom_paths, force_refresh))
         .await
         .map_err(|e| format!("Detection failed: {}", e))
 }
@@ -211,6 +216,7 @@ pub async fn set_tool_path(
     } else {
         s.tool_paths.insert(tool_name, path);
     }
+    tools::invalidate_detect_cache();
     s.save()
 }
 
@@ -225,6 +231,7 @@ pub async fn install_tool(formula: String) -> Result<String, String> {
         .output()
         .await
         .map_err(|e| format!("Failed to run brew: {}", e))?;
+    tools::invalidate_detect_cache();
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use super::jobs::{Job, JobStatus};
+
+fn file_path() -> Option<PathBuf> {
+    super::config_dir().map(|p| p.join("job_status.json"))
+}
+
+/// Serialize a status map with stable key order, so unchanged maps produce
+/// identical output and can be compared cheaply.
+pub fn to_json(statuses: &HashMap<String, JobStatus>) -> Result<String, String> {
+    let sorted: BTreeMap<&String, &JobStatus> = statuses.iter().collect();
+    serde_json::to_string(&sorted).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+pub fn load() -> HashMap<String, JobStatus> {
+    let Some(path) = file_path() else {
+        return HashMap::new();
+    };
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(e) => {
+            log::debug!(
+                "job_status_store::load: read {} failed: {}",
+                path.display(),
+                e
+            );
+            return HashMap::new();
+        }
+    };
+    match serde_json::from_str(&contents) {
+        Ok(map) => map,
+        Err(e) => {
+            log::warn!(
+                "job_status_store::load: parse {} failed: {}",
+                path.display(),
+                e
+            );
+            HashMap::new()
+        }
+    }
+}
+
+/// Load the persisted map, dropping entries that can no longer be true:
+/// `Running` tmux runs whose pane is gone, binary runs, which are restored
+/// separately from their runtime state files, and `Queued` runs, whose
+/// waiting task died with the previous process.
+pub fn load_reconciled() -> HashMap<String, JobStatus> {
+    let mut statuses = load();
+    statuses.retain(|slug, status| match status {
+        JobStatus::Running {
+            pane_id: Some(pane_id),
+            ..
+        } => {
+            let alive = crate::tmux::pane_exists(pane_id);
+            if !alive {
+                log::info!(
+                    "Dropping stale running status for {} (pane {} gone)",
+                    slug,
+                    pane_id
+                );
+            }
+            alive
+        }
+        JobStatus::Running { pane_id: None, .. } | JobStatus::Queued { .. } => false,
+        _ => true,
+    });
+    statuses
+}
+
+/// Give jobs with no persisted status the outcome of their last finished run,
+/// so a lost or reset status file does not show every job as never run.
+pub fn seed_from_history(
+    statuses: &mut HashMap<String, JobStatus>,
+    history: &crate::history::HistoryStore,
+    jobs: &[Job],
+) {
+    let last_runs = match history.last_finished_runs() {
+        Ok(runs) => runs,
+        Err(e) => {
+            log::warn!("job_status_store: failed to read last runs: {}", e);
+            return;
+        }
+    };
+    for job in jobs {
+        if statuses.contains_key(&job.slug) {
+            continue;
+        }
+        // A run finalized without an exit code, e.g. an agent pane that was
+        // gone when the app restarted, says nothing about success; leave the
+        // job idle rather than report a made-up failure.
+        let Some((finished_at, Some(exit_code))) = last_runs.get(&job.slug) else {
+            continue;
+        };
+        let status = match *exit_code {
+            0 => JobStatus::Success {
+                last_run: finished_at.clone(),
+            },
+            code => JobStatus::Failed {
+                last_run: finished_at.clone(),
+                exit_code: code,
+                consecutive_failures: history
+                    .failure_streak(&job.slug, Some(code))
+                    .unwrap_or(1)
+                    .max(1),
+            },
+        };
+        statuses.insert(job.slug.clone(), status);
+    }
+}
+
+pub fn save_json(contents: &str) -> Result<(), String> {
+    let path = file_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents)
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        format!(
+            "Failed to rename {} -> {}: {}",
+            tmp.display(),
+            path.display(),
+            e
+        )
+    })
+}
@@ -8,11 +8,21 @@ use std::path::PathBuf;
 #[serde(rename_all = "snake_case")]
 pub enum JobType {
     Binary,
+    /// Inline script in `path`, run with `sh -c`.
+    Shell,
     Claude,
     #[serde(alias = "folder")]
     Job,
 }
 
+impl JobType {
+    /// Binary and shell jobs run as a child process of the app rather than in
+    /// a tmux pane.
+    pub fn is_process(&self) -> bool {
+        matches!(self, JobType::Binary | JobType::Shell)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum TelegramLogMode {
@@ -69,6 +79,22 @@ impl Default for TelegramNotify {
     }
 }
 
+/// Answers a recurring agent prompt without asking anyone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoAnswerRule {
+    /// Case-insensitive substring of the prompt text.
+    pub pattern: String,
+    /// Option to send, e.g. "1", or "y" for a yes/no prompt.
+    pub answer: String,
+}
+
+impl AutoAnswerRule {
+    pub fn matches(&self, prompt: &str) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        !pattern.is_empty() && prompt.to_lowercase().contains(&pattern)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "state", rename_all = "snake_case")]
 pub enum JobStatus {
@@ -81,16 +107,56 @@ pub enum JobStatus {
         #[serde(skip_serializing_if = "Option::is_none")]
         tmux_session: Option<String>,
     },
+    /// Triggered but waiting for a `max_concurrent` slot.
+    Queued {
+        queued_at: String,
+    },
     Success {
         last_run: String,
     },
     Failed {
         last_run: String,
         exit_code: i32,
+        /// Failures in a row ending with this one, all with `exit_code`.
+        /// 0 for statuses saved before this was tracked.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        consecutive_failures: u32,
     },
     Paused,
 }
 
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl JobStatus {
+    /// Whether the last completed run succeeded. `None` for states that carry
+    /// no finished outcome (never run, running, queued, paused).
+    pub fn last_outcome(&self) -> Option<bool> {
+        match self {
+            JobStatus::Success { .. } => Some(true),
+            JobStatus::Failed { .. } => Some(false),
+            JobStatus::Idle
+            | JobStatus::Running { .. }
+            | JobStatus::Queued { .. }
+            | JobStatus::Paused => None,
+        }
+    }
+
+    /// Exit code and length of the failure streak this status ends, if it is
+    /// a failure.
+    pub fn failure_streak(&self) -> Option<(i32, u32)> {
+        match self {
+            JobStatus::Failed {
+                exit_code,
+                consecutive_failures,
+                ..
+            } => Some((*exit_code, (*consecutive_failures).max(1))),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Job {
     pub name: String,
@@ -137,6 +203,133 @@ pub struct Job {
     pub added_at: Option<String>,
     #[serde(default = "default_max_history")]
     pub max_history: u32,
+    /// Start a run (trigger "enable") when the job is toggled on, so a new
+    /// schedule can be verified without waiting for the next cron tick.
+    #[serde(default)]
+    pub run_on_enable: bool,
+    /// Days to keep this job's run history and logs. Overrides the global
+    /// retention window when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_retention_days: Option<u32>,
+    /// Record the work dir's git branch and short commit on each run and
+    /// append them to the completion notification.
+    #[serde(default)]
+    pub include_git_info: bool,
+    /// Only notify when a run's outcome differs from the previous run's
+    /// (success to failure or back). The first known outcome always notifies.
+    #[serde(default)]
+    pub notify_on_change_only: bool,
+    /// Directories besides the job's own work dir that the job may read and
+    /// write. Claude Code file permissions are scoped to these plus the work
+    /// dir, and auto-yes declines file prompts for paths outside them.
+    #[serde(default)]
+    pub allowed_dirs: Vec<String>,
+    /// Don't write `.claude/settings.local.json` into this job's directory;
+    /// the user manages Claude Code permissions there themselves. Only the
+    /// settings file: a folder job's context.md is still generated. See
+    /// `manage_project_files` to opt out of both.
+    #[serde(default)]
+    pub skip_claude_settings: bool,
+    /// IANA timezone (e.g. "America/New_York") the cron schedule is evaluated
+    /// in. Falls back to the settings default, then local time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Run once on startup if a scheduled time passed while the app was
+    /// closed or the machine asleep.
+    #[serde(default)]
+    pub catch_up: bool,
+    /// Run once this many seconds after the app starts, e.g. to warm a cache.
+    /// Independent of `cron`; a config reload does not start it again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_boot_secs: Option<u64>,
+    /// When false, ClawTab writes no generated files for this job: neither
+    /// its context.md nor the project's `.claude/settings.local.json`. Implies
+    /// `skip_claude_settings`, which stops only the settings file. Either
+    /// flag on one job skips the settings file for every job sharing its
+    /// directory.
+    #[serde(default = "default_true")]
+    pub manage_project_files: bool,
+    /// Binary jobs: extra attempts after a non-zero exit, each recorded as
+    /// its own run. Failure is only notified after the last one.
+    #[serde(default)]
+    pub retries: u32,
+    /// Binary jobs: seconds to wait before each retry.
+    #[serde(default)]
+    pub retry_delay_secs: u64,
+    /// Minimum seconds between run starts, whatever triggers them. A run
+    /// requested sooner is skipped. 0 disables the cooldown.
+    #[serde(default)]
+    pub min_interval_secs: u64,
+    /// Binary jobs: bytes of stdout and of stderr kept in run history. Past
+    /// this, the middle of the output is dropped (the log file still gets it
+    /// all). None uses 8 MiB; 0 keeps everything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<u64>,
+    /// Binary jobs: run the process on a pseudo-terminal instead of pipes,
+    /// for programs that only behave interactively on a tty. stdout and
+    /// stderr arrive merged. Batch runs (`items`) always use pipes.
+    #[serde(default)]
+    pub pty: bool,
+    /// Shell command run (`sh -c`) in the work dir before the job. A non-zero
+    /// exit skips the job and records the run as failed with the precheck's
+    /// output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precheck_command: Option<String>,
+    /// Shell command run before each launch, like `precheck_command`, but a
+    /// non-zero exit records the run as skipped rather than failed: no
+    /// failure status, no notification, no hooks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_if: Option<String>,
+    /// Claude and folder jobs: days a saved browser login stays good. An
+    /// older one gets a Telegram prompt to log in again before the run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_max_age_days: Option<u32>,
+    /// Claude and folder jobs: URL fetched with the saved browser login's
+    /// cookies before the run. A redirect, 401 or 403 means the login expired.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_check_url: Option<String>,
+    /// Skip the run, rather than only prompting, when the browser login looks
+    /// stale.
+    #[serde(default)]
+    pub skip_on_stale_session: bool,
+    /// Claude and folder jobs: shell command typed into the job's pane once
+    /// the agent exits, before the pane is killed. Its output is part of the
+    /// run's output; the agent's exit code still decides the outcome.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_command: Option<String>,
+    /// Jobs sharing a mutex group never run at the same time; a run
+    /// triggered while another member is running queues until it ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutex_group: Option<String>,
+    /// Prompts answered automatically while this job runs. The first rule
+    /// matching a prompt whose options include its answer wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_answer: Vec<AutoAnswerRule>,
+    /// Binary jobs: inputs for a batch run. The command runs once per item,
+    /// with the item in `CLAWTAB_ITEM`, and the run fails if any item does.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<String>,
+    /// Binary jobs: file with more batch items, one per line. Relative paths
+    /// resolve against the work dir; blank lines are skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items_file: Option<String>,
+    /// Binary jobs: how many batch items run at once. None runs them one at
+    /// a time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<u32>,
+    /// Run after a successful run: a shell command (`sh -c` in the work dir,
+    /// with `CWT_EXIT_CODE`, `CWT_RUN_ID` and `CWT_JOB_NAME` set), or
+    /// `run_job("name")` to start another job. Its output is appended to the
+    /// run's stderr.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_success: Option<String>,
+    /// Like `on_success`, after a failed run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<String>,
+    /// Jobs (by name or `group/name`) started when a run succeeds. A job
+    /// already earlier in the chain is not started again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -162,6 +355,22 @@ pub fn job_group(job: &Job) -> &str {
     }
 }
 
+/// Directories a job may access files in: its folder and work dir, or
+/// `default_work_dir` when it has neither, plus its `allowed_dirs`.
+pub fn file_access_dirs(job: &Job, default_work_dir: &str) -> Vec<String> {
+    let mut dirs: Vec<String> = [&job.folder_path, &job.work_dir]
+        .into_iter()
+        .flatten()
+        .filter(|d| !d.is_empty())
+        .cloned()
+        .collect();
+    if dirs.is_empty() {
+        dirs.push(default_work_dir.to_string());
+    }
+    dirs.extend(job.allowed_dirs.iter().cloned());
+    dirs
+}
+
 /// Resolve a job reference.
 ///
 /// A reference may be a stable slug, an explicit `group/name` pair, or a
@@ -230,6 +439,12 @@ impl JobsConfig {
         Self::jobs_dir()
     }
 
+    /// File holding the user-defined job order as a list of slugs. Job
+    /// definitions live in one directory each, so the order is kept separately.
+    fn order_file_path() -> Option<PathBuf> {
+        Self::jobs_dir().map(|p| p.join("order.yaml"))
+    }
+
     fn legacy_file_path() -> Option<PathBuf> {
         super::config_dir().map(|p| p.join("jobs.yaml"))
     }
@@ -308,9 +523,31 @@ impl JobsConfig {
         }
 
         jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        apply_job_order(&mut jobs, &Self::load_order());
         Self { jobs }
     }
 
+    fn load_order() -> Vec<String> {
+        Self::order_file_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|contents| serde_yml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Reorder the jobs to match `ordered` and persist the new order.
+    /// `ordered` must reference every job exactly once (by slug, group/name,
+    /// or unique name).
+    pub fn reorder(&mut self, ordered: &[String]) -> Result<(), String> {
+        let slugs = ordered_slugs(&self.jobs, ordered)?;
+        apply_job_order(&mut self.jobs, &slugs);
+        self.save_order()
+    }
+
+    pub fn save_order(&self) -> Result<(), String> {
+        let slugs: Vec<&str> = self.jobs.iter().map(|j| j.slug.as_str()).collect();
+        write_order(&slugs)
+    }
+
     fn load_job_yaml(path: &std::path::Path, slug: &str) -> Option<Job> {
         match std::fs::read_to_string(path) {
             Ok(contents) => match serde_yml::from_str::<Job>(&contents) {
@@ -375,19 +612,72 @@ impl JobsConfig {
             std::fs::remove_dir_all(&job_dir)
                 .map_err(|e| format!("Failed to remove job directory: {}", e))?;
         }
-        // Clean up empty parent (project) directory if it's now empty
-        if let Some(parent) = job_dir.parent() {
-            if parent != jobs_dir && parent.is_dir() {
-                let is_empty = parent
-                    .read_dir()
-                    .map(|mut d| d.next().is_none())
-                    .unwrap_or(false);
-                if is_empty {
-                    let _ = std::fs::remove_dir(parent);
+        remove_empty_project_dir(&jobs_dir, &job_dir);
+        Ok(())
+    }
+
+    /// Move a job's directory, including its logs, from `old_slug` to
+    /// `new_slug`. Fails if something already lives at the new slug.
+    pub fn move_job_dir(&self, old_slug: &str, new_slug: &str) -> Result<(), String> {
+        let jobs_dir = Self::jobs_dir().ok_or("Could not determine config directory")?;
+        let old_dir = jobs_dir.join(old_slug);
+        let new_dir = jobs_dir.join(new_slug);
+        if !old_dir.is_dir() {
+            return Ok(());
+        }
+        if new_dir.exists() {
+            return Err(format!("Job directory already exists: {}", new_slug));
+        }
+        if let Some(parent) = new_dir.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create job directory: {}", e))?;
+        }
+        std::fs::rename(&old_dir, &new_dir)
+            .map_err(|e| format!("Failed to move job directory: {}", e))?;
+        remove_empty_project_dir(&jobs_dir, &old_dir);
+
+        // Keep the job's place in a custom order.
+        let mut order = Self::load_order();
+        match order.iter_mut().find(|s| *s == old_slug) {
+            Some(entry) => *entry = new_slug.to_string(),
+            None => return Ok(()),
+        }
+        write_order(&order)
+    }
+
+    /// Give every job with a malformed or duplicate slug a fresh one from
+    /// `derive_slug`, move its directory (and logs) there and reload. A
+    /// duplicate shares its directory with the job that keeps the slug, so
+    /// only its job.yaml is written to the new place. Jobs that fail to move
+    /// are logged and left out of the report.
+    pub fn normalize_slugs(&mut self) -> Result<Vec<SlugChange>, String> {
+        let plan = plan_slug_changes(&self.jobs);
+        let mut changes = Vec::new();
+        for (idx, change) in plan {
+            let mut job = self.jobs[idx].clone();
+            if change.reason == SlugIssue::Malformed {
+                if let Err(e) = self.move_job_dir(&change.old_slug, &change.new_slug) {
+                    log::warn!("Failed to normalize slug '{}': {}", change.old_slug, e);
+                    continue;
                 }
             }
+            job.slug = change.new_slug.clone();
+            if let Err(e) = self.save_job(&job) {
+                log::warn!("Failed to normalize slug '{}': {}", change.old_slug, e);
+                continue;
+            }
+            log::info!(
+                "Normalized {} slug '{}' to '{}'",
+                change.reason.as_str(),
+                change.old_slug,
+                change.new_slug
+            );
+            changes.push(change);
         }
-        Ok(())
+        if !changes.is_empty() {
+            *self = Self::load();
+        }
+        Ok(changes)
     }
 
     fn migrate_legacy() {
@@ -492,6 +782,43 @@ impl JobsConfig {
     }
 }
 
+/// Resolve each reference in `ordered` to a slug, requiring the list to be a
+/// permutation of `jobs`.
+fn ordered_slugs(jobs: &[Job], ordered: &[String]) -> Result<Vec<String>, String> {
+    if ordered.len() != jobs.len() {
+        return Err(format!(
+            "Job order must list all {} jobs, got {}",
+            jobs.len(),
+            ordered.len()
+        ));
+    }
+    let mut seen = std::collections::HashSet::new();
+    let mut slugs = Vec::with_capacity(ordered.len());
+    for reference in ordered {
+        let slug = find_job(jobs, reference)?.slug.clone();
+        if !seen.insert(slug.clone()) {
+            return Err(format!("Job listed more than once: {}", reference));
+        }
+        slugs.push(slug);
+    }
+    Ok(slugs)
+}
+
+/// Stable-sort `jobs` so slugs listed in `order` come first, in that order.
+/// Jobs missing from `order` (added since the last reorder) keep their
+/// relative position after the ordered ones.
+fn apply_job_order(jobs: &mut [Job], order: &[String]) {
+    if order.is_empty() {
+        return;
+    }
+    let rank: HashMap<&str, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, slug)| (slug.as_str(), i))
+        .collect();
+    jobs.sort_by_key(|j| rank.get(j.slug.as_str()).copied().unwrap_or(usize::MAX));
+}
+
 /// Migrate a single flat-slug directory. No-op when the dir is not a flat slug
 /// or migration is unnecessary.
 fn migrate_one_flat_slug_dir(jobs_dir: &std::path::Path, path: &std::path::Path) {
@@ -596,6 +923,32 @@ fn write_migrated_job(
     }
 }
 
+fn write_order<S: serde::Serialize>(slugs: &[S]) -> Result<(), String> {
+    let path = JobsConfig::order_file_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create jobs directory: {}", e))?;
+    }
+    let contents =
+        serde_yml::to_string(slugs).map_err(|e| format!("Failed to serialize job order: {}", e))?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write order.yaml: {}", e))
+}
+
+/// Clean up the parent (project) directory of `job_dir` if it's now empty.
+fn remove_empty_project_dir(jobs_dir: &std::path::Path, job_dir: &std::path::Path) {
+    if let Some(parent) = job_dir.parent() {
+        if parent != jobs_dir && parent.is_dir() {
+            let is_empty = parent
+                .read_dir()
+                .map(|mut d| d.next().is_none())
+                .unwrap_or(false);
+            if is_empty {
+                let _ = std::fs::remove_dir(parent);
+            }
+        }
+    }
+}
+
 fn move_logs_dir(old_path: &std::path::Path, new_dir: &std::path::Path) {
     let old_logs = old_path.join("logs");
     if !old_logs.is_dir() {
@@ -815,6 +1168,104 @@ fn remove_cwt_dir(cwt_dir: &std::path::Path) {
     }
 }
 
+/// First line of every context file ClawTab generates.
+const GENERATED_CONTEXT_MARKER: &str = "<!-- Auto-generated by ClawTab";
+
+/// What `repair_cwt_layout` found in a project dir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutIssue {
+    /// A `CLAUDE.md` generated by an older version, which conflicts with
+    /// the central context. Removed.
+    GeneratedClaudeMd,
+    /// A `.cwt/` directory not yet moved to central config. Migrated, then
+    /// removed.
+    LegacyCwtDir,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutFix {
+    pub path: String,
+    pub issue: LayoutIssue,
+    /// False on a dry run, or when the fix failed.
+    pub applied: bool,
+}
+
+/// Find leftovers of the per-project `.cwt/` layout in folder jobs' project
+/// dirs, fixing them when `apply` is set. A `CLAUDE.md` only counts when it
+/// starts with ClawTab's generated-file marker, so user-written ones stay.
+pub fn repair_cwt_layout(jobs: &[Job], apply: bool) -> Result<Vec<LayoutFix>, String> {
+    let jobs_dir = JobsConfig::jobs_dir().ok_or("Could not determine config directory")?;
+    Ok(repair_layout(&jobs_dir, jobs, apply))
+}
+
+fn repair_layout(jobs_dir: &std::path::Path, jobs: &[Job], apply: bool) -> Vec<LayoutFix> {
+    let mut fixes = Vec::new();
+    let mut seen_projects = std::collections::HashSet::new();
+    let mut legacy_dirs = Vec::new();
+    for job in jobs.iter().filter(|j| j.job_type == JobType::Job) {
+        let Some(folder_path) = job.folder_path.as_ref() else {
+            continue;
+        };
+        let project_root = std::path::Path::new(folder_path);
+        let cwt_dir = project_root.join(".cwt");
+        if seen_projects.insert(folder_path.clone()) {
+            let mut candidates = vec![project_root.join("CLAUDE.md"), cwt_dir.join("CLAUDE.md")];
+            if let Ok(entries) = std::fs::read_dir(&cwt_dir) {
+                candidates.extend(entries.flatten().map(|e| e.path().join("CLAUDE.md")));
+            }
+            for path in candidates.into_iter().filter(|p| is_generated_context(p)) {
+                let applied = apply && remove_stale_file(&path);
+                fixes.push(LayoutFix {
+                    path: path.display().to_string(),
+                    issue: LayoutIssue::GeneratedClaudeMd,
+                    applied,
+                });
+            }
+            if cwt_dir.is_dir() {
+                let project_slug = job.slug.split('/').next().unwrap_or(&job.slug);
+                legacy_dirs.push((cwt_dir.clone(), jobs_dir.join(project_slug)));
+            }
+        }
+        if apply && cwt_dir.is_dir() {
+            let job_id = job.job_id.as_deref().unwrap_or("default");
+            migrate_per_job_files(&cwt_dir, job_id, &jobs_dir.join(&job.slug));
+        }
+    }
+    // Only once every job of a project has copied its own files.
+    for (cwt_dir, central_project_dir) in legacy_dirs {
+        if apply {
+            migrate_project_files(&cwt_dir, &central_project_dir);
+            remove_cwt_dir(&cwt_dir);
+        }
+        fixes.push(LayoutFix {
+            path: cwt_dir.display().to_string(),
+            issue: LayoutIssue::LegacyCwtDir,
+            applied: apply && !cwt_dir.exists(),
+        });
+    }
+    fixes
+}
+
+fn is_generated_context(path: &std::path::Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim_start().starts_with(GENERATED_CONTEXT_MARKER))
+        .unwrap_or(false)
+}
+
+fn remove_stale_file(path: &std::path::Path) -> bool {
+    match std::fs::remove_file(path) {
+        Ok(()) => {
+            log::info!("Removed stale {}", path.display());
+            true
+        }
+        Err(e) => {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
 /// Derive a slug from a folder path or name + optional job_id.
 /// Returns "project-slug/job-id" for multi-job, or "project-slug/default" when no job_id.
 /// Appends -2, -3, etc. if duplicate.
@@ -855,6 +1306,76 @@ pub fn derive_slug(input: &str, job_id: Option<&str>, existing_jobs: &[Job]) ->
     }
 }
 
+/// Why `normalize_slugs` replaced a slug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlugIssue {
+    /// Not `project/job` with both parts as `derive_slug` would write them.
+    Malformed,
+    /// Same slug as an earlier job.
+    Duplicate,
+}
+
+impl SlugIssue {
+    fn as_str(self) -> &'static str {
+        match self {
+            SlugIssue::Malformed => "malformed",
+            SlugIssue::Duplicate => "duplicate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlugChange {
+    pub old_slug: String,
+    pub new_slug: String,
+    pub reason: SlugIssue,
+}
+
+/// Job indices whose slug needs replacing, with the replacement. The first
+/// job holding a well-formed slug keeps it. New slugs are derived from the
+/// job's group, since loading takes the group from the slug's project part,
+/// and avoid every slug that stays or was already handed out.
+fn plan_slug_changes(jobs: &[Job]) -> Vec<(usize, SlugChange)> {
+    let mut placed: Vec<Job> = Vec::new();
+    let mut issues = Vec::new();
+    for (idx, job) in jobs.iter().enumerate() {
+        if !is_well_formed_slug(&job.slug) {
+            issues.push((idx, SlugIssue::Malformed));
+        } else if placed.iter().any(|j| j.slug == job.slug) {
+            issues.push((idx, SlugIssue::Duplicate));
+        } else {
+            placed.push(job.clone());
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (idx, reason) in issues {
+        let mut job = jobs[idx].clone();
+        let new_slug = derive_slug(job_group(&job), job.job_id.as_deref(), &placed);
+        let old_slug = std::mem::replace(&mut job.slug, new_slug.clone());
+        placed.push(job);
+        changes.push((
+            idx,
+            SlugChange {
+                old_slug,
+                new_slug,
+                reason,
+            },
+        ));
+    }
+    changes
+}
+
+/// `project/job`, each part already in `slugify` form.
+fn is_well_formed_slug(slug: &str) -> bool {
+    let parts: Vec<&str> = slug.split('/').collect();
+    parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && slugify(part, part.len()) == *part)
+}
+
 /// Slugify a string: lowercase, keep [a-z0-9-], collapse dashes, truncate.
 fn slugify(input: &str, max_len: usize) -> String {
     let mut slug: String = input
@@ -908,6 +1429,17 @@ mod tests {
         )
     }
 
+    #[test]
+    fn shell_jobs_keep_their_script_in_path() {
+        let job = parse_job(
+            "name: t\njob_type: shell\nenabled: true\npath: |\n  echo one\n  echo two\ncron: ''\n",
+        );
+        assert_eq!(job.job_type, JobType::Shell);
+        assert!(job.job_type.is_process());
+        assert_eq!(job.path, "echo one\necho two\n");
+        assert!(!JobType::Claude.is_process());
+    }
+
     #[test]
     fn params_accepts_empty() {
         let job = parse_job(&base_yaml("params: []"));
@@ -982,4 +1514,147 @@ mod tests {
             "hello-world/default"
         );
     }
+
+    #[test]
+    fn ordered_slugs_requires_permutation() {
+        let jobs = vec![
+            test_job("a", "g", "g/a"),
+            test_job("b", "g", "g/b"),
+            test_job("c", "g", "g/c"),
+        ];
+
+        assert_eq!(
+            ordered_slugs(&jobs, &["c".into(), "g/a".into(), "g/b".into()]).unwrap(),
+            vec!["g/c", "g/a", "g/b"]
+        );
+        assert!(ordered_slugs(&jobs, &["a".into(), "b".into()]).is_err());
+        assert!(ordered_slugs(&jobs, &["a".into(), "a".into(), "b".into()])
+            .unwrap_err()
+            .contains("more than once"));
+        assert!(ordered_slugs(&jobs, &["a".into(), "b".into(), "x".into()]).is_err());
+    }
+
+    #[test]
+    fn apply_job_order_keeps_unlisted_jobs_last() {
+        let mut jobs = vec![
+            test_job("a", "g", "g/a"),
+            test_job("b", "g", "g/b"),
+            test_job("c", "g", "g/c"),
+        ];
+
+        apply_job_order(&mut jobs, &["g/c".into(), "g/a".into()]);
+        let slugs: Vec<&str> = jobs.iter().map(|j| j.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["g/c", "g/a", "g/b"]);
+    }
+
+    #[test]
+    fn last_outcome_ignores_states_without_a_finished_run() {
+        let success = JobStatus::Success {
+            last_run: "t".into(),
+        };
+        let failed = JobStatus::Failed {
+            last_run: "t".into(),
+            exit_code: 1,
+            consecutive_failures: 0,
+        };
+        assert_eq!(success.last_outcome(), Some(true));
+        assert_eq!(failed.last_outcome(), Some(false));
+        assert_eq!(JobStatus::Idle.last_outcome(), None);
+        assert_eq!(JobStatus::Paused.last_outcome(), None);
+    }
+
+    #[test]
+    fn notify_on_change_only_defaults_off() {
+        let yaml = base_yaml("notify_on_change_only: true");
+        assert!(parse_job(&yaml).notify_on_change_only);
+        assert!(!parse_job(&base_yaml("")).notify_on_change_only);
+    }
+
+    #[test]
+    fn project_files_are_managed_unless_opted_out() {
+        assert!(parse_job(&base_yaml("")).manage_project_files);
+        assert!(!parse_job(&base_yaml("manage_project_files: false")).manage_project_files);
+    }
+
+    #[test]
+    fn plans_new_slugs_for_malformed_and_duplicate_ones() {
+        let mut jobs = vec![
+            test_job("Deploy", "web", "web/deploy"),
+            test_job("Deploy", "web", "web/deploy"),
+            test_job("Backup DB", "ops", "Ops/Backup_DB"),
+            test_job("flat", "flat", "flat"),
+            test_job("ok", "tools", "tools/lint-2"),
+        ];
+        for job in &mut jobs {
+            job.job_id = Some(job.name.clone());
+        }
+        let plan = plan_slug_changes(&jobs);
+        let summary: Vec<(usize, &str, SlugIssue)> = plan
+            .iter()
+            .map(|(i, c)| (*i, c.new_slug.as_str(), c.reason))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "web/deploy-2", SlugIssue::Duplicate),
+                (2, "ops/backup-db", SlugIssue::Malformed),
+                (3, "flat/flat", SlugIssue::Malformed),
+            ]
+        );
+    }
+
+    #[test]
+    fn new_slugs_never_collide_with_kept_or_planned_ones() {
+        let mut jobs = vec![
+            test_job("a", "a", "a/a"),
+            test_job("a", "A", "A/a"),
+            test_job("a", "a!", "a!/a"),
+        ];
+        for job in &mut jobs {
+            job.job_id = Some("a".to_string());
+        }
+        let new_slugs: Vec<String> = plan_slug_changes(&jobs)
+            .into_iter()
+            .map(|(_, c)| c.new_slug)
+            .collect();
+        assert_eq!(new_slugs, vec!["a/a-2", "a/a-3"]);
+    }
+
+    #[test]
+    fn repair_layout_removes_generated_claude_md_and_migrates_cwt() {
+        let project = tempfile::tempdir().unwrap();
+        let central = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(root.join("CLAUDE.md"), "# My notes\n").unwrap();
+        std::fs::create_dir_all(root.join(".cwt/t")).unwrap();
+        std::fs::write(
+            root.join(".cwt/t/CLAUDE.md"),
+            "<!-- Auto-generated by ClawTab. Regenerated on settings/jobs change. -->\n",
+        )
+        .unwrap();
+        std::fs::write(root.join(".cwt/t/run.sh"), "echo hi\n").unwrap();
+
+        let mut job = test_job("t", "proj", "proj/t");
+        job.folder_path = Some(root.display().to_string());
+        let jobs = vec![job];
+
+        let preview = repair_layout(central.path(), &jobs, false);
+        let issues: Vec<(LayoutIssue, bool)> =
+            preview.iter().map(|f| (f.issue, f.applied)).collect();
+        assert_eq!(
+            issues,
+            vec![
+                (LayoutIssue::GeneratedClaudeMd, false),
+                (LayoutIssue::LegacyCwtDir, false)
+            ]
+        );
+        assert!(root.join(".cwt").is_dir());
+
+        let applied = repair_layout(central.path(), &jobs, true);
+        assert!(applied.iter().all(|f| f.applied));
+        assert!(!root.join(".cwt").exists());
+        assert!(root.join("CLAUDE.md").is_file());
+        assert!(central.path().join("proj/t/run.sh").is_file());
+        assert!(!central.path().join("proj/t/CLAUDE.md").exists());
+    }
 }
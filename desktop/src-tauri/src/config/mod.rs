@@ -1,5 +1,7 @@
+pub mod job_status_store;
 pub mod jobs;
 pub mod protected_panes;
+pub mod scheduler_state;
 pub mod settings;
 
 use std::path::PathBuf;
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Scheduler bookkeeping that must survive restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SchedulerState {
+    /// End of the last cron window the scheduler evaluated.
+    #[serde(default)]
+    pub last_tick: Option<DateTime<Utc>>,
+}
+
+fn file_path() -> Option<PathBuf> {
+    super::config_dir().map(|p| p.join("scheduler_state.json"))
+}
+
+pub fn load_last_tick() -> Option<DateTime<Utc>> {
+    let path = file_path()?;
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(e) => {
+            log::debug!(
+                "scheduler_state::load_last_tick: read {} failed: {}",
+                path.display(),
+                e
+            );
+            return None;
+        }
+    };
+    match serde_json::from_str::<SchedulerState>(&contents) {
+        Ok(state) => state.last_tick,
+        Err(e) => {
+            log::warn!(
+                "scheduler_state::load_last_tick: parse {} failed: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+pub fn save_last_tick(tick: DateTime<Utc>) -> Result<(), String> {
+    let path = file_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let payload = SchedulerState {
+        last_tick: Some(tick),
+    };
+    let json =
+        serde_json::to_string(&payload).map_err(|e| format!("Failed to serialize: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        format!(
+            "Failed to rename {} -> {}: {}",
+            tmp.display(),
+            path.display(),
+            e
+        )
+    })
+}
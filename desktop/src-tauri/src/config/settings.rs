@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use super::jobs::{job_group, Job};
 use crate::agent_session::ProcessProvider;
 use crate::telegram::TelegramConfig;
 
@@ -133,6 +134,86 @@ impl ShortcutSettings {
     }
 }
 
+/// Extra patterns for recognising numbered agent prompts in pane output.
+/// Entries extend the built-in Claude/Codex patterns rather than replace them,
+/// so an upstream UI change can be absorbed without a new release.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PromptDetectionSettings {
+    /// Characters that may prefix an option line, e.g. the `❯` cursor.
+    pub option_markers: String,
+    /// Separators between the option number and its label, e.g. `) `.
+    pub number_separators: Vec<String>,
+    /// Phrases (case-insensitive) that mark output as an interactive prompt.
+    pub prompt_indicators: Vec<String>,
+}
+
+/// Follow-up for questions that nobody answers.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AnswerTimeoutSettings {
+    /// Seconds a question may wait before a reminder is sent. 0 disables
+    /// reminders and actions.
+    pub reminder_secs: u64,
+    /// Applied to job panes after another `reminder_secs` without an answer.
+    pub action: AnswerTimeoutAction,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerTimeoutAction {
+    /// Keep waiting after the reminder.
+    #[default]
+    None,
+    /// Pick the prompt's "No" option.
+    Decline,
+    /// Kill the job's pane.
+    Stop,
+}
+
+/// Throttling of failure notifications for jobs that keep failing the same
+/// way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailureAlertSettings {
+    /// Consecutive failures with the same exit code after which per-run
+    /// failure alerts stop. 0 alerts on every failure.
+    pub flap_threshold: u32,
+    /// While muted, alert again every this many further failures. 0 stays
+    /// quiet until the job recovers.
+    pub remind_every: u32,
+}
+
+impl Default for FailureAlertSettings {
+    fn default() -> Self {
+        Self {
+            flap_threshold: 3,
+            remind_every: 10,
+        }
+    }
+}
+
+/// Telegram warnings when a Claude quota bucket fills up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageAlertSettings {
+    pub enabled: bool,
+    /// Utilization percent that sends a warning. 0 disables it.
+    pub warn_percent: f64,
+    /// Utilization percent that sends a critical alert. 0 disables it.
+    pub critical_percent: f64,
+}
+
+impl Default for UsageAlertSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            warn_percent: 80.0,
+            critical_percent: 95.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RelaySettings {
     pub enabled: bool,
@@ -144,6 +225,14 @@ pub struct RelaySettings {
     pub device_id: String,
     #[serde(default)]
     pub device_name: String,
+    /// Seconds between websocket pings to the relay. 0 uses the default;
+    /// values are clamped to stay well under the server's client timeout.
+    #[serde(default)]
+    pub heartbeat_secs: u64,
+    /// Milliseconds to coalesce job log output before sending it to the
+    /// relay. Unset uses 250ms; 0 sends every chunk immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_batch_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,6 +261,11 @@ pub struct AppSettings {
     pub setup_completed: bool,
     pub telegram: Option<TelegramConfig>,
     pub secrets_backend: String,
+    /// Which jobs may receive each secret: secret key -> job groups,
+    /// `group/name` pairs or slugs. A secret not listed here goes to any job
+    /// that asks for it.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub secret_scopes: HashMap<String, Vec<String>>,
     pub preferred_browser: String,
     pub auto_update_enabled: bool,
     /// User-specified custom paths for tools, keyed by tool name
@@ -201,10 +295,214 @@ pub struct AppSettings {
     /// connected mobile clients don't receive notifications.
     #[serde(default = "default_true")]
     pub notify_questions_remote: bool,
+    /// Attach a rendered image of the question's pane text to mobile push
+    /// notifications. Needs a relay with `PUBLIC_URL` set.
+    #[serde(default)]
+    pub question_screenshots: bool,
     /// Auto-release captured panes back to their original tmux windows when
     /// the ClawTab app loses focus, and re-capture when it regains focus.
     #[serde(default)]
     pub auto_release_on_blur: bool,
+    /// Send a Telegram message when the Claude session or weekly quota resets.
+    #[serde(default)]
+    pub notify_usage_reset: bool,
+    #[serde(default)]
+    pub usage_alerts: UsageAlertSettings,
+    /// Hold back scheduled Claude and folder jobs while the Claude session
+    /// quota is at or above `usage_pause_percent`, and run them once it
+    /// resets. Binary jobs are not affected.
+    #[serde(default)]
+    pub pause_when_usage_exhausted: bool,
+    #[serde(default = "default_usage_pause_percent")]
+    pub usage_pause_percent: f64,
+    #[serde(default)]
+    pub prompt_detection: PromptDetectionSettings,
+    #[serde(default)]
+    pub answer_timeout: AnswerTimeoutSettings,
+    #[serde(default)]
+    pub failure_alerts: FailureAlertSettings,
+    /// IANA timezone for cron schedules of jobs without their own
+    /// `timezone`. None uses local time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_timezone: Option<String>,
+    /// Maximum jobs running at once; further triggers queue until a slot
+    /// frees. 0 means unlimited.
+    #[serde(default)]
+    pub max_concurrent: u32,
+    /// Days of run history kept for jobs without their own
+    /// `history_retention_days`. 0 keeps history forever.
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: u32,
+    /// Encrypt the stdout/stderr of recorded runs with a key kept in the
+    /// keychain. Rows written while this was off stay plaintext.
+    #[serde(default)]
+    pub encrypt_history: bool,
+    /// Exact Playwright version installed for browser logins. Changing it
+    /// reinstalls Playwright on the next login.
+    #[serde(default = "default_playwright_version")]
+    pub playwright_version: String,
+    /// Where Playwright keeps its downloaded browsers, passed as
+    /// `PLAYWRIGHT_BROWSERS_PATH`. None uses the platform cache dir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playwright_cache_dir: Option<String>,
+    /// Per-machine overrides keyed by hostname (as shown by `get_hostname`).
+    /// The current host's profile is applied on load, taking precedence over
+    /// the base values above.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub host_profiles: HashMap<String, HostProfile>,
+}
+
+/// Settings that may differ per machine when one settings.yaml is synced
+/// across hosts. Unset fields fall through to the base settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_tmux_session: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_work_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_provider: Option<ProcessProvider>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_editor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_terminal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_browser: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<u32>,
+}
+
+impl HostProfile {
+    /// Overlay the profile's set fields onto `s`.
+    fn apply_to(&self, s: &mut AppSettings) {
+        overlay(&self.default_tmux_session, &mut s.default_tmux_session);
+        overlay(&self.default_work_dir, &mut s.default_work_dir);
+        overlay(&self.default_provider, &mut s.default_provider);
+        overlay_opt(&self.default_model, &mut s.default_model);
+        overlay(&self.claude_path, &mut s.claude_path);
+        overlay(&self.preferred_editor, &mut s.preferred_editor);
+        overlay(&self.preferred_terminal, &mut s.preferred_terminal);
+        overlay(&self.preferred_browser, &mut s.preferred_browser);
+        overlay_opt(&self.default_timezone, &mut s.default_timezone);
+        overlay(&self.max_concurrent, &mut s.max_concurrent);
+    }
+
+    /// Inverse of `apply_to` before saving: move the effective value of each
+    /// overridden field into the profile and put `base`'s value back in `s`,
+    /// so edits made on this host don't leak into other hosts.
+    fn absorb_from(&mut self, s: &mut AppSettings, base: &AppSettings) {
+        absorb(
+            &mut self.default_tmux_session,
+            &mut s.default_tmux_session,
+            &base.default_tmux_session,
+        );
+        absorb(
+            &mut self.default_work_dir,
+            &mut s.default_work_dir,
+            &base.default_work_dir,
+        );
+        absorb(
+            &mut self.default_provider,
+            &mut s.default_provider,
+            &base.default_provider,
+        );
+        absorb_opt(
+            &mut self.default_model,
+            &mut s.default_model,
+            &base.default_model,
+        );
+        absorb(&mut self.claude_path, &mut s.claude_path, &base.claude_path);
+        absorb(
+            &mut self.preferred_editor,
+            &mut s.preferred_editor,
+            &base.preferred_editor,
+        );
+        absorb(
+            &mut self.preferred_terminal,
+            &mut s.preferred_terminal,
+            &base.preferred_terminal,
+        );
+        absorb(
+            &mut self.preferred_browser,
+            &mut s.preferred_browser,
+            &base.preferred_browser,
+        );
+        absorb_opt(
+            &mut self.default_timezone,
+            &mut s.default_timezone,
+            &base.default_timezone,
+        );
+        absorb(
+            &mut self.max_concurrent,
+            &mut s.max_concurrent,
+            &base.max_concurrent,
+        );
+    }
+}
+
+fn overlay<T: Clone>(profile: &Option<T>, value: &mut T) {
+    if let Some(v) = profile {
+        *value = v.clone();
+    }
+}
+
+fn overlay_opt<T: Clone>(profile: &Option<T>, value: &mut Option<T>) {
+    if profile.is_some() {
+        *value = profile.clone();
+    }
+}
+
+fn absorb<T: Clone>(profile: &mut Option<T>, value: &mut T, base: &T) {
+    if profile.is_some() {
+        *profile = Some(std::mem::replace(value, base.clone()));
+    }
+}
+
+/// Clearing an optional field on this host drops the override.
+fn absorb_opt<T: Clone>(profile: &mut Option<T>, value: &mut Option<T>, base: &Option<T>) {
+    if profile.is_some() {
+        *profile = std::mem::replace(value, base.clone());
+    }
+}
+
+/// Whether `job` may receive the secret `key` under `secret_scopes`.
+pub fn secret_in_scope(scopes: &HashMap<String, Vec<String>>, key: &str, job: &Job) -> bool {
+    let Some(allowed) = scopes.get(key) else {
+        return true;
+    };
+    let group = job_group(job);
+    allowed.iter().any(|scope| {
+        scope == group
+            || *scope == job.slug
+            || scope.split_once('/') == Some((group, job.name.as_str()))
+    })
+}
+
+/// This machine's hostname, without a trailing `.local`. Keys `host_profiles`.
+pub fn current_hostname() -> String {
+    gethostname::gethostname()
+        .to_string_lossy()
+        .trim_end_matches(".local")
+        .to_string()
+}
+
+fn default_usage_pause_percent() -> f64 {
+    100.0
+}
+
+fn default_history_retention_days() -> u32 {
+    crate::history::DEFAULT_RETENTION_DAYS
+}
+
+fn default_playwright_version() -> String {
+    "1.50.0".to_string()
 }
 
 fn default_true() -> bool {
@@ -230,6 +528,7 @@ impl Default for AppSettings {
             setup_completed: false,
             telegram: None,
             secrets_backend: "both".to_string(),
+            secret_scopes: HashMap::new(),
             preferred_browser: "chrome".to_string(),
             auto_update_enabled: true,
             tool_paths: HashMap::new(),
@@ -243,7 +542,22 @@ impl Default for AppSettings {
             shortcuts: ShortcutSettings::default(),
             notify_questions_local: true,
             notify_questions_remote: true,
+            question_screenshots: false,
             auto_release_on_blur: false,
+            notify_usage_reset: false,
+            usage_alerts: UsageAlertSettings::default(),
+            pause_when_usage_exhausted: false,
+            usage_pause_percent: default_usage_pause_percent(),
+            prompt_detection: PromptDetectionSettings::default(),
+            answer_timeout: AnswerTimeoutSettings::default(),
+            failure_alerts: FailureAlertSettings::default(),
+            default_timezone: None,
+            max_concurrent: 0,
+            history_retention_days: default_history_retention_days(),
+            encrypt_history: false,
+            playwright_version: default_playwright_version(),
+            playwright_cache_dir: None,
+            host_profiles: HashMap::new(),
         }
     }
 }
@@ -253,7 +567,17 @@ impl AppSettings {
         super::config_dir().map(|p| p.join("settings.yaml"))
     }
 
+    /// Load settings with this host's profile applied.
     pub fn load() -> Self {
+        let mut settings = Self::load_base();
+        if let Some(profile) = settings.host_profiles.get(&current_hostname()).cloned() {
+            profile.apply_to(&mut settings);
+        }
+        settings
+    }
+
+    /// Settings as stored, without any host profile applied.
+    fn load_base() -> Self {
         if let Some(path) = Self::file_path() {
             if let Ok(contents) = std::fs::read_to_string(&path) {
                 if let Ok(mut settings) = serde_yml::from_str::<Self>(&contents) {
@@ -272,15 +596,46 @@ impl AppSettings {
             std::fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create config directory: {}", e))?;
         }
+        let mut stored = self.clone();
+        let mut profiles = std::mem::take(&mut stored.host_profiles);
+        if let Some(profile) = profiles.get_mut(&current_hostname()) {
+            profile.absorb_from(&mut stored, &Self::load_base());
+        }
+        stored.host_profiles = profiles;
         let contents =
-            serde_yml::to_string(self).map_err(|e| format!("Failed to serialize: {}", e))?;
+            serde_yml::to_string(&stored).map_err(|e| format!("Failed to serialize: {}", e))?;
         std::fs::write(&path, contents).map_err(|e| format!("Failed to write settings: {}", e))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DetectedProcessOverride;
+    use super::{secret_in_scope, AppSettings, DetectedProcessOverride, HostProfile};
+    use crate::config::jobs::Job;
+    use std::collections::HashMap;
+
+    #[test]
+    fn host_profile_overrides_base_and_saves_back_to_itself() {
+        let base = AppSettings::default();
+        let mut profile = HostProfile {
+            default_work_dir: Some("/srv/jobs".to_string()),
+            default_timezone: Some("UTC".to_string()),
+            ..Default::default()
+        };
+        let mut effective = base.clone();
+        profile.apply_to(&mut effective);
+        assert_eq!(effective.default_work_dir, "/srv/jobs");
+        assert_eq!(effective.default_timezone.as_deref(), Some("UTC"));
+        assert_eq!(effective.preferred_editor, base.preferred_editor);
+
+        // Edit on this host, then split back into base + profile for saving
+        effective.default_work_dir = "/srv/other".to_string();
+        effective.preferred_editor = "code".to_string();
+        profile.absorb_from(&mut effective, &base);
+        assert_eq!(profile.default_work_dir.as_deref(), Some("/srv/other"));
+        assert_eq!(effective.default_work_dir, base.default_work_dir);
+        assert_eq!(effective.preferred_editor, "code");
+    }
 
     #[test]
     fn process_override_identity_rejects_recycled_panes_and_sessions() {
@@ -294,4 +649,21 @@ mod tests {
         assert!(!process_override.matches_identity("100", Some("session-b")));
         assert!(!process_override.matches_identity("100", None));
     }
+
+    #[test]
+    fn scoped_secrets_go_only_to_listed_groups_and_jobs() {
+        let job: Job = serde_yml::from_str(
+            "name: deploy\njob_type: binary\nenabled: true\npath: ./deploy.sh\ncron: ''\ngroup: ops\nslug: ops/deploy\n",
+        )
+        .unwrap();
+        let scopes = HashMap::from([
+            ("AWS_KEY".to_string(), vec!["ops".to_string()]),
+            ("DB_PASS".to_string(), vec!["ops/deploy".to_string()]),
+            ("STRIPE_KEY".to_string(), vec!["billing".to_string()]),
+        ]);
+        assert!(secret_in_scope(&scopes, "AWS_KEY", &job));
+        assert!(secret_in_scope(&scopes, "DB_PASS", &job));
+        assert!(!secret_in_scope(&scopes, "STRIPE_KEY", &job));
+        assert!(secret_in_scope(&scopes, "UNLISTED", &job));
+    }
 }
@@ -1,7 +1,15 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 
+/// How many directory levels below the scan root are searched for `.cwt/`.
+const DISCOVER_MAX_DEPTH: usize = 6;
+/// Stop walking after this many directories so a scan of `~` stays bounded.
+const DISCOVER_MAX_DIRS: usize = 20_000;
+/// Directories that never hold project roots worth scanning into.
+const DISCOVER_SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
 /// Represents a folder job's context. Scripts are found in the central config directory.
 #[derive(Debug, Clone, Serialize)]
 pub struct CwtFolder {
@@ -72,6 +80,110 @@ impl CwtFolder {
     }
 }
 
+/// A `.cwt/<job>/job.md` found on disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredJob {
+    /// Project root containing the `.cwt/` folder.
+    pub folder_path: String,
+    pub job_name: String,
+    /// The `.cwt/<job>` folder, suitable as the `source` of `import_job_folder`.
+    pub source: String,
+}
+
+/// Walk `root` looking for `.cwt/<job>/job.md`. Hidden directories, common
+/// build output, names listed in each directory's `.gitignore` and symlinked
+/// directories are skipped; depth and the number of visited directories are
+/// capped.
+pub fn discover_jobs(root: &Path) -> Vec<DiscoveredJob> {
+    let mut found = Vec::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut stack: Vec<(PathBuf, usize)> = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        if visited.len() >= DISCOVER_MAX_DIRS {
+            log::warn!(
+                "Stopped .cwt discovery under {} after {} directories",
+                root.display(),
+                DISCOVER_MAX_DIRS
+            );
+            break;
+        }
+        // Canonical paths guard against loops through bind mounts or a root
+        // that is itself reached through a symlink.
+        let canonical = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if !visited.insert(canonical) {
+            continue;
+        }
+
+        collect_cwt_jobs(&dir, &mut found);
+        if depth >= DISCOVER_MAX_DEPTH {
+            continue;
+        }
+
+        let ignored = read_gitignore_names(&dir);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // DirEntry::file_type does not follow symlinks.
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.')
+                || DISCOVER_SKIP_DIRS.contains(&name.as_str())
+                || ignored.contains(&name)
+            {
+                continue;
+            }
+            stack.push((entry.path(), depth + 1));
+        }
+    }
+
+    found.sort_by(|a, b| {
+        a.folder_path
+            .cmp(&b.folder_path)
+            .then_with(|| a.job_name.cmp(&b.job_name))
+    });
+    found
+}
+
+fn collect_cwt_jobs(project_root: &Path, found: &mut Vec<DiscoveredJob>) {
+    let Ok(entries) = std::fs::read_dir(project_root.join(".cwt")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let job_dir = entry.path();
+        if !job_dir.join("job.md").is_file() {
+            continue;
+        }
+        found.push(DiscoveredJob {
+            folder_path: project_root.to_string_lossy().into_owned(),
+            job_name: entry.file_name().to_string_lossy().into_owned(),
+            source: job_dir.to_string_lossy().into_owned(),
+        });
+    }
+}
+
+/// Plain directory names from `dir/.gitignore`. Glob and negated patterns are
+/// not interpreted; a missed pattern only costs scan time.
+fn read_gitignore_names(dir: &Path) -> HashSet<String> {
+    let Ok(content) = std::fs::read_to_string(dir.join(".gitignore")) else {
+        return HashSet::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('!'))
+        .map(|l| l.trim_start_matches('/').trim_end_matches('/'))
+        .filter(|l| !l.is_empty() && !l.contains(['*', '?', '[', '/']))
+        .map(str::to_string)
+        .collect()
+}
+
 #[allow(dead_code)]
 fn list_scripts(dir: &Path) -> Vec<String> {
     let mut scripts = Vec::new();
@@ -104,3 +216,32 @@ fn list_scripts(dir: &Path) -> Vec<String> {
     scripts.sort();
     scripts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_job(root: &Path, project: &str, job: &str) {
+        let dir = root.join(project).join(".cwt").join(job);
+        std::fs::create_dir_all(&dir).expect("create job dir");
+        std::fs::write(dir.join("job.md"), "prompt").expect("write job.md");
+    }
+
+    #[test]
+    fn discover_finds_jobs_and_skips_ignored_dirs() {
+        let directory = tempfile::tempdir().expect("tempdir");
+        let root = directory.path();
+        add_job(root, "app", "deploy");
+        add_job(root, "app", "lint");
+        add_job(root, "app/node_modules/pkg", "nested");
+        add_job(root, "generated/out", "skipped");
+        std::fs::create_dir_all(root.join("app/.cwt/no-prompt")).expect("create dir");
+        std::fs::write(root.join(".gitignore"), "/generated/\n").expect("write gitignore");
+
+        let found = discover_jobs(root);
+        let names: Vec<&str> = found.iter().map(|j| j.job_name.as_str()).collect();
+        assert_eq!(names, vec!["deploy", "lint"]);
+        assert!(found[0].folder_path.ends_with("app"));
+        assert!(found[0].source.ends_with(".cwt/deploy"));
+    }
+}
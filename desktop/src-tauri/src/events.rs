@@ -19,6 +19,9 @@ pub trait EventSink: Send + Sync {
     fn emit_relay_status_changed(&self, status: ipc::IpcRelayStatus) {
         let _ = status;
     }
+    fn emit_usage_pause_changed(&self, pause: Option<crate::scheduler::usage_pause::UsagePause>) {
+        let _ = pause;
+    }
 }
 
 /// Tauri-backed event sink that emits to the webview frontend.
@@ -70,6 +73,10 @@ impl EventSink for TauriEventSink {
         use tauri::Emitter;
         let _ = self.app_handle.emit("relay-status-changed", status);
     }
+
+    fn emit_usage_pause_changed(&self, pause: Option<crate::scheduler::usage_pause::UsagePause>) {
+        crate::set_usage_pause(&self.app_handle, pause);
+    }
 }
 
 /// Broadcasts events to all IPC event subscribers. Used by the daemon.
@@ -143,6 +150,10 @@ impl EventSink for IpcBroadcastEventSink {
     fn emit_relay_status_changed(&self, status: ipc::IpcRelayStatus) {
         self.spawn_broadcast(IpcEvent::RelayStatusChanged(status));
     }
+
+    fn emit_usage_pause_changed(&self, pause: Option<crate::scheduler::usage_pause::UsagePause>) {
+        self.spawn_broadcast(IpcEvent::UsagePauseChanged(pause));
+    }
 }
 
 /// Desktop-side loop that connects to the daemon's event server and forwards
@@ -167,6 +178,11 @@ pub async fn run_daemon_event_subscription(
         };
 
         log::info!("Subscribed to daemon event stream");
+        if let Ok(ipc::IpcResponse::UsagePause(pause)) =
+            ipc::send_command(ipc::IpcCommand::GetUsagePause).await
+        {
+            crate::set_usage_pause(&app_handle, pause);
+        }
         let mut lines = reader.lines();
         loop {
             match lines.next_line().await {
@@ -194,6 +210,9 @@ pub async fn run_daemon_event_subscription(
                         IpcEvent::RelayStatusChanged(status) => {
                             let _ = app_handle.emit("relay-status-changed", status);
                         }
+                        IpcEvent::UsagePauseChanged(pause) => {
+                            crate::set_usage_pause(&app_handle, pause);
+                        }
                         IpcEvent::Notification { title, body } => {
                             use tauri_plugin_notification::NotificationExt;
                             if let Err(e) = app_handle
@@ -0,0 +1,212 @@
+//! Zip of everything recorded about one run, for bug reports and audits.
+//! Every file in it has secret values replaced with `***`.
+
+use std::io::Write;
+
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::{AutoAnswerRecord, RunItemRecord, RunRecord};
+
+/// What goes into a run bundle besides the history row.
+#[derive(Default)]
+pub struct BundleInputs {
+    /// The job's config as YAML, if the job still exists.
+    pub job_yaml: Option<String>,
+    /// The run's on-disk log file.
+    pub log: Option<String>,
+    /// Agent jobs: the prompt assembled from the job as it is now, with the
+    /// param defaults.
+    pub prompt: Option<String>,
+    /// Folder jobs: the job's `job.md` as it is now.
+    pub job_md: Option<String>,
+    /// `job.md` or the prompt file changed after the run started, so
+    /// `prompt.md` and `job.md` may differ from what the run used.
+    pub prompt_changed_since_run: bool,
+    pub auto_answers: Vec<AutoAnswerRecord>,
+    pub items: Vec<RunItemRecord>,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    #[serde(flatten)]
+    run: RunRecord,
+    exported_at: String,
+    files: &'a [&'a str],
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    prompt_changed_since_run: bool,
+}
+
+/// Build the zip for `record`, scrubbing `secrets` from every file.
+pub fn build(
+    record: &RunRecord,
+    inputs: BundleInputs,
+    secrets: &[String],
+) -> Result<Vec<u8>, String> {
+    let mut files: Vec<(&str, String)> = Vec::new();
+    if !record.stdout.is_empty() {
+        files.push(("stdout.txt", record.stdout.clone()));
+    }
+    if !record.stderr.is_empty() {
+        files.push(("stderr.txt", record.stderr.clone()));
+    }
+    if let Some(log) = inputs.log {
+        files.push(("run.log", log));
+    }
+    if let Some(yaml) = inputs.job_yaml {
+        files.push(("job.yaml", yaml));
+    }
+    if let Some(prompt) = inputs.prompt {
+        files.push(("prompt.md", prompt));
+    }
+    if let Some(job_md) = inputs.job_md {
+        files.push(("job.md", job_md));
+    }
+    for (_, content) in &mut files {
+        *content = crate::secrets::scrub(content, secrets);
+    }
+    if !inputs.auto_answers.is_empty() {
+        files.push(("auto_answers.json", json(&inputs.auto_answers, secrets)?));
+    }
+    if !inputs.items.is_empty() {
+        files.push(("items.json", json(&inputs.items, secrets)?));
+    }
+
+    let names: Vec<&str> = files.iter().map(|(name, _)| *name).collect();
+    let manifest = Manifest {
+        run: RunRecord {
+            stdout: String::new(),
+            stderr: String::new(),
+            ..record.clone()
+        },
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        files: &names,
+        prompt_changed_since_run: inputs.prompt_changed_since_run,
+    };
+    let manifest = json(&manifest, secrets)?;
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in std::iter::once(("run.json", manifest)).chain(files) {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+    }
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+/// `value` as pretty JSON with `secrets` scrubbed from its strings. Scrubbing
+/// after serializing would miss secrets with characters JSON escapes.
+fn json<T: Serialize>(value: &T, secrets: &[String]) -> Result<String, String> {
+    let mut value = serde_json::to_value(value)
+        .map_err(|e| format!("Failed to serialize bundle file: {}", e))?;
+    scrub_strings(&mut value, secrets);
+    serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize bundle file: {}", e))
+}
+
+fn scrub_strings(value: &mut serde_json::Value, secrets: &[String]) {
+    match value {
+        serde_json::Value::String(s) => *s = crate::secrets::scrub(s, secrets),
+        serde_json::Value::Array(items) => {
+            items.iter_mut().for_each(|v| scrub_strings(v, secrets));
+        }
+        serde_json::Value::Object(map) => {
+            map.values_mut().for_each(|v| scrub_strings(v, secrets));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn record() -> RunRecord {
+        RunRecord {
+            id: "run-1".to_string(),
+            job_id: "ops/deploy".to_string(),
+            started_at: "2026-10-01T10:00:00+00:00".to_string(),
+            finished_at: Some("2026-10-01T10:01:00+00:00".to_string()),
+            exit_code: Some(1),
+            trigger: "manual".to_string(),
+            stdout: "token=hunter2\n".to_string(),
+            stderr: "failed\n".to_string(),
+            pane_id: None,
+            log_path: None,
+            git_branch: None,
+            git_commit: None,
+            launch_ms: None,
+            output_truncated: false,
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn bundle_holds_every_part_with_secrets_scrubbed() {
+        let inputs = BundleInputs {
+            log: Some("full log hunter2".to_string()),
+            job_md: Some("Deploy with hunter2".to_string()),
+            ..Default::default()
+        };
+        let bytes = build(&record(), inputs, &["hunter2".to_string()]).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["job.md", "run.json", "run.log", "stderr.txt", "stdout.txt"]
+        );
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            assert!(!content.contains("hunter2"), "{} leaks", file.name());
+        }
+
+        let mut manifest = String::new();
+        archive
+            .by_name("run.json")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest["exit_code"], 1);
+        assert_eq!(manifest["stdout"], "");
+        assert_eq!(manifest["files"][0], "stdout.txt");
+    }
+
+    #[test]
+    fn json_files_scrub_secrets_that_json_escapes() {
+        let secret = r#"pa"ss\word"#.to_string();
+        let inputs = BundleInputs {
+            auto_answers: vec![AutoAnswerRecord {
+                run_id: "run-1".to_string(),
+                answered_at: "2026-10-01T10:00:30+00:00".to_string(),
+                pattern: "Password".to_string(),
+                answer: secret.clone(),
+                prompt: format!("Password ({})?", secret),
+            }],
+            ..Default::default()
+        };
+        let bytes = build(&record(), inputs, std::slice::from_ref(&secret)).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("auto_answers.json")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        let answers: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(answers[0]["answer"], "***");
+        assert_eq!(answers[0]["prompt"], "Password (***)?");
+    }
+}
@@ -0,0 +1,217 @@
+//! AES-256-GCM for the `stdout`/`stderr` columns when `encrypt_history` is
+//! on. Encrypted values carry a prefix, so rows written before encryption
+//! was enabled (or after it was turned off) stay readable as they are.
+
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::secrets::keychain::ITEM_NOT_FOUND_EXIT;
+use crate::secrets::SecretsManager;
+
+/// Keychain item holding the base64 key. Its service differs from the one
+/// `SecretsManager` lists, so jobs and the agent never get the key as a
+/// secret and it can't be deleted from the Secrets panel.
+const KEY_SERVICE: &str = "cc.clawtab.history";
+const KEY_ACCOUNT: &str = "encryption-key";
+/// Where the key used to be kept, as an ordinary secret. Moved on load.
+const LEGACY_KEY_SECRET: &str = "CLAWTAB_HISTORY_KEY";
+pub(super) const PREFIX: &str = "enc:v1:";
+const KEY_LEN: usize = 32;
+
+pub struct HistoryCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl HistoryCipher {
+    pub(super) fn from_key(bytes: &[u8]) -> Result<Self, String> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| "Invalid history encryption key".to_string())?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// The cipher for the key stored in the keychain, if there is one. A key
+    /// still kept as the `CLAWTAB_HISTORY_KEY` secret is moved to its own
+    /// keychain item first.
+    pub fn load(secrets: &mut SecretsManager) -> Result<Option<Self>, String> {
+        let encoded = match read_key()? {
+            Some(encoded) => encoded,
+            None => match migrate_legacy_key(secrets)? {
+                Some(encoded) => encoded,
+                None => return Ok(None),
+            },
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Failed to decode history encryption key: {}", e))?;
+        Self::from_key(&bytes).map(Some)
+    }
+
+    /// Like `load`, generating and storing a new key when none exists yet.
+    pub fn load_or_create(secrets: &mut SecretsManager) -> Result<Self, String> {
+        if let Some(cipher) = Self::load(secrets)? {
+            return Ok(cipher);
+        }
+        let mut bytes = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| "Failed to generate history encryption key".to_string())?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+        write_key(&encoded)?;
+        Self::from_key(&bytes)
+    }
+
+    /// Encrypt `text` with a fresh nonce. Empty output stays empty so the
+    /// "no output" checks in SQL keep working.
+    pub fn encrypt(&self, text: &str) -> Result<String, String> {
+        if text.is_empty() {
+            return Ok(String::new());
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| "Failed to generate nonce".to_string())?;
+        let mut sealed = text.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| "Failed to encrypt run output".to_string())?;
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&sealed);
+        Ok(format!(
+            "{}{}",
+            PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(data)
+        ))
+    }
+
+    /// Decrypt a stored value. Values without the prefix are plaintext and
+    /// come back unchanged.
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let Some(encoded) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let mut data = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Failed to decode run output: {}", e))?;
+        if data.len() < NONCE_LEN {
+            return Err("Encrypted run output is truncated".to_string());
+        }
+        let (nonce, sealed) = data.split_at_mut(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| "Encrypted run output is truncated".to_string())?;
+        let plain = self
+            .key
+            .open_in_place(nonce, Aad::empty(), sealed)
+            .map_err(|_| "Failed to decrypt run output".to_string())?;
+        String::from_utf8(plain.to_vec())
+            .map_err(|e| format!("Decrypted output is not UTF-8: {}", e))
+    }
+}
+
+/// The stored key, or None when there is no keychain item (or no keychain).
+fn read_key() -> Result<Option<String>, String> {
+    let output = match std::process::Command::new("security")
+        .args([
+            "find-generic-password",
+            "-s",
+            KEY_SERVICE,
+            "-a",
+            KEY_ACCOUNT,
+            "-w",
+        ])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to run security command: {}", e)),
+    };
+    if output.status.success() {
+        let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok(Some(key));
+    }
+    if output.status.code() == Some(ITEM_NOT_FOUND_EXIT) {
+        return Ok(None);
+    }
+    Err(format!(
+        "Keychain error: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+fn write_key(encoded: &str) -> Result<(), String> {
+    let output = std::process::Command::new("security")
+        .args([
+            "add-generic-password",
+            "-s",
+            KEY_SERVICE,
+            "-a",
+            KEY_ACCOUNT,
+            "-w",
+            encoded,
+            "-U",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run security command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Keychain error: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Move a key kept as the `CLAWTAB_HISTORY_KEY` secret to its own item.
+fn migrate_legacy_key(secrets: &mut SecretsManager) -> Result<Option<String>, String> {
+    let Some(encoded) = secrets.get(LEGACY_KEY_SECRET).cloned() else {
+        return Ok(None);
+    };
+    write_key(&encoded)?;
+    if let Err(e) = secrets.delete(LEGACY_KEY_SECRET) {
+        log::warn!("Failed to remove the old history key secret: {}", e);
+    }
+    Ok(Some(encoded))
+}
+
+/// True when `stored` was written by `HistoryCipher::encrypt`.
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_passes_plaintext_through() {
+        let cipher = HistoryCipher::from_key(&[7u8; KEY_LEN]).unwrap();
+        let sealed = cipher.encrypt("hello\nworld").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.contains("hello"));
+        assert_ne!(sealed, cipher.encrypt("hello\nworld").unwrap());
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), "hello\nworld");
+        assert_eq!(
+            cipher.decrypt("old plaintext row").unwrap(),
+            "old plaintext row"
+        );
+        assert_eq!(cipher.encrypt("").unwrap(), "");
+    }
+
+    #[test]
+    fn rejects_output_sealed_with_another_key() {
+        let sealed = HistoryCipher::from_key(&[1u8; KEY_LEN])
+            .unwrap()
+            .encrypt("secret")
+            .unwrap();
+        let other = HistoryCipher::from_key(&[2u8; KEY_LEN]).unwrap();
+        assert!(other.decrypt(&sealed).is_err());
+    }
+}
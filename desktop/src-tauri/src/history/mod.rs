@@ -1,6 +1,21 @@
-use rusqlite::{params, Connection};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::config::jobs::Job;
+use crate::secrets::SecretsManager;
+
+use self::cipher::HistoryCipher;
+
+pub mod bundle;
+pub mod cipher;
+
+/// Default for the `history_retention_days` setting.
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Shown in place of encrypted output when the key is missing or wrong.
+const UNREADABLE_OUTPUT: &str = "[encrypted output: key unavailable]";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunRecord {
@@ -17,10 +32,149 @@ pub struct RunRecord {
     pub pane_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_path: Option<String>,
+    /// Branch checked out in the work dir when the run started, for jobs with
+    /// `include_git_info`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Short HEAD commit hash captured alongside `git_branch`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Time from trigger to pane-ready for tmux runs, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_ms: Option<u64>,
+    /// Captured output went past the job's `max_output_bytes`; `stdout` and
+    /// `stderr` keep only the start and end.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub output_truncated: bool,
+    /// The job never ran: its `run_if` check exited non-zero, or its browser
+    /// login was stale. Not a success or a failure; `stderr` says why.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+/// A prompt answered by one of the job's `auto_answer` rules.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoAnswerRecord {
+    pub run_id: String,
+    pub answered_at: String,
+    /// The rule's pattern that matched.
+    pub pattern: String,
+    pub answer: String,
+    /// The prompt text as shown in the pane.
+    pub prompt: String,
+}
+
+/// Outcome of one item of a batch binary run.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunItemRecord {
+    pub run_id: String,
+    /// Position of the item in the job's item list.
+    pub item_index: u32,
+    pub item: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// None when the process was killed by a signal.
+    pub exit_code: Option<i32>,
+}
+
+/// Aggregate launch latency over the runs that recorded `launch_ms`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LaunchStats {
+    pub runs: u64,
+    pub avg_ms: Option<f64>,
+    pub min_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+}
+
+/// Outcome and duration summary for one job's recorded runs. Durations cover
+/// finished runs only. Skipped runs are left out.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobStats {
+    pub runs: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub avg_duration_ms: Option<f64>,
+    pub max_duration_ms: Option<u64>,
+    pub last_exit_code: Option<i32>,
+}
+
+/// One page of run history plus the total number of matching runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    pub records: Vec<RunRecord>,
+    pub total: u64,
+}
+
+impl RunRecord {
+    /// Combined stdout/stderr as a single string for copying or sharing.
+    /// Prefers the on-disk log when it holds more than the stored copy: tmux
+    /// jobs write their full capture there and interrupted runs may have
+    /// nothing in the database at all.
+    pub fn full_output(&self) -> String {
+        let stored = if self.stderr.is_empty() {
+            self.stdout.clone()
+        } else if self.stdout.is_empty() {
+            self.stderr.clone()
+        } else {
+            format!("{}\n--- stderr ---\n{}", self.stdout, self.stderr)
+        };
+        match self
+            .log_path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+        {
+            Some(content) if content.len() > stored.len() => content,
+            _ => stored,
+        }
+    }
+}
+
+/// Apply the `encrypt_history` setting to `history`, logging failures so a
+/// denied keychain doesn't stop startup.
+pub fn configure_encryption(
+    history: &Mutex<HistoryStore>,
+    secrets: &Mutex<SecretsManager>,
+    enabled: bool,
+) {
+    if let Err(e) = history
+        .lock()
+        .configure_encryption(enabled, &mut secrets.lock())
+    {
+        log::warn!("History encryption unavailable: {}", e);
+    }
+}
+
+/// Column list shared by every query that maps rows through `run_from_row`.
+const RUN_COLUMNS: &str = "id, job_name, started_at, finished_at, exit_code, trigger_type, \
+     stdout, stderr, pane_id, log_path, git_branch, git_commit, launch_ms, output_truncated, \
+     skipped";
+
+fn run_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RunRecord> {
+    Ok(RunRecord {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        started_at: row.get(2)?,
+        finished_at: row.get(3)?,
+        exit_code: row.get(4)?,
+        trigger: row.get(5)?,
+        stdout: row.get(6)?,
+        stderr: row.get(7)?,
+        pane_id: row.get(8)?,
+        log_path: row.get(9)?,
+        git_branch: row.get(10)?,
+        git_commit: row.get(11)?,
+        launch_ms: row.get(12)?,
+        output_truncated: row.get(13)?,
+        skipped: row.get(14)?,
+    })
 }
 
 pub struct HistoryStore {
     conn: Connection,
+    /// Loaded whenever a key exists, so encrypted rows stay readable after
+    /// `encrypt_history` is turned off.
+    cipher: Option<HistoryCipher>,
+    encrypt: bool,
 }
 
 impl HistoryStore {
@@ -34,6 +188,14 @@ impl HistoryStore {
         let conn =
             Connection::open(&path).map_err(|e| format!("Failed to open database: {}", e))?;
 
+        let store = Self::with_connection(conn)?;
+        crate::agent::migrate_legacy_agent_storage();
+        store.backfill_orphan_logs();
+        Ok(store)
+    }
+
+    /// The store over `conn`, with its tables created or migrated.
+    fn with_connection(conn: Connection) -> Result<Self, String> {
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS runs (
                 id TEXT PRIMARY KEY,
@@ -57,13 +219,20 @@ impl HistoryStore {
             .ok();
         conn.execute_batch("ALTER TABLE runs ADD COLUMN log_path TEXT;")
             .ok();
-
-        // Auto-prune entries older than 30 days
-        conn.execute(
-            "DELETE FROM runs WHERE started_at < datetime('now', '-30 days')",
-            [],
+        conn.execute_batch("ALTER TABLE runs ADD COLUMN git_branch TEXT;")
+            .ok();
+        conn.execute_batch("ALTER TABLE runs ADD COLUMN git_commit TEXT;")
+            .ok();
+        conn.execute_batch("ALTER TABLE runs ADD COLUMN launch_ms INTEGER;")
+            .ok();
+        conn.execute_batch(
+            "ALTER TABLE runs ADD COLUMN output_truncated INTEGER NOT NULL DEFAULT 0;",
         )
         .ok();
+        conn.execute_batch("ALTER TABLE runs ADD COLUMN skipped INTEGER NOT NULL DEFAULT 0;")
+            .ok();
+
+        Self::create_run_detail_tables(&conn)?;
 
         // Clean up stale reattach records (unfinished with no output)
         conn.execute(
@@ -72,10 +241,47 @@ impl HistoryStore {
         )
         .ok();
 
-        let store = Self { conn };
-        crate::agent::migrate_legacy_agent_storage();
-        store.backfill_orphan_logs();
-        Ok(store)
+        Ok(Self {
+            conn,
+            cipher: None,
+            encrypt: false,
+        })
+    }
+
+    /// Tables of per-run details, deleted along with their run.
+    fn create_run_detail_tables(conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS auto_answers (
+                run_id TEXT NOT NULL,
+                answered_at TEXT NOT NULL,
+                pattern TEXT NOT NULL,
+                answer TEXT NOT NULL,
+                prompt TEXT NOT NULL DEFAULT ''
+            );
+            CREATE INDEX IF NOT EXISTS idx_auto_answers_run ON auto_answers(run_id);
+            CREATE TRIGGER IF NOT EXISTS runs_delete_auto_answers AFTER DELETE ON runs
+            BEGIN
+                DELETE FROM auto_answers WHERE run_id = OLD.id;
+            END;",
+        )
+        .map_err(|e| format!("Failed to create tables: {}", e))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS run_items (
+                run_id TEXT NOT NULL,
+                item_index INTEGER NOT NULL,
+                item TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL,
+                exit_code INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_run_items_run ON run_items(run_id);
+            CREATE TRIGGER IF NOT EXISTS runs_delete_run_items AFTER DELETE ON runs
+            BEGIN
+                DELETE FROM run_items WHERE run_id = OLD.id;
+            END;",
+        )
+        .map_err(|e| format!("Failed to create tables: {}", e))
     }
 
     /// One-shot scan of ~/.config/clawtab/jobs/<slug>/logs/ that pairs
@@ -170,11 +376,67 @@ impl HistoryStore {
         crate::config::config_dir().map(|p| p.join("history.db"))
     }
 
+    /// Apply the `encrypt_history` setting. Enabling it creates the keychain
+    /// key on first use; disabling it keeps an existing key for reading.
+    pub fn configure_encryption(
+        &mut self,
+        enabled: bool,
+        secrets: &mut SecretsManager,
+    ) -> Result<(), String> {
+        self.encrypt = enabled;
+        self.cipher = if enabled {
+            Some(HistoryCipher::load_or_create(secrets)?)
+        } else {
+            HistoryCipher::load(secrets)?
+        };
+        Ok(())
+    }
+
+    /// Output as it should be written. With encryption on but no usable key
+    /// the output is dropped rather than stored in plaintext.
+    pub(crate) fn seal(&self, text: &str) -> String {
+        if !self.encrypt {
+            return text.to_string();
+        }
+        match self.cipher.as_ref().map(|c| c.encrypt(text)) {
+            Some(Ok(sealed)) => sealed,
+            Some(Err(e)) => {
+                log::warn!("Not storing run output: {}", e);
+                String::new()
+            }
+            None => {
+                log::warn!("Not storing run output: history encryption key unavailable");
+                String::new()
+            }
+        }
+    }
+
+    pub(crate) fn open_output(&self, stored: String) -> String {
+        if !cipher::is_encrypted(&stored) {
+            return stored;
+        }
+        match self.cipher.as_ref().map(|c| c.decrypt(&stored)) {
+            Some(Ok(text)) => text,
+            Some(Err(e)) => {
+                log::warn!("{}", e);
+                UNREADABLE_OUTPUT.to_string()
+            }
+            None => UNREADABLE_OUTPUT.to_string(),
+        }
+    }
+
+    /// Decrypt the output columns of a record read from the database.
+    fn open(&self, mut record: RunRecord) -> RunRecord {
+        record.stdout = self.open_output(std::mem::take(&mut record.stdout));
+        record.stderr = self.open_output(std::mem::take(&mut record.stderr));
+        record
+    }
+
     pub fn insert(&self, record: &RunRecord) -> Result<(), String> {
         self.conn
             .execute(
-                "INSERT INTO runs (id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT INTO runs (id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path, git_branch, git_commit)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 params![
                     record.id,
                     record.job_id,
@@ -182,10 +444,12 @@ impl HistoryStore {
                     record.finished_at,
                     record.exit_code,
                     record.trigger,
-                    record.stdout,
-                    record.stderr,
+                    self.seal(&record.stdout),
+                    self.seal(&record.stderr),
                     record.pane_id,
                     record.log_path,
+                    record.git_branch,
+                    record.git_commit,
                 ],
             )
             .map_err(|e| format!("Failed to insert run record: {}", e))?;
@@ -212,6 +476,95 @@ impl HistoryStore {
         Ok(())
     }
 
+    pub fn mark_output_truncated(&self, id: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE runs SET output_truncated = 1 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| format!("Failed to update output_truncated: {}", e))?;
+        Ok(())
+    }
+
+    pub fn mark_skipped(&self, id: &str) -> Result<(), String> {
+        self.conn
+            .execute("UPDATE runs SET skipped = 1 WHERE id = ?1", params![id])
+            .map_err(|e| format!("Failed to update skipped: {}", e))?;
+        Ok(())
+    }
+
+    pub fn update_launch_ms(&self, id: &str, launch_ms: u64) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE runs SET launch_ms = ?1 WHERE id = ?2",
+                params![launch_ms as i64, id],
+            )
+            .map_err(|e| format!("Failed to update launch_ms: {}", e))?;
+        Ok(())
+    }
+
+    /// Launch latency across all runs, or one job's runs when `job_id` is set.
+    pub fn launch_stats(&self, job_id: Option<&str>) -> Result<LaunchStats, String> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(launch_ms), AVG(launch_ms), MIN(launch_ms), MAX(launch_ms)
+                 FROM runs WHERE launch_ms IS NOT NULL AND (?1 IS NULL OR job_name = ?1)",
+                params![job_id],
+                |row| {
+                    Ok(LaunchStats {
+                        runs: row.get::<_, i64>(0)? as u64,
+                        avg_ms: row.get(1)?,
+                        min_ms: row.get::<_, Option<i64>>(2)?.map(|v| v as u64),
+                        max_ms: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to query launch stats: {}", e))
+    }
+
+    pub fn job_stats(&self, job_id: &str) -> Result<JobStats, String> {
+        let mut stats = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*),
+                        COUNT(CASE WHEN exit_code = 0 THEN 1 END),
+                        COUNT(CASE WHEN exit_code <> 0 THEN 1 END),
+                        AVG(duration_ms),
+                        MAX(duration_ms)
+                 FROM (
+                   SELECT exit_code,
+                          CASE WHEN finished_at IS NOT NULL
+                               THEN (julianday(finished_at) - julianday(started_at)) * 86400000.0
+                          END AS duration_ms
+                   FROM runs WHERE job_name = ?1 AND skipped = 0
+                 )",
+                params![job_id],
+                |row| {
+                    Ok(JobStats {
+                        runs: row.get::<_, i64>(0)? as u64,
+                        succeeded: row.get::<_, i64>(1)? as u64,
+                        failed: row.get::<_, i64>(2)? as u64,
+                        avg_duration_ms: row.get(3)?,
+                        max_duration_ms: row.get::<_, Option<f64>>(4)?.map(|v| v.round() as u64),
+                        last_exit_code: None,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to query job stats: {}", e))?;
+        stats.last_exit_code = self
+            .conn
+            .query_row(
+                "SELECT exit_code FROM runs
+                 WHERE job_name = ?1 AND exit_code IS NOT NULL AND skipped = 0
+                 ORDER BY started_at DESC LIMIT 1",
+                params![job_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query job stats: {}", e))?;
+        Ok(stats)
+    }
+
     pub fn update_finished(
         &self,
         id: &str,
@@ -223,41 +576,53 @@ impl HistoryStore {
         self.conn
             .execute(
                 "UPDATE runs SET finished_at = ?1, exit_code = ?2, stdout = ?3, stderr = ?4 WHERE id = ?5",
-                params![finished_at, exit_code, stdout, stderr, id],
+                params![
+                    finished_at,
+                    exit_code,
+                    self.seal(stdout),
+                    self.seal(stderr),
+                    id
+                ],
+            )
+            .map_err(|e| format!("Failed to update run record: {}", e))?;
+        Ok(())
+    }
+
+    /// Append `text` to a run's stderr on a line of its own.
+    pub fn append_stderr(&self, id: &str, text: &str) -> Result<(), String> {
+        let record = self
+            .get_by_id(id)?
+            .ok_or_else(|| format!("Run not found: {}", id))?;
+        let mut stderr = record.stderr;
+        if !stderr.is_empty() && !stderr.ends_with('\n') {
+            stderr.push('\n');
+        }
+        stderr.push_str(text);
+        self.conn
+            .execute(
+                "UPDATE runs SET stderr = ?1 WHERE id = ?2",
+                params![self.seal(&stderr), id],
             )
             .map_err(|e| format!("Failed to update run record: {}", e))?;
         Ok(())
     }
 
-    pub fn get_recent(&self, limit: usize) -> Result<Vec<RunRecord>, String> {
+    pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<RunRecord>, String> {
         let mut stmt = self
             .conn
-            .prepare(
-                "SELECT id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path
-                 FROM runs ORDER BY started_at DESC LIMIT ?1",
-            )
+            .prepare(&format!(
+                "SELECT {} FROM runs ORDER BY started_at DESC LIMIT ?1 OFFSET ?2",
+                RUN_COLUMNS
+            ))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let rows = stmt
-            .query_map(params![limit as i64], |row| {
-                Ok(RunRecord {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    finished_at: row.get(3)?,
-                    exit_code: row.get(4)?,
-                    trigger: row.get(5)?,
-                    stdout: row.get(6)?,
-                    stderr: row.get(7)?,
-                    pane_id: row.get(8)?,
-                    log_path: row.get(9)?,
-                })
-            })
+            .query_map(params![limit as i64, offset as i64], run_from_row)
             .map_err(|e| format!("Failed to query history: {}", e))?;
 
         let mut records = Vec::new();
         for row in rows {
-            records.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+            records.push(self.open(row.map_err(|e| format!("Failed to read row: {}", e))?));
         }
         Ok(records)
     }
@@ -265,131 +630,355 @@ impl HistoryStore {
     pub fn get_by_id(&self, id: &str) -> Result<Option<RunRecord>, String> {
         let mut stmt = self
             .conn
-            .prepare(
-                "SELECT id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path
-                 FROM runs WHERE id = ?1",
-            )
+            .prepare(&format!("SELECT {} FROM runs WHERE id = ?1", RUN_COLUMNS))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let mut rows = stmt
-            .query_map(params![id], |row| {
-                Ok(RunRecord {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    finished_at: row.get(3)?,
-                    exit_code: row.get(4)?,
-                    trigger: row.get(5)?,
-                    stdout: row.get(6)?,
-                    stderr: row.get(7)?,
-                    pane_id: row.get(8)?,
-                    log_path: row.get(9)?,
-                })
-            })
+            .query_map(params![id], run_from_row)
             .map_err(|e| format!("Failed to query history: {}", e))?;
 
         match rows.next() {
-            Some(Ok(record)) => Ok(Some(record)),
+            Some(Ok(record)) => Ok(Some(self.open(record))),
             Some(Err(e)) => Err(format!("Failed to read row: {}", e)),
             None => Ok(None),
         }
     }
 
-    pub fn get_by_job_id(&self, job_id: &str, limit: usize) -> Result<Vec<RunRecord>, String> {
+    pub fn get_by_job_id(
+        &self,
+        job_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<RunRecord>, String> {
         let mut stmt = self
             .conn
-            .prepare(
-                "SELECT id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path
-                 FROM runs WHERE job_name = ?1 ORDER BY started_at DESC LIMIT ?2",
-            )
+            .prepare(&format!(
+                "SELECT {} FROM runs WHERE job_name = ?1 \
+                 ORDER BY started_at DESC LIMIT ?2 OFFSET ?3",
+                RUN_COLUMNS
+            ))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let rows = stmt
-            .query_map(params![job_id, limit as i64], |row| {
-                Ok(RunRecord {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    finished_at: row.get(3)?,
-                    exit_code: row.get(4)?,
-                    trigger: row.get(5)?,
-                    stdout: row.get(6)?,
-                    stderr: row.get(7)?,
-                    pane_id: row.get(8)?,
-                    log_path: row.get(9)?,
-                })
-            })
+            .query_map(params![job_id, limit as i64, offset as i64], run_from_row)
             .map_err(|e| format!("Failed to query history: {}", e))?;
 
         let mut records = Vec::new();
         for row in rows {
-            records.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+            records.push(self.open(row.map_err(|e| format!("Failed to read row: {}", e))?));
         }
         Ok(records)
     }
 
-    pub fn get_unfinished_by_job(&self, job_id: &str) -> Result<Option<RunRecord>, String> {
+    /// Number of runs, optionally for one job.
+    pub fn count(&self, job_id: Option<&str>) -> Result<u64, String> {
+        let total: i64 = match job_id {
+            Some(job_id) => self.conn.query_row(
+                "SELECT COUNT(*) FROM runs WHERE job_name = ?1",
+                params![job_id],
+                |row| row.get(0),
+            ),
+            None => self
+                .conn
+                .query_row("SELECT COUNT(*) FROM runs", [], |row| row.get(0)),
+        }
+        .map_err(|e| format!("Failed to count history: {}", e))?;
+        Ok(u64::try_from(total).unwrap_or(0))
+    }
+
+    /// Most recent runs whose trigger is one of `triggers`, optionally limited
+    /// to one job. An empty `triggers` list matches every trigger.
+    pub fn get_filtered(
+        &self,
+        triggers: &[String],
+        job_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RunRecord>, String> {
+        let limit = limit as i64;
+        let mut sql_params: Vec<&dyn rusqlite::ToSql> = vec![&limit];
+        let mut clauses: Vec<String> = Vec::new();
+        if let Some(ref job_id) = job_id {
+            sql_params.push(job_id as &dyn rusqlite::ToSql);
+            clauses.push(format!("job_name = ?{}", sql_params.len()));
+        }
+        if !triggers.is_empty() {
+            let first = sql_params.len() + 1;
+            let placeholders: Vec<String> = (first..first + triggers.len())
+                .map(|i| format!("?{}", i))
+                .collect();
+            clauses.push(format!("trigger_type IN ({})", placeholders.join(", ")));
+            sql_params.extend(triggers.iter().map(|t| t as &dyn rusqlite::ToSql));
+        }
+        let filter = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {} ", clauses.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT {} FROM runs {}ORDER BY started_at DESC LIMIT ?1",
+            RUN_COLUMNS, filter
+        );
+
         let mut stmt = self
             .conn
-            .prepare(
-                "SELECT id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path
-                 FROM runs WHERE job_name = ?1 AND finished_at IS NULL ORDER BY started_at DESC LIMIT 1",
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(sql_params.as_slice(), run_from_row)
+            .map_err(|e| format!("Failed to query history: {}", e))?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(self.open(row.map_err(|e| format!("Failed to read row: {}", e))?));
+        }
+        Ok(records)
+    }
+
+    /// Most recent runs whose job name, stdout or stderr contains `query`
+    /// (case-insensitive for ASCII). `%` and `_` in the query match literally.
+    /// Encrypted output can't be matched in SQL, so with a key loaded those
+    /// rows are decrypted and matched here.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<RunRecord>, String> {
+        let pattern = format!(
+            "%{}%",
+            query
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let encrypted = format!("{}%", cipher::PREFIX);
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM runs \
+                 WHERE job_name LIKE ?1 ESCAPE '\\' \
+                    OR stdout LIKE ?1 ESCAPE '\\' \
+                    OR stderr LIKE ?1 ESCAPE '\\' \
+                    OR (?2 AND (stdout LIKE ?3 OR stderr LIKE ?3)) \
+                 ORDER BY started_at DESC",
+                RUN_COLUMNS
+            ))
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![pattern, self.cipher.is_some(), encrypted],
+                run_from_row,
             )
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+
+        let needle = query.to_ascii_lowercase();
+        let matches = |text: &str| text.to_ascii_lowercase().contains(&needle);
+        let mut records = Vec::new();
+        for row in rows {
+            if records.len() >= limit {
+                break;
+            }
+            let record = self.open(row.map_err(|e| format!("Failed to read row: {}", e))?);
+            if matches(&record.job_id) || matches(&record.stdout) || matches(&record.stderr) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn get_unfinished_by_job(&self, job_id: &str) -> Result<Option<RunRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM runs WHERE job_name = ?1 AND finished_at IS NULL ORDER BY started_at DESC LIMIT 1",
+                RUN_COLUMNS
+            ))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let mut rows = stmt
-            .query_map(params![job_id], |row| {
-                Ok(RunRecord {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    finished_at: row.get(3)?,
-                    exit_code: row.get(4)?,
-                    trigger: row.get(5)?,
-                    stdout: row.get(6)?,
-                    stderr: row.get(7)?,
-                    pane_id: row.get(8)?,
-                    log_path: row.get(9)?,
-                })
-            })
+            .query_map(params![job_id], run_from_row)
             .map_err(|e| format!("Failed to query history: {}", e))?;
 
         match rows.next() {
-            Some(Ok(record)) => Ok(Some(record)),
+            Some(Ok(record)) => Ok(Some(self.open(record))),
             Some(Err(e)) => Err(format!("Failed to read row: {}", e)),
             None => Ok(None),
         }
     }
 
-    pub fn get_unfinished_with_pane(&self) -> Result<Vec<RunRecord>, String> {
+    /// `(finished_at, exit_code)` of each job's most recent finished run,
+    /// keyed by job id. Skipped runs don't count.
+    pub fn last_finished_runs(
+        &self,
+    ) -> Result<std::collections::HashMap<String, (String, Option<i32>)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT job_name, finished_at, exit_code FROM runs r
+                 WHERE finished_at IS NOT NULL AND skipped = 0 AND NOT EXISTS (
+                   SELECT 1 FROM runs n
+                   WHERE n.job_name = r.job_name AND n.finished_at IS NOT NULL
+                     AND n.skipped = 0 AND n.started_at > r.started_at
+                 )",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (row.get::<_, String>(1)?, row.get::<_, Option<i32>>(2)?),
+                ))
+            })
+            .map_err(|e| format!("Failed to query last runs: {}", e))?;
+        let mut map = std::collections::HashMap::new();
+        for r in rows {
+            let (job_id, run) = r.map_err(|e| format!("Failed to read row: {}", e))?;
+            map.insert(job_id, run);
+        }
+        Ok(map)
+    }
+
+    /// How many of the job's most recent finished runs in a row ended with
+    /// `exit_code`. Skipped runs don't count.
+    pub fn failure_streak(&self, job_id: &str, exit_code: Option<i32>) -> Result<u32, String> {
         let mut stmt = self
             .conn
             .prepare(
-                "SELECT id, job_name, started_at, finished_at, exit_code, trigger_type, stdout, stderr, pane_id, log_path
-                 FROM runs WHERE finished_at IS NULL AND pane_id IS NOT NULL ORDER BY started_at DESC",
+                "SELECT exit_code FROM runs
+                 WHERE job_name = ?1 AND finished_at IS NOT NULL AND skipped = 0
+                 ORDER BY started_at DESC",
             )
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(params![job_id], |row| row.get::<_, Option<i32>>(0))
+            .map_err(|e| format!("Failed to query history: {}", e))?;
+        let mut streak = 0;
+        for r in rows {
+            if r.map_err(|e| format!("Failed to read row: {}", e))? != exit_code {
+                break;
+            }
+            streak += 1;
+        }
+        Ok(streak)
+    }
 
+    /// `started_at` of each job's most recent run, keyed by job id.
+    pub fn last_started_runs(&self) -> Result<std::collections::HashMap<String, String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT job_name, MAX(started_at) FROM runs GROUP BY job_name")
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
         let rows = stmt
             .query_map([], |row| {
-                Ok(RunRecord {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    finished_at: row.get(3)?,
-                    exit_code: row.get(4)?,
-                    trigger: row.get(5)?,
-                    stdout: row.get(6)?,
-                    stderr: row.get(7)?,
-                    pane_id: row.get(8)?,
-                    log_path: row.get(9)?,
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("Failed to query last runs: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read row: {}", e))
+    }
+
+    pub fn record_auto_answer(&self, record: &AutoAnswerRecord) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO auto_answers (run_id, answered_at, pattern, answer, prompt)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    record.run_id,
+                    record.answered_at,
+                    record.pattern,
+                    record.answer,
+                    self.seal(&record.prompt),
+                ],
+            )
+            .map_err(|e| format!("Failed to record auto-answer: {}", e))?;
+        Ok(())
+    }
+
+    /// Auto-answers given during a run, oldest first.
+    pub fn get_auto_answers(&self, run_id: &str) -> Result<Vec<AutoAnswerRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT run_id, answered_at, pattern, answer, prompt FROM auto_answers
+                 WHERE run_id = ?1 ORDER BY answered_at",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok(AutoAnswerRecord {
+                    run_id: row.get(0)?,
+                    answered_at: row.get(1)?,
+                    pattern: row.get(2)?,
+                    answer: row.get(3)?,
+                    prompt: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query auto-answers: {}", e))?;
+        rows.map(|r| {
+            r.map(|mut record| {
+                record.prompt = self.open_output(std::mem::take(&mut record.prompt));
+                record
+            })
+            .map_err(|e| format!("Failed to read row: {}", e))
+        })
+        .collect()
+    }
+
+    pub fn record_run_item(&self, record: &RunItemRecord) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO run_items (run_id, item_index, item, started_at, finished_at, exit_code)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    record.run_id,
+                    record.item_index,
+                    record.item,
+                    record.started_at,
+                    record.finished_at,
+                    record.exit_code,
+                ],
+            )
+            .map_err(|e| format!("Failed to record run item: {}", e))?;
+        Ok(())
+    }
+
+    /// Per-item results of a batch run, in item order.
+    pub fn get_run_items(&self, run_id: &str) -> Result<Vec<RunItemRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT run_id, item_index, item, started_at, finished_at, exit_code FROM run_items
+                 WHERE run_id = ?1 ORDER BY item_index",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok(RunItemRecord {
+                    run_id: row.get(0)?,
+                    item_index: row.get(1)?,
+                    item: row.get(2)?,
+                    started_at: row.get(3)?,
+                    finished_at: row.get(4)?,
+                    exit_code: row.get(5)?,
                 })
             })
+            .map_err(|e| format!("Failed to query run items: {}", e))?;
+        rows.map(|r| r.map_err(|e| format!("Failed to read row: {}", e)))
+            .collect()
+    }
+
+    pub fn get_unfinished_with_pane(&self) -> Result<Vec<RunRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM runs WHERE finished_at IS NULL AND pane_id IS NOT NULL ORDER BY started_at DESC",
+                RUN_COLUMNS
+            ))
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map([], run_from_row)
             .map_err(|e| format!("Failed to query history: {}", e))?;
 
         let mut records = Vec::new();
         for row in rows {
-            records.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+            records.push(self.open(row.map_err(|e| format!("Failed to read row: {}", e))?));
         }
         Ok(records)
     }
@@ -421,6 +1010,19 @@ impl HistoryStore {
         Ok(map)
     }
 
+    /// Move all runs of `old_slug` to `new_slug`, pointing their log paths at
+    /// the job's new directory.
+    pub fn rename_job(&self, old_slug: &str, new_slug: &str) -> Result<usize, String> {
+        self.conn
+            .execute(
+                "UPDATE runs SET job_name = ?2,
+                    log_path = REPLACE(log_path, '/jobs/' || ?1 || '/', '/jobs/' || ?2 || '/')
+                 WHERE job_name = ?1",
+                params![old_slug, new_slug],
+            )
+            .map_err(|e| format!("Failed to rename job in history: {}", e))
+    }
+
     pub fn delete_by_id(&self, id: &str) -> Result<(), String> {
         self.conn
             .execute("DELETE FROM runs WHERE id = ?1", params![id])
@@ -483,6 +1085,64 @@ impl HistoryStore {
         Ok(pane_ids)
     }
 
+    /// Delete runs (and their on-disk logs) older than each job's retention
+    /// window. Jobs with `history_retention_days` use their own cutoff; every
+    /// other row falls back to `default_days`. A window of 0 days never prunes.
+    pub fn prune_expired(&self, jobs: &[Job], default_days: u32) -> Result<(), String> {
+        let overridden: Vec<(&str, u32)> = jobs
+            .iter()
+            .filter_map(|j| j.history_retention_days.map(|d| (j.slug.as_str(), d)))
+            .collect();
+
+        for (slug, days) in overridden.iter().filter(|(_, days)| *days > 0) {
+            self.conn
+                .execute(
+                    "DELETE FROM runs WHERE job_name = ?1 AND started_at < datetime('now', ?2)",
+                    params![slug, format!("-{} days", days)],
+                )
+                .map_err(|e| format!("Failed to prune history for {}: {}", slug, e))?;
+        }
+
+        if default_days > 0 {
+            self.prune_default(&overridden, default_days)?;
+        }
+
+        if let Some(jobs_dir) = crate::config::jobs::JobsConfig::jobs_dir_public() {
+            for job in jobs {
+                let days = job.history_retention_days.unwrap_or(default_days);
+                if days > 0 {
+                    prune_log_dir(&jobs_dir.join(&job.slug).join("logs"), days);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete runs older than `days` for every job not in `overridden`.
+    fn prune_default(&self, overridden: &[(&str, u32)], days: u32) -> Result<(), String> {
+        let modifier = format!("-{} days", days);
+        let mut sql = "DELETE FROM runs WHERE started_at < datetime('now', ?1)".to_string();
+        if !overridden.is_empty() {
+            let placeholders: Vec<String> = (2..=overridden.len() + 1)
+                .map(|i| format!("?{}", i))
+                .collect();
+            sql.push_str(&format!(
+                " AND job_name NOT IN ({})",
+                placeholders.join(", ")
+            ));
+        }
+        let mut sql_params: Vec<&dyn rusqlite::ToSql> = vec![&modifier];
+        sql_params.extend(
+            overridden
+                .iter()
+                .map(|(slug, _)| slug as &dyn rusqlite::ToSql),
+        );
+        self.conn
+            .execute(&sql, sql_params.as_slice())
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        Ok(())
+    }
+
     pub fn clear(&self) -> Result<(), String> {
         self.conn
             .execute("DELETE FROM runs", [])
@@ -490,3 +1150,133 @@ impl HistoryStore {
         Ok(())
     }
 }
+
+/// Remove log files under `dir` last modified more than `days` ago.
+fn prune_log_dir(dir: &Path, days: u32) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let max_age = std::time::Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let expired = entry
+            .metadata()
+            .ok()
+            .filter(|m| m.is_file())
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if expired {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove expired log {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str, job_id: &str, started_at: &str, stdout: &str) -> RunRecord {
+        RunRecord {
+            id: id.to_string(),
+            job_id: job_id.to_string(),
+            started_at: started_at.to_string(),
+            finished_at: None,
+            exit_code: Some(0),
+            trigger: "manual".to_string(),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            pane_id: None,
+            log_path: None,
+            git_branch: None,
+            git_commit: None,
+            launch_ms: None,
+            output_truncated: false,
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn search_matches_decrypted_output() {
+        let mut store =
+            HistoryStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        store
+            .insert(&run("r1", "plain", "2026-01-01T00:00:00Z", "Deploy OK"))
+            .unwrap();
+        store.cipher = Some(HistoryCipher::from_key(&[7u8; 32]).unwrap());
+        store.encrypt = true;
+        store
+            .insert(&run(
+                "r2",
+                "sealed",
+                "2026-01-02T00:00:00Z",
+                "deploy failed",
+            ))
+            .unwrap();
+        store
+            .insert(&run("r3", "other", "2026-01-03T00:00:00Z", "nothing here"))
+            .unwrap();
+
+        let ids = |records: Vec<RunRecord>| records.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(store.search("DEPLOY", 10).unwrap()), ["r2", "r1"]);
+        assert_eq!(ids(store.search("deploy", 1).unwrap()), ["r2"]);
+        assert_eq!(ids(store.search("nothing", 10).unwrap()), ["r3"]);
+    }
+
+    #[test]
+    fn get_filtered_matches_trigger_set_and_job() {
+        let store = HistoryStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        for (id, job, trigger) in [
+            ("r1", "deploy", "cron"),
+            ("r2", "deploy", "manual"),
+            ("r3", "backup", "cron"),
+            ("r4", "deploy", "telegram"),
+        ] {
+            let mut record = run(id, job, &format!("2026-01-0{}T00:00:00Z", &id[1..]), "");
+            record.trigger = trigger.to_string();
+            store.insert(&record).unwrap();
+        }
+
+        let ids = |records: Vec<RunRecord>| records.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        let triggers = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            ids(store.get_filtered(&[], None, 10).unwrap()),
+            ["r4", "r3", "r2", "r1"]
+        );
+        assert_eq!(
+            ids(store
+                .get_filtered(&triggers(&["cron", "manual"]), None, 10)
+                .unwrap()),
+            ["r3", "r2", "r1"]
+        );
+        assert_eq!(
+            ids(store
+                .get_filtered(&triggers(&["cron"]), Some("deploy"), 10)
+                .unwrap()),
+            ["r1"]
+        );
+        assert_eq!(
+            ids(store.get_filtered(&[], Some("deploy"), 2).unwrap()),
+            ["r4", "r2"]
+        );
+    }
+
+    #[test]
+    fn full_output_joins_streams_and_prefers_a_longer_log() {
+        let mut record = run("r1", "job", "2026-01-01T00:00:00Z", "out");
+        assert_eq!(record.full_output(), "out");
+        record.stderr = "err".to_string();
+        assert_eq!(record.full_output(), "out\n--- stderr ---\nerr");
+
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("run.log");
+        std::fs::write(&log, "full pane capture from the log file").unwrap();
+        record.log_path = Some(log.to_string_lossy().into_owned());
+        assert_eq!(record.full_output(), "full pane capture from the log file");
+
+        std::fs::write(&log, "x").unwrap();
+        assert_eq!(record.full_output(), "out\n--- stderr ---\nerr");
+    }
+}
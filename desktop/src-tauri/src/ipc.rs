@@ -27,12 +27,17 @@ pub enum IpcCommand {
     ListJobs,
     RunJob {
         name: String,
+        /// Values for `{placeholder}` params in folder job prompts.
+        #[serde(default)]
+        params: std::collections::HashMap<String, String>,
     },
     /// Start a job for the CLI and return the generated run id immediately.
     /// The CLI uses that id to follow the correct binary log even when a job
     /// finishes before the next status poll.
     RunJobCli {
         name: String,
+        #[serde(default)]
+        params: std::collections::HashMap<String, String>,
     },
     PauseJob {
         name: String,
@@ -44,6 +49,10 @@ pub enum IpcCommand {
         name: String,
     },
     GetStatus,
+    /// Replace the daemon's job status map (debug builds or CLAWTAB_DEV=1).
+    ImportJobStatus {
+        statuses: std::collections::HashMap<String, crate::config::jobs::JobStatus>,
+    },
     OpenSettings,
     GetAutoYesPanes,
     SetAutoYesPanes {
@@ -88,6 +97,12 @@ pub enum IpcCommand {
     GetRelayStatus,
     RelayConnect,
     RelayDisconnect,
+    /// Round-trip a protocol ping through the relay server.
+    RelayPing,
+    /// Recent relay messages that could not be delivered.
+    GetDroppedRelayMessages,
+    /// Whether scheduled agent jobs are held back for Claude usage.
+    GetUsagePause,
 
     // Settings
     ReloadSettings,
@@ -100,12 +115,22 @@ pub enum IpcCommand {
     StopJob {
         name: String,
     },
+    /// Drop the oldest run of the job still waiting for a `max_concurrent`
+    /// slot. Running instances are untouched.
+    CancelQueuedRun {
+        name: String,
+    },
     ToggleJob {
         name: String,
     },
     DeleteJob {
         name: String,
     },
+    /// A job's slug changed: move its status entry and reload the config.
+    RenameJobStatus {
+        old_slug: String,
+        new_slug: String,
+    },
 
     // Answer questions from UI (cross-process)
     AnswerQuestion {
@@ -120,6 +145,9 @@ pub enum IpcCommand {
     RunJobNow {
         name: String,
         params: std::collections::HashMap<String, String>,
+        /// Skip the `max_concurrent` queue.
+        #[serde(default)]
+        bypass_limit: bool,
     },
     SigintJob {
         name: String,
@@ -213,6 +241,9 @@ pub struct IpcRelayStatus {
     pub configured: bool,
     pub server_url: String,
     pub device_name: String,
+    /// The relay no longer serves this app's protocol version.
+    #[serde(default)]
+    pub update_required: bool,
 }
 
 /// Shared response type for both the daemon and desktop sockets. The desktop
@@ -238,6 +269,9 @@ pub enum IpcResponse {
         session_started_at: Option<String>,
     },
     RelayStatus(IpcRelayStatus),
+    RelayPing(crate::relay::RelayPingResult),
+    DroppedRelayMessages(Vec<crate::relay::DroppedRelayMessage>),
+    UsagePause(Option<crate::scheduler::usage_pause::UsagePause>),
     PaneCreated {
         pane_id: Option<String>,
         tmux_session: Option<String>,
@@ -264,6 +298,7 @@ pub enum IpcEvent {
     QuestionsChanged,
     AgentActivityChanged(Vec<AgentActivity>),
     RelayStatusChanged(IpcRelayStatus),
+    UsagePauseChanged(Option<crate::scheduler::usage_pause::UsagePause>),
     /// Daemon-originated notification request. The desktop client, when
     /// subscribed, displays this via tauri-plugin-notification. The daemon
     /// falls back to native engine notifications when no subscriber is present.
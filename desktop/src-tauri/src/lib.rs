@@ -7,7 +7,7 @@ mod aerospace;
 pub mod agent;
 pub mod agent_hooks;
 pub mod agent_session;
-#[cfg(feature = "desktop")]
+mod answer_timeout;
 mod browser;
 mod claude_usage;
 #[cfg(feature = "desktop")]
@@ -29,6 +29,7 @@ pub mod native_notifications;
 pub mod notifications;
 pub mod process_snapshot;
 pub mod pty;
+mod question_image;
 pub mod questions;
 pub mod relay;
 pub mod scheduler;
@@ -57,7 +58,7 @@ use tauri::{
 };
 
 #[cfg(feature = "desktop")]
-use clawtab_protocol::ClaudeQuestion;
+use clawtab_protocol::{ClaudeQuestion, NoticeLevel};
 
 #[cfg(feature = "desktop")]
 use config::jobs::{JobStatus, JobsConfig};
@@ -87,6 +88,10 @@ pub struct AppState {
     pub notification_state: Arc<Mutex<notifications::NotificationState>>,
     pub app_handle: Arc<Mutex<Option<tauri::AppHandle>>>,
     pub pty_manager: pty::SharedPtyManager,
+    /// Last usage shown in the tray, reused when only the pause changes.
+    pub tray_usage: Arc<Mutex<Option<usage::UsageSnapshot>>>,
+    /// Set while the daemon holds back scheduled agent jobs for usage.
+    pub usage_pause: Arc<Mutex<Option<scheduler::usage_pause::UsagePause>>>,
 }
 
 #[cfg(feature = "desktop")]
@@ -249,15 +254,28 @@ pub(crate) fn refresh_tray_usage_menu(
         return Ok(());
     };
 
-    let show_tray_icon = app
-        .try_state::<AppState>()
+    let state = app.try_state::<AppState>();
+    let show_tray_icon = state
+        .as_ref()
         .map(|state| state.settings.lock().show_tray_icon)
         .unwrap_or(true);
     tray.set_visible(show_tray_icon)?;
 
+    // Without a new snapshot (e.g. only the pause changed) keep the last one.
+    let cached = state.as_ref().and_then(|state| {
+        let mut tray_usage = state.tray_usage.lock();
+        if let Some(snapshot) = snapshot {
+            *tray_usage = Some(snapshot.clone());
+        }
+        tray_usage.clone()
+    });
+    let pause = state
+        .as_ref()
+        .and_then(|state| state.usage_pause.lock().clone());
+
     let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
     let sep1 = PredefinedMenuItem::separator(app)?;
-    let (claude, codex, antigravity, zai) = tray_usage_labels(snapshot);
+    let (claude, codex, antigravity, zai) = tray_usage_labels(cached.as_ref().or(snapshot));
     let claude_item = MenuItem::with_id(app, "usage_claude", claude, false, None::<&str>)?;
     let codex_item = MenuItem::with_id(app, "usage_codex", codex, false, None::<&str>)?;
     let antigravity_item =
@@ -278,9 +296,31 @@ pub(crate) fn refresh_tray_usage_menu(
             &quit_item,
         ],
     )?;
+    if let Some(pause) = pause {
+        let label = format!(
+            "Scheduled agent jobs paused (Claude at {:.0}%)",
+            pause.utilization
+        );
+        let pause_item = MenuItem::with_id(app, "usage_pause", label, false, None::<&str>)?;
+        tray_menu.insert(&pause_item, 2)?;
+    }
     tray.set_menu(Some(tray_menu))
 }
 
+/// Record whether the daemon is holding back scheduled agent jobs, and show
+/// it in the tray and the UI.
+#[cfg(feature = "desktop")]
+pub(crate) fn set_usage_pause(
+    app: &tauri::AppHandle,
+    pause: Option<scheduler::usage_pause::UsagePause>,
+) {
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.usage_pause.lock() = pause.clone();
+    }
+    let _ = refresh_tray_usage_menu(app, None);
+    let _ = app.emit("usage-pause-changed", pause);
+}
+
 #[cfg(feature = "desktop")]
 fn tray_usage_labels(snapshot: Option<&usage::UsageSnapshot>) -> (String, String, String, String) {
     match snapshot {
@@ -458,6 +498,8 @@ fn build_app_state(
         notification_state: Arc::new(Mutex::new(notifications::NotificationState::new())),
         app_handle: Arc::clone(ipc_app_handle),
         pty_manager: Arc::clone(pty_manager),
+        tray_usage: Arc::new(Mutex::new(None)),
+        usage_pause: Arc::new(Mutex::new(None)),
     }
 }
 
@@ -698,8 +740,12 @@ fn register_menu_events(app: &tauri::App) {
 #[cfg(feature = "desktop")]
 fn start_usage_loop(app: &tauri::App) {
     let secrets_for_usage = app.state::<AppState>().secrets.clone();
+    let settings_for_usage = app.state::<AppState>().settings.clone();
+    let relay_for_usage = app.state::<AppState>().relay.clone();
     let app_for_usage = app.handle().clone();
     tauri::async_runtime::spawn(async move {
+        let mut reset_tracker = claude_usage::UsageResetTracker::default();
+        let mut threshold_tracker = claude_usage::UsageThresholdTracker::default();
         loop {
             let zai_token = {
                 let secrets = secrets_for_usage.lock();
@@ -711,11 +757,79 @@ fn start_usage_loop(app: &tauri::App) {
             };
             let usage = usage::fetch_usage_snapshot(zai_token).await;
             let _ = refresh_tray_usage_menu(&app_for_usage, Some(&usage));
+            notify_usage_events(
+                &settings_for_usage,
+                &relay_for_usage,
+                &mut reset_tracker,
+                &mut threshold_tracker,
+            )
+            .await;
             tokio::time::sleep(std::time::Duration::from_secs(5 * 60)).await;
         }
     });
 }
 
+/// Tell Telegram and the relay when a Claude quota bucket drops back to zero
+/// or crosses a usage alert threshold. Only polls the raw usage API when one
+/// of those settings is on.
+#[cfg(feature = "desktop")]
+async fn notify_usage_events(
+    settings: &Arc<Mutex<AppSettings>>,
+    relay: &Arc<Mutex<Option<relay::RelayHandle>>>,
+    tracker: &mut claude_usage::UsageResetTracker,
+    thresholds: &mut claude_usage::UsageThresholdTracker,
+) {
+    let (telegram, notify_reset, alerts) = {
+        let s = settings.lock();
+        if !s.notify_usage_reset && !s.usage_alerts.enabled {
+            return;
+        }
+        (
+            s.telegram.clone(),
+            s.notify_usage_reset,
+            s.usage_alerts.clone(),
+        )
+    };
+    let telegram = telegram.filter(|t| t.is_configured());
+    // Cached fallback readings carry no new information to alert on.
+    let usage = match claude_usage::fetch_usage().await {
+        Ok(u) if u.cached_at.is_some() => return,
+        Ok(u) => u,
+        Err(e) => {
+            log::debug!("Usage check skipped: {}", e);
+            return;
+        }
+    };
+    if notify_reset {
+        for bucket in tracker.observe(&usage) {
+            let body = format!("{} quota reset, fresh capacity available", bucket);
+            crate::relay::push_notice(relay, NoticeLevel::Info, "Claude", &body, false);
+            if let Some(telegram) = &telegram {
+                telegram::notify(telegram, &format!("<b>Claude</b>: {}", body)).await;
+            }
+        }
+    }
+    if alerts.enabled {
+        for alert in thresholds.observe(&usage, alerts.warn_percent, alerts.critical_percent) {
+            let (label, level) = match alert.level {
+                claude_usage::UsageLevel::Critical => ("critical", NoticeLevel::Error),
+                _ => ("warning", NoticeLevel::Warning),
+            };
+            let mut body = format!(
+                "{} quota at {:.0}% ({})",
+                alert.bucket, alert.usage.utilization, label
+            );
+            if let Some(resets) = alert.usage.resets_in_human() {
+                body.push_str(&format!(", resets {}", resets));
+            }
+            crate::relay::push_notice(relay, level, "Claude", &body, false);
+            if let Some(telegram) = &telegram {
+                telegram::notify(telegram, &format!("<b>Claude</b>: {}", body)).await;
+            }
+        }
+    }
+}
+
 #[cfg(feature = "desktop")]
 fn register_settings_close_hide(app: &tauri::App) {
     if let Some(settings_window) = app.get_webview_window("settings") {
@@ -758,6 +872,7 @@ pub fn run() {
     let history = Arc::new(Mutex::new(
         HistoryStore::new().expect("failed to initialize history database"),
     ));
+    history::configure_encryption(&history, &secrets, settings.lock().encrypt_history);
     run_startup_migrations(&jobs_config);
     refresh_agent_contexts(&settings, &jobs_config);
 
@@ -786,11 +901,21 @@ pub fn run() {
             commands::agent_hooks::install_agent_integration,
             commands::agent_hooks::remove_agent_integration,
             commands::jobs::get_jobs,
+            commands::jobs::get_next_runs,
+            commands::jobs::simulate_schedule,
+            commands::jobs::preview_claude_settings,
+            commands::jobs::validate_folder_job,
+            commands::jobs::preview_job,
+            commands::jobs::get_job_params,
+            commands::jobs::normalize_job_slugs,
+            commands::jobs::migrate_cwt_layout,
             commands::jobs::get_cached_jobs_snapshot,
             commands::jobs::save_cached_jobs_snapshot,
             commands::jobs::save_job,
             commands::jobs::rename_job,
+            commands::jobs::reorder_jobs,
             commands::jobs::import_job_folder,
+            commands::jobs::discover_cwt_jobs,
             commands::jobs::duplicate_job,
             commands::jobs::delete_job,
             commands::jobs::toggle_job,
@@ -799,6 +924,7 @@ pub fn run() {
             commands::jobs::resume_job,
             commands::jobs::sigint_job,
             commands::jobs::stop_job,
+            commands::jobs::cancel_queued_run,
             commands::jobs::restart_job,
             commands::jobs::run_agent,
             commands::jobs::open_agent_editor,
@@ -818,15 +944,28 @@ pub fn run() {
             commands::jobs::write_cwt_shared_at,
             commands::jobs::derive_job_slug,
             commands::secrets::list_secrets,
+            commands::secrets::get_keychain_status,
             commands::secrets::set_secret,
             commands::secrets::delete_secret,
             commands::secrets::gopass_available,
             commands::secrets::list_gopass_store,
             commands::secrets::fetch_gopass_value,
             commands::history::get_history,
+            commands::history::get_history_filtered,
+            commands::history::get_history_page,
+            commands::history::search_history,
+            commands::history::get_retention,
+            commands::history::set_retention,
+            commands::history::get_launch_stats,
+            commands::history::get_job_stats,
+            commands::history::get_auto_answers,
+            commands::history::get_run_items,
             commands::history::get_run_detail,
             commands::history::get_job_runs,
+            commands::history::get_run_output,
+            commands::history::share_run,
             commands::history::open_run_log,
+            commands::history::export_run_bundle,
             commands::history::tail_run_log,
             commands::history::delete_run,
             commands::history::delete_runs,
@@ -838,6 +977,9 @@ pub fn run() {
             commands::settings::get_hostname,
             commands::settings::open_logs_folder,
             commands::status::get_job_statuses,
+            commands::status::get_group_statuses,
+            commands::status::export_job_status,
+            commands::status::import_job_status,
             commands::status::get_running_job_logs,
             commands::status::send_job_input,
             commands::tmux::list_tmux_sessions,
@@ -876,6 +1018,8 @@ pub fn run() {
             commands::browser::check_browser_session,
             commands::browser::clear_browser_session,
             commands::browser::check_playwright_installed,
+            commands::browser::export_session,
+            commands::browser::import_session,
             commands::settings::set_titlebar_visibility,
             commands::settings::set_tray_icon_visibility,
             commands::updater::get_version,
@@ -887,6 +1031,10 @@ pub fn run() {
             commands::relay::get_relay_settings,
             commands::relay::set_relay_settings,
             commands::relay::get_relay_status,
+            commands::self_test::self_test,
+            commands::relay::relay_ping_roundtrip,
+            commands::relay::relay_probe,
+            commands::relay::get_dropped_relay_messages,
             commands::relay::relay_login,
             commands::relay::relay_pair_device,
             commands::relay::relay_sign_out,
@@ -903,6 +1051,8 @@ pub fn run() {
             commands::processes::detect_processes,
             commands::processes::focus_detected_process,
             commands::processes::get_detected_process_logs,
+            commands::processes::archive_detected_process,
+            commands::processes::test_prompt_detection,
             commands::processes::send_detected_process_input,
             commands::processes::get_active_questions,
             commands::processes::get_auto_yes_panes,
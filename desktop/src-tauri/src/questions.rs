@@ -7,10 +7,14 @@ use clawtab_protocol::{ClaudeQuestion, QuestionOption};
 
 use crate::agent_hooks::{HookAgentState, HookRuntime};
 use crate::agent_session::{detect_process_provider, ProcessProvider, ProcessSnapshot};
-use crate::config::jobs::{JobStatus, JobsConfig};
-use crate::config::settings::AppSettings;
+use crate::answer_timeout::{question_summary, AnswerTimeouts, Due};
+use crate::config::jobs::{file_access_dirs, AutoAnswerRule, Job, JobStatus, JobsConfig};
+use crate::config::settings::{AnswerTimeoutAction, AppSettings, PromptDetectionSettings};
+use crate::history::{AutoAnswerRecord, HistoryStore};
 use crate::ipc::AgentActivity;
 use crate::relay::RelayHandle;
+use crate::scheduler::lifecycle::{self, Event};
+use crate::secrets::SecretsManager;
 
 type DetectedAgent = (
     String,
@@ -31,6 +35,9 @@ type DetectedAgent = (
 
 const RECENT_ACTIVITY_WINDOW: Duration = Duration::from_secs(8);
 const PROCESS_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+/// Loop ticks an auto-answered question may stay on screen before the
+/// answer is sent again.
+const AUTO_ANSWER_RETRY_TICKS: u32 = 6;
 
 /// Strip ANSI escape sequences from text.
 fn strip_ansi(text: &str) -> String {
@@ -78,11 +85,40 @@ fn strip_ansi(text: &str) -> String {
     result
 }
 
+/// Characters that may prefix an option line (cursor, quote and list markers).
+const OPTION_MARKERS: &str = ">~`|›»❯▸▶";
+/// Separator between the option number and its label.
+const NUMBER_SEPARATOR: &str = ". ";
+
+/// What the numbered-prompt detector found in a piece of pane output.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PromptDetection {
+    /// The last contiguous group of numbered options, before prompt gating.
+    pub candidates: Vec<QuestionOption>,
+    /// Whether the output contains an interactive prompt indicator.
+    pub has_prompt_indicator: bool,
+    /// Options that would be reported as a question.
+    pub options: Vec<QuestionOption>,
+}
+
 /// Parse numbered options from interactive terminal output.
 /// Matches lines like "1. Fix the bug" or "  > 2. Skip this step"
 /// Only returns options if the output looks like an interactive prompt
 /// (contains prompt indicators like navigation hints or approval text.)
 pub fn parse_numbered_options(text: &str) -> Vec<QuestionOption> {
+    parse_numbered_options_with(text, &PromptDetectionSettings::default())
+}
+
+/// [`parse_numbered_options`] with user-supplied patterns added to the built-ins.
+pub fn parse_numbered_options_with(
+    text: &str,
+    patterns: &PromptDetectionSettings,
+) -> Vec<QuestionOption> {
+    detect_numbered_prompt(text, patterns).options
+}
+
+/// Run the numbered-prompt detector and report each stage of the result.
+pub fn detect_numbered_prompt(text: &str, patterns: &PromptDetectionSettings) -> PromptDetection {
     let text = &strip_ansi(text);
     let lines: Vec<&str> = text.lines().collect();
     let tail = if lines.len() > 30 {
@@ -90,6 +126,14 @@ pub fn parse_numbered_options(text: &str) -> Vec<QuestionOption> {
     } else {
         &lines
     };
+    let mut separators = vec![NUMBER_SEPARATOR];
+    separators.extend(
+        patterns
+            .number_separators
+            .iter()
+            .map(String::as_str)
+            .filter(|s| !s.is_empty()),
+    );
 
     // Collect all contiguous groups of numbered items, keep only the last group.
     // This avoids picking up numbered plans/lists that appear before the actual prompt.
@@ -97,33 +141,12 @@ pub fn parse_numbered_options(text: &str) -> Vec<QuestionOption> {
     let mut current_group: Vec<QuestionOption> = Vec::new();
 
     for line in tail {
-        let trimmed =
-            line.trim_start_matches(|c: char| c.is_whitespace() || ">~`|›»❯▸▶".contains(c));
-        if let Some(rest) = trimmed.strip_prefix(|c: char| c.is_ascii_digit()) {
-            let digit_end = rest.find(". ");
-            if let Some(dot_pos) = digit_end {
-                let number_str = &trimmed[..trimmed.len() - rest.len() + dot_pos];
-                if number_str.chars().all(|c| c.is_ascii_digit()) {
-                    let mut label = rest[dot_pos + 2..].trim().to_string();
-                    if !label.is_empty() {
-                        // Truncate long labels (e.g. "Yes, and don't ask again: mkdir -p ...")
-                        if label.len() > 60 {
-                            let mut end = 60;
-                            while end > 0 && !label.is_char_boundary(end) {
-                                end -= 1;
-                            }
-                            label = format!("{}...", label[..end].trim_end());
-                        }
-                        current_group.push(QuestionOption {
-                            number: number_str.to_string(),
-                            label,
-                            selected: false,
-                            col: 0,
-                        });
-                        continue;
-                    }
-                }
-            }
+        let trimmed = line.trim_start_matches(|c: char| {
+            c.is_whitespace() || OPTION_MARKERS.contains(c) || patterns.option_markers.contains(c)
+        });
+        if let Some(option) = parse_option_line(trimmed, &separators) {
+            current_group.push(option);
+            continue;
         }
         // Only break the group on lines that look like real content (not
         // description lines, separators, or blanks between numbered options).
@@ -145,17 +168,87 @@ pub fn parse_numbered_options(text: &str) -> Vec<QuestionOption> {
         groups.push(current_group);
     }
 
-    let options = groups.into_iter().last().unwrap_or_default();
-
-    if options.is_empty() {
-        return options;
+    let candidates = groups.into_iter().last().unwrap_or_default();
+    let has_prompt_indicator = has_interactive_prompt_indicator(text, &patterns.prompt_indicators);
+    let options = if has_prompt_indicator {
+        candidates.clone()
+    } else {
+        Vec::new()
+    };
+    PromptDetection {
+        candidates,
+        has_prompt_indicator,
+        options,
     }
+}
+
+/// Suffixes that mark a line as a yes/no confirmation, compared lowercased.
+const YES_NO_SUFFIXES: &[&str] = &["(y/n)", "[y/n]", "(yes/no)", "[yes/no]"];
 
-    if !has_interactive_prompt_indicator(text) {
+/// Detect a `(y/n)` / `[Y/n]` confirmation on the last non-empty line and
+/// return Yes/No options answered with `y` / `n`.
+pub fn parse_yes_no_options(text: &str) -> Vec<QuestionOption> {
+    let text = strip_ansi(text);
+    let Some(last) = text.lines().rev().map(str::trim).find(|l| !l.is_empty()) else {
+        return Vec::new();
+    };
+    let lower = last.to_lowercase();
+    let lower = lower.trim_end_matches(|c: char| c.is_whitespace() || ":?>".contains(c));
+    if !YES_NO_SUFFIXES.iter().any(|s| lower.ends_with(s)) {
         return Vec::new();
     }
+    [("y", "Yes"), ("n", "No")]
+        .into_iter()
+        .map(|(number, label)| QuestionOption {
+            number: number.to_string(),
+            label: label.to_string(),
+            selected: false,
+            col: 0,
+        })
+        .collect()
+}
+
+/// Detect a bare `> ` input prompt directly under a question, returning the
+/// question line. Requiring the question keeps an idle shell or agent input
+/// box from counting as a prompt.
+pub fn parse_free_text_prompt(text: &str) -> Option<String> {
+    let text = strip_ansi(text);
+    let mut lines = text.lines().rev().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next()? != ">" {
+        return None;
+    }
+    let question = lines.next()?;
+    (question.ends_with('?') || question.ends_with(':')).then(|| question.to_string())
+}
 
-    options
+/// Parse "N<sep>label" where one of `separators` directly follows the number.
+fn parse_option_line(trimmed: &str, separators: &[&str]) -> Option<QuestionOption> {
+    let digits = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    if digits == 0 {
+        return None;
+    }
+    let rest = &trimmed[digits..];
+    let sep = separators.iter().find(|sep| rest.starts_with(**sep))?;
+    let mut label = rest[sep.len()..].trim().to_string();
+    if label.is_empty() {
+        return None;
+    }
+    // Truncate long labels (e.g. "Yes, and don't ask again: mkdir -p ...")
+    if label.len() > 60 {
+        let mut end = 60;
+        while end > 0 && !label.is_char_boundary(end) {
+            end -= 1;
+        }
+        label = format!("{}...", label[..end].trim_end());
+    }
+    Some(QuestionOption {
+        number: trimmed[..digits].to_string(),
+        label,
+        selected: false,
+        col: 0,
+    })
 }
 
 /// Check whether the terminal output contains indicators of an interactive prompt.
@@ -165,7 +258,7 @@ pub fn parse_numbered_options(text: &str) -> Vec<QuestionOption> {
 /// Both should be detected so notification cards appear for all interactive prompts.
 /// Checks the last 12 non-empty lines (not just the very last) to handle trailing
 /// whitespace or invisible characters left by TUI rendering.
-fn has_interactive_prompt_indicator(text: &str) -> bool {
+fn has_interactive_prompt_indicator(text: &str, extra: &[String]) -> bool {
     let tail: Vec<String> = text
         .lines()
         .rev()
@@ -188,6 +281,10 @@ fn has_interactive_prompt_indicator(text: &str) -> bool {
     joined.contains("would you like to run the following command")
         || joined.contains("would you like to run this command")
         || joined.contains("yes, proceed (y)")
+        || extra
+            .iter()
+            .map(|phrase| phrase.trim().to_lowercase())
+            .any(|phrase| !phrase.is_empty() && joined.contains(&phrase))
 }
 
 /// Check whether stripped terminal output looks like an opencode select-box prompt.
@@ -456,6 +553,110 @@ fn find_yes_option(options: &[QuestionOption]) -> Option<String> {
     best.map(|(opt, _)| opt.number.clone())
 }
 
+/// How to turn a prompt down.
+#[derive(Debug, PartialEq, Eq)]
+enum Decline {
+    /// Pick the option with this number.
+    Option(String),
+    /// Press Esc. The only negative option asks what to do instead, and its
+    /// text input would leave the pane waiting.
+    Escape,
+}
+
+/// Find how to turn a prompt down: a bare "No" (or "Deny", ...) label first,
+/// then any other negative option that doesn't open a text input.
+fn find_no_option(options: &[QuestionOption]) -> Option<Decline> {
+    const NEGATIVE_PREFIXES: &[&str] = &["no", "deny", "reject", "decline", "cancel"];
+    let words = |opt: &QuestionOption| {
+        let lower = opt.label.to_lowercase();
+        lower
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_string()
+    };
+    let negative: Vec<(&QuestionOption, String)> = options
+        .iter()
+        .map(|opt| (opt, words(opt)))
+        .filter(|(_, label)| {
+            NEGATIVE_PREFIXES.iter().any(|p| {
+                label
+                    .strip_prefix(p)
+                    .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric()))
+            })
+        })
+        .collect();
+    let asks_for_text =
+        |label: &str| label.contains("tell ") || label.contains("what to do differently");
+    negative
+        .iter()
+        .find(|(_, label)| NEGATIVE_PREFIXES.contains(&label.as_str()))
+        .or_else(|| negative.iter().find(|(_, label)| !asks_for_text(label)))
+        .map(|(opt, _)| Decline::Option(opt.number.clone()))
+        .or_else(|| (!negative.is_empty()).then_some(Decline::Escape))
+}
+
+/// Press the option or key that turns a prompt down.
+async fn send_decline(q: &ClaudeQuestion, decline: Decline) -> Result<(), String> {
+    match decline {
+        Decline::Option(opt) => send_option(q, &opt).await,
+        Decline::Escape => crate::tmux::send_key_to_pane(&q.pane_id, "Escape"),
+    }
+}
+
+/// Path a Claude Code file permission prompt asks about: the argument of a
+/// `Read(...)`-style line, or the line under an "Edit file" or "Create file"
+/// header. None for any other prompt.
+fn file_prompt_path(context: &str) -> Option<String> {
+    const TOOLS: &[&str] = &["Read", "Edit", "MultiEdit", "Write", "NotebookEdit"];
+    const HEADERS: &[&str] = &["read file", "edit file", "create file", "write file"];
+    let is_frame = |c: char| c.is_whitespace() || matches!(c, '│' | '╭' | '╮' | '╰' | '╯' | '─');
+    let lines: Vec<&str> = context.lines().map(|l| l.trim_matches(is_frame)).collect();
+    let tool_arg = lines.iter().rev().find_map(|line| {
+        let (tool, rest) = line.split_once('(')?;
+        let arg = rest.strip_suffix(')')?;
+        (TOOLS.contains(&tool) && !arg.is_empty()).then(|| arg.to_string())
+    });
+    tool_arg.or_else(|| {
+        let header = lines
+            .iter()
+            .rposition(|l| HEADERS.contains(&l.to_lowercase().as_str()))?;
+        lines[header + 1..]
+            .iter()
+            .find(|l| !l.is_empty())
+            .map(|l| l.to_string())
+    })
+}
+
+/// `path` made absolute against `cwd`, with `~/` expanded and `.`/`..`
+/// resolved without touching the filesystem.
+fn resolve_path(path: &str, cwd: &str) -> std::path::PathBuf {
+    let expanded = match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => std::path::PathBuf::from(path),
+    };
+    let mut resolved = std::path::PathBuf::new();
+    for part in std::path::Path::new(cwd).join(expanded).components() {
+        match part {
+            std::path::Component::ParentDir => {
+                resolved.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// The path of a file permission prompt when it lies outside every one of
+/// `dirs`. Relative paths are taken from the pane's working directory.
+fn out_of_scope_path(q: &ClaudeQuestion, dirs: &[String]) -> Option<String> {
+    let path = file_prompt_path(&q.context_lines)?;
+    let target = resolve_path(&path, &q.cwd);
+    let allowed = dirs
+        .iter()
+        .any(|dir| target.starts_with(resolve_path(dir, &q.cwd)));
+    (!allowed).then_some(path)
+}
+
 /// Runs the question detection loop. The idle path is intentionally conservative:
 /// question detection is expensive because it scans tmux panes and captures output,
 /// so we back off heavily when there are no active prompts to watch.
@@ -472,12 +673,16 @@ pub async fn question_detection_loop(
     notification_state: Arc<Mutex<crate::notifications::NotificationState>>,
     event_sink: Arc<dyn crate::events::EventSink>,
     hook_runtime: HookRuntime,
+    history: Arc<Mutex<HistoryStore>>,
+    secrets: Arc<Mutex<SecretsManager>>,
 ) {
     let mut question_cache: HashMap<String, CachedQuestion> = HashMap::new();
     let mut activity_tracker = ActivityTracker::default();
     let mut last_sent_ids: HashSet<String> = HashSet::new();
     let mut ticks_since_send: u32 = 0;
     let mut auto_answered_ids: HashMap<String, u32> = HashMap::new();
+    let mut answer_timeouts = AnswerTimeouts::default();
+    let mut lifecycle_questions: HashMap<String, TrackedQuestion> = HashMap::new();
     let mut local_notifications_initialized = false;
     let mut question_signature = String::new();
     let mut process_snapshot = ProcessSnapshot::capture();
@@ -497,7 +702,9 @@ pub async fn question_detection_loop(
 
         prune_stale_auto_yes_panes(&auto_yes_panes, &detection.all_pane_ids);
 
-        let detected_panes = update_question_cache(&processes, &mut question_cache);
+        let prompt_patterns = settings.lock().prompt_detection.clone();
+        let detected_panes =
+            update_question_cache(&processes, &prompt_patterns, &mut question_cache);
         evict_stale_cache_entries(&mut question_cache, &detected_panes);
 
         let questions: Vec<ClaudeQuestion> = question_cache
@@ -505,7 +712,31 @@ pub async fn question_detection_loop(
             .map(|c| c.question.clone())
             .collect();
 
-        auto_answer_questions(&questions, &auto_yes_panes, &mut auto_answered_ids);
+        log_question_lifecycle(
+            &questions,
+            &job_status,
+            &detection.all_pane_ids,
+            &mut lifecycle_questions,
+        );
+        apply_auto_answer_rules(
+            &questions,
+            &jobs_config,
+            &job_status,
+            &history,
+            &secrets,
+            &settings,
+            &mut auto_answered_ids,
+        )
+        .await;
+        auto_answer_questions(
+            &questions,
+            &auto_yes_panes,
+            &jobs_config,
+            &job_status,
+            &settings,
+            &mut auto_answered_ids,
+        )
+        .await;
         retain_auto_answered_for_present(&questions, &mut auto_answered_ids);
 
         log::debug!("[questions] storing {} active questions", questions.len());
@@ -565,12 +796,30 @@ pub async fn question_detection_loop(
         send_relay_questions(
             questions.clone(),
             apns_questions,
+            settings_snapshot.question_screenshots,
             &auto_yes_panes,
             &relay,
             &mut last_sent_ids,
             &mut ticks_since_send,
         );
 
+        let unanswered = answer_timeouts.due(
+            &questions,
+            &auto_yes_panes.lock(),
+            &settings_snapshot.answer_timeout,
+            Instant::now(),
+        );
+        for (question, due) in unanswered {
+            handle_unanswered(
+                &question,
+                due,
+                settings_snapshot.answer_timeout.action,
+                &relay,
+                notifier.as_ref(),
+            )
+            .await;
+        }
+
         let sleep_ms = pick_sleep_ms(&auto_yes_panes, &question_cache, &processes, &hook_runtime);
         tokio::select! {
             () = tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)) => {}
@@ -579,6 +828,211 @@ pub async fn question_detection_loop(
     }
 }
 
+/// A question on a job run's pane, logged as `prompt_detected`.
+struct TrackedQuestion {
+    run_id: String,
+    slug: String,
+    pane_id: String,
+}
+
+/// Log `prompt_detected` for new questions on the panes of running jobs, and
+/// `answered` once such a question is gone while its pane is still open.
+fn log_question_lifecycle(
+    questions: &[ClaudeQuestion],
+    job_status: &Mutex<HashMap<String, JobStatus>>,
+    live_panes: &HashSet<String>,
+    tracked: &mut HashMap<String, TrackedQuestion>,
+) {
+    tracked.retain(|question_id, t| {
+        if questions.iter().any(|q| &q.question_id == question_id) {
+            return true;
+        }
+        if live_panes.contains(&t.pane_id) {
+            lifecycle::log(
+                Event::Answered,
+                &t.run_id,
+                &t.slug,
+                serde_json::json!({ "question_id": question_id, "pane_id": t.pane_id }),
+            );
+        }
+        false
+    });
+    let new: Vec<&ClaudeQuestion> = questions
+        .iter()
+        .filter(|q| !tracked.contains_key(&q.question_id))
+        .collect();
+    if new.is_empty() {
+        return;
+    }
+    let statuses = job_status.lock();
+    for q in new {
+        let Some((run_id, slug)) = run_on_pane(&statuses, &q.pane_id) else {
+            continue;
+        };
+        lifecycle::log(
+            Event::PromptDetected,
+            &run_id,
+            &slug,
+            serde_json::json!({
+                "question_id": q.question_id,
+                "pane_id": q.pane_id,
+                "options": q.options.len(),
+            }),
+        );
+        tracked.insert(
+            q.question_id.clone(),
+            TrackedQuestion {
+                run_id,
+                slug,
+                pane_id: q.pane_id.clone(),
+            },
+        );
+    }
+}
+
+/// Run id and slug of the running job whose pane is `pane_id`.
+fn run_on_pane(statuses: &HashMap<String, JobStatus>, pane_id: &str) -> Option<(String, String)> {
+    statuses.iter().find_map(|(slug, status)| match status {
+        JobStatus::Running {
+            run_id,
+            pane_id: Some(pane),
+            ..
+        } if pane == pane_id => Some((run_id.clone(), slug.clone())),
+        _ => None,
+    })
+}
+
+/// The first rule matching the prompt whose answer is one of its options.
+/// Free-text prompts take any answer.
+fn matching_rule<'a>(
+    rules: &'a [AutoAnswerRule],
+    q: &ClaudeQuestion,
+) -> Option<&'a AutoAnswerRule> {
+    rules.iter().find(|rule| {
+        rule.matches(&q.context_lines)
+            && (q.input_mode == "text" || q.options.iter().any(|o| o.number == rule.answer))
+    })
+}
+
+/// Answer questions on job panes from the job's `auto_answer` rules and
+/// record each answer in the run's history, with the job's secrets scrubbed
+/// from the prompt.
+async fn apply_auto_answer_rules(
+    questions: &[ClaudeQuestion],
+    jobs_config: &Mutex<JobsConfig>,
+    job_status: &Mutex<HashMap<String, JobStatus>>,
+    history: &Mutex<HistoryStore>,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+    auto_answered_ids: &mut HashMap<String, u32>,
+) {
+    for q in questions {
+        if auto_answered_ids
+            .get(&q.question_id)
+            .is_some_and(|ticks| *ticks < AUTO_ANSWER_RETRY_TICKS)
+        {
+            continue;
+        }
+        let Some((run_id, slug)) = run_on_pane(&job_status.lock(), &q.pane_id) else {
+            continue;
+        };
+        let Some((job, rule)) = job_rule(jobs_config, &slug, q) else {
+            continue;
+        };
+        log::info!(
+            "[questions] auto-answering pane {} question {} with {} (rule '{}' of {})",
+            q.pane_id,
+            q.question_id,
+            rule.answer,
+            rule.pattern,
+            slug
+        );
+        if let Err(e) = send_option(q, &rule.answer).await {
+            log::error!("[questions] auto-answer rule send_keys failed: {}", e);
+            continue;
+        }
+        auto_answered_ids.insert(q.question_id.clone(), 0);
+        let redact = crate::scheduler::executor::secret_values(&job, secrets, settings);
+        let record = AutoAnswerRecord {
+            run_id,
+            answered_at: chrono::Utc::now().to_rfc3339(),
+            pattern: rule.pattern,
+            answer: rule.answer,
+            prompt: crate::secrets::scrub(&q.context_lines, &redact),
+        };
+        if let Err(e) = history.lock().record_auto_answer(&record) {
+            log::warn!("[questions] failed to record auto-answer: {}", e);
+        }
+    }
+}
+
+/// The job `slug` and its first `auto_answer` rule that answers `q`.
+fn job_rule(
+    jobs_config: &Mutex<JobsConfig>,
+    slug: &str,
+    q: &ClaudeQuestion,
+) -> Option<(Job, AutoAnswerRule)> {
+    let config = jobs_config.lock();
+    let job = config.jobs.iter().find(|j| j.slug == slug)?;
+    let rule = matching_rule(&job.auto_answer, q)?.clone();
+    Some((job.clone(), rule))
+}
+
+/// Remind about a question nobody has answered, or apply the timeout action.
+async fn handle_unanswered(
+    q: &ClaudeQuestion,
+    due: Due,
+    action: AnswerTimeoutAction,
+    relay: &Arc<Mutex<Option<RelayHandle>>>,
+    notifier: &dyn crate::notifications::Notifier,
+) {
+    let who = q.matched_job.as_deref().unwrap_or(&q.window_name);
+    match (due, action) {
+        (Due::Remind, _) => {
+            log::info!(
+                "[questions] {} unanswered in pane {}, reminding",
+                q.question_id,
+                q.pane_id
+            );
+            notifier.notify_question(q);
+            crate::relay::push_notice(
+                relay,
+                clawtab_protocol::NoticeLevel::Warning,
+                &format!("{} is waiting", who),
+                &format!(
+                    "Claude is still waiting for your answer to: {}",
+                    question_summary(q)
+                ),
+                true,
+            );
+        }
+        (Due::Act, AnswerTimeoutAction::Decline) => {
+            let Some(decline) = find_no_option(&q.options) else {
+                log::warn!(
+                    "[questions] no decline option for unanswered {}",
+                    q.question_id
+                );
+                return;
+            };
+            log::info!(
+                "[questions] declining unanswered {} with {:?}",
+                q.question_id,
+                decline
+            );
+            if let Err(e) = send_decline(q, decline).await {
+                log::error!("[questions] failed to decline {}: {}", q.question_id, e);
+            }
+        }
+        (Due::Act, AnswerTimeoutAction::Stop) => {
+            log::info!("[questions] stopping {} after unanswered question", who);
+            if let Err(e) = crate::tmux::kill_pane(&q.pane_id) {
+                log::error!("[questions] failed to stop pane {}: {}", q.pane_id, e);
+            }
+        }
+        (Due::Act, AnswerTimeoutAction::None) => {}
+    }
+}
+
 fn filter_visible_questions(questions: &[ClaudeQuestion]) -> Vec<ClaudeQuestion> {
     questions
         .iter()
@@ -699,6 +1153,7 @@ fn prune_stale_auto_yes_panes(
 
 fn update_question_cache(
     processes: &[DetectedAgent],
+    prompt_patterns: &PromptDetectionSettings,
     question_cache: &mut HashMap<String, CachedQuestion>,
 ) -> HashSet<String> {
     let mut detected = HashSet::new();
@@ -720,6 +1175,20 @@ fn update_question_cache(
     ) in processes
     {
         if try_numbered_question(
+            pane_id,
+            cwd,
+            tmux_session,
+            window_name,
+            log_lines,
+            matched_group,
+            matched_job,
+            prompt_patterns,
+            &mut detected,
+            question_cache,
+        ) {
+            continue;
+        }
+        if try_plain_question(
             pane_id,
             cwd,
             tmux_session,
@@ -756,10 +1225,11 @@ fn try_numbered_question(
     log_lines: &str,
     matched_group: &Option<String>,
     matched_job: &Option<String>,
+    prompt_patterns: &PromptDetectionSettings,
     detected: &mut HashSet<String>,
     cache: &mut HashMap<String, CachedQuestion>,
 ) -> bool {
-    let options = parse_numbered_options(log_lines);
+    let options = parse_numbered_options_with(log_lines, prompt_patterns);
     if options.is_empty() {
         return false;
     }
@@ -794,6 +1264,69 @@ fn try_numbered_question(
     true
 }
 
+/// Yes/no confirmations and free-text `> ` prompts. Free-text questions have
+/// no options and `input_mode` "text"; the reply is typed into the pane.
+#[allow(clippy::too_many_arguments)]
+fn try_plain_question(
+    pane_id: &str,
+    cwd: &str,
+    tmux_session: &str,
+    window_name: &str,
+    log_lines: &str,
+    matched_group: &Option<String>,
+    matched_job: &Option<String>,
+    detected: &mut HashSet<String>,
+    cache: &mut HashMap<String, CachedQuestion>,
+) -> bool {
+    let yes_no = parse_yes_no_options(log_lines);
+    let (options, input_mode, question_id) = if !yes_no.is_empty() {
+        let question_id = make_question_id(pane_id, &yes_no);
+        (yes_no, String::new(), question_id)
+    } else if let Some(question) = parse_free_text_prompt(log_lines) {
+        let key = QuestionOption {
+            number: String::new(),
+            label: question,
+            selected: false,
+            col: 0,
+        };
+        (
+            Vec::new(),
+            "text".to_string(),
+            make_question_id(pane_id, &[key]),
+        )
+    } else {
+        return false;
+    };
+    log::debug!(
+        "[questions] pane {} ({}): plain prompt, {} options",
+        pane_id,
+        cwd,
+        options.len()
+    );
+    detected.insert(pane_id.to_string());
+    let q = ClaudeQuestion {
+        pane_id: pane_id.to_string(),
+        cwd: cwd.to_string(),
+        tmux_session: tmux_session.to_string(),
+        window_name: window_name.to_string(),
+        question_id,
+        context_lines: last_context_lines(log_lines),
+        options,
+        input_mode,
+        button_row: 0,
+        matched_group: matched_group.clone(),
+        matched_job: matched_job.clone(),
+    };
+    cache.insert(
+        pane_id.to_string(),
+        CachedQuestion {
+            question: q,
+            miss_count: 0,
+        },
+    );
+    true
+}
+
 #[allow(clippy::too_many_arguments)]
 fn try_opencode_question(
     pane_id: &str,
@@ -870,9 +1403,12 @@ fn evict_stale_cache_entries(
     }
 }
 
-fn auto_answer_questions(
+async fn auto_answer_questions(
     questions: &[ClaudeQuestion],
     auto_yes_panes: &Arc<Mutex<HashSet<String>>>,
+    jobs_config: &Mutex<JobsConfig>,
+    job_status: &Mutex<HashMap<String, JobStatus>>,
+    settings: &Arc<Mutex<AppSettings>>,
     auto_answered_ids: &mut HashMap<String, u32>,
 ) {
     let yes_panes = auto_yes_panes.lock().clone();
@@ -893,7 +1429,7 @@ fn auto_answer_questions(
             continue;
         }
         if let Some(ticks) = auto_answered_ids.get(&q.question_id) {
-            if *ticks < 6 {
+            if *ticks < AUTO_ANSWER_RETRY_TICKS {
                 log::debug!(
                     "[questions] question {} already auto-answered ({} ticks ago), skipping",
                     q.question_id,
@@ -907,11 +1443,33 @@ fn auto_answer_questions(
                 ticks
             );
         }
-        try_send_auto_answer(q, auto_answered_ids);
+        let scope = pane_file_scope(&q.pane_id, jobs_config, job_status, settings);
+        try_send_auto_answer(q, scope.as_deref(), auto_answered_ids).await;
     }
 }
 
-fn try_send_auto_answer(q: &ClaudeQuestion, auto_answered_ids: &mut HashMap<String, u32>) {
+/// Directories the job running in `pane_id` may access, None for a pane
+/// without a job run.
+fn pane_file_scope(
+    pane_id: &str,
+    jobs_config: &Mutex<JobsConfig>,
+    job_status: &Mutex<HashMap<String, JobStatus>>,
+    settings: &Arc<Mutex<AppSettings>>,
+) -> Option<Vec<String>> {
+    let (_, slug) = run_on_pane(&job_status.lock(), pane_id)?;
+    let default_work_dir = settings.lock().default_work_dir.clone();
+    let config = jobs_config.lock();
+    let job = config.jobs.iter().find(|j| j.slug == slug)?;
+    Some(file_access_dirs(job, &default_work_dir))
+}
+
+/// Auto-yes `q`, unless it is a file permission prompt for a path outside
+/// the job's `scope`, which is declined instead.
+async fn try_send_auto_answer(
+    q: &ClaudeQuestion,
+    scope: Option<&[String]>,
+    auto_answered_ids: &mut HashMap<String, u32>,
+) {
     let options_summary: Vec<String> = q
         .options
         .iter()
@@ -923,6 +1481,18 @@ fn try_send_auto_answer(q: &ClaudeQuestion, auto_answered_ids: &mut HashMap<Stri
         q.question_id,
         options_summary
     );
+    if !auto_yes_applies(q) {
+        log::debug!(
+            "[questions] pane {} question {} is a plain (y/n) prompt outside an agent job, not auto-answering",
+            q.pane_id,
+            q.question_id
+        );
+        return;
+    }
+    if let Some(path) = scope.and_then(|dirs| out_of_scope_path(q, dirs)) {
+        decline_out_of_scope(q, &path, auto_answered_ids).await;
+        return;
+    }
     let Some(opt) = find_yes_option(&q.options) else {
         log::warn!(
             "[questions] no yes option found for pane {} question {}, options: {:?}",
@@ -938,16 +1508,7 @@ fn try_send_auto_answer(q: &ClaudeQuestion, auto_answered_ids: &mut HashMap<Stri
         q.question_id,
         opt
     );
-    let send_result = if q.input_mode == "select" {
-        if let Some(target_opt) = q.options.iter().find(|o| o.number == opt) {
-            crate::tmux::send_mouse_click_to_pane(&q.pane_id, target_opt.col, q.button_row)
-        } else {
-            Err("option not found".to_string())
-        }
-    } else {
-        crate::tmux::send_keys_to_tui_pane(&q.pane_id, &opt)
-    };
-    match send_result {
+    match send_option(q, &opt).await {
         Ok(()) => {
             auto_answered_ids.insert(q.question_id.clone(), 0);
         }
@@ -957,6 +1518,55 @@ fn try_send_auto_answer(q: &ClaudeQuestion, auto_answered_ids: &mut HashMap<Stri
     }
 }
 
+async fn decline_out_of_scope(
+    q: &ClaudeQuestion,
+    path: &str,
+    auto_answered_ids: &mut HashMap<String, u32>,
+) {
+    let Some(decline) = find_no_option(&q.options) else {
+        log::warn!(
+            "[questions] pane {} asks for {} outside the job's allowed dirs, but has no decline option",
+            q.pane_id,
+            path
+        );
+        return;
+    };
+    log::info!(
+        "[questions] declining pane {} question {}: {} is outside the job's allowed dirs",
+        q.pane_id,
+        q.question_id,
+        path
+    );
+    match send_decline(q, decline).await {
+        Ok(()) => {
+            auto_answered_ids.insert(q.question_id.clone(), 0);
+        }
+        Err(e) => log::error!("[questions] failed to decline {}: {}", q.question_id, e),
+    }
+}
+
+/// Plain `(y/n)` prompts can come from any command in the pane, such as an
+/// `rm -i`, so auto-yes only confirms them in panes running an agent job.
+/// Numbered menus are the agent's own and always apply.
+fn auto_yes_applies(q: &ClaudeQuestion) -> bool {
+    let yes_no = q.options.iter().any(|o| o.number == "y");
+    !yes_no || q.matched_job.is_some()
+}
+
+/// Answer a question with option `number`, clicking for select-style prompts.
+async fn send_option(q: &ClaudeQuestion, number: &str) -> Result<(), String> {
+    if q.input_mode == "select" {
+        let target = q
+            .options
+            .iter()
+            .find(|o| o.number == number)
+            .ok_or("option not found")?;
+        crate::tmux::send_mouse_click_to_pane(&q.pane_id, target.col, q.button_row)
+    } else {
+        crate::tmux::send_answer_to_pane(&q.pane_id, number).await
+    }
+}
+
 fn retain_auto_answered_for_present(
     questions: &[ClaudeQuestion],
     auto_answered_ids: &mut HashMap<String, u32>,
@@ -974,6 +1584,7 @@ fn retain_auto_answered_for_present(
 fn send_relay_questions(
     questions: Vec<ClaudeQuestion>,
     apns_questions: Vec<ClaudeQuestion>,
+    screenshots: bool,
     auto_yes_panes: &Arc<Mutex<HashSet<String>>>,
     relay: &Arc<Mutex<Option<RelayHandle>>>,
     last_sent_ids: &mut HashSet<String>,
@@ -1003,16 +1614,24 @@ fn send_relay_questions(
     if !changed && !periodic_resend {
         return;
     }
+    // Images go out once per question, before the push that first carries it.
+    let new_apns_questions: Vec<&ClaudeQuestion> = apns_questions
+        .iter()
+        .filter(|q| screenshots && !last_sent_ids.contains(&q.question_id))
+        .collect();
     *last_sent_ids = current_ids;
     *ticks_since_send = 0;
-    let msg = clawtab_protocol::DesktopMessage::ClaudeQuestions {
-        questions: relay_questions,
-        apns_questions: Some(apns_questions),
-    };
     let guard = relay.lock();
-    if let Some(handle) = guard.as_ref() {
-        handle.send_message(&msg);
+    let Some(handle) = guard.as_ref() else {
+        return;
+    };
+    for q in new_apns_questions {
+        handle.send_question_image(q);
     }
+    handle.send_message(&clawtab_protocol::DesktopMessage::ClaudeQuestions {
+        questions: relay_questions,
+        apns_questions: Some(apns_questions),
+    });
 }
 
 fn pick_sleep_ms(
@@ -1469,10 +2088,14 @@ fn detect_question_processes(
 #[cfg(test)]
 mod tests {
     use super::{
-        find_yes_option, parse_numbered_options, parse_opencode_buttons, resolved_hook_activity,
-        ActivityTracker, DetectedAgent, HookAgentState, ProcessProvider,
+        auto_yes_applies, detect_numbered_prompt, file_prompt_path, find_no_option,
+        find_yes_option, matching_rule, out_of_scope_path, parse_free_text_prompt,
+        parse_numbered_options, parse_opencode_buttons, parse_yes_no_options,
+        resolved_hook_activity, ActivityTracker, Decline, DetectedAgent, HookAgentState,
+        ProcessProvider, PromptDetectionSettings,
     };
-    use clawtab_protocol::QuestionOption;
+    use crate::config::jobs::AutoAnswerRule;
+    use clawtab_protocol::{ClaudeQuestion, QuestionOption};
     use std::collections::HashSet;
     use std::time::{Duration, Instant};
 
@@ -1733,6 +2356,28 @@ Enter to select · Esc to cancel
         assert_eq!(options[1].label, "No");
     }
 
+    #[test]
+    fn custom_prompt_patterns_extend_builtins() {
+        let text = r#"
+Pick one:
+→ 1) Keep going
+  2) Stop here
+Press return to choose
+"#;
+        assert!(parse_numbered_options(text).is_empty());
+
+        let patterns = PromptDetectionSettings {
+            option_markers: "→".to_string(),
+            number_separators: vec![") ".to_string()],
+            prompt_indicators: vec!["Press return to choose".to_string()],
+        };
+        let detection = detect_numbered_prompt(text, &patterns);
+        assert!(detection.has_prompt_indicator);
+        assert_eq!(detection.options.len(), 2);
+        assert_eq!(detection.options[0].label, "Keep going");
+        assert_eq!(detection.options[1].number, "2");
+    }
+
     #[test]
     fn parses_codex_command_approval_prompt() {
         let text = r#"
@@ -1765,6 +2410,127 @@ Plan:
         assert!(options.is_empty());
     }
 
+    #[test]
+    fn parses_yes_no_confirmation_on_last_line() {
+        let options = parse_yes_no_options("Writing config.toml\nOverwrite existing file? [Y/n]: ");
+        let answers: Vec<(&str, &str)> = options
+            .iter()
+            .map(|o| (o.number.as_str(), o.label.as_str()))
+            .collect();
+        assert_eq!(answers, vec![("y", "Yes"), ("n", "No")]);
+        assert_eq!(find_yes_option(&options).as_deref(), Some("y"));
+        assert!(parse_yes_no_options("Continue (y/n)\nContinuing...").is_empty());
+    }
+
+    #[test]
+    fn auto_yes_confirms_plain_prompts_only_in_agent_job_panes() {
+        let mut q = ClaudeQuestion {
+            pane_id: "%1".to_string(),
+            cwd: "/tmp".to_string(),
+            tmux_session: "main".to_string(),
+            window_name: "zsh".to_string(),
+            question_id: "%1:0".to_string(),
+            context_lines: "remove notes.txt? (y/n)".to_string(),
+            options: parse_yes_no_options("remove notes.txt? (y/n)"),
+            input_mode: String::new(),
+            button_row: 0,
+            matched_group: None,
+            matched_job: None,
+        };
+        assert!(!auto_yes_applies(&q));
+        q.matched_job = Some("default/review".to_string());
+        assert!(auto_yes_applies(&q));
+        q.matched_job = None;
+        q.options = parse_numbered_options("Proceed?\n❯ 1. Yes\n  2. No");
+        assert!(auto_yes_applies(&q));
+    }
+
+    #[test]
+    fn reads_the_path_of_file_permission_prompts() {
+        let read = "Read file\n\n  Read(/etc/hosts)\n\nDo you want to proceed?\n❯ 1. Yes\n  2. No";
+        assert_eq!(file_prompt_path(read).as_deref(), Some("/etc/hosts"));
+        let edit = "Edit file\n╭──────────╮\n│ src/main.rs │\n│ 1 fn main() {} │\n╰──────────╯\n\
+                    Do you want to make this edit to main.rs?";
+        assert_eq!(file_prompt_path(edit).as_deref(), Some("src/main.rs"));
+        let bash = "Bash command\n\n  ls -la\n\nDo you want to proceed?";
+        assert!(file_prompt_path(bash).is_none());
+    }
+
+    #[test]
+    fn auto_yes_declines_file_prompts_outside_the_allowed_dirs() {
+        let prompt = "Do you want to proceed?\n❯ 1. Yes\n  2. Yes, during this session\n  \
+                      3. No, and tell Claude what to do differently (esc)";
+        let mut q = ClaudeQuestion {
+            pane_id: "%1".to_string(),
+            cwd: "/work/proj".to_string(),
+            tmux_session: "main".to_string(),
+            window_name: "agent".to_string(),
+            question_id: "%1:0".to_string(),
+            context_lines: format!("Read file\n  Read(../notes/todo.md)\n{}", prompt),
+            options: parse_numbered_options(prompt),
+            input_mode: String::new(),
+            button_row: 0,
+            matched_group: None,
+            matched_job: Some("default/review".to_string()),
+        };
+        let work_dir_only = vec!["/work/proj".to_string()];
+        assert_eq!(
+            out_of_scope_path(&q, &work_dir_only).as_deref(),
+            Some("../notes/todo.md")
+        );
+        assert_eq!(find_no_option(&q.options), Some(Decline::Escape));
+        let with_notes = vec!["/work/proj".to_string(), "/work/notes/".to_string()];
+        assert!(out_of_scope_path(&q, &with_notes).is_none());
+        q.context_lines = format!("Read file\n  Read(/work/project-b/a.txt)\n{}", prompt);
+        assert!(out_of_scope_path(&q, &work_dir_only).is_some());
+        q.context_lines = format!("Create file\n│ src/lib.rs │\n{}", prompt);
+        assert!(out_of_scope_path(&q, &work_dir_only).is_none());
+    }
+
+    #[test]
+    fn parses_free_text_prompt_only_under_a_question() {
+        assert_eq!(
+            parse_free_text_prompt("What should the branch be called?\n> \n").as_deref(),
+            Some("What should the branch be called?")
+        );
+        assert!(parse_free_text_prompt("done\n> ").is_none());
+        assert!(parse_free_text_prompt("Name:\n> typed").is_none());
+    }
+
+    #[test]
+    fn auto_answer_rule_needs_matching_prompt_and_option() {
+        let rule = |pattern: &str, answer: &str| AutoAnswerRule {
+            pattern: pattern.to_string(),
+            answer: answer.to_string(),
+        };
+        let rules = vec![rule("make this edit", "3"), rule("MAKE THIS EDIT", "1")];
+        let option = |number: &str| QuestionOption {
+            number: number.to_string(),
+            label: format!("option {}", number),
+            selected: false,
+            col: 0,
+        };
+        let mut q = ClaudeQuestion {
+            pane_id: "%1".to_string(),
+            cwd: "/tmp".to_string(),
+            tmux_session: "main".to_string(),
+            window_name: "agent".to_string(),
+            question_id: "%1:0".to_string(),
+            context_lines: "Do you want to make this edit to main.rs?".to_string(),
+            options: vec![option("1"), option("2")],
+            input_mode: String::new(),
+            button_row: 0,
+            matched_group: None,
+            matched_job: None,
+        };
+        assert_eq!(
+            matching_rule(&rules, &q).map(|r| r.answer.as_str()),
+            Some("1")
+        );
+        q.context_lines = "Do you want to run this command?".to_string();
+        assert!(matching_rule(&rules, &q).is_none());
+    }
+
     #[test]
     fn prefers_session_scoped_yes_over_broader_yes() {
         let options = vec![
@@ -1791,6 +2557,33 @@ Plan:
         assert_eq!(find_yes_option(&options), Some("2".to_string()));
     }
 
+    #[test]
+    fn decline_picks_no_but_not_words_starting_with_no() {
+        let option = |number: &str, label: &str| QuestionOption {
+            number: number.to_string(),
+            label: label.to_string(),
+            selected: false,
+            col: 0,
+        };
+        let options = vec![
+            option("1", "Yes"),
+            option("2", "Notify the team first"),
+            option("3", "No, and tell Claude what to do differently (esc)"),
+            option("4", "No"),
+        ];
+        assert_eq!(
+            find_no_option(&options),
+            Some(Decline::Option("4".to_string()))
+        );
+        assert_eq!(find_no_option(&options[..3]), Some(Decline::Escape));
+        assert_eq!(find_no_option(&options[..2]), None);
+        let deny = vec![option("1", "Allow"), option("2", "Deny this request")];
+        assert_eq!(
+            find_no_option(&deny),
+            Some(Decline::Option("2".to_string()))
+        );
+    }
+
     #[test]
     fn prefers_one_time_codex_approval_over_persistent_allowlist() {
         let options = vec![
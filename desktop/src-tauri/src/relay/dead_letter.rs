@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Dropped messages kept for diagnostics; the oldest are evicted first.
+const CAPACITY: usize = 100;
+
+/// Message kinds that a reconnect can safely resend. State snapshots
+/// (`jobs_changed`, `status_update`, ...) are superseded by the full state
+/// pushed on connect, so replaying them would only roll state back.
+const REPLAYABLE: &[&str] = &["log_chunk", "job_notification", "trigger_result", "notice"];
+
+static DROPPED: Mutex<DeadLetters> = Mutex::new(DeadLetters::new());
+
+/// A relay message the desktop tried to send but could not deliver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedRelayMessage {
+    pub dropped_at: String,
+    /// Protocol message type, e.g. `status_update`.
+    pub kind: String,
+    pub reason: String,
+    /// Serialized message, absent when serialization itself failed.
+    pub payload: Option<String>,
+    /// Resent after the relay reconnected.
+    pub replayed: bool,
+}
+
+pub(super) struct DeadLetters {
+    entries: VecDeque<DroppedRelayMessage>,
+}
+
+impl DeadLetters {
+    const fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, entry: DroppedRelayMessage) {
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Payloads of replayable messages not yet resent, oldest first. Each is
+    /// marked replayed so a second reconnect does not send it again.
+    fn take_replayable(&mut self) -> Vec<String> {
+        self.entries
+            .iter_mut()
+            .filter(|e| !e.replayed && REPLAYABLE.contains(&e.kind.as_str()))
+            .filter_map(|e| {
+                let payload = e.payload.clone()?;
+                e.replayed = true;
+                Some(payload)
+            })
+            .collect()
+    }
+}
+
+/// Record an undeliverable message. `payload` is the serialized message.
+pub(super) fn record(payload: Option<String>, reason: &str) {
+    let kind = payload
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string());
+    log::warn!("Relay: dropped {} message: {}", kind, reason);
+    DROPPED.lock().push(DroppedRelayMessage {
+        dropped_at: chrono::Utc::now().to_rfc3339(),
+        kind,
+        reason: reason.to_string(),
+        payload,
+        replayed: false,
+    });
+}
+
+/// Messages to resend once a new session is up.
+pub(super) fn take_replayable() -> Vec<String> {
+    DROPPED.lock().take_replayable()
+}
+
+/// Recently dropped relay messages, oldest first.
+pub fn dropped_messages() -> Vec<DroppedRelayMessage> {
+    DROPPED.lock().entries.iter().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: &str) -> DroppedRelayMessage {
+        DroppedRelayMessage {
+            dropped_at: String::new(),
+            kind: kind.to_string(),
+            reason: "connection lost".to_string(),
+            payload: Some(format!("{{\"type\":\"{}\"}}", kind)),
+            replayed: false,
+        }
+    }
+
+    #[test]
+    fn evicts_oldest_past_capacity() {
+        let mut dl = DeadLetters::new();
+        for _ in 0..CAPACITY {
+            dl.push(entry("notice"));
+        }
+        dl.push(entry("log_chunk"));
+        assert_eq!(dl.entries.len(), CAPACITY);
+        assert_eq!(dl.entries.back().unwrap().kind, "log_chunk");
+    }
+
+    #[test]
+    fn replays_each_message_once_and_skips_state_snapshots() {
+        let mut dl = DeadLetters::new();
+        dl.push(entry("status_update"));
+        dl.push(entry("log_chunk"));
+        assert_eq!(dl.take_replayable(), vec!["{\"type\":\"log_chunk\"}"]);
+        assert!(dl.take_replayable().is_empty());
+        assert_eq!(dl.entries.len(), 2);
+    }
+}
@@ -99,7 +99,7 @@ async fn dispatch_sync(
     if let Some(resp) = dispatch_job_msg(&msg, jobs_config, ctx, event_sink).await {
         return Some(resp);
     }
-    if let Some(resp) = dispatch_process_msg(&msg, &ctx.history) {
+    if let Some(resp) = dispatch_process_msg(&msg, &ctx.history).await {
         return Some(resp);
     }
     dispatch_pty_msg(msg, ctx, pty_manager, event_sink)
@@ -221,7 +221,7 @@ async fn dispatch_job_msg(
     }
 }
 
-fn dispatch_process_msg(
+async fn dispatch_process_msg(
     msg: &ClientMessage,
     history: &Arc<Mutex<HistoryStore>>,
 ) -> Option<DesktopMessage> {
@@ -276,7 +276,7 @@ fn dispatch_process_msg(
             let result = if let Some(text) = freetext {
                 crate::tmux::send_keys_to_tui_pane_freetext(pane_id, answer, text)
             } else {
-                crate::tmux::send_keys_to_tui_pane(pane_id, answer)
+                crate::tmux::send_answer_to_pane(pane_id, answer).await
             };
             Some(DesktopMessage::SendDetectedProcessInputAck {
                 id: id.clone(),
@@ -644,7 +644,7 @@ fn get_run_history(
     history: &Arc<Mutex<HistoryStore>>,
 ) -> Vec<clawtab_protocol::RunRecord> {
     let h = history.lock();
-    match h.get_by_job_id(name, limit as usize) {
+    match h.get_by_job_id(name, limit as usize, 0) {
         Ok(runs) => runs
             .into_iter()
             .map(|r| clawtab_protocol::RunRecord {
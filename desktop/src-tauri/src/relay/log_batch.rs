@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+/// Default delay before buffered log output is sent to the relay.
+pub(super) const DEFAULT_LOG_BATCH_MS: u64 = 250;
+/// A job's buffer is sent immediately once it grows past this many bytes.
+const MAX_BATCH_BYTES: usize = 32 * 1024;
+
+/// Pending log output per job, kept in first-seen order so that a flush
+/// replays jobs in the order their output arrived.
+#[derive(Default)]
+pub(super) struct LogBatch {
+    pending: Vec<(String, String)>,
+}
+
+impl LogBatch {
+    /// Append `content` to the job's buffer. Returns the buffered output when
+    /// the job has crossed the size cap and should be sent right away.
+    pub(super) fn push(&mut self, name: &str, content: &str) -> Option<String> {
+        let idx = match self.pending.iter().position(|(n, _)| n == name) {
+            Some(idx) => {
+                let buf = &mut self.pending[idx].1;
+                buf.push('\n');
+                buf.push_str(content);
+                idx
+            }
+            None => {
+                self.pending.push((name.to_string(), content.to_string()));
+                self.pending.len() - 1
+            }
+        };
+        if self.pending[idx].1.len() >= MAX_BATCH_BYTES {
+            return Some(self.pending.remove(idx).1);
+        }
+        None
+    }
+
+    /// Remove and return one job's buffered output.
+    pub(super) fn take(&mut self, name: &str) -> Option<String> {
+        let idx = self.pending.iter().position(|(n, _)| n == name)?;
+        Some(self.pending.remove(idx).1)
+    }
+
+    /// Remove and return everything buffered, in arrival order.
+    pub(super) fn drain(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Resolve the configured batch interval. `None` uses the default and
+/// `Some(0)` disables batching.
+pub(super) fn batch_interval(ms: Option<u64>) -> Option<Duration> {
+    match ms.unwrap_or(DEFAULT_LOG_BATCH_MS) {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+/// Spread flushes by +/-20% so a burst of output does not settle into a
+/// fixed cadence against the relay's own timers.
+pub(super) fn jittered(interval: Duration) -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    let percent = 80 + u64::from(nanos % 41);
+    interval * u32::try_from(percent).unwrap_or(100) / 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesces_per_job_in_arrival_order() {
+        let mut batch = LogBatch::default();
+        assert!(batch.push("b", "one").is_none());
+        assert!(batch.push("a", "two").is_none());
+        assert!(batch.push("b", "three").is_none());
+        assert_eq!(
+            batch.drain(),
+            vec![
+                ("b".to_string(), "one\nthree".to_string()),
+                ("a".to_string(), "two".to_string()),
+            ]
+        );
+        assert!(batch.drain().is_empty());
+    }
+
+    #[test]
+    fn flushes_job_past_size_cap() {
+        let mut batch = LogBatch::default();
+        let big = "x".repeat(MAX_BATCH_BYTES);
+        assert!(batch.push("a", "small").is_none());
+        assert!(batch.push("b", "other").is_none());
+        let flushed = batch.push("a", &big).expect("cap reached");
+        assert!(flushed.starts_with("small\n"));
+        assert_eq!(batch.take("a"), None);
+        assert_eq!(batch.take("b").as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn interval_zero_disables_batching() {
+        assert_eq!(batch_interval(Some(0)), None);
+        assert_eq!(
+            batch_interval(None),
+            Some(Duration::from_millis(DEFAULT_LOG_BATCH_MS))
+        );
+        let j = jittered(Duration::from_millis(1000));
+        assert!(j >= Duration::from_millis(800) && j <= Duration::from_millis(1200));
+    }
+}
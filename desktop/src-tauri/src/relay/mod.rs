@@ -1,43 +1,199 @@
+mod dead_letter;
 mod handler;
+mod log_batch;
+mod probe;
 
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use base64::Engine;
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite::Message;
 
-use clawtab_protocol::{DesktopMessage, JobStatus as RemoteJobStatus, RemoteJob};
+use clawtab_protocol::{
+    capabilities, desktop_capabilities, ClaudeQuestion, DesktopMessage,
+    JobStatus as RemoteJobStatus, NoticeLevel, RemoteJob, ServerMessage, PROTOCOL_VERSION,
+};
 
 use crate::config::jobs::{Job, JobStatus, JobsConfig};
 use crate::pty::SharedPtyManager;
 
 pub use crate::process_snapshot::detect_processes_snapshot;
+pub use dead_letter::{dropped_messages, DroppedRelayMessage};
+pub use probe::{probe, ProbeError, RelayProbe};
 
 const RELAY_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_HEARTBEAT_SECS: u64 = 30;
+const MIN_HEARTBEAT_SECS: u64 = 10;
+/// The relay server drops clients after 90s without a pong; keep at least two
+/// pings inside that window.
+const MAX_HEARTBEAT_SECS: u64 = 45;
+/// Pings that may go unanswered before the connection is considered dead.
+const BASE_PONG_MISSES: u32 = 3;
+/// Tolerance ceiling reached after repeated late pongs.
+const MAX_PONG_MISSES: u32 = 6;
+const RELAY_PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+type PendingPings = Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<u32>>>>;
+
+/// What the relay announced in its welcome message.
+#[derive(Debug, Clone)]
+struct RelayPeer {
+    protocol_version: u32,
+    min_protocol_version: u32,
+    capabilities: Vec<String>,
+}
+
+type SharedPeer = Arc<Mutex<Option<RelayPeer>>>;
 
 /// Relay connection state, shared via Arc<Mutex<..>> in AppState.
 pub struct RelayHandle {
     tx: mpsc::UnboundedSender<String>,
     cancel: tokio_util::sync::CancellationToken,
+    pings: PendingPings,
+    logs: Arc<Mutex<log_batch::LogBatch>>,
+    log_interval: Option<Duration>,
+    peer: SharedPeer,
+}
+
+/// Outcome of a successful relay round-trip probe.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RelayPingResult {
+    pub latency_ms: u64,
+    pub mobile_clients: u32,
 }
 
 impl RelayHandle {
-    /// Send a protocol message to the relay server.
+    /// Send a protocol message to the relay server. Messages that cannot be
+    /// queued end up in the dead-letter log.
     pub fn send_message(&self, msg: &DesktopMessage) {
-        if let Ok(json) = serde_json::to_string(msg) {
-            let _ = self.tx.send(json);
+        match serde_json::to_string(msg) {
+            Ok(json) => {
+                if let Err(mpsc::error::SendError(json)) = self.tx.send(json) {
+                    dead_letter::record(Some(json), "session closed");
+                }
+            }
+            Err(e) => dead_letter::record(None, &format!("serialization failed: {}", e)),
+        }
+    }
+
+    /// Queue log output for the next batch flush, or send it now when
+    /// batching is disabled or the job's buffer is full.
+    fn push_log(&self, name: &str, content: &str) {
+        if self.log_interval.is_none() {
+            self.send_log_chunk(name, content.to_string());
+            return;
+        }
+        let full = self.logs.lock().push(name, content);
+        if let Some(content) = full {
+            self.send_log_chunk(name, content);
+        }
+    }
+
+    /// Send any buffered output for a job ahead of a status change.
+    fn flush_logs(&self, name: &str) {
+        let pending = self.logs.lock().take(name);
+        if let Some(content) = pending {
+            self.send_log_chunk(name, content);
+        }
+    }
+
+    fn send_log_chunk(&self, name: &str, content: String) {
+        self.send_message(&log_chunk_message(name, content));
+    }
+
+    /// Send a protocol ping and return a receiver resolved with the relay's
+    /// mobile client count once the matching pong arrives.
+    fn ping(&self) -> (String, tokio::sync::oneshot::Receiver<u32>) {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+        self.pings.lock().insert(id.clone(), done_tx);
+        self.send_message(&DesktopMessage::Ping { id: id.clone() });
+        (id, done_rx)
+    }
+
+    /// Whether the relay supports an optional feature from
+    /// `clawtab_protocol::capabilities`. Relays that predate negotiation, and
+    /// a session whose welcome has not arrived yet, are assumed to.
+    fn supports(&self, capability: &str) -> bool {
+        match self.peer.lock().as_ref() {
+            Some(peer) if peer.protocol_version > 0 => {
+                peer.capabilities.iter().any(|c| c == capability)
+            }
+            _ => true,
+        }
+    }
+
+    /// Send a rendered image of the question's pane text ahead of the push
+    /// that carries it. Skipped unless the relay advertises
+    /// `QUESTION_IMAGE`; older relays would drop it anyway.
+    pub fn send_question_image(&self, q: &ClaudeQuestion) {
+        let advertised = self.peer.lock().as_ref().is_some_and(|peer| {
+            peer.capabilities
+                .iter()
+                .any(|c| c == capabilities::QUESTION_IMAGE)
+        });
+        if !advertised {
+            return;
+        }
+        match crate::question_image::render_png(&q.context_lines) {
+            Ok(png) => self.send_message(&DesktopMessage::QuestionImage {
+                question_id: q.question_id.clone(),
+                png: base64::engine::general_purpose::STANDARD.encode(png),
+            }),
+            Err(e) => log::warn!("Failed to render question {}: {}", q.question_id, e),
         }
     }
 
+    /// The relay no longer serves this app's protocol version.
+    pub fn update_required(&self) -> bool {
+        self.peer
+            .lock()
+            .as_ref()
+            .is_some_and(|peer| PROTOCOL_VERSION < peer.min_protocol_version)
+    }
+
     /// Disconnect from the relay server.
     pub fn disconnect(&self) {
         self.cancel.cancel();
     }
 }
 
+/// Measure a desktop -> relay -> desktop round-trip over the live session.
+pub async fn ping_roundtrip(
+    relay: &Arc<Mutex<Option<RelayHandle>>>,
+) -> Result<RelayPingResult, String> {
+    let started = std::time::Instant::now();
+    let (pings, id, done) = match relay.lock().as_ref() {
+        Some(handle) if !handle.supports(capabilities::PING) => {
+            return Err("The relay server is too old to answer pings".to_string())
+        }
+        Some(handle) => {
+            let (id, done) = handle.ping();
+            (Arc::clone(&handle.pings), id, done)
+        }
+        None => return Err("Desktop is not connected to the relay".to_string()),
+    };
+    match tokio::time::timeout(RELAY_PING_TIMEOUT, done).await {
+        Ok(Ok(mobile_clients)) => Ok(RelayPingResult {
+            latency_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            mobile_clients,
+        }),
+        Ok(Err(_)) => Err("Relay connection closed before the ping was answered".to_string()),
+        Err(_) => {
+            // A pong after the deadline finds nothing to answer.
+            pings.lock().remove(&id);
+            Err(format!(
+                "Relay did not answer within {}s",
+                RELAY_PING_TIMEOUT.as_secs()
+            ))
+        }
+    }
+}
+
 /// Push the full job list + statuses to relay. Called on connect and on job config change.
 pub fn push_full_state(
     handle: &RelayHandle,
@@ -82,6 +238,8 @@ pub fn push_status_update(
     {
         let guard = relay.lock();
         if let Some(handle) = guard.as_ref() {
+            // Log output always lands before the status that ends it.
+            handle.flush_logs(job_id);
             handle.send_message(&DesktopMessage::StatusUpdate {
                 name: job_id.to_string(),
                 status: status_to_remote(status),
@@ -109,7 +267,36 @@ pub fn push_job_notification(
     }
 }
 
-/// Push a log chunk to relay for a specific job.
+/// Push a toast notice to the user's mobiles. With `push` the relay also
+/// delivers it as an APNs alert, so reserve that for things worth a buzz.
+pub fn push_notice(
+    relay: &Arc<Mutex<Option<RelayHandle>>>,
+    level: NoticeLevel,
+    title: &str,
+    body: &str,
+    push: bool,
+) {
+    let guard = relay.lock();
+    if let Some(handle) = guard.as_ref().filter(|h| h.supports(capabilities::NOTICE)) {
+        handle.send_message(&DesktopMessage::Notice {
+            level,
+            title: title.to_string(),
+            body: body.to_string(),
+            push,
+        });
+    }
+}
+
+fn log_chunk_message(name: &str, content: String) -> DesktopMessage {
+    DesktopMessage::LogChunk {
+        name: name.to_string(),
+        content,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Push a log chunk to relay for a specific job. Chunks are coalesced per job
+/// and sent on the session's batch interval.
 pub fn push_log_chunk(relay: &Arc<Mutex<Option<RelayHandle>>>, job_id: &str, content: &str) {
     if content.is_empty() {
         return;
@@ -117,11 +304,7 @@ pub fn push_log_chunk(relay: &Arc<Mutex<Option<RelayHandle>>>, job_id: &str, con
     {
         let guard = relay.lock();
         if let Some(handle) = guard.as_ref() {
-            handle.send_message(&DesktopMessage::LogChunk {
-                name: job_id.to_string(),
-                content: content.to_string(),
-                timestamp: chrono::Utc::now().to_rfc3339(),
-            });
+            handle.push_log(job_id, content);
         }
     }
 }
@@ -137,7 +320,10 @@ pub fn push_trigger_result(
 ) {
     {
         let guard = relay.lock();
-        if let Some(handle) = guard.as_ref() {
+        if let Some(handle) = guard
+            .as_ref()
+            .filter(|h| h.supports(capabilities::TRIGGER_RESULT))
+        {
             handle.send_message(&DesktopMessage::TriggerResult {
                 trigger_id: trigger_id.to_string(),
                 status: status.to_string(),
@@ -149,6 +335,18 @@ pub fn push_trigger_result(
     }
 }
 
+/// WebSocket endpoint of the relay at `server_url`. For an http(s) URL, `/ws`
+/// goes after its path, so a relay served under a prefix such as
+/// `https://example.com/clawtab/` is reached at `/clawtab/ws`. A ws(s) URL is
+/// used as given.
+pub fn ws_url(server_url: &str) -> String {
+    let base = server_url.trim().trim_end_matches('/');
+    match base.strip_prefix("http") {
+        Some(rest) => format!("ws{}/ws", rest),
+        None => base.to_string(),
+    }
+}
+
 /// Check subscription status via HTTP. Returns (subscribed, Option<new_access_token>, Option<new_refresh_token>).
 pub async fn check_subscription_http(
     server_url: &str,
@@ -363,24 +561,34 @@ async fn attempt_session(
             let (ws_sink, ws_stream) = ws_stream.split();
             let (tx, rx) = mpsc::unbounded_channel::<String>();
             let cancel = tokio_util::sync::CancellationToken::new();
+            let pings = PendingPings::default();
+            let peer = SharedPeer::default();
+            let logs = Arc::new(Mutex::new(log_batch::LogBatch::default()));
+            let log_interval = log_batch::batch_interval(
+                ctx.settings
+                    .lock()
+                    .relay
+                    .as_ref()
+                    .and_then(|r| r.log_batch_ms),
+            );
             let handle = RelayHandle {
                 tx: tx.clone(),
                 cancel: cancel.clone(),
+                pings: pings.clone(),
+                logs: logs.clone(),
+                log_interval,
+                peer: peer.clone(),
             };
 
-            push_full_state(&handle, jobs_config, job_status);
-            let processes = crate::process_snapshot::detect_processes_snapshot(
+            announce_session(
+                &handle,
                 jobs_config,
-                &ctx.job_status,
+                job_status,
+                auto_yes_panes,
+                ctx,
                 pty_manager,
             )
             .await;
-            handle.send_message(&DesktopMessage::DetectedProcesses {
-                id: "desktop_process_snapshot".to_string(),
-                processes,
-            });
-            let pane_ids: Vec<String> = auto_yes_panes.lock().iter().cloned().collect();
-            handle.send_message(&DesktopMessage::AutoYesPanes { pane_ids });
             {
                 let mut guard = relay.lock();
                 *guard = Some(handle);
@@ -393,6 +601,10 @@ async fn attempt_session(
                     rx,
                     tx,
                     cancel: cancel.clone(),
+                    pings,
+                    logs: Arc::clone(&logs),
+                    log_interval,
+                    peer,
                 },
                 jobs_config,
                 ctx,
@@ -405,6 +617,7 @@ async fn attempt_session(
                 let mut guard = relay.lock();
                 *guard = None;
             }
+            keep_batched_logs(&logs);
 
             if cancel.is_cancelled() {
                 log::info!("Relay: disconnected by user");
@@ -413,16 +626,47 @@ async fn attempt_session(
             log::info!("Relay: connection lost, reconnecting in {:?}", backoff);
             SessionOutcome::Retry
         }
-        Err(e) => {
-            let err_str = e.to_string();
-            log::error!("Relay: connect failed: {}", err_str);
-            if err_str.contains("403") {
-                log::info!("Relay: subscription required (403 from server)");
-                *relay_sub_required.lock() = true;
-                return SessionOutcome::Done;
-            }
-            SessionOutcome::Retry
-        }
+        Err(e) => connect_failed(&e.to_string(), relay_sub_required),
+    }
+}
+
+/// A 403 means the account needs a subscription: stop retrying until it
+/// has one.
+fn connect_failed(err_str: &str, relay_sub_required: &Arc<Mutex<bool>>) -> SessionOutcome {
+    log::error!("Relay: connect failed: {}", err_str);
+    if err_str.contains("403") {
+        log::info!("Relay: subscription required (403 from server)");
+        *relay_sub_required.lock() = true;
+        return SessionOutcome::Done;
+    }
+    SessionOutcome::Retry
+}
+
+/// Bring a new session up to date: the full job state, detected processes
+/// and auto-yes panes, then the messages a dropped session left behind.
+async fn announce_session(
+    handle: &RelayHandle,
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    job_status: &Arc<Mutex<std::collections::HashMap<String, JobStatus>>>,
+    auto_yes_panes: &Arc<Mutex<std::collections::HashSet<String>>>,
+    ctx: &crate::job_context::JobContext,
+    pty_manager: &SharedPtyManager,
+) {
+    push_full_state(handle, jobs_config, job_status);
+    let processes = crate::process_snapshot::detect_processes_snapshot(
+        jobs_config,
+        &ctx.job_status,
+        pty_manager,
+    )
+    .await;
+    handle.send_message(&DesktopMessage::DetectedProcesses {
+        id: "desktop_process_snapshot".to_string(),
+        processes,
+    });
+    let pane_ids: Vec<String> = auto_yes_panes.lock().iter().cloned().collect();
+    handle.send_message(&DesktopMessage::AutoYesPanes { pane_ids });
+    for json in dead_letter::take_replayable() {
+        let _ = handle.tx.send(json);
     }
 }
 
@@ -432,6 +676,10 @@ struct SessionChannels<S, R> {
     rx: mpsc::UnboundedReceiver<String>,
     tx: mpsc::UnboundedSender<String>,
     cancel: tokio_util::sync::CancellationToken,
+    pings: PendingPings,
+    logs: Arc<Mutex<log_batch::LogBatch>>,
+    log_interval: Option<Duration>,
+    peer: SharedPeer,
 }
 
 async fn run_session<S, R>(
@@ -450,23 +698,39 @@ async fn run_session<S, R>(
         mut rx,
         tx,
         cancel,
+        pings,
+        logs,
+        log_interval,
+        peer,
     } = channels;
-    let mut heartbeat = tokio::time::interval(Duration::from_secs(30));
-    let mut last_pong = tokio::time::Instant::now();
-    let pong_timeout = Duration::from_secs(90); // 3 missed pings
+    let interval = heartbeat_interval(
+        ctx.settings
+            .lock()
+            .relay
+            .as_ref()
+            .map_or(0, |r| r.heartbeat_secs),
+    );
+    let mut ticker = tokio::time::interval(interval);
+    let mut heartbeat = Heartbeat::new(interval, tokio::time::Instant::now());
+    let next_log_flush =
+        |now: tokio::time::Instant| now + log_interval.map_or(Duration::ZERO, log_batch::jittered);
+    let mut log_flush_at = next_log_flush(tokio::time::Instant::now());
 
     loop {
         tokio::select! {
             Some(msg) = rx.recv() => {
-                if !send_ws_message(&mut ws_sink, Message::Text(msg.into())).await {
+                if !send_ws_message(&mut ws_sink, Message::Text(msg.clone().into())).await {
+                    dead_letter::record(Some(msg), "websocket send failed");
                     break;
                 }
             }
             Some(msg) = ws_stream.next() => {
                 match msg {
                     Ok(Message::Text(text)) => {
-                        let response = handler::handle_incoming(
+                        let response = handle_text(
                             &text,
+                            &pings,
+                            &peer,
                             jobs_config,
                             ctx,
                             pty_manager,
@@ -482,24 +746,226 @@ async fn run_session<S, R>(
                         }
                     }
                     Ok(Message::Pong(_)) => {
-                        last_pong = tokio::time::Instant::now();
+                        heartbeat.on_pong(tokio::time::Instant::now());
                     }
                     Ok(Message::Close(_)) | Err(_) => break,
                     _ => {}
                 }
             }
-            _ = heartbeat.tick() => {
-                if last_pong.elapsed() > pong_timeout {
-                    log::warn!("Relay: no pong received in {:?}, reconnecting", last_pong.elapsed());
-                    break;
-                }
-                if !send_ws_message(&mut ws_sink, Message::Ping(vec![].into())).await {
+            _ = ticker.tick() => {
+                if !send_heartbeat(&mut ws_sink, &mut heartbeat).await {
                     break;
                 }
             }
+            _ = tokio::time::sleep_until(log_flush_at), if log_interval.is_some() => {
+                queue_batched_logs(&logs, &tx);
+                log_flush_at = next_log_flush(tokio::time::Instant::now());
+            }
             _ = cancel.cancelled() => break,
         }
     }
+
+    // Anything still queued never reached the relay.
+    rx.close();
+    while let Ok(msg) = rx.try_recv() {
+        dead_letter::record(Some(msg), "connection lost");
+    }
+}
+
+/// Answer a text frame: relay messages are handled here, anything else goes
+/// to the mobile message handler. Returns the reply to send, if any.
+async fn handle_text(
+    text: &str,
+    pings: &PendingPings,
+    peer: &SharedPeer,
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    ctx: &crate::job_context::JobContext,
+    pty_manager: &SharedPtyManager,
+    event_sink: &dyn crate::events::EventSink,
+) -> Option<String> {
+    match handle_server_message(text, pings, peer) {
+        ServerFrame::Handled => return None,
+        ServerFrame::Reply(json) => return Some(json),
+        ServerFrame::Other => {}
+    }
+    handler::handle_incoming(text, jobs_config, ctx, pty_manager, event_sink).await
+}
+
+/// Send a websocket ping, unless the last ones went unanswered for too long.
+/// Returns false when the session should reconnect.
+async fn send_heartbeat<S>(ws_sink: &mut S, heartbeat: &mut Heartbeat) -> bool
+where
+    S: SinkExt<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    let now = tokio::time::Instant::now();
+    if heartbeat.timed_out(now) {
+        log::warn!(
+            "Relay: no pong received in {:?}, reconnecting",
+            now - heartbeat.last_pong
+        );
+        return false;
+    }
+    if !send_ws_message(ws_sink, Message::Ping(vec![].into())).await {
+        return false;
+    }
+    heartbeat.on_ping_sent(now);
+    true
+}
+
+/// Queue every job's batched log output for sending.
+fn queue_batched_logs(logs: &Mutex<log_batch::LogBatch>, tx: &mpsc::UnboundedSender<String>) {
+    for (name, content) in logs.lock().drain() {
+        if let Ok(json) = serde_json::to_string(&log_chunk_message(&name, content)) {
+            let _ = tx.send(json);
+        }
+    }
+}
+
+/// Record output still batched when the session ended as dropped, so the
+/// next session replays it.
+fn keep_batched_logs(logs: &Mutex<log_batch::LogBatch>) {
+    for (name, content) in logs.lock().drain() {
+        if let Ok(json) = serde_json::to_string(&log_chunk_message(&name, content)) {
+            dead_letter::record(Some(json), "connection lost");
+        }
+    }
+}
+
+/// What `handle_server_message` made of a text frame.
+enum ServerFrame {
+    Handled,
+    /// Handled, and this reply goes back to the relay.
+    Reply(String),
+    /// Not a relay message; it came from a mobile.
+    Other,
+}
+
+/// Handle messages from the relay itself rather than a mobile. A welcome
+/// from a relay that negotiates is answered with our own `Hello`.
+fn handle_server_message(text: &str, pings: &PendingPings, peer: &SharedPeer) -> ServerFrame {
+    match serde_json::from_str::<ServerMessage>(text) {
+        Ok(ServerMessage::Pong { id, mobile_clients }) => {
+            if let Some(done) = pings.lock().remove(&id) {
+                let _ = done.send(mobile_clients);
+            }
+            ServerFrame::Handled
+        }
+        Ok(ServerMessage::Welcome {
+            server_version,
+            protocol_version,
+            min_protocol_version,
+            capabilities,
+            ..
+        }) => {
+            let welcomed = RelayPeer {
+                protocol_version,
+                min_protocol_version,
+                capabilities,
+            };
+            log_peer_protocol(&server_version, &welcomed);
+            *peer.lock() = Some(welcomed);
+            // Relays that predate negotiation would fan the hello out to mobiles.
+            if protocol_version == 0 {
+                return ServerFrame::Handled;
+            }
+            serde_json::to_string(&hello_message()).map_or(ServerFrame::Handled, ServerFrame::Reply)
+        }
+        _ => ServerFrame::Other,
+    }
+}
+
+fn hello_message() -> DesktopMessage {
+    DesktopMessage::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        capabilities: vec![
+            desktop_capabilities::PTY.to_string(),
+            desktop_capabilities::TRIGGER_RESULT.to_string(),
+        ],
+    }
+}
+
+fn log_peer_protocol(server_version: &str, peer: &RelayPeer) {
+    if PROTOCOL_VERSION < peer.min_protocol_version {
+        log::error!(
+            "Relay: server {} needs protocol {} or newer, this app speaks {}. Update ClawTab to use remote access.",
+            server_version,
+            peer.min_protocol_version,
+            PROTOCOL_VERSION
+        );
+    } else if peer.protocol_version < PROTOCOL_VERSION {
+        log::info!(
+            "Relay: server {} speaks protocol {} (ours {}), features it lacks are disabled",
+            server_version,
+            peer.protocol_version,
+            PROTOCOL_VERSION
+        );
+    } else {
+        log::debug!(
+            "Relay: server {} protocol {}, capabilities {:?}",
+            server_version,
+            peer.protocol_version,
+            peer.capabilities
+        );
+    }
+}
+
+fn heartbeat_interval(secs: u64) -> Duration {
+    let secs = if secs == 0 {
+        DEFAULT_HEARTBEAT_SECS
+    } else {
+        secs
+    };
+    Duration::from_secs(secs.clamp(MIN_HEARTBEAT_SECS, MAX_HEARTBEAT_SECS))
+}
+
+/// Ping/pong bookkeeping for one session. Pongs that take longer than half an
+/// interval widen the miss budget (up to `MAX_PONG_MISSES`) so a slow link
+/// degrades to a more tolerant deadline instead of flapping; prompt pongs
+/// shrink it back.
+struct Heartbeat {
+    interval: Duration,
+    misses_allowed: u32,
+    ping_sent: Option<tokio::time::Instant>,
+    last_pong: tokio::time::Instant,
+}
+
+impl Heartbeat {
+    fn new(interval: Duration, now: tokio::time::Instant) -> Self {
+        Self {
+            interval,
+            misses_allowed: BASE_PONG_MISSES,
+            ping_sent: None,
+            last_pong: now,
+        }
+    }
+
+    fn on_ping_sent(&mut self, now: tokio::time::Instant) {
+        if self.ping_sent.is_none() {
+            self.ping_sent = Some(now);
+        }
+    }
+
+    fn on_pong(&mut self, now: tokio::time::Instant) {
+        if let Some(sent) = self.ping_sent.take() {
+            if now - sent > self.interval / 2 {
+                if self.misses_allowed < MAX_PONG_MISSES {
+                    self.misses_allowed += 1;
+                    log::info!(
+                        "Relay: late pong ({:?}), allowing {} missed pings",
+                        now - sent,
+                        self.misses_allowed
+                    );
+                }
+            } else if self.misses_allowed > BASE_PONG_MISSES {
+                self.misses_allowed -= 1;
+            }
+        }
+        self.last_pong = now;
+    }
+
+    fn timed_out(&self, now: tokio::time::Instant) -> bool {
+        now - self.last_pong > self.interval * self.misses_allowed
+    }
 }
 
 async fn send_ws_message<S>(ws_sink: &mut S, message: Message) -> bool
@@ -531,7 +997,11 @@ fn job_to_remote(job: &Job) -> RemoteJob {
     RemoteJob {
         name: job.name.clone(),
         job_type: match job.job_type {
-            crate::config::jobs::JobType::Binary => "binary".to_string(),
+            // Remote clients use "shell" for shell panes; a shell job runs
+            // like a binary job.
+            crate::config::jobs::JobType::Binary | crate::config::jobs::JobType::Shell => {
+                "binary".to_string()
+            }
             crate::config::jobs::JobType::Claude => "claude".to_string(),
             crate::config::jobs::JobType::Job => "job".to_string(),
         },
@@ -559,12 +1029,16 @@ fn status_to_remote(status: &JobStatus) -> RemoteJobStatus {
             run_id: run_id.clone(),
             started_at: started_at.clone(),
         },
+        JobStatus::Queued { queued_at } => RemoteJobStatus::Queued {
+            queued_at: queued_at.clone(),
+        },
         JobStatus::Success { last_run } => RemoteJobStatus::Success {
             last_run: last_run.clone(),
         },
         JobStatus::Failed {
             last_run,
             exit_code,
+            ..
         } => RemoteJobStatus::Failed {
             last_run: last_run.clone(),
             exit_code: *exit_code,
@@ -572,3 +1046,44 @@ fn status_to_remote(status: &JobStatus) -> RemoteJobStatus {
         JobStatus::Paused => RemoteJobStatus::Paused,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_server_message, ws_url, PendingPings, ServerFrame, SharedPeer};
+
+    #[test]
+    fn ws_url_keeps_the_path_prefix() {
+        assert_eq!(
+            ws_url("https://relay.example.com"),
+            "wss://relay.example.com/ws"
+        );
+        assert_eq!(
+            ws_url("https://example.com/clawtab/"),
+            "wss://example.com/clawtab/ws"
+        );
+        assert_eq!(ws_url("http://localhost:8080"), "ws://localhost:8080/ws");
+        assert_eq!(
+            ws_url("wss://example.com/custom/ws"),
+            "wss://example.com/custom/ws"
+        );
+    }
+
+    #[test]
+    fn welcome_is_answered_with_hello_only_when_relay_negotiates() {
+        let pings = PendingPings::default();
+        let peer = SharedPeer::default();
+
+        let welcome = r#"{"type":"welcome","connection_id":"c","server_version":"1","protocol_version":1,"min_protocol_version":1,"capabilities":[]}"#;
+        match handle_server_message(welcome, &pings, &peer) {
+            ServerFrame::Reply(json) => assert!(json.contains("\"hello\""), "got {json}"),
+            _ => panic!("expected a hello reply"),
+        }
+        assert!(peer.lock().is_some());
+
+        let legacy = r#"{"type":"welcome","connection_id":"c","server_version":"0"}"#;
+        assert!(matches!(
+            handle_server_message(legacy, &pings, &peer),
+            ServerFrame::Handled
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a relay probe failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeError {
+    /// Not an http(s) URL.
+    InvalidUrl,
+    /// The host name did not resolve.
+    Dns,
+    /// Nothing is listening on the host and port.
+    Refused,
+    Timeout,
+    /// The server answered, but its TLS certificate is invalid, expired or
+    /// for another host.
+    Certificate,
+    /// `/health` answered with a non-success status or an unexpected body.
+    Http,
+    /// Any other connection failure.
+    Connect,
+}
+
+/// Outcome of checking a relay URL before it is saved.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelayProbe {
+    /// `/health` answered successfully.
+    pub reachable: bool,
+    /// The URL is https and the certificate passed verification.
+    pub tls_verified: bool,
+    pub server_version: Option<String>,
+    pub latency_ms: Option<u64>,
+    pub error_kind: Option<ProbeError>,
+    pub error: Option<String>,
+}
+
+impl RelayProbe {
+    fn failed(kind: ProbeError, error: String) -> Self {
+        Self {
+            reachable: false,
+            tls_verified: false,
+            server_version: None,
+            latency_ms: None,
+            error_kind: Some(kind),
+            error: Some(error),
+        }
+    }
+}
+
+/// GET `<server_url>/health` and report reachability, certificate validity
+/// and the server version. Certificate problems are told apart from other
+/// connection failures by retrying without certificate verification.
+pub async fn probe(server_url: &str) -> RelayProbe {
+    let base = server_url.trim().trim_end_matches('/');
+    let https = match reqwest::Url::parse(base).map(|u| u.scheme().to_string()) {
+        Ok(scheme) if scheme == "https" => true,
+        Ok(scheme) if scheme == "http" => false,
+        _ => {
+            return RelayProbe::failed(
+                ProbeError::InvalidUrl,
+                format!("Not an http(s) URL: {}", server_url),
+            )
+        }
+    };
+    let health_url = format!("{}/health", base);
+
+    let started = Instant::now();
+    let resp = match get(&health_url, false).await {
+        Ok(resp) => resp,
+        Err(e) => {
+            let mut kind = classify(&e);
+            if https && kind == ProbeError::Connect && get(&health_url, true).await.is_ok() {
+                kind = ProbeError::Certificate;
+            }
+            return RelayProbe::failed(kind, e.to_string());
+        }
+    };
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).ok();
+
+    let status = resp.status();
+    if !status.is_success() {
+        return RelayProbe {
+            tls_verified: https,
+            latency_ms,
+            ..RelayProbe::failed(ProbeError::Http, format!("/health returned {}", status))
+        };
+    }
+    match resp.json::<serde_json::Value>().await {
+        Ok(body) => RelayProbe {
+            reachable: true,
+            tls_verified: https,
+            server_version: body["version"].as_str().map(str::to_string),
+            latency_ms,
+            error_kind: None,
+            error: None,
+        },
+        Err(e) => RelayProbe {
+            tls_verified: https,
+            latency_ms,
+            ..RelayProbe::failed(
+                ProbeError::Http,
+                format!("/health is not a ClawTab relay response: {}", e),
+            )
+        },
+    }
+}
+
+async fn get(url: &str, accept_invalid_certs: bool) -> Result<reqwest::Response, reqwest::Error> {
+    reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .build()?
+        .get(url)
+        .send()
+        .await
+}
+
+fn classify(e: &reqwest::Error) -> ProbeError {
+    if e.is_timeout() {
+        return ProbeError::Timeout;
+    }
+    let mut source: Option<&(dyn std::error::Error + 'static)> = std::error::Error::source(e);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            if io.kind() == std::io::ErrorKind::ConnectionRefused {
+                return ProbeError::Refused;
+            }
+        }
+        let message = err.to_string();
+        if message.contains("dns error") || message.contains("failed to lookup address") {
+            return ProbeError::Dns;
+        }
+        source = err.source();
+    }
+    ProbeError::Connect
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_bad_urls_and_refused_connections_distinctly() {
+        let bad = probe("relay.example.com").await;
+        assert_eq!(bad.error_kind, Some(ProbeError::InvalidUrl));
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let refused = probe(&format!("http://127.0.0.1:{}/", port)).await;
+        assert!(!refused.reachable);
+        assert_eq!(refused.error_kind, Some(ProbeError::Refused));
+    }
+}
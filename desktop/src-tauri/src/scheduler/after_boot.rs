@@ -0,0 +1,128 @@
+//! `after_boot_secs` jobs: one run each, that many seconds after the scheduler
+//! starts. The set of jobs is taken at startup and each is dropped once it
+//! fires, so a config reload never starts a second run.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::config::jobs::{Job, JobsConfig};
+use crate::job_context::JobContext;
+
+/// Upper bound on one sleep, so edits to the delay or the enabled flag made
+/// while waiting are picked up.
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+struct Pending {
+    slugs: HashSet<String>,
+}
+
+impl Pending {
+    fn new(jobs: &[Job]) -> Self {
+        Self {
+            slugs: delays(jobs).into_iter().map(|(slug, _)| slug).collect(),
+        }
+    }
+
+    /// Slugs whose delay has passed at `elapsed`, removed from the pending
+    /// set. Jobs that were disabled or lost their delay since startup are
+    /// dropped without firing.
+    fn take_due(&mut self, delays: &[(String, u64)], elapsed: Duration) -> Vec<String> {
+        self.slugs
+            .retain(|slug| delays.iter().any(|(s, _)| s == slug));
+        let due: Vec<String> = delays
+            .iter()
+            .filter(|(slug, secs)| {
+                self.slugs.contains(slug) && elapsed >= Duration::from_secs(*secs)
+            })
+            .map(|(slug, _)| slug.clone())
+            .collect();
+        for slug in &due {
+            self.slugs.remove(slug);
+        }
+        due
+    }
+
+    /// Time until the next pending job is due, if any are left.
+    fn next_wait(&self, delays: &[(String, u64)], elapsed: Duration) -> Option<Duration> {
+        delays
+            .iter()
+            .filter(|(slug, _)| self.slugs.contains(slug))
+            .map(|(_, secs)| Duration::from_secs(*secs).saturating_sub(elapsed))
+            .min()
+    }
+}
+
+/// `(slug, after_boot_secs)` of the enabled jobs that have a delay.
+fn delays(jobs: &[Job]) -> Vec<(String, u64)> {
+    jobs.iter()
+        .filter(|j| j.enabled)
+        .filter_map(|j| j.after_boot_secs.map(|secs| (j.slug.clone(), secs)))
+        .collect()
+}
+
+/// Start each `after_boot_secs` job once its delay since `started` has
+/// passed. Returns when none are left.
+pub(super) async fn run(jobs_config: Arc<Mutex<JobsConfig>>, ctx: JobContext, started: Instant) {
+    let mut pending = Pending::new(&jobs_config.lock().jobs);
+    loop {
+        let jobs = jobs_config.lock().jobs.clone();
+        let delays = delays(&jobs);
+        let elapsed = started.elapsed();
+        for slug in pending.take_due(&delays, elapsed) {
+            if let Some(job) = jobs.iter().find(|j| j.slug == slug) {
+                log::info!(
+                    "Running job '{}' {}s after startup",
+                    job.name,
+                    elapsed.as_secs()
+                );
+                super::spawn_cron_job(job.clone(), ctx.clone(), "after_boot");
+            }
+        }
+        let Some(wait) = pending.next_wait(&delays, elapsed) else {
+            return;
+        };
+        tokio::time::sleep(wait.min(MAX_WAIT)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(slugs: &[&str]) -> Pending {
+        Pending {
+            slugs: slugs.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn entries(delays: &[(&str, u64)]) -> Vec<(String, u64)> {
+        delays.iter().map(|(s, d)| (s.to_string(), *d)).collect()
+    }
+
+    #[test]
+    fn fires_each_job_once_after_its_delay() {
+        let mut p = pending(&["a/warm", "b/sync"]);
+        let d = entries(&[("a/warm", 10), ("b/sync", 60)]);
+        let secs = Duration::from_secs;
+        assert!(p.take_due(&d, secs(5)).is_empty());
+        assert_eq!(p.next_wait(&d, secs(5)), Some(secs(5)));
+        assert_eq!(p.take_due(&d, secs(10)), vec!["a/warm".to_string()]);
+        assert!(p.take_due(&d, secs(20)).is_empty());
+        assert_eq!(p.take_due(&d, secs(61)), vec!["b/sync".to_string()]);
+        assert_eq!(p.next_wait(&d, secs(61)), None);
+    }
+
+    #[test]
+    fn jobs_added_or_disabled_after_startup_never_fire() {
+        let mut p = pending(&["a/warm"]);
+        let secs = Duration::from_secs;
+        // Disabled while waiting, then re-enabled: it was dropped for good.
+        assert!(p.take_due(&[], secs(1)).is_empty());
+        let d = entries(&[("a/warm", 5), ("c/new", 5)]);
+        assert!(p.take_due(&d, secs(10)).is_empty());
+        assert_eq!(p.next_wait(&d, secs(10)), None);
+    }
+}
@@ -0,0 +1,350 @@
+//! Global cap on how many jobs may be `Running` at once (`max_concurrent`).
+//!
+//! Slots are counted from the status map rather than held as semaphore
+//! permits: a tmux run stays `Running` after `execute_job` returns, until its
+//! monitor or stale-pane cleanup moves it on, so the status map is the only
+//! place that knows when a slot frees up.
+//!
+//! Waiting runs are kept in a FIFO queue and admitted in trigger order. Each
+//! entry has its own id, so one queued run can be cancelled without touching
+//! the job's other queued or running instances.
+//!
+//! Jobs sharing a `mutex_group` also wait for each other, whatever the
+//! global cap. Admission records the group's holder, as does reattaching a
+//! run after a restart; the hold ends once that job is no longer `Running`.
+//! Runs waiting on a held group don't hold up the runs queued behind them.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::Utc;
+use parking_lot::Mutex;
+
+use crate::config::jobs::{Job, JobStatus};
+use crate::job_context::JobContext;
+
+use super::lifecycle::{self, Event};
+
+/// Serializes admission so two triggers can't both claim the last slot.
+static ADMISSION: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+static QUEUE: Mutex<RunQueue> = Mutex::new(RunQueue::new());
+
+/// Held mutex groups, each mapped to the slug of the job holding it.
+static MUTEX_GROUPS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn mutex_groups() -> &'static Mutex<HashMap<String, String>> {
+    MUTEX_GROUPS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Proof of admission. Hold it until the job is marked `Running`.
+pub(crate) type Slot = tokio::sync::MutexGuard<'static, ()>;
+
+/// The queued run was removed by `cancel_queued_run` before it got a slot.
+pub(crate) struct Cancelled;
+
+struct QueueEntry {
+    id: u64,
+    slug: String,
+    mutex_group: Option<String>,
+    /// Status to restore when the job's last queued run is cancelled.
+    previous: Option<JobStatus>,
+}
+
+struct RunQueue {
+    next_id: u64,
+    entries: VecDeque<QueueEntry>,
+}
+
+impl RunQueue {
+    const fn new() -> Self {
+        Self {
+            next_id: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Append a run of `slug`. A job that already has a queued run keeps the
+    /// status from before its first one, not `Queued`.
+    fn push(&mut self, job: &Job, current: Option<JobStatus>) -> u64 {
+        let slug = job.slug.as_str();
+        let previous = match self.entries.iter().find(|e| e.slug == slug) {
+            Some(earlier) => earlier.previous.clone(),
+            None => current,
+        };
+        self.next_id += 1;
+        self.entries.push_back(QueueEntry {
+            id: self.next_id,
+            slug: slug.to_string(),
+            mutex_group: job.mutex_group.clone(),
+            previous,
+        });
+        self.next_id
+    }
+
+    fn contains(&self, id: u64) -> bool {
+        self.entries.iter().any(|e| e.id == id)
+    }
+
+    /// Whether a run queued now, or the entry `id`, is next in line. Entries
+    /// waiting on a group in `held` are passed over.
+    fn is_next(&self, id: Option<u64>, held: &HashMap<String, String>) -> bool {
+        for entry in &self.entries {
+            if Some(entry.id) == id {
+                return true;
+            }
+            if !entry
+                .mutex_group
+                .as_ref()
+                .is_some_and(|g| held.contains_key(g))
+            {
+                return false;
+            }
+        }
+        id.is_none()
+    }
+
+    /// Like `is_next`, but only runs queued on `group` count. For runs that
+    /// bypass the concurrency limit, which still take their turn on the group.
+    fn is_next_in_group(&self, id: Option<u64>, group: &str) -> bool {
+        self.entries
+            .iter()
+            .find(|e| Some(e.id) == id || e.mutex_group.as_deref() == Some(group))
+            .is_none_or(|e| Some(e.id) == id)
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.entries.retain(|e| e.id != id);
+    }
+
+    /// Remove the oldest queued run of `slug`.
+    fn cancel(&mut self, slug: &str) -> Option<QueueEntry> {
+        let pos = self.entries.iter().position(|e| e.slug == slug)?;
+        self.entries.remove(pos)
+    }
+
+    fn has_slug(&self, slug: &str) -> bool {
+        self.entries.iter().any(|e| e.slug == slug)
+    }
+}
+
+/// A run's place in the queue, given up when the waiting future ends for
+/// any reason.
+struct QueuedRun(u64);
+
+impl Drop for QueuedRun {
+    fn drop(&mut self) {
+        QUEUE.lock().remove(self.0);
+    }
+}
+
+/// Wait until the job's mutex group is free, fewer than `max_concurrent`
+/// jobs are running (unless `bypass_limit`) and every run queued earlier has
+/// started, reporting the job as `Queued` while it waits. A `bypass_limit`
+/// run only waits for earlier runs on its group. `Ok(None)` when
+/// neither a limit nor a mutex group applies.
+pub(crate) async fn wait_for_slot(
+    job: &Job,
+    ctx: &JobContext,
+    run_id: &str,
+    bypass_limit: bool,
+) -> Result<Option<Slot>, Cancelled> {
+    let mut queued: Option<QueuedRun> = None;
+    loop {
+        let limit = if bypass_limit {
+            0
+        } else {
+            ctx.settings.lock().max_concurrent as usize
+        };
+        if limit == 0 && job.mutex_group.is_none() {
+            return Ok(None);
+        }
+        let slot = ADMISSION.lock().await;
+        let first_wait = queued.is_none();
+        let holder = {
+            let mut queue = QUEUE.lock();
+            let id = queued.as_ref().map(|q| q.0);
+            if id.is_some_and(|id| !queue.contains(id)) {
+                return Err(Cancelled);
+            }
+            let mut held = mutex_groups().lock();
+            let statuses = ctx.job_status.lock();
+            release_finished(&mut held, &statuses);
+            let holder = job.mutex_group.as_ref().and_then(|g| held.get(g).cloned());
+            let next = match (&job.mutex_group, bypass_limit) {
+                (Some(group), true) => queue.is_next_in_group(id, group),
+                _ => queue.is_next(id, &held),
+            };
+            if holder.is_none() && next && (limit == 0 || running_count(&statuses) < limit) {
+                if let Some(group) = &job.mutex_group {
+                    held.insert(group.clone(), job.slug.clone());
+                }
+                return Ok(Some(slot));
+            }
+            if first_wait {
+                let current = statuses.get(&job.slug).cloned();
+                queued = Some(QueuedRun(queue.push(job, current)));
+            }
+            holder
+        };
+        drop(slot);
+        if first_wait {
+            let fields = match holder {
+                Some(holder) => {
+                    log::info!(
+                        "Job '{}' queued: mutex group held by '{}'",
+                        job.name,
+                        holder
+                    );
+                    serde_json::json!({ "mutex_group": job.mutex_group, "held_by": holder })
+                }
+                None => {
+                    log::info!(
+                        "Job '{}' queued: {} concurrent job(s) already running",
+                        job.name,
+                        limit
+                    );
+                    serde_json::json!({ "max_concurrent": limit })
+                }
+            };
+            lifecycle::log(Event::Queued, run_id, &job.slug, fields);
+            mark_queued(job, ctx);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Record `job` as the holder of its mutex group, for a run that was
+/// reattached rather than admitted here. Holds only live in memory, so
+/// without this a restart would let a second run into the group.
+pub(crate) fn hold_group(job: &Job) {
+    if let Some(group) = &job.mutex_group {
+        mutex_groups()
+            .lock()
+            .insert(group.clone(), job.slug.clone());
+    }
+}
+
+/// Drop the holds of jobs that are no longer running.
+fn release_finished(held: &mut HashMap<String, String>, statuses: &HashMap<String, JobStatus>) {
+    held.retain(|_, slug| matches!(statuses.get(slug), Some(JobStatus::Running { .. })));
+}
+
+/// Cancel the oldest queued run of `slug`, leaving running instances alone.
+/// Once the job has no queued runs left, a `Queued` status goes back to what
+/// it was before queueing. Returns the job's status afterwards, or `None`
+/// when nothing was queued.
+pub fn cancel_queued_run(slug: &str, ctx: &JobContext) -> Option<JobStatus> {
+    let (entry, last) = {
+        let mut queue = QUEUE.lock();
+        let entry = queue.cancel(slug)?;
+        let last = !queue.has_slug(slug);
+        (entry, last)
+    };
+    log::info!("Cancelled queued run of '{}'", slug);
+    let mut status = ctx.job_status.lock();
+    let current = status.get(slug).cloned().unwrap_or(JobStatus::Idle);
+    if !last || !matches!(current, JobStatus::Queued { .. }) {
+        return Some(current);
+    }
+    let restored = entry.previous.unwrap_or(JobStatus::Idle);
+    status.insert(slug.to_string(), restored.clone());
+    drop(status);
+    crate::relay::push_status_update(&ctx.relay, slug, &restored);
+    Some(restored)
+}
+
+fn running_count(statuses: &HashMap<String, JobStatus>) -> usize {
+    statuses
+        .values()
+        .filter(|s| matches!(s, JobStatus::Running { .. }))
+        .count()
+}
+
+/// Report the job as `Queued`, unless an instance of it is running: that
+/// status stays so the running instance keeps its slot and its pane.
+fn mark_queued(job: &Job, ctx: &JobContext) {
+    let new_status = JobStatus::Queued {
+        queued_at: Utc::now().to_rfc3339(),
+    };
+    let mut status = ctx.job_status.lock();
+    if matches!(status.get(&job.slug), Some(JobStatus::Running { .. })) {
+        return;
+    }
+    status.insert(job.slug.clone(), new_status.clone());
+    drop(status);
+    crate::relay::push_status_update(&ctx.relay, &job.slug, &new_status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(slug: &str, mutex_group: Option<&str>) -> Job {
+        let yaml = "name: t\njob_type: binary\nenabled: true\npath: /bin/true\ncron: ''\n";
+        let mut job: Job = serde_yml::from_str(yaml).unwrap();
+        job.slug = slug.to_string();
+        job.mutex_group = mutex_group.map(str::to_string);
+        job
+    }
+
+    #[test]
+    fn admits_in_order_and_cancels_the_oldest_run_of_a_job() {
+        let none = HashMap::new();
+        let mut q = RunQueue::new();
+        assert!(q.is_next(None, &none));
+        let a = q.push(&job("a/job", None), Some(JobStatus::Idle));
+        let b = q.push(&job("b/job", None), None);
+        let a2 = q.push(
+            &job("a/job", None),
+            Some(JobStatus::Queued {
+                queued_at: String::new(),
+            }),
+        );
+        assert!(!q.is_next(None, &none));
+        assert!(q.is_next(Some(a), &none) && !q.is_next(Some(b), &none));
+
+        let cancelled = q.cancel("a/job").unwrap();
+        assert_eq!(cancelled.id, a);
+        assert!(!q.contains(a) && q.is_next(Some(b), &none));
+        assert!(q.has_slug("a/job"));
+        // The second run kept the pre-queue status, not `Queued`.
+        assert!(matches!(
+            q.cancel("a/job").unwrap().previous,
+            Some(JobStatus::Idle)
+        ));
+        assert!(!q.contains(a2) && q.cancel("a/job").is_none());
+    }
+
+    #[test]
+    fn runs_waiting_on_a_held_group_do_not_block_the_queue() {
+        let mut q = RunQueue::new();
+        let db = q.push(&job("a/migrate", Some("db")), None);
+        let other = q.push(&job("b/report", None), None);
+        let mut held = HashMap::from([("db".to_string(), "c/backup".to_string())]);
+        assert!(q.is_next(Some(other), &held));
+        assert!(!q.is_next(Some(other), &HashMap::new()));
+        assert!(q.is_next(Some(db), &HashMap::new()));
+        // A run bypassing the limit only queues behind its own group.
+        assert!(!q.is_next_in_group(None, "db"));
+        assert!(q.is_next_in_group(None, "cache"));
+        assert!(q.is_next_in_group(Some(db), "db"));
+
+        let running = JobStatus::Running {
+            run_id: String::new(),
+            started_at: String::new(),
+            pane_id: None,
+            tmux_session: None,
+        };
+        release_finished(
+            &mut held,
+            &HashMap::from([("c/backup".to_string(), running)]),
+        );
+        assert_eq!(held.len(), 1);
+        release_finished(&mut held, &HashMap::new());
+        assert!(held.is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+use chrono::{DateTime, Local, LocalResult, TimeZone, Utc};
+use cron::Schedule;
+
+/// Wall clock a job's cron expressions are evaluated on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CronZone {
+    Local,
+    Named(chrono_tz::Tz),
+}
+
+impl CronZone {
+    /// The job's IANA `timezone`, else the settings default, else the system
+    /// local zone. Unknown names are logged and skipped.
+    pub fn resolve(job_tz: Option<&str>, default_tz: Option<&str>) -> Self {
+        for name in [job_tz, default_tz]
+            .into_iter()
+            .flatten()
+            .filter(|n| !n.is_empty())
+        {
+            match name.parse::<chrono_tz::Tz>() {
+                Ok(tz) => return CronZone::Named(tz),
+                Err(_) => log::warn!("Unknown timezone '{}', ignoring", name),
+            }
+        }
+        CronZone::Local
+    }
+
+    /// Whether any schedule fires in `(since, now]`. Fire times are matched on
+    /// this zone's wall clock, so a DST change neither skips nor repeats a run:
+    /// a time inside a spring-forward gap fires once the clock jumps past it,
+    /// and a time repeated by a fall-back fires only on its first occurrence.
+    pub fn fires_between(
+        self,
+        schedules: &[Schedule],
+        since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        match self {
+            CronZone::Local => fires_between_in(&Local, schedules, since, now),
+            CronZone::Named(tz) => fires_between_in(&tz, schedules, since, now),
+        }
+    }
+
+    /// Earliest fire time after `after` across `schedules`.
+    pub fn next_after(self, schedules: &[Schedule], after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            CronZone::Local => next_after_in(&Local, schedules, after),
+            CronZone::Named(tz) => next_after_in(&tz, schedules, after),
+        }
+    }
+
+    /// Fire times in `(since, until]` across `schedules`, in order and at
+    /// most `limit`, matched on the wall clock like
+    /// [`fires_between`](Self::fires_between): a time in a spring-forward gap
+    /// is reported when the clock jumps past it, and a repeated time once.
+    pub fn fire_times(
+        self,
+        schedules: &[Schedule],
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: usize,
+    ) -> Vec<DateTime<Utc>> {
+        match self {
+            CronZone::Local => fire_times_in(&Local, schedules, since, until, limit),
+            CronZone::Named(tz) => fire_times_in(&tz, schedules, since, until, limit),
+        }
+    }
+}
+
+fn fires_between_in<Z: TimeZone>(
+    tz: &Z,
+    schedules: &[Schedule],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    // Walk the schedule over naive wall-clock times; Utc stands in for a
+    // zone without offsets.
+    let from = since.with_timezone(tz).naive_local();
+    let to = now.with_timezone(tz).naive_local();
+    schedules.iter().any(|s| {
+        s.after(&Utc.from_utc_datetime(&from))
+            .map(|t| t.naive_utc())
+            .take_while(|t| *t <= to)
+            .any(|wall| match tz.from_local_datetime(&wall) {
+                // Repeated by a fall-back: only the first occurrence counts.
+                // Some zone data also reports the transition instant itself.
+                LocalResult::Ambiguous(a, b) => [a, b]
+                    .into_iter()
+                    .map(|t| t.with_timezone(&Utc))
+                    .filter(|t| t.with_timezone(tz).naive_local() == wall)
+                    .min()
+                    .is_none_or(|first| first > since && first <= now),
+                // Single, or skipped over by a spring-forward gap
+                _ => true,
+            })
+    })
+}
+
+fn fire_times_in<Z: TimeZone>(
+    tz: &Z,
+    schedules: &[Schedule],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    limit: usize,
+) -> Vec<DateTime<Utc>> {
+    let from = since.with_timezone(tz).naive_local();
+    let to = until.with_timezone(tz).naive_local();
+    let mut times: Vec<DateTime<Utc>> = schedules
+        .iter()
+        .flat_map(|s| {
+            s.after(&Utc.from_utc_datetime(&from))
+                .map(|t| t.naive_utc())
+                .take_while(|t| *t <= to)
+                .filter_map(|wall| match tz.from_local_datetime(&wall) {
+                    LocalResult::Single(t) => Some(t.with_timezone(&Utc)),
+                    LocalResult::Ambiguous(a, b) => Some(a.min(b).with_timezone(&Utc)),
+                    // Skipped over: fires once the clock is past the gap
+                    LocalResult::None => (1..=180)
+                        .map(|m| wall + chrono::Duration::minutes(m))
+                        .find_map(|w| tz.from_local_datetime(&w).earliest())
+                        .map(|t| t.with_timezone(&Utc)),
+                })
+                .filter(|t| *t > since && *t <= until)
+                .take(limit)
+        })
+        .collect();
+    times.sort();
+    times.dedup();
+    times.truncate(limit);
+    times
+}
+
+fn next_after_in<Z: TimeZone>(
+    tz: &Z,
+    schedules: &[Schedule],
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let after = after.with_timezone(tz);
+    schedules
+        .iter()
+        .filter_map(|s| s.after(&after).next())
+        .map(|t| t.with_timezone(&Utc))
+        .min()
+}
+
+/// Reject a timezone name `CronZone::resolve` would ignore. Unset or empty
+/// is valid.
+pub fn validate_timezone(name: Option<&str>) -> Result<(), String> {
+    match name.filter(|n| !n.is_empty()) {
+        Some(n) => n
+            .parse::<chrono_tz::Tz>()
+            .map(|_| ())
+            .map_err(|_| format!("unknown timezone '{}'", n)),
+        None => Ok(()),
+    }
+}
+
+/// Rewrite a single standard crontab expression into the 6-field form the
+/// `cron` crate expects: 5-field input gains a leading `0` seconds field, and
+/// day-of-week values move from 0=Sun to 1=Sun. Other field counts are
+/// returned unchanged for the parser to accept or reject.
+pub fn normalize_cron(cron: &str) -> String {
+    let parts: Vec<&str> = cron.split_whitespace().collect();
+    if parts.len() == 5 {
+        // 5-field cron: min hour dom month dow - prepend seconds
+        let dow = translate_dow(parts[4]);
+        format!(
+            "0 {} {} {} {} {}",
+            parts[0], parts[1], parts[2], parts[3], dow
+        )
+    } else if parts.len() == 6 {
+        // 6-field cron: sec min hour dom month dow
+        let dow = translate_dow(parts[5]);
+        format!(
+            "{} {} {} {} {} {}",
+            parts[0], parts[1], parts[2], parts[3], parts[4], dow
+        )
+    } else {
+        cron.to_string()
+    }
+}
+
+fn parse_single_cron(cron: &str) -> Result<Schedule, String> {
+    normalize_cron(cron)
+        .parse()
+        .map_err(|e| format!("invalid cron expression '{}': {}", cron, e))
+}
+
+fn cron_segments(cron: &str) -> Vec<&str> {
+    cron.split('|')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Translate day-of-week values from standard cron (0=Sun, 1-6=Mon-Sat)
+/// to the `cron` crate format (1=Sun, 2-7=Mon-Sat). Handles comma-separated
+/// lists and ranges.
+fn translate_dow(dow: &str) -> String {
+    if dow == "*" || dow == "?" {
+        return dow.to_string();
+    }
+    dow.split(',')
+        .map(|part| {
+            if part.contains('-') {
+                // Handle ranges like 0-5
+                let bounds: Vec<&str> = part.split('-').collect();
+                if bounds.len() == 2 {
+                    let lo = bounds[0]
+                        .parse::<u8>()
+                        .map(|v| if v <= 6 { v + 1 } else { v })
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|_| bounds[0].to_string());
+                    let hi = bounds[1]
+                        .parse::<u8>()
+                        .map(|v| if v <= 6 { v + 1 } else { v })
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|_| bounds[1].to_string());
+                    format!("{}-{}", lo, hi)
+                } else {
+                    part.to_string()
+                }
+            } else {
+                part.parse::<u8>()
+                    .map(|v| if v <= 6 { v + 1 } else { v })
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|_| part.to_string())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parse a job's cron field (one or more expressions joined by `|`). Invalid
+/// segments are skipped; `None` when nothing parses.
+pub(super) fn parse_cron(cron: &str) -> Option<Vec<Schedule>> {
+    let schedules: Vec<Schedule> = cron_segments(cron)
+        .into_iter()
+        .filter_map(|p| parse_single_cron(p).ok())
+        .collect();
+    if schedules.is_empty() {
+        None
+    } else {
+        Some(schedules)
+    }
+}
+
+/// Earliest upcoming fire time across a job's cron expressions, evaluated in
+/// `zone`. `None` for an empty or unparseable field.
+pub fn next_run(cron: &str, zone: CronZone) -> Option<DateTime<Utc>> {
+    zone.next_after(&parse_cron(cron)?, Utc::now())
+}
+
+/// Check every `|`-separated expression in a job's cron field, so a typo is
+/// reported at save time instead of the job silently never firing. An empty
+/// field (no schedule) is valid.
+pub fn validate_cron(cron: &str) -> Result<(), String> {
+    for segment in cron_segments(cron) {
+        parse_single_cron(segment)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn five_field_expressions_gain_seconds_and_shift_weekdays() {
+        assert_eq!(normalize_cron("*/15 * * * *"), "0 */15 * * * *");
+        assert_eq!(normalize_cron("0 9 * * 1-5"), "0 0 9 * * 2-6");
+        assert_eq!(normalize_cron("30 0 9 * * 0,6"), "30 0 9 * * 1,7");
+    }
+
+    #[test]
+    fn validate_reports_each_bad_segment() {
+        assert!(validate_cron("").is_ok());
+        assert!(validate_cron("0 9 * * 1-5 | */15 * * * *").is_ok());
+        let err = validate_cron("0 9 * * 1-5 | 61 * * * *").unwrap_err();
+        assert!(err.contains("61 * * * *"));
+        assert!(parse_cron("0 9 * * 1-5 | nonsense").is_some_and(|s| s.len() == 1));
+    }
+
+    fn ny(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        chrono_tz::America::New_York
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// Count windows of 30s ticks across `[start, end)` in which `cron` fires.
+    fn count_fires(cron: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> usize {
+        let zone = CronZone::resolve(Some("America/New_York"), None);
+        let schedules = parse_cron(cron).unwrap();
+        let mut fires = 0;
+        let mut since = start;
+        while since < end {
+            let now = since + chrono::Duration::seconds(30);
+            if zone.fires_between(&schedules, since, now) {
+                fires += 1;
+            }
+            since = now;
+        }
+        fires
+    }
+
+    #[test]
+    fn schedule_follows_the_job_timezone() {
+        // 8am New York is 12:00 UTC in summer
+        assert_eq!(
+            count_fires("0 8 * * *", ny(2026, 7, 1, 7, 0), ny(2026, 7, 1, 9, 0)),
+            1
+        );
+        assert!(
+            !CronZone::resolve(Some("America/New_York"), None).fires_between(
+                &parse_cron("0 8 * * *").unwrap(),
+                Utc.with_ymd_and_hms(2026, 7, 1, 7, 59, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 7, 1, 8, 1, 0).unwrap(),
+            )
+        );
+    }
+
+    #[test]
+    fn dst_transitions_neither_skip_nor_repeat() {
+        // 2:30 does not exist on 2026-03-08; 1:30 happens twice on 2026-11-01
+        let spring = ny(2026, 3, 8, 0, 0);
+        assert_eq!(
+            count_fires("30 2 * * *", spring, spring + chrono::Duration::hours(6)),
+            1
+        );
+        let fall = ny(2026, 11, 1, 0, 0);
+        assert_eq!(
+            count_fires("30 1 * * *", fall, fall + chrono::Duration::hours(6)),
+            1
+        );
+    }
+
+    #[test]
+    fn fire_times_match_the_tick_check() {
+        let zone = CronZone::resolve(Some("America/New_York"), None);
+        let schedules = parse_cron("0 9 * * 1-5 | 0 9 * * *").unwrap();
+        // Mon 2026-07-06 through Sun 2026-07-12, one fire per day
+        let times = zone.fire_times(&schedules, ny(2026, 7, 6, 0, 0), ny(2026, 7, 13, 0, 0), 100);
+        assert_eq!(times.len(), 7);
+        assert_eq!(times[0], ny(2026, 7, 6, 9, 0));
+        assert_eq!(
+            zone.fire_times(&schedules, ny(2026, 7, 6, 0, 0), ny(2026, 7, 13, 0, 0), 3)
+                .len(),
+            3
+        );
+
+        let spring = ny(2026, 3, 8, 0, 0);
+        let gap = zone.fire_times(
+            &parse_cron("30 2 * * *").unwrap(),
+            spring,
+            spring + chrono::Duration::hours(6),
+            10,
+        );
+        assert_eq!(gap, vec![ny(2026, 3, 8, 3, 0)]);
+        let fall = ny(2026, 11, 1, 0, 0);
+        let repeated = zone.fire_times(
+            &parse_cron("30 1 * * *").unwrap(),
+            fall,
+            fall + chrono::Duration::hours(6),
+            10,
+        );
+        assert_eq!(repeated.len(), 1);
+    }
+
+    #[test]
+    fn unknown_timezones_fall_back() {
+        assert_eq!(
+            CronZone::resolve(Some("Mars/Olympus"), None),
+            CronZone::Local
+        );
+        assert_eq!(
+            CronZone::resolve(Some(""), Some("Europe/Tallinn")),
+            CronZone::Named(chrono_tz::Europe::Tallinn)
+        );
+        assert!(validate_timezone(Some("Mars/Olympus")).is_err());
+        assert!(validate_timezone(None).is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::Utc;
+use futures_util::stream::{self, StreamExt};
+use parking_lot::Mutex;
+use tokio::process::Command;
+
+use crate::config::jobs::Job;
+use crate::history::RunItemRecord;
+
+use super::binary::{append_line, finish_capture, open_stream_log, stream_to_buf, BinaryOutput};
+use super::binary_runtime;
+use super::capture::{CappedOutput, DEFAULT_MAX_OUTPUT_BYTES};
+
+/// The job's batch items: `items`, then the non-blank lines of `items_file`.
+/// None when the job is not a batch job.
+pub(super) fn load_items(job: &Job, work_dir: &str) -> Result<Option<Vec<String>>, String> {
+    if job.items.is_empty() && job.items_file.is_none() {
+        return Ok(None);
+    }
+    let mut items = job.items.clone();
+    if let Some(file) = &job.items_file {
+        let path = Path::new(work_dir).join(file);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read items file {}: {}", path.display(), e))?;
+        items.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string),
+        );
+    }
+    Ok(Some(items))
+}
+
+/// State shared by the items of one batch run.
+struct Batch<'a> {
+    job: &'a Job,
+    run_id: &'a str,
+    started_at: &'a str,
+    stdout: Arc<Mutex<CappedOutput>>,
+    stderr: Arc<Mutex<CappedOutput>>,
+    log_file: Option<Arc<Mutex<std::fs::File>>>,
+    /// Some item has registered the run with `binary_runtime`.
+    registered: AtomicBool,
+}
+
+impl Batch<'_> {
+    /// Run one item, with its output lines tagged `[item]`. Returns None when
+    /// the run was stopped before the item started.
+    async fn run_item(
+        &self,
+        index: usize,
+        item: String,
+        mut cmd: Command,
+    ) -> Option<RunItemRecord> {
+        if self.registered.load(Ordering::SeqCst)
+            && !binary_runtime::is_registered(&self.job.slug, self.run_id)
+        {
+            return None;
+        }
+        let started_at = Utc::now().to_rfc3339();
+        cmd.env("CLAWTAB_ITEM", &item);
+        cmd.env("CLAWTAB_ITEM_INDEX", index.to_string());
+        let prefix = format!("[{}] ", item);
+        let exit_code = match self.spawn_and_wait(cmd, &prefix).await {
+            Ok(code) => code,
+            Err(e) => {
+                append_line(
+                    &self.stderr,
+                    self.log_file.as_deref(),
+                    &format!("{}{}", prefix, e),
+                );
+                Some(-1)
+            }
+        };
+        Some(RunItemRecord {
+            run_id: self.run_id.to_string(),
+            item_index: index as u32,
+            item,
+            started_at,
+            finished_at: Utc::now().to_rfc3339(),
+            exit_code,
+        })
+    }
+
+    async fn spawn_and_wait(&self, mut cmd: Command, prefix: &str) -> Result<Option<i32>, String> {
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to spawn process: {}", e))?;
+        let pid = child.id();
+        if let Some(pid) = pid {
+            binary_runtime::register_batch_item(self.job, self.run_id, self.started_at, pid);
+            self.registered.store(true, Ordering::SeqCst);
+        }
+        let stdout_task = child.stdout.take().map(|pipe| {
+            stream_to_buf(
+                pipe,
+                Arc::clone(&self.stdout),
+                self.log_file.clone(),
+                Some(prefix.to_string()),
+            )
+        });
+        let stderr_task = child.stderr.take().map(|pipe| {
+            stream_to_buf(
+                pipe,
+                Arc::clone(&self.stderr),
+                self.log_file.clone(),
+                Some(prefix.to_string()),
+            )
+        });
+
+        let status = child.wait().await;
+        if let Some(pid) = pid {
+            binary_runtime::release_batch_item(&self.job.slug, pid);
+        }
+        for task in [stdout_task, stderr_task].into_iter().flatten() {
+            let _ = task.await;
+        }
+        status
+            .map(|s| s.code())
+            .map_err(|e| format!("Failed to wait for process: {}", e))
+    }
+}
+
+/// Run the job's command once per item, at most `parallelism` at a time,
+/// each with the item in `CLAWTAB_ITEM`. Output from all items goes to the
+/// run's stdout/stderr; per-item exit codes come back in `items`.
+pub(super) async fn execute_binary_batch(
+    job: &Job,
+    items: Vec<String>,
+    run_id: &str,
+    started_at: &str,
+    build: impl Fn() -> Command,
+    stream_log_path: Option<&Path>,
+) -> BinaryOutput {
+    let cap = job.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+    let batch = Batch {
+        job,
+        run_id,
+        started_at,
+        stdout: Arc::new(Mutex::new(CappedOutput::new(cap))),
+        stderr: Arc::new(Mutex::new(CappedOutput::new(cap))),
+        log_file: open_stream_log(stream_log_path),
+        registered: AtomicBool::new(false),
+    };
+    let parallelism = job.parallelism.unwrap_or(1).max(1) as usize;
+    let total = items.len();
+    log::info!(
+        "[{}] Started batch job '{}': {} items, {} at a time",
+        run_id,
+        job.name,
+        total,
+        parallelism
+    );
+
+    let mut results: Vec<RunItemRecord> = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| batch.run_item(index, item, build()))
+        .buffer_unordered(parallelism)
+        .filter_map(|result| async move { result })
+        .collect()
+        .await;
+    binary_runtime::unregister(&job.slug);
+    results.sort_by_key(|r| r.item_index);
+
+    let skipped = total - results.len();
+    if skipped > 0 {
+        append_line(
+            &batch.stderr,
+            batch.log_file.as_deref(),
+            &format!("Run stopped; {} of {} items not started", skipped, total),
+        );
+    }
+    let (stdout, stdout_truncated) = finish_capture(batch.stdout);
+    let (stderr, stderr_truncated) = finish_capture(batch.stderr);
+    let truncated = stdout_truncated || stderr_truncated;
+    if truncated {
+        log::warn!(
+            "[{}] Output of '{}' exceeded {} bytes and was truncated in history",
+            run_id,
+            job.name,
+            cap
+        );
+    }
+
+    BinaryOutput {
+        code: batch_exit_code(&results, skipped),
+        stdout,
+        stderr,
+        truncated,
+        items: results,
+    }
+}
+
+/// 0 when every item succeeded. Otherwise the first failed item's code (None
+/// if it was killed), or None when a stop left items unstarted.
+fn batch_exit_code(results: &[RunItemRecord], skipped: usize) -> Option<i32> {
+    match results.iter().find(|r| r.exit_code != Some(0)) {
+        Some(failed) => failed.exit_code,
+        None if skipped > 0 => None,
+        None => Some(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(index: u32, exit_code: Option<i32>) -> RunItemRecord {
+        RunItemRecord {
+            run_id: "run".to_string(),
+            item_index: index,
+            item: format!("item-{}", index),
+            started_at: String::new(),
+            finished_at: String::new(),
+            exit_code,
+        }
+    }
+
+    #[test]
+    fn batch_fails_with_the_first_failed_item() {
+        assert_eq!(
+            batch_exit_code(&[item(0, Some(0)), item(1, Some(0))], 0),
+            Some(0)
+        );
+        assert_eq!(
+            batch_exit_code(&[item(0, Some(0)), item(1, Some(3)), item(2, Some(1))], 0),
+            Some(3)
+        );
+        assert_eq!(batch_exit_code(&[item(0, None), item(1, Some(2))], 0), None);
+        assert_eq!(batch_exit_code(&[item(0, Some(0))], 2), None);
+    }
+
+    #[test]
+    fn items_combine_the_list_and_file() {
+        let dir = std::env::temp_dir().join(format!("clawtab-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("items.txt"), "b\n\n  c  \n").unwrap();
+        let yaml = "name: t\njob_type: binary\nenabled: true\npath: /bin/true\ncron: ''\n";
+        let mut job: Job = serde_yml::from_str(yaml).unwrap();
+        let work_dir = dir.to_string_lossy();
+
+        assert_eq!(load_items(&job, &work_dir).unwrap(), None);
+        job.items = vec!["a".to_string()];
+        job.items_file = Some("items.txt".to_string());
+        assert_eq!(
+            load_items(&job, &work_dir).unwrap(),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        job.items_file = Some("missing.txt".to_string());
+        assert!(load_items(&job, &work_dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,14 +2,28 @@ use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tokio::io::{AsyncBufReadExt, AsyncRead};
+use tokio::io::AsyncRead;
 use tokio::process::Command;
 use tokio::task::JoinHandle;
 
-use crate::config::jobs::Job;
+use crate::config::jobs::{Job, JobType};
 use crate::config::settings::AppSettings;
+use crate::history::RunItemRecord;
 use crate::secrets::SecretsManager;
 
+use super::capture::{read_line_capped, CappedOutput, DEFAULT_MAX_OUTPUT_BYTES};
+
+/// Exit code and captured output of a binary run.
+pub(super) struct BinaryOutput {
+    pub(super) code: Option<i32>,
+    pub(super) stdout: String,
+    pub(super) stderr: String,
+    /// Either stream went past `max_output_bytes` and lost its middle.
+    pub(super) truncated: bool,
+    /// Per-item results of a batch run; empty for a single run.
+    pub(super) items: Vec<RunItemRecord>,
+}
+
 pub(super) async fn execute_binary_job(
     job: &Job,
     run_id: &str,
@@ -19,16 +33,34 @@ pub(super) async fn execute_binary_job(
     params: &HashMap<String, String>,
     result_file: Option<&std::path::Path>,
     stream_log_path: Option<&std::path::Path>,
-) -> Result<(Option<i32>, String, String), String> {
-    let mut cmd = build_command(
-        job,
-        secrets,
-        settings,
-        params,
-        result_file,
-        stream_log_path,
-        run_id,
-    );
+) -> Result<BinaryOutput, String> {
+    let build = || {
+        build_command(
+            job,
+            secrets,
+            settings,
+            params,
+            result_file,
+            stream_log_path,
+            run_id,
+        )
+    };
+    if let Some(items) = super::batch::load_items(job, &work_dir(job, settings))? {
+        return Ok(super::batch::execute_binary_batch(
+            job,
+            items,
+            run_id,
+            started_at,
+            build,
+            stream_log_path,
+        )
+        .await);
+    }
+    let mut cmd = build();
+    if job.pty {
+        return super::pty_run::execute_pty_job(job, run_id, started_at, &cmd, stream_log_path)
+            .await;
+    }
 
     let mut child = cmd
         .spawn()
@@ -56,11 +88,12 @@ pub(super) async fn execute_binary_job(
         .ok_or_else(|| "Failed to capture stderr".to_string())?;
 
     let log_file = open_stream_log(stream_log_path);
-    let stdout_buf = Arc::new(Mutex::new(String::new()));
-    let stderr_buf = Arc::new(Mutex::new(String::new()));
+    let cap = job.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+    let stdout_buf = Arc::new(Mutex::new(CappedOutput::new(cap)));
+    let stderr_buf = Arc::new(Mutex::new(CappedOutput::new(cap)));
 
-    let stdout_task = stream_to_buf(stdout_pipe, Arc::clone(&stdout_buf), log_file.clone());
-    let stderr_task = stream_to_buf(stderr_pipe, Arc::clone(&stderr_buf), log_file.clone());
+    let stdout_task = stream_to_buf(stdout_pipe, Arc::clone(&stdout_buf), log_file.clone(), None);
+    let stderr_task = stream_to_buf(stderr_pipe, Arc::clone(&stderr_buf), log_file.clone(), None);
 
     let status = child
         .wait()
@@ -70,14 +103,41 @@ pub(super) async fn execute_binary_job(
     let _ = stdout_task.await;
     let _ = stderr_task.await;
 
-    let stdout = Arc::try_unwrap(stdout_buf)
-        .map(|m| m.into_inner())
-        .unwrap_or_default();
-    let stderr = Arc::try_unwrap(stderr_buf)
-        .map(|m| m.into_inner())
-        .unwrap_or_default();
+    let (stdout, stdout_truncated) = finish_capture(stdout_buf);
+    let (stderr, stderr_truncated) = finish_capture(stderr_buf);
+    let truncated = stdout_truncated || stderr_truncated;
+    if truncated {
+        log::warn!(
+            "[{}] Output of '{}' exceeded {} bytes and was truncated in history",
+            run_id,
+            job.name,
+            cap
+        );
+    }
 
-    Ok((status.code(), stdout, stderr))
+    Ok(BinaryOutput {
+        code: status.code(),
+        stdout,
+        stderr,
+        truncated,
+        items: Vec::new(),
+    })
+}
+
+pub(super) fn finish_capture(buf: Arc<Mutex<CappedOutput>>) -> (String, bool) {
+    match Arc::try_unwrap(buf) {
+        Ok(m) => {
+            let out = m.into_inner();
+            let truncated = out.truncated();
+            (out.finish(), truncated)
+        }
+        Err(_) => (String::new(), false),
+    }
+}
+
+/// Env var a trigger param is passed in, e.g. `CLAWTAB_PARAM_BRANCH`.
+pub(super) fn param_env_key(param: &str) -> String {
+    format!("CLAWTAB_PARAM_{}", param.to_ascii_uppercase())
 }
 
 /// Build the tokio Command with env_clear + minimal PATH/HOME passthrough,
@@ -92,12 +152,17 @@ fn build_command(
     stream_log_path: Option<&std::path::Path>,
     run_id: &str,
 ) -> Command {
-    let work_dir = job.work_dir.clone().unwrap_or_else(|| {
-        let s = settings.lock();
-        s.default_work_dir.clone()
-    });
+    let work_dir = work_dir(job, settings);
 
-    let mut cmd = Command::new(&job.path);
+    let mut cmd = match job.job_type {
+        // `$0` is "sh"; `args` become `$1`, `$2`, ... of the script.
+        JobType::Shell => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(&job.path).arg("sh");
+            cmd
+        }
+        _ => Command::new(&job.path),
+    };
     cmd.args(&job.args);
     cmd.env_clear();
 
@@ -109,10 +174,13 @@ fn build_command(
     }
 
     {
+        let keys = super::params::scoped_secret_keys(job, settings);
         let sm = secrets.lock();
-        for key in &job.secret_keys {
+        for key in &keys {
             if let Some(value) = sm.get(key) {
                 cmd.env(key, value);
+            } else {
+                super::params::warn_missing_secret(&sm, key, &job.slug);
             }
         }
     }
@@ -137,8 +205,7 @@ fn build_command(
     // per-invocation inputs from /v1/triggers/run without needing a Claude
     // agent for templating.
     for (k, v) in params {
-        let key = format!("CLAWTAB_PARAM_{}", k.to_ascii_uppercase());
-        cmd.env(key, v);
+        cmd.env(param_env_key(k), v);
     }
 
     cmd.current_dir(&work_dir);
@@ -158,11 +225,18 @@ fn build_command(
     cmd
 }
 
+fn work_dir(job: &Job, settings: &Arc<Mutex<AppSettings>>) -> String {
+    job.work_dir.clone().unwrap_or_else(|| {
+        let s = settings.lock();
+        s.default_work_dir.clone()
+    })
+}
+
 /// Open the streaming log file in truncate+write mode. Returns None and logs
 /// a warning on failure so a missing log doesn't fail the whole run.
 /// Writers from the two reader tasks share an Arc<Mutex<File>> so interleaving
 /// stays line-coherent.
-fn open_stream_log(path: Option<&std::path::Path>) -> Option<Arc<Mutex<std::fs::File>>> {
+pub(super) fn open_stream_log(path: Option<&std::path::Path>) -> Option<Arc<Mutex<std::fs::File>>> {
     let p = path?;
     match std::fs::OpenOptions::new()
         .create(true)
@@ -179,31 +253,39 @@ fn open_stream_log(path: Option<&std::path::Path>) -> Option<Arc<Mutex<std::fs::
 }
 
 /// Read `pipe` line-by-line; append each line to `buf` (and to `file` if open)
-/// until EOF. Shared by the stdout and stderr readers.
-fn stream_to_buf<R>(
+/// until EOF. Shared by the stdout and stderr readers. The log file gets every
+/// line; `buf` keeps only what fits its cap. Batch items tag their lines with
+/// `prefix`.
+pub(super) fn stream_to_buf<R>(
     pipe: R,
-    buf: Arc<Mutex<String>>,
+    buf: Arc<Mutex<CappedOutput>>,
     file: Option<Arc<Mutex<std::fs::File>>>,
+    prefix: Option<String>,
 ) -> JoinHandle<()>
 where
     R: AsyncRead + Unpin + Send + 'static,
 {
     tokio::spawn(async move {
-        let mut reader = tokio::io::BufReader::new(pipe).lines();
-        while let Ok(Some(line)) = reader.next_line().await {
-            append_line(&buf, file.as_deref(), &line);
+        let mut reader = tokio::io::BufReader::new(pipe);
+        let mut line = Vec::new();
+        while let Ok(true) = read_line_capped(&mut reader, &mut line).await {
+            let text = String::from_utf8_lossy(&line);
+            match &prefix {
+                Some(p) => append_line(&buf, file.as_deref(), &format!("{}{}", p, text)),
+                None => append_line(&buf, file.as_deref(), &text),
+            }
         }
     })
 }
 
 /// Append a line to the in-memory buffer and (if open) to the shared log file.
 /// One lock per line on each side keeps stdout/stderr writes from tearing.
-fn append_line(buf: &Mutex<String>, file: Option<&Mutex<std::fs::File>>, line: &str) {
-    {
-        let mut b = buf.lock();
-        b.push_str(line);
-        b.push('\n');
-    }
+pub(super) fn append_line(
+    buf: &Mutex<CappedOutput>,
+    file: Option<&Mutex<std::fs::File>>,
+    line: &str,
+) {
+    buf.lock().push_line(line);
     if let Some(f) = file {
         use std::io::Write;
         let mut g = f.lock();
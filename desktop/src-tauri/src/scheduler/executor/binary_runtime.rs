@@ -12,6 +12,13 @@ pub struct BinaryRuntimeState {
     pub started_at: String,
     pub pid: u32,
     pub pgid: i32,
+    /// Other live processes of the same batch run, each its own group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batch_pids: Vec<u32>,
+    /// A batch run that is still starting items, so it counts as running
+    /// between them. In memory only: the batch does not survive a restart.
+    #[serde(skip)]
+    pub batch_in_progress: bool,
 }
 
 static RUNNING: OnceLock<Mutex<HashMap<String, BinaryRuntimeState>>> = OnceLock::new();
@@ -27,12 +34,70 @@ pub fn register(job: &Job, run_id: &str, started_at: &str, pid: u32) {
         started_at: started_at.to_string(),
         pid,
         pgid: pid as i32,
+        batch_pids: Vec::new(),
+        batch_in_progress: false,
     };
     running().lock().insert(job.slug.clone(), state.clone());
-    if let Err(e) = write_state(&state) {
+    persist(&state);
+}
+
+/// Add a batch item's process to the run. The first item registers the run
+/// and marks the batch in progress.
+pub fn register_batch_item(job: &Job, run_id: &str, started_at: &str, pid: u32) {
+    let joined = {
+        let mut map = running().lock();
+        map.get_mut(&job.slug)
+            .filter(|state| state.run_id == run_id)
+            .map(|state| {
+                state.batch_pids.push(pid);
+                state.clone()
+            })
+    };
+    match joined {
+        Some(state) => persist(&state),
+        None => {
+            register(job, run_id, started_at, pid);
+            if let Some(state) = running().lock().get_mut(&job.slug) {
+                state.batch_in_progress = true;
+            }
+        }
+    }
+}
+
+/// Drop a finished batch item's process. The run stays registered, and
+/// running, until the batch unregisters it, with a live process as its main
+/// pid if any is left.
+pub fn release_batch_item(slug: &str, pid: u32) {
+    let released = {
+        let mut map = running().lock();
+        map.get_mut(slug).map(|state| {
+            state.batch_pids.retain(|p| *p != pid);
+            if state.pid == pid && !state.batch_pids.is_empty() {
+                state.pid = state.batch_pids.remove(0);
+                state.pgid = state.pid as i32;
+            }
+            state.clone()
+        })
+    };
+    if let Some(state) = released {
+        persist(&state);
+    }
+}
+
+/// Whether `run_id` is still the registered run of `slug`; false once it is
+/// stopped.
+pub fn is_registered(slug: &str, run_id: &str) -> bool {
+    running()
+        .lock()
+        .get(slug)
+        .is_some_and(|state| state.run_id == run_id)
+}
+
+fn persist(state: &BinaryRuntimeState) {
+    if let Err(e) = write_state(state) {
         log::warn!(
             "Failed to persist binary runtime state for {}: {}",
-            job.slug,
+            state.slug,
             e
         );
     }
@@ -79,7 +144,10 @@ pub fn is_running(slug: &str) -> bool {
     let Some(state) = state else {
         return false;
     };
-    if process_alive(state.pid) {
+    if state.batch_in_progress
+        || process_alive(state.pid)
+        || state.batch_pids.iter().any(|pid| process_alive(*pid))
+    {
         return true;
     }
     unregister(slug);
@@ -91,7 +159,7 @@ pub fn reattach_running_binary_jobs(jobs_config: &JobsConfig) -> HashMap<String,
     for job in jobs_config
         .jobs
         .iter()
-        .filter(|job| matches!(job.job_type, JobType::Binary))
+        .filter(|job| job.job_type.is_process())
     {
         let Some(state) = read_state(&job.slug) else {
             continue;
@@ -106,7 +174,7 @@ pub fn reattach_running_binary_jobs(jobs_config: &JobsConfig) -> HashMap<String,
     for job in jobs_config
         .jobs
         .iter()
-        .filter(|job| matches!(job.job_type, JobType::Binary))
+        .filter(|job| job.job_type.is_process())
     {
         if statuses.contains_key(&job.slug) {
             continue;
@@ -159,7 +227,12 @@ fn discover_external_state(slug: &str, job: Option<&Job>) -> Option<BinaryRuntim
     let job_dir = JobsConfig::jobs_dir_public()
         .map(|dir| dir.join(slug).to_string_lossy().into_owned())
         .unwrap_or_default();
-    let job_path = job.map(|j| j.path.clone()).unwrap_or_default();
+    // A shell job's path is its script text, which can be as short as `make`
+    // and would match unrelated processes.
+    let job_path = job
+        .filter(|j| j.job_type != JobType::Shell)
+        .map(|j| j.path.clone())
+        .unwrap_or_default();
     let output = std::process::Command::new("ps")
         .args(["-Ao", "pid=,ppid=,pgid=,command="])
         .output()
@@ -184,6 +257,8 @@ fn discover_external_state(slug: &str, job: Option<&Job>) -> Option<BinaryRuntim
             started_at: chrono::Utc::now().to_rfc3339(),
             pid: row.pid,
             pgid: row.pgid,
+            batch_pids: Vec::new(),
+            batch_in_progress: false,
         });
     }
     None
@@ -222,6 +297,11 @@ fn process_alive(pid: u32) -> bool {
 }
 
 fn kill_process_group(state: &BinaryRuntimeState) -> Result<(), String> {
+    for pid in &state.batch_pids {
+        unsafe {
+            libc::kill(-(*pid as i32), libc::SIGTERM);
+        }
+    }
     let pgid = state.pgid;
     if pgid <= 0 {
         return Err(format!("Invalid process group for {}", state.slug));
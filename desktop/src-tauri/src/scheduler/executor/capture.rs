@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// Bytes of each output stream kept in memory when the job sets no
+/// `max_output_bytes`.
+pub(super) const DEFAULT_MAX_OUTPUT_BYTES: u64 = 8 * 1024 * 1024;
+/// A line longer than this is split, so output without newlines can't grow
+/// the read buffer without bound.
+pub(super) const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Keeps the first and last lines of a stream within a byte cap, half for
+/// each end, and counts what was dropped in between.
+pub(super) struct CappedOutput {
+    half: usize,
+    head: String,
+    head_full: bool,
+    tail: VecDeque<String>,
+    tail_bytes: usize,
+    dropped_bytes: u64,
+}
+
+impl CappedOutput {
+    /// `cap` of 0 keeps everything.
+    pub(super) fn new(cap: u64) -> Self {
+        let half = match usize::try_from(cap) {
+            Ok(0) | Err(_) => usize::MAX,
+            Ok(cap) => cap / 2,
+        };
+        Self {
+            half,
+            head: String::new(),
+            head_full: false,
+            tail: VecDeque::new(),
+            tail_bytes: 0,
+            dropped_bytes: 0,
+        }
+    }
+
+    pub(super) fn push_line(&mut self, line: &str) {
+        let len = line.len() + 1;
+        if !self.head_full {
+            if self.head.len().saturating_add(len) <= self.half {
+                self.head.push_str(line);
+                self.head.push('\n');
+                return;
+            }
+            self.head_full = true;
+        }
+        self.tail.push_back(line.to_string());
+        self.tail_bytes += len;
+        while self.tail_bytes > self.half {
+            let Some(old) = self.tail.pop_front() else {
+                break;
+            };
+            self.tail_bytes -= old.len() + 1;
+            self.dropped_bytes += old.len() as u64 + 1;
+        }
+    }
+
+    pub(super) fn truncated(&self) -> bool {
+        self.dropped_bytes > 0
+    }
+
+    /// The kept output, with a marker where lines were dropped.
+    pub(super) fn finish(self) -> String {
+        let mut out = self.head;
+        if self.dropped_bytes > 0 {
+            out.push_str(&format!(
+                "[... {} bytes of output truncated ...]\n",
+                self.dropped_bytes
+            ));
+        }
+        for line in self.tail {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Read the next line into `line` without its line ending. Returns false at
+/// EOF. Lines past `MAX_LINE_BYTES` are returned in pieces.
+pub(super) async fn read_line_capped<R>(reader: &mut R, line: &mut Vec<u8>) -> std::io::Result<bool>
+where
+    R: AsyncBufRead + Unpin,
+{
+    line.clear();
+    loop {
+        let buf = reader.fill_buf().await?;
+        if buf.is_empty() {
+            return Ok(!line.is_empty());
+        }
+        let window = &buf[..buf.len().min(MAX_LINE_BYTES - line.len())];
+        if let Some(pos) = window.iter().position(|&b| b == b'\n') {
+            line.extend_from_slice(&window[..pos]);
+            reader.consume(pos + 1);
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return Ok(true);
+        }
+        let taken = window.len();
+        line.extend_from_slice(window);
+        reader.consume(taken);
+        if line.len() >= MAX_LINE_BYTES {
+            return Ok(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_everything_under_the_cap() {
+        let mut out = CappedOutput::new(100);
+        out.push_line("one");
+        out.push_line("two");
+        assert!(!out.truncated());
+        assert_eq!(out.finish(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn keeps_head_and_tail_with_marker() {
+        let mut out = CappedOutput::new(24);
+        for i in 0..10 {
+            out.push_line(&format!("line{}", i));
+        }
+        assert!(out.truncated());
+        assert_eq!(
+            out.finish(),
+            "line0\nline1\n[... 36 bytes of output truncated ...]\nline8\nline9\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn splits_overlong_lines_and_strips_crlf() {
+        let mut data = vec![b'x'; MAX_LINE_BYTES + 10];
+        data.extend_from_slice(b"\r\nend\n");
+        let mut reader = tokio::io::BufReader::new(&data[..]);
+        let mut line = Vec::new();
+        assert!(read_line_capped(&mut reader, &mut line).await.unwrap());
+        assert_eq!(line.len(), MAX_LINE_BYTES);
+        assert!(read_line_capped(&mut reader, &mut line).await.unwrap());
+        assert_eq!(line, vec![b'x'; 10]);
+        assert!(read_line_capped(&mut reader, &mut line).await.unwrap());
+        assert_eq!(line, b"end");
+        assert!(!read_line_capped(&mut reader, &mut line).await.unwrap());
+    }
+}
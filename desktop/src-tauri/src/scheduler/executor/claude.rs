@@ -6,9 +6,9 @@ use crate::config::jobs::Job;
 use crate::config::settings::AppSettings;
 use crate::secrets::SecretsManager;
 
-use super::params::{apply_params, collect_env_vars};
-use super::tmux_spawn::{spawn_agent_pane, SpawnArgs};
-use super::{project_window_name, resolve_agent_model, TmuxHandle};
+use super::params::{apply_params, apply_secret_refs, collect_env_vars};
+use super::tmux_spawn::{resolve_agent_launch, spawn_agent_pane, AgentLaunch, SpawnArgs};
+use super::{project_window_name, TmuxHandle};
 
 pub(super) async fn execute_claude_job(
     job: &Job,
@@ -17,29 +17,13 @@ pub(super) async fn execute_claude_job(
     params: &HashMap<String, String>,
     result_file: Option<&std::path::Path>,
 ) -> Result<(Option<i32>, String, String, Option<TmuxHandle>), String> {
-    let (provider, model, tmux_session, work_dir, agent_command) = {
-        let s = settings.lock();
-        let provider = job.agent_provider.unwrap_or(s.default_provider);
-        let model = resolve_agent_model(job, &s, provider);
-        let session = job
-            .tmux_session
-            .clone()
-            .unwrap_or_else(|| s.default_tmux_session.clone());
-        let wd = job
-            .work_dir
-            .clone()
-            .unwrap_or_else(|| s.default_work_dir.clone());
-        let command = match provider {
-            crate::agent_session::ProcessProvider::Claude => s.claude_path.clone(),
-            crate::agent_session::ProcessProvider::Codex
-            | crate::agent_session::ProcessProvider::Opencode
-            | crate::agent_session::ProcessProvider::Antigravity => {
-                provider.binary_name().to_string()
-            }
-            crate::agent_session::ProcessProvider::Shell => String::new(),
-        };
-        (provider, model, session, wd, command)
-    };
+    let AgentLaunch {
+        provider,
+        model,
+        tmux_session,
+        work_dir,
+        agent_command,
+    } = resolve_agent_launch(job, &settings.lock());
 
     let mut env_vars = collect_env_vars(job, secrets, settings);
     if let Some(p) = result_file {
@@ -49,21 +33,9 @@ pub(super) async fn execute_claude_job(
         ));
     }
 
-    let raw_prompt = std::fs::read_to_string(&job.path)
-        .map_err(|e| format!("Failed to read prompt file {}: {}", job.path, e))?;
-    let raw_prompt = apply_params(raw_prompt, params);
-
-    let prompt_content = if job.skill_paths.is_empty() {
-        raw_prompt
-    } else {
-        let skill_refs = job
-            .skill_paths
-            .iter()
-            .map(|p| format!("@{}", p))
-            .collect::<Vec<_>>()
-            .join(" ");
-        format!("{}\n\n{}", skill_refs, raw_prompt)
-    };
+    let (template, redact) = apply_secret_refs(read_prompt(job)?, job, secrets, settings);
+    let raw_prompt = apply_params(template, params);
+    let prompt_content = with_skill_refs(job, raw_prompt);
 
     spawn_agent_pane(SpawnArgs {
         tmux_session,
@@ -76,6 +48,27 @@ pub(super) async fn execute_claude_job(
         prompt_content,
         slug: &job.slug,
         aerospace_workspace: job.aerospace_workspace.as_deref(),
+        redact,
     })
     .await
 }
+
+/// The job's prompt file as written, placeholders and secret refs unfilled.
+pub(super) fn read_prompt(job: &Job) -> Result<String, String> {
+    std::fs::read_to_string(&job.path)
+        .map_err(|e| format!("Failed to read prompt file {}: {}", job.path, e))
+}
+
+/// Prefix the prompt with `@path` references to the job's skills.
+pub(super) fn with_skill_refs(job: &Job, raw_prompt: String) -> String {
+    if job.skill_paths.is_empty() {
+        return raw_prompt;
+    }
+    let skill_refs = job
+        .skill_paths
+        .iter()
+        .map(|p| format!("@{}", p))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("{}\n\n{}", skill_refs, raw_prompt)
+}
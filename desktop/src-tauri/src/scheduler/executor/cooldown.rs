@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+
+use crate::config::jobs::Job;
+use crate::job_context::JobContext;
+
+/// Seconds left in the job's `min_interval_secs` cooldown, measured from the
+/// start of its latest recorded run. `None` when the job may start now.
+pub(super) fn remaining(job: &Job, ctx: &JobContext) -> Option<u64> {
+    if job.min_interval_secs == 0 {
+        return None;
+    }
+    let last = ctx
+        .history
+        .lock()
+        .get_by_job_id(&job.slug, 1, 0)
+        .ok()?
+        .into_iter()
+        .next()?;
+    remaining_since(job.min_interval_secs, &last.started_at, Utc::now())
+}
+
+fn remaining_since(min_interval_secs: u64, last_started: &str, now: DateTime<Utc>) -> Option<u64> {
+    let started = DateTime::parse_from_rfc3339(last_started).ok()?;
+    let elapsed = u64::try_from((now - started.with_timezone(&Utc)).num_seconds()).unwrap_or(0);
+    min_interval_secs
+        .checked_sub(elapsed)
+        .filter(|left| *left > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_down_from_last_start() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(remaining_since(60, "2025-01-01T11:59:30Z", now), Some(30));
+        assert_eq!(remaining_since(60, "2025-01-01T11:59:00Z", now), None);
+        assert_eq!(remaining_since(60, "2025-01-01T11:00:00Z", now), None);
+        assert_eq!(remaining_since(60, "not a date", now), None);
+    }
+}
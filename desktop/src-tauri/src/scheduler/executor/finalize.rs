@@ -2,12 +2,14 @@ use std::sync::Arc;
 
 use chrono::Utc;
 
-use crate::config::jobs::{Job, JobStatus, NotifyTarget};
+use crate::config::jobs::{job_group, Job, JobStatus, NotifyTarget};
 use crate::job_context::JobContext;
+use crate::scheduler::lifecycle::{self, Event};
 use crate::telegram::{ActiveAgent, TelegramConfig};
 
 use super::super::monitor::MonitorParams;
-use super::notification::{build_telegram_stream, send_job_notification};
+use super::flapping::{self, Alert};
+use super::notification::{self, build_telegram_stream, send_job_notification};
 use super::TmuxHandle;
 
 /// Per-run context computed once at the top of `execute_job` and passed to
@@ -21,6 +23,21 @@ pub(super) struct RunCtx<'a> {
     pub trigger_id: &'a Option<String>,
     pub result_file: &'a Option<std::path::PathBuf>,
     pub telegram_config: &'a Option<TelegramConfig>,
+    /// `branch commit` of the work dir, when the job opted into git info.
+    pub git_label: Option<&'a str>,
+    /// Outcome of the job's previous run, read before this run marked it
+    /// Running. Drives `notify_on_change_only`.
+    pub previous_outcome: Option<bool>,
+    /// Exit code and length of the failure streak the job was on before this
+    /// run. Drives flap suppression.
+    pub previous_failure: Option<(i32, u32)>,
+    /// Milliseconds from `execute_job` entry until the executor returned. For
+    /// tmux jobs that is the point the agent command was sent to its pane.
+    pub launch_ms: u64,
+    /// No retry follows if this run fails, so a failure is reported.
+    pub final_attempt: bool,
+    /// Slugs of the runs whose `triggers` or hooks led to this one.
+    pub chain: &'a [String],
 }
 
 /// Wire up a freshly-spawned tmux pane: update Running status with pane info,
@@ -29,10 +46,21 @@ pub(super) struct RunCtx<'a> {
 pub(super) fn attach_monitor(
     rc: &RunCtx<'_>,
     handle: TmuxHandle,
+    stderr: String,
     pane_tx: &mut Option<tokio::sync::oneshot::Sender<(String, String)>>,
     use_auto_yes: bool,
 ) {
     publish_running_status(rc, &handle);
+    lifecycle::log(
+        Event::PaneReady,
+        rc.run_id,
+        &rc.job.slug,
+        serde_json::json!({
+            "pane_id": handle.pane_id,
+            "tmux_session": handle.tmux_session,
+            "launch_ms": rc.launch_ms,
+        }),
+    );
     notify_pane_listener(pane_tx, &handle);
     persist_pane_id(rc, &handle);
     super::enforce_live_pane_retention(rc.job, rc.ctx, rc.job.max_history as usize);
@@ -44,7 +72,7 @@ pub(super) fn attach_monitor(
         register_active_agent(rc, &handle);
     }
 
-    let params = build_monitor_params(rc, handle);
+    let params = build_monitor_params(rc, handle, stderr);
     tokio::spawn(super::super::monitor::monitor_pane(params));
 }
 
@@ -74,6 +102,7 @@ fn notify_pane_listener(
 fn persist_pane_id(rc: &RunCtx<'_>, handle: &TmuxHandle) {
     let h = rc.ctx.history.lock();
     let _ = h.update_pane_id(rc.run_id, &handle.pane_id);
+    let _ = h.update_launch_ms(rc.run_id, rc.launch_ms);
 }
 
 fn register_auto_yes(rc: &RunCtx<'_>, handle: &TmuxHandle) {
@@ -87,11 +116,10 @@ fn register_auto_yes(rc: &RunCtx<'_>, handle: &TmuxHandle) {
 }
 
 fn register_active_agent(rc: &RunCtx<'_>, handle: &TmuxHandle) {
-    let chat_id = rc.job.telegram_chat_id.or_else(|| {
-        rc.telegram_config
-            .as_ref()
-            .and_then(|c| c.chat_ids.first().copied())
-    });
+    let chat_id = match rc.telegram_config {
+        Some(c) => c.chat_for_job(rc.job.telegram_chat_id, job_group(rc.job)),
+        None => rc.job.telegram_chat_id,
+    };
     let Some(chat_id) = chat_id else { return };
 
     let ctx = rc.ctx;
@@ -113,19 +141,19 @@ fn register_active_agent(rc: &RunCtx<'_>, handle: &TmuxHandle) {
     ctx.active_agents_notify.notify_waiters();
 }
 
-fn build_monitor_params(rc: &RunCtx<'_>, handle: TmuxHandle) -> MonitorParams {
+fn build_monitor_params(rc: &RunCtx<'_>, handle: TmuxHandle, stderr: String) -> MonitorParams {
     let job = rc.job;
     let ctx = rc.ctx;
     let telegram = if job.notify_target == NotifyTarget::Telegram {
-        build_telegram_stream(rc.telegram_config, job.telegram_chat_id)
+        build_telegram_stream(rc.telegram_config, job.telegram_chat_id, job_group(job))
     } else {
         None
     };
-    let notify_on_success = rc
+    let (notify_on_success, notify_on_failure) = rc
         .telegram_config
         .as_ref()
-        .map(|c| c.notify_on_success)
-        .unwrap_or(true);
+        .map(|c| (c.notify_on_success, c.notify_on_failure))
+        .unwrap_or((true, true));
 
     MonitorParams {
         tmux_session: handle.tmux_session,
@@ -137,18 +165,33 @@ fn build_monitor_params(rc: &RunCtx<'_>, handle: TmuxHandle) -> MonitorParams {
         agent_group: (job.group == "agent").then(|| crate::agent::agent_group_from_slug(&job.slug)),
         agent_prompt_path: (job.group == "agent").then(|| std::path::PathBuf::from(&job.path)),
         kill_on_end: job.kill_on_end,
+        post_command: job.post_command.clone(),
+        telegram_log_mode: job.telegram_log_mode.clone(),
         telegram,
         telegram_notify: job.telegram_notify.clone(),
         notify_target: job.notify_target.clone(),
         history: Arc::clone(&ctx.history),
         job_status: Arc::clone(&ctx.job_status),
         notify_on_success,
+        notify_on_failure,
         relay: Arc::clone(&ctx.relay),
         notifier: ctx.notifier.clone(),
         is_reattach: false,
         protected_panes: Arc::clone(&ctx.protected_panes),
         trigger_id: rc.trigger_id.clone(),
         result_file: rc.result_file.clone(),
+        git_label: rc.git_label.map(str::to_string),
+        stderr,
+        post_run_hooks: super::PostRunHooks::new(job, ctx, rc.chain),
+        redact: [
+            handle.redact,
+            super::secret_values(job, &ctx.secrets, &ctx.settings),
+        ]
+        .concat(),
+        notify_on_change_only: job.notify_on_change_only,
+        previous_outcome: rc.previous_outcome,
+        previous_failure: rc.previous_failure,
+        failure_alerts: ctx.settings.lock().failure_alerts.clone(),
     }
 }
 
@@ -166,12 +209,30 @@ pub(super) struct RunOutcome<'a> {
 /// and (for trigger-initiated runs) push the trigger result.
 pub(super) async fn finalize_run(rc: &RunCtx<'_>, outcome: RunOutcome<'_>) {
     let finished_at = Utc::now().to_rfc3339();
+    let streak = if outcome.success {
+        0
+    } else {
+        flapping::next_streak(rc.previous_failure, outcome.exit_code.unwrap_or(-1))
+    };
     log_outcome(rc, &outcome);
-    publish_terminal_status(rc, &outcome, &finished_at);
+    publish_terminal_status(rc, &outcome, &finished_at, streak);
     record_history(rc, &outcome, &finished_at);
-    dispatch_notification(rc, &outcome).await;
-    if let Some(tid) = rc.trigger_id {
-        push_trigger_result(rc, tid, &outcome);
+    // A failure with a retry pending is only recorded
+    if outcome.success || rc.final_attempt {
+        let alert = {
+            let settings = rc.ctx.settings.lock();
+            flapping::classify(rc.previous_failure, streak, &settings.failure_alerts)
+        };
+        dispatch_notification(rc, &outcome, alert).await;
+        push_failure_notice(rc, &outcome, alert);
+        if let Some(tid) = rc.trigger_id {
+            push_trigger_result(rc, tid, &outcome);
+        }
+        if let Some(hooks) = super::PostRunHooks::new(rc.job, rc.ctx, rc.chain) {
+            hooks
+                .run(rc.run_id, outcome.success, outcome.exit_code)
+                .await;
+        }
     }
     if rc.job.group == "agent" {
         crate::agent::remove_agent_prompt(&std::path::PathBuf::from(&rc.job.path));
@@ -179,6 +240,21 @@ pub(super) async fn finalize_run(rc: &RunCtx<'_>, outcome: RunOutcome<'_>) {
 }
 
 fn log_outcome(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>) {
+    let event = if outcome.success {
+        Event::Completed
+    } else {
+        Event::Failed
+    };
+    lifecycle::log(
+        event,
+        rc.run_id,
+        &rc.job.slug,
+        serde_json::json!({
+            "exit_code": outcome.exit_code,
+            "error": outcome.error,
+            "launch_ms": rc.launch_ms,
+        }),
+    );
     if let Some(err) = outcome.error.filter(|_| !outcome.success) {
         log::error!("[{}] Job '{}' failed: {}", rc.run_id, rc.job.name, err);
     } else {
@@ -191,7 +267,12 @@ fn log_outcome(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>) {
     }
 }
 
-fn publish_terminal_status(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, finished_at: &str) {
+fn publish_terminal_status(
+    rc: &RunCtx<'_>,
+    outcome: &RunOutcome<'_>,
+    finished_at: &str,
+    streak: u32,
+) {
     let new_status = if outcome.success {
         JobStatus::Success {
             last_run: finished_at.to_string(),
@@ -200,6 +281,7 @@ fn publish_terminal_status(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, finished_a
         JobStatus::Failed {
             last_run: finished_at.to_string(),
             exit_code: outcome.exit_code.unwrap_or(-1),
+            consecutive_failures: streak,
         }
     };
     let ctx = rc.ctx;
@@ -210,54 +292,72 @@ fn publish_terminal_status(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, finished_a
 }
 
 fn record_history(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, finished_at: &str) {
+    let scrub = super::secret_values(rc.job, &rc.ctx.secrets, &rc.ctx.settings);
+    let stdout = crate::secrets::scrub(outcome.stdout, &scrub);
+    let stderr = crate::secrets::scrub(outcome.error.unwrap_or(outcome.stderr), &scrub);
     let h = rc.ctx.history.lock();
-    let stderr_for_db = outcome.error.unwrap_or(outcome.stderr);
-    if let Err(e) = h.update_finished(
-        rc.run_id,
-        finished_at,
-        outcome.exit_code,
-        outcome.stdout,
-        stderr_for_db,
-    ) {
+    if let Err(e) = h.update_finished(rc.run_id, finished_at, outcome.exit_code, &stdout, &stderr) {
         log::error!("Failed to update run record: {}", e);
     }
 }
 
-async fn dispatch_notification(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>) {
+async fn dispatch_notification(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, alert: Alert) {
     let job = rc.job;
     let ctx = rc.ctx;
+    if alert == Alert::Muted {
+        log::debug!(
+            "[{}] '{}' keeps failing, notification muted",
+            rc.run_id,
+            job.name
+        );
+        return;
+    }
+    if job.notify_on_change_only && rc.previous_outcome == Some(outcome.success) {
+        log::debug!(
+            "[{}] Outcome unchanged for '{}', skipping notification",
+            rc.run_id,
+            job.name
+        );
+        return;
+    }
     match job.notify_target {
         NotifyTarget::Telegram => {
             if job.telegram_notify.finish {
                 let Some(ref tg) = rc.telegram_config else {
                     return;
                 };
-                send_job_notification(
-                    tg,
-                    job.telegram_chat_id,
-                    crate::config::jobs::job_group(job),
-                    &job.name,
-                    outcome.exit_code,
-                    outcome.success,
-                )
-                .await;
+                send_job_notification(tg, job, outcome.exit_code, alert, rc.git_label).await;
             }
         }
         NotifyTarget::App => {
-            let event = if outcome.success {
-                "completed"
-            } else {
-                "failed"
-            };
-            crate::relay::push_job_notification(&ctx.relay, &job.slug, event, rc.run_id);
+            let event = alert.app_event();
+            crate::relay::push_job_notification(&ctx.relay, &job.slug, &event, rc.run_id);
             if let Some(ref n) = ctx.notifier {
-                n.notify_job(&job.name, event);
+                n.notify_job(&job.name, &event);
             }
         }
         NotifyTarget::None => {}
     }
 }
 
+/// Surface a failed run as a mobile toast, led by the first line of its error.
+fn push_failure_notice(rc: &RunCtx<'_>, outcome: &RunOutcome<'_>, alert: Alert) {
+    let body = match outcome.error.and_then(|e| e.lines().next()) {
+        Some(line) => line.to_string(),
+        None => match outcome.exit_code {
+            Some(code) => format!("Exited with code {}", code),
+            None => "Exited without a status code".to_string(),
+        },
+    };
+    notification::push_failure_notice(
+        &rc.ctx.relay,
+        &rc.job.notify_target,
+        &rc.job.name,
+        alert,
+        &body,
+    );
+}
+
 fn push_trigger_result(rc: &RunCtx<'_>, trigger_id: &str, outcome: &RunOutcome<'_>) {
     let relay = &rc.ctx.relay;
     if outcome.success {
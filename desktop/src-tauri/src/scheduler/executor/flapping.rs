@@ -0,0 +1,119 @@
+use crate::config::settings::FailureAlertSettings;
+
+/// How to notify about a finished run, given the job's failure streak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::scheduler) enum Alert {
+    Success,
+    Failure,
+    /// Failure `n` in a row: notify once as a streak summary.
+    Streak(u32),
+    /// Failure inside a throttled streak: stay quiet.
+    Muted,
+    /// First success after a throttled streak of `n` failures.
+    Recovered(u32),
+}
+
+impl Alert {
+    /// Completes "Job <name> ..." in notifications.
+    pub(in crate::scheduler) fn status_text(self) -> String {
+        match self {
+            Alert::Success => "finished".to_string(),
+            Alert::Failure | Alert::Muted => "failed".to_string(),
+            Alert::Streak(n) => format!("has failed {} times in a row", n),
+            Alert::Recovered(n) => format!("recovered after {} failures", n),
+        }
+    }
+
+    /// Event name for app notifications.
+    pub(in crate::scheduler) fn app_event(self) -> String {
+        match self {
+            Alert::Success => "completed".to_string(),
+            _ => self.status_text(),
+        }
+    }
+
+    /// Whether a chat with these success / failure preferences hears about
+    /// this run. A recovery is news to anyone who was told about the failures.
+    pub(in crate::scheduler) fn wanted(self, on_success: bool, on_failure: bool) -> bool {
+        match self {
+            Alert::Success => on_success,
+            Alert::Failure | Alert::Streak(_) => on_failure,
+            Alert::Recovered(_) => on_failure || on_success,
+            Alert::Muted => false,
+        }
+    }
+}
+
+/// Length of the failure streak this outcome leaves. The streak only grows
+/// while the exit code stays the same.
+pub(in crate::scheduler) fn next_streak(previous: Option<(i32, u32)>, exit_code: i32) -> u32 {
+    match previous {
+        Some((code, n)) if code == exit_code => n + 1,
+        _ => 1,
+    }
+}
+
+/// Pick the alert for a finished run. `previous` is the streak before this
+/// run, `streak` the one after it (0 for a success).
+pub(in crate::scheduler) fn classify(
+    previous: Option<(i32, u32)>,
+    streak: u32,
+    settings: &FailureAlertSettings,
+) -> Alert {
+    let threshold = settings.flap_threshold;
+    if streak == 0 {
+        return match previous {
+            Some((_, n)) if threshold > 0 && n >= threshold => Alert::Recovered(n),
+            _ => Alert::Success,
+        };
+    }
+    if threshold == 0 || streak < threshold {
+        Alert::Failure
+    } else if streak == threshold
+        || (settings.remind_every > 0 && (streak - threshold).is_multiple_of(settings.remind_every))
+    {
+        Alert::Streak(streak)
+    } else {
+        Alert::Muted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(flap_threshold: u32, remind_every: u32) -> FailureAlertSettings {
+        FailureAlertSettings {
+            flap_threshold,
+            remind_every,
+        }
+    }
+
+    #[test]
+    fn streak_resets_on_a_different_exit_code() {
+        assert_eq!(next_streak(None, 1), 1);
+        assert_eq!(next_streak(Some((1, 4)), 1), 5);
+        assert_eq!(next_streak(Some((1, 4)), 2), 1);
+    }
+
+    #[test]
+    fn mutes_after_threshold_and_reminds_periodically() {
+        let s = settings(3, 10);
+        let alerts: Vec<Alert> = (1..=14).map(|n| classify(None, n, &s)).collect();
+        assert_eq!(alerts[1], Alert::Failure);
+        assert_eq!(alerts[2], Alert::Streak(3));
+        assert_eq!(alerts[3], Alert::Muted);
+        assert_eq!(alerts[12], Alert::Streak(13));
+        assert_eq!(alerts[13], Alert::Muted);
+    }
+
+    #[test]
+    fn recovery_is_reported_only_after_a_throttled_streak() {
+        let s = settings(3, 0);
+        assert_eq!(classify(Some((1, 5)), 0, &s), Alert::Recovered(5));
+        assert_eq!(classify(Some((1, 2)), 0, &s), Alert::Success);
+        assert_eq!(classify(None, 7, &s), Alert::Muted);
+        assert_eq!(classify(None, 7, &settings(0, 0)), Alert::Failure);
+        assert_eq!(classify(Some((1, 7)), 0, &settings(0, 0)), Alert::Success);
+    }
+}
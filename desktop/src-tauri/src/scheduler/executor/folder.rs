@@ -1,4 +1,5 @@
 use parking_lot::Mutex;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -6,9 +7,11 @@ use crate::config::jobs::Job;
 use crate::config::settings::AppSettings;
 use crate::secrets::SecretsManager;
 
-use super::params::{apply_params, collect_env_vars};
-use super::tmux_spawn::{spawn_agent_pane, SpawnArgs};
-use super::{project_window_name, resolve_agent_model, TmuxHandle};
+use super::params::{
+    apply_param_defaults, apply_params, apply_secret_refs, collect_env_vars, placeholder_names,
+};
+use super::tmux_spawn::{resolve_agent_launch, spawn_agent_pane, AgentLaunch, SpawnArgs};
+use super::{project_window_name, TmuxHandle};
 
 pub(super) async fn execute_folder_job(
     job: &Job,
@@ -29,46 +32,17 @@ pub(super) async fn execute_folder_job(
 
     let _folder = CwtFolder::from_path_with_job(project_root, job_id)?;
 
-    let central_job_md = crate::config::jobs::central_job_md_path(&job.slug)
-        .ok_or("Could not determine config directory")?;
-
-    if !central_job_md.exists() {
-        return Err(format!(
-            "No job.md found for '{}' at {}",
-            job.slug,
-            central_job_md.display()
-        ));
-    }
+    let (template, redact) = apply_secret_refs(read_job_md(job)?, job, secrets, settings);
+    let raw_prompt = apply_params(template, params);
 
-    let raw_prompt = std::fs::read_to_string(&central_job_md)
-        .map_err(|e| format!("Failed to read {}: {}", central_job_md.display(), e))?;
-    let raw_prompt = apply_params(raw_prompt, params);
-
-    let (provider, model, tmux_session, work_dir, agent_command) = {
-        let s = settings.lock();
-        let provider = job.agent_provider.unwrap_or(s.default_provider);
-        let model = resolve_agent_model(job, &s, provider);
-        let session = job
-            .tmux_session
-            .clone()
-            .unwrap_or_else(|| s.default_tmux_session.clone());
-        let command = match provider {
-            crate::agent_session::ProcessProvider::Claude => s.claude_path.clone(),
-            crate::agent_session::ProcessProvider::Codex
-            | crate::agent_session::ProcessProvider::Opencode
-            | crate::agent_session::ProcessProvider::Antigravity => {
-                provider.binary_name().to_string()
-            }
-            crate::agent_session::ProcessProvider::Shell => String::new(),
-        };
-        (provider, model, session, folder_path.clone(), command)
-    };
-
-    let prompt_content = if provider == crate::agent_session::ProcessProvider::Shell {
-        raw_prompt
-    } else {
-        build_folder_prompt(job, raw_prompt)
-    };
+    let AgentLaunch {
+        provider,
+        model,
+        tmux_session,
+        work_dir,
+        agent_command,
+    } = resolve_folder_launch(job, &settings.lock())?;
+    let prompt_content = folder_prompt(job, provider, raw_prompt);
 
     let mut env_vars = collect_env_vars(job, secrets, settings);
     if let Some(p) = result_file {
@@ -89,10 +63,130 @@ pub(super) async fn execute_folder_job(
         prompt_content,
         slug: &job.slug,
         aerospace_workspace: job.aerospace_workspace.as_deref(),
+        redact,
     })
     .await
 }
 
+/// Folder jobs always run in their project folder.
+pub(super) fn resolve_folder_launch(job: &Job, s: &AppSettings) -> Result<AgentLaunch, String> {
+    let folder_path = job
+        .folder_path
+        .clone()
+        .ok_or("Folder job requires folder_path")?;
+    Ok(AgentLaunch {
+        work_dir: folder_path,
+        ..resolve_agent_launch(job, s)
+    })
+}
+
+/// The prompt sent to the pane. Shell panes get the job.md as-is, agents get
+/// it wrapped in the project and job context.
+pub(super) fn folder_prompt(
+    job: &Job,
+    provider: crate::agent_session::ProcessProvider,
+    raw_prompt: String,
+) -> String {
+    if provider == crate::agent_session::ProcessProvider::Shell {
+        raw_prompt
+    } else {
+        build_folder_prompt(job, raw_prompt)
+    }
+}
+
+pub(super) fn read_job_md(job: &Job) -> Result<String, String> {
+    let central_job_md = crate::config::jobs::central_job_md_path(&job.slug)
+        .ok_or("Could not determine config directory")?;
+
+    if !central_job_md.exists() {
+        return Err(format!(
+            "No job.md found for '{}' at {}",
+            job.slug,
+            central_job_md.display()
+        ));
+    }
+
+    std::fs::read_to_string(&central_job_md)
+        .map_err(|e| format!("Failed to read {}: {}", central_job_md.display(), e))
+}
+
+/// An `@path` reference in an assembled folder-job prompt.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptReference {
+    pub reference: String,
+    /// Where the agent will look for it, relative refs resolved against the
+    /// job's folder.
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Dry assembly of a folder job's prompt, for diagnosing broken refs.
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderPromptReport {
+    pub prompt: String,
+    pub references: Vec<PromptReference>,
+    /// `{name}` placeholders left after applying the param defaults.
+    pub unresolved_params: Vec<String>,
+}
+
+/// Assemble the prompt the way `execute_folder_job` does, using param
+/// defaults only. `{{secret:..}}` tokens are left unresolved so no secret
+/// values end up in the report.
+pub fn validate_folder_prompt(job: &Job) -> Result<FolderPromptReport, String> {
+    let folder_path = job
+        .folder_path
+        .as_ref()
+        .ok_or("Folder job requires folder_path")?;
+    let project_root = std::path::Path::new(folder_path);
+
+    let mut params = HashMap::new();
+    apply_param_defaults(job, &mut params);
+    let prompt = build_folder_prompt(job, apply_params(read_job_md(job)?, &params));
+
+    let references = prompt_references(&prompt)
+        .into_iter()
+        .map(|reference| {
+            let path = resolve_reference(project_root, &reference);
+            PromptReference {
+                exists: path.exists(),
+                path: path.display().to_string(),
+                reference,
+            }
+        })
+        .collect();
+    let unresolved_params = placeholder_names(&prompt);
+    Ok(FolderPromptReport {
+        prompt,
+        references,
+        unresolved_params,
+    })
+}
+
+fn resolve_reference(root: &std::path::Path, reference: &str) -> std::path::PathBuf {
+    if let Some(rest) = reference.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    root.join(reference)
+}
+
+/// `@path` tokens that start a word, with trailing punctuation trimmed.
+/// Mid-word `@` (e.g. email addresses) is not a reference.
+fn prompt_references(prompt: &str) -> Vec<String> {
+    let mut refs: Vec<String> = Vec::new();
+    for word in prompt.split_whitespace() {
+        let Some(reference) = word.strip_prefix('@') else {
+            continue;
+        };
+        let reference = reference.trim_end_matches([',', '.', ';', ':', ')', '"', '\'', '`']);
+        if !reference.is_empty() && !refs.iter().any(|r| r == reference) {
+            refs.push(reference.to_string());
+        }
+    }
+    refs
+}
+
 /// Compose the folder-job prompt: shared context, per-job context, skill refs,
 /// then the user's prompt. Empty parts are skipped.
 fn build_folder_prompt(job: &Job, raw_prompt: String) -> String {
@@ -123,3 +217,17 @@ fn build_folder_prompt(job: &Job, raw_prompt: String) -> String {
     parts.push(raw_prompt);
     parts.join("\n\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::prompt_references;
+
+    #[test]
+    fn finds_word_initial_references_only() {
+        let prompt = "Read @docs/plan.md, then @~/notes.txt.\nMail me@example.com";
+        assert_eq!(
+            prompt_references(prompt),
+            vec!["docs/plan.md", "~/notes.txt"]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::process::Command;
+
+use crate::config::jobs::{Job, JobType};
+use crate::config::settings::AppSettings;
+
+/// Branch and short HEAD commit of the repository a job runs in.
+pub(super) struct GitInfo {
+    /// `None` on a detached HEAD.
+    pub branch: Option<String>,
+    pub commit: String,
+}
+
+impl GitInfo {
+    /// Text appended to notifications, e.g. `main a1b2c3d`.
+    pub fn label(&self) -> String {
+        match self.branch {
+            Some(ref branch) => format!("{} {}", branch, self.commit),
+            None => self.commit.clone(),
+        }
+    }
+}
+
+/// Capture git info for the job's work dir. Returns None when the job did not
+/// opt in, `git` is missing, or the directory is not inside a repository.
+pub(super) async fn capture_for_job(
+    job: &Job,
+    settings: &Arc<Mutex<AppSettings>>,
+) -> Option<GitInfo> {
+    if !job.include_git_info {
+        return None;
+    }
+    let dir = match job.job_type {
+        JobType::Job => job.folder_path.clone()?,
+        _ => job
+            .work_dir
+            .clone()
+            .unwrap_or_else(|| settings.lock().default_work_dir.clone()),
+    };
+    capture(Path::new(&dir)).await
+}
+
+async fn capture(dir: &Path) -> Option<GitInfo> {
+    let commit = rev_parse(dir, &["--short", "HEAD"]).await?;
+    let branch = rev_parse(dir, &["--abbrev-ref", "HEAD"])
+        .await
+        .filter(|b| b != "HEAD");
+    Some(GitInfo { branch, commit })
+}
+
+async fn rev_parse(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("rev-parse")
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
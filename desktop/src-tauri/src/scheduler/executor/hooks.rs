@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::jobs::{find_job, Job, JobsConfig};
+use crate::job_context::JobContext;
+
+/// A hook still running after this is killed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What an `on_success` / `on_failure` value asks for.
+#[derive(Debug, PartialEq)]
+enum Hook<'a> {
+    Shell(&'a str),
+    /// `run_job("name")`: start another job by name or `group/name`.
+    RunJob(&'a str),
+}
+
+fn parse(hook: &str) -> Option<Hook<'_>> {
+    let hook = hook.trim();
+    if hook.is_empty() {
+        return None;
+    }
+    let target = hook
+        .strip_prefix("run_job(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(|arg| arg.trim().trim_matches(|c| c == '"' || c == '\''));
+    Some(match target {
+        Some(name) => Hook::RunJob(name),
+        None => Hook::Shell(hook),
+    })
+}
+
+/// Longest chain of runs started by `triggers` and `run_job(...)` hooks. A
+/// job past it is not started.
+const MAX_CHAIN_DEPTH: usize = 8;
+
+/// What a finished run does next: its job's `on_success` / `on_failure` hook
+/// and, on success, the jobs in `triggers`. Built when the run starts and,
+/// for tmux runs, kept by the monitor until the exit code is in.
+pub struct PostRunHooks {
+    job: Job,
+    ctx: JobContext,
+    /// Slugs of the runs that led to this one, oldest first.
+    chain: Vec<String>,
+}
+
+impl PostRunHooks {
+    /// None when the job has no hooks and no triggers.
+    pub fn new(job: &Job, ctx: &JobContext, chain: &[String]) -> Option<Self> {
+        (job.on_success.is_some() || job.on_failure.is_some() || !job.triggers.is_empty()).then(
+            || Self {
+                job: job.clone(),
+                ctx: ctx.clone(),
+                chain: chain.to_vec(),
+            },
+        )
+    }
+
+    /// Run the hook matching the outcome, then start the triggered jobs on
+    /// success. What each did is appended to the run's stderr in history.
+    pub async fn run(&self, run_id: &str, success: bool, exit_code: Option<i32>) {
+        let (name, hook) = if success {
+            ("on_success", &self.job.on_success)
+        } else {
+            ("on_failure", &self.job.on_failure)
+        };
+        if let Some(hook) = hook.as_deref().and_then(parse) {
+            log::info!("[{}] Running {} hook for '{}'", run_id, name, self.job.name);
+            let report = match hook {
+                Hook::RunJob(target) => self.start_job(&JobsConfig::load().jobs, target, "hook"),
+                Hook::Shell(command) => {
+                    run_shell(&self.job, &self.ctx, command, run_id, exit_code).await
+                }
+            };
+            self.record(run_id, name, &report);
+        }
+        if success && !self.job.triggers.is_empty() {
+            let jobs = JobsConfig::load().jobs;
+            let report: String = self
+                .job
+                .triggers
+                .iter()
+                .map(|target| self.start_job(&jobs, target, "chain"))
+                .collect();
+            self.record(run_id, "triggers", &report);
+        }
+    }
+
+    fn record(&self, run_id: &str, what: &str, report: &str) {
+        let ctx = &self.ctx;
+        let report = crate::secrets::scrub(
+            &format!("[{}] {}", what, report),
+            &super::secret_values(&self.job, &ctx.secrets, &ctx.settings),
+        );
+        if let Err(e) = ctx.history.lock().append_stderr(run_id, &report) {
+            log::warn!("[{}] Failed to record {} output: {}", run_id, what, e);
+        }
+    }
+
+    /// Start the job named `target` (a name or `group/name`) with its param
+    /// defaults, unless it is already in this chain or the chain is too long.
+    /// `jobs` is read from disk, so a job added since startup is found.
+    fn start_job(&self, jobs: &[Job], target: &str, trigger: &str) -> String {
+        let next = match find_job(jobs, target) {
+            Ok(next) => next.clone(),
+            Err(e) => return format!("{}\n", e),
+        };
+        let mut chain = self.chain.clone();
+        chain.push(self.job.slug.clone());
+        if let Err(reason) = check_chain(&chain, &next.slug) {
+            log::warn!(
+                "Not starting '{}' after {}: {}",
+                next.name,
+                chain.join(" -> "),
+                reason
+            );
+            return format!("skipped {}: {}\n", next.name, reason);
+        }
+        let report = format!("started {}\n", next.name);
+        let ctx = self.ctx.clone();
+        let trigger = trigger.to_string();
+        tokio::spawn(async move {
+            super::execute_job(
+                &next,
+                &ctx,
+                &trigger,
+                &HashMap::new(),
+                super::ExecuteOpts {
+                    chain,
+                    ..Default::default()
+                },
+            )
+            .await;
+        });
+        report
+    }
+}
+
+/// Whether the job `next` may follow the runs in `chain`.
+fn check_chain(chain: &[String], next: &str) -> Result<(), &'static str> {
+    if chain.iter().any(|slug| slug == next) {
+        return Err("cycle");
+    }
+    if chain.len() >= MAX_CHAIN_DEPTH {
+        return Err("chain too long");
+    }
+    Ok(())
+}
+
+async fn run_shell(
+    job: &Job,
+    ctx: &JobContext,
+    command: &str,
+    run_id: &str,
+    exit_code: Option<i32>,
+) -> String {
+    let mut cmd = super::precheck::build_command(job, ctx, command);
+    cmd.env(
+        "CWT_EXIT_CODE",
+        exit_code.map(|c| c.to_string()).unwrap_or_default(),
+    );
+    cmd.env("CWT_RUN_ID", run_id);
+    cmd.env("CWT_JOB_NAME", &job.name);
+    let output = match tokio::time::timeout(HOOK_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return format!("failed to spawn: {}\n", e),
+        Err(_) => return format!("timed out after {}s\n", HOOK_TIMEOUT.as_secs()),
+    };
+    let status = match output.status.code() {
+        Some(code) => format!("exit {}", code),
+        None => "killed".to_string(),
+    };
+    if !output.status.success() {
+        log::warn!("[{}] Hook for '{}' ended with {}", run_id, job.name, status);
+    }
+    format!(
+        "{}\n{}{}",
+        status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_job_calls_are_told_apart_from_shell_commands() {
+        assert_eq!(parse("  "), None);
+        assert_eq!(
+            parse("curl -fsS https://example.com/ping"),
+            Some(Hook::Shell("curl -fsS https://example.com/ping"))
+        );
+        assert_eq!(
+            parse("run_job(\"ops/deploy\")"),
+            Some(Hook::RunJob("ops/deploy"))
+        );
+        assert_eq!(parse(" run_job('report') "), Some(Hook::RunJob("report")));
+    }
+
+    #[test]
+    fn chains_stop_at_cycles_and_the_depth_limit() {
+        let chain: Vec<String> = ["a/scrape", "a/transform"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(check_chain(&chain, "a/report"), Ok(()));
+        assert_eq!(check_chain(&chain, "a/scrape"), Err("cycle"));
+        assert_eq!(check_chain(&chain, "a/transform"), Err("cycle"));
+        let long: Vec<String> = (0..MAX_CHAIN_DEPTH).map(|i| format!("a/{}", i)).collect();
+        assert_eq!(check_chain(&long, "a/next"), Err("chain too long"));
+    }
+}
@@ -3,13 +3,22 @@
 // fixing every legacy function across the crate in this PR.
 #![warn(clippy::too_many_lines, clippy::cognitive_complexity)]
 
+mod batch;
 mod binary;
 pub mod binary_runtime;
+mod capture;
 mod claude;
+mod cooldown;
 mod finalize;
+pub(super) mod flapping;
 mod folder;
-mod notification;
+mod git_info;
+mod hooks;
+pub(super) mod notification;
 mod params;
+mod precheck;
+mod preview;
+mod pty_run;
 mod tmux_spawn;
 
 use std::collections::{HashMap, HashSet};
@@ -20,17 +29,25 @@ use crate::config::jobs::{Job, JobStatus, JobType};
 use crate::config::settings::AppSettings;
 use crate::history::RunRecord;
 use crate::job_context::JobContext;
+use crate::scheduler::lifecycle::{self, Event};
 
 use binary::execute_binary_job;
 use claude::execute_claude_job;
 use finalize::{attach_monitor, finalize_run, RunCtx, RunOutcome};
 use folder::execute_folder_job;
+pub use folder::{validate_folder_prompt, FolderPromptReport, PromptReference};
+pub use hooks::PostRunHooks;
 use params::apply_param_defaults;
+pub use params::job_params;
+pub(crate) use params::secret_values;
+pub use preview::{preview_job, JobPreview};
 
 /// Result from a tmux job: the tmux session and pane ID for monitoring.
 pub(super) struct TmuxHandle {
     pub(super) tmux_session: String,
     pub(super) pane_id: String,
+    /// Secret values the pane was given in its prompt.
+    pub(super) redact: Vec<String>,
 }
 
 /// Per-call options for `execute_job`. Use `ExecuteOpts::default()` for a
@@ -49,6 +66,12 @@ pub struct ExecuteOpts {
     /// structured result. On finish the monitor reads that file and pushes
     /// a TriggerResult to the relay.
     pub trigger_id: Option<String>,
+    /// Start immediately even when `max_concurrent` jobs are already running.
+    /// The job's `mutex_group` is still honoured.
+    pub bypass_concurrency_limit: bool,
+    /// Slugs of the runs whose `triggers` or `run_job(...)` hooks started
+    /// this one, oldest first. Guards chains against cycles.
+    pub chain: Vec<String>,
 }
 
 pub(super) fn resolve_agent_model(
@@ -82,42 +105,209 @@ pub(super) fn project_window_name(job: &Job) -> String {
     format!("cwt-{}-{}", project, suffix)
 }
 
+/// One run of a job. Binary jobs with `retries` may make several, each with
+/// its own run record.
+struct Attempt {
+    run_id: String,
+    trigger: String,
+    /// Outcome before the first attempt, so retries don't count as a change.
+    previous_outcome: Option<bool>,
+    /// Failure streak before the first attempt.
+    previous_failure: Option<(i32, u32)>,
+    /// Status before the first attempt, put back when `run_if` skips the run.
+    previous_status: Option<JobStatus>,
+    /// Preconditions are only checked before the first attempt; a retry
+    /// always runs, so a failure is never followed by a skip.
+    first_attempt: bool,
+    /// No retry follows a failure of this attempt.
+    final_attempt: bool,
+}
+
+impl Attempt {
+    /// Attempt `n` of `retries + 1`, counting from 0. `run_id` is only given
+    /// for the first one; retries get a fresh id and say so in their trigger.
+    fn new(
+        n: u32,
+        retries: u32,
+        run_id: Option<String>,
+        trigger: &str,
+        previous: Option<&JobStatus>,
+    ) -> Self {
+        Self {
+            run_id: run_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            trigger: if n == 0 {
+                trigger.to_string()
+            } else {
+                format!("{} (retry {}/{})", trigger, n, retries)
+            },
+            previous_outcome: previous.and_then(JobStatus::last_outcome),
+            previous_failure: previous.and_then(JobStatus::failure_streak),
+            previous_status: previous.cloned(),
+            first_attempt: n == 0,
+            final_attempt: n == retries,
+        }
+    }
+}
+
+/// Whether another attempt follows attempt `n` that ended with `outcome`.
+/// Only a failure is retried, not a tmux run or a skipped or cancelled one.
+fn retries_after(outcome: Option<bool>, n: u32, retries: u32) -> bool {
+    outcome == Some(false) && n < retries
+}
+
 pub async fn execute_job(
     job: &Job,
     ctx: &JobContext,
     trigger: &str,
     params: &HashMap<String, String>,
-    opts: ExecuteOpts,
+    mut opts: ExecuteOpts,
 ) {
-    let merged_params = merge_param_defaults(job, params);
-    let params: &HashMap<String, String> = merged_params.as_ref().unwrap_or(params);
+    if let Some(left) = cooldown::remaining(job, ctx) {
+        log::info!(
+            "Job '{}' ({}) skipped due to cooldown, {}s left",
+            job.name,
+            trigger,
+            left
+        );
+        if let Some(ref tid) = opts.trigger_id {
+            crate::relay::push_trigger_result(
+                &ctx.relay,
+                tid,
+                "skipped",
+                None,
+                None,
+                Some(format!("Skipped due to cooldown, {}s left", left)),
+            );
+        }
+        return;
+    }
+    let previous = ctx.job_status.lock().get(&job.slug).cloned();
+    let retries = if job.job_type.is_process() {
+        job.retries
+    } else {
+        0
+    };
 
-    let mut pane_tx = opts.pane_tx;
-    let trigger_id = opts.trigger_id;
+    for n in 0..=retries {
+        let run_id = if n == 0 {
+            opts.run_id.take().or_else(|| opts.trigger_id.clone())
+        } else {
+            None
+        };
+        let attempt = Attempt::new(n, retries, run_id, trigger, previous.as_ref());
+        let outcome = run_attempt(job, ctx, params, &mut opts, attempt).await;
+        if !retries_after(outcome, n, retries) {
+            return;
+        }
+        log::info!(
+            "Job '{}' failed, retrying in {}s ({}/{})",
+            job.name,
+            job.retry_delay_secs,
+            n + 1,
+            retries
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(job.retry_delay_secs)).await;
+    }
+}
 
-    let run_id = opts
-        .run_id
-        .clone()
-        .or_else(|| trigger_id.clone())
-        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-    let started_at = Utc::now().to_rfc3339();
+/// Run one attempt. Returns whether it succeeded, or `None` for tmux jobs,
+/// whose outcome the pane monitor settles later, and for runs cancelled
+/// while queued.
+async fn run_attempt(
+    job: &Job,
+    ctx: &JobContext,
+    params: &HashMap<String, String>,
+    opts: &mut ExecuteOpts,
+    mut attempt: Attempt,
+) -> Option<bool> {
+    let run_id = attempt.run_id.clone();
+    let Some(started_at) =
+        start_when_admitted(job, ctx, &run_id, opts.bypass_concurrency_limit).await
+    else {
+        if let Some(ref tid) = opts.trigger_id {
+            crate::relay::push_trigger_result(
+                &ctx.relay,
+                tid,
+                "skipped",
+                None,
+                None,
+                Some("Cancelled while queued".to_string()),
+            );
+        }
+        return None;
+    };
+
+    let skip = if attempt.first_attempt {
+        skip_reason(job, ctx, &run_id).await
+    } else {
+        None
+    };
+    if let Some(reason) = skip {
+        record_skipped(
+            job,
+            ctx,
+            &run_id,
+            &started_at,
+            &attempt.trigger,
+            &reason,
+            attempt.previous_status.take(),
+        );
+        if let Some(ref tid) = opts.trigger_id {
+            crate::relay::push_trigger_result(&ctx.relay, tid, "skipped", None, None, Some(reason));
+        }
+        return None;
+    }
+    launch_attempt(job, ctx, params, opts, attempt, started_at).await
+}
+
+/// Record the admitted attempt's run, start it and handle what the executor
+/// returned.
+async fn launch_attempt(
+    job: &Job,
+    ctx: &JobContext,
+    params: &HashMap<String, String>,
+    opts: &mut ExecuteOpts,
+    attempt: Attempt,
+    started_at: String,
+) -> Option<bool> {
+    let Attempt {
+        run_id,
+        trigger,
+        previous_outcome,
+        previous_failure,
+        final_attempt,
+        ..
+    } = attempt;
+    let launch_started = std::time::Instant::now();
+    let merged_params = merge_param_defaults(job, params);
+    let params: &HashMap<String, String> = merged_params.as_ref().unwrap_or(params);
+    let trigger_id = opts.trigger_id.clone();
 
     let result_file = prepare_result_file(job, &run_id, trigger_id.as_deref());
     let stream_log_path = prepare_stream_log(job, &run_id);
 
-    mark_running(job, ctx, &run_id, &started_at);
+    let git = git_info::capture_for_job(job, &ctx.settings).await;
+    let git_label = git.as_ref().map(git_info::GitInfo::label);
+
     insert_history_and_prune(
         job,
         ctx,
         &run_id,
         &started_at,
-        trigger,
+        &trigger,
         stream_log_path.as_deref(),
+        git.as_ref(),
     );
     let keep_existing = job.max_history.saturating_sub(1) as usize;
     enforce_live_pane_retention(job, ctx, keep_existing);
 
     log::info!("[{}] Starting job '{}' ({})", run_id, job.name, trigger);
+    lifecycle::log(
+        Event::Started,
+        &run_id,
+        &job.slug,
+        serde_json::json!({ "trigger": trigger, "job_type": job.job_type }),
+    );
 
     let result = dispatch_job(
         job,
@@ -129,6 +319,7 @@ pub async fn execute_job(
         stream_log_path.as_deref(),
     )
     .await;
+    let launch_ms = u64::try_from(launch_started.elapsed().as_millis()).unwrap_or(u64::MAX);
 
     let telegram_config = {
         let s = ctx.settings.lock();
@@ -143,9 +334,15 @@ pub async fn execute_job(
         trigger_id: &trigger_id,
         result_file: &result_file,
         telegram_config: &telegram_config,
+        git_label: git_label.as_deref(),
+        previous_outcome,
+        previous_failure,
+        launch_ms,
+        final_attempt,
+        chain: &opts.chain,
     };
 
-    handle_result(&rc, result, &mut pane_tx, opts.use_auto_yes).await;
+    handle_result(&rc, result, &mut opts.pane_tx, opts.use_auto_yes).await
 }
 
 /// Fill missing param entries from each JobParam's declared default. Returns
@@ -194,7 +391,7 @@ fn prepare_result_file(
 /// Compute the streaming log path for binary jobs and create its parent dir.
 /// tmux jobs return None (their output lives in tmux's scrollback / capture).
 fn prepare_stream_log(job: &Job, run_id: &str) -> Option<std::path::PathBuf> {
-    if !matches!(job.job_type, JobType::Binary) {
+    if !job.job_type.is_process() {
         return None;
     }
     let path = crate::config::jobs::JobsConfig::jobs_dir_public().map(|d| {
@@ -219,6 +416,29 @@ fn ensure_parent_dir(path: &std::path::Path, kind: &str) {
     }
 }
 
+/// Wait until the concurrency queue admits the run, then mark the job
+/// Running. `bypass` skips the `max_concurrent` limit but not the mutex
+/// group. Returns the start time, taken on admission, or `None` when the run
+/// was cancelled while queued.
+async fn start_when_admitted(
+    job: &Job,
+    ctx: &JobContext,
+    run_id: &str,
+    bypass: bool,
+) -> Option<String> {
+    let _slot = match crate::scheduler::concurrency::wait_for_slot(job, ctx, run_id, bypass).await {
+        Ok(slot) => slot,
+        Err(crate::scheduler::concurrency::Cancelled) => {
+            log::info!("Queued run of '{}' cancelled before it started", job.name);
+            lifecycle::log(Event::Cancelled, run_id, &job.slug, serde_json::json!({}));
+            return None;
+        }
+    };
+    let started_at = Utc::now().to_rfc3339();
+    mark_running(job, ctx, run_id, &started_at);
+    Some(started_at)
+}
+
 /// Mark the job as Running and push the status update. pane_id stays None
 /// here; tmux jobs fill it in once the pane is created.
 fn mark_running(job: &Job, ctx: &JobContext, run_id: &str, started_at: &str) {
@@ -234,6 +454,67 @@ fn mark_running(job: &Job, ctx: &JobContext, run_id: &str, started_at: &str) {
     crate::relay::push_status_update(&ctx.relay, &job.slug, &new_status);
 }
 
+/// Why this run is skipped: `run_if` exited non-zero, or the browser login is
+/// stale and the job skips on that.
+async fn skip_reason(job: &Job, ctx: &JobContext, run_id: &str) -> Option<String> {
+    if let Some(reason) = precheck::run_if(job, ctx, run_id).await {
+        return Some(reason);
+    }
+    check_browser_login(job, ctx, run_id).await
+}
+
+/// Prompt for a new browser login when the job's saved one looks stale.
+/// Returns the skip reason when the job skips runs on a stale login.
+async fn check_browser_login(job: &Job, ctx: &JobContext, run_id: &str) -> Option<String> {
+    let reason = precheck::stale_session(job, run_id).await?;
+    log::warn!(
+        "[{}] Browser login for '{}' looks stale: {}",
+        run_id,
+        job.name,
+        reason
+    );
+    let telegram_config = ctx.settings.lock().telegram.clone();
+    if let Some(ref tg) = telegram_config {
+        notification::send_reauth_notification(tg, job, &reason, job.skip_on_stale_session).await;
+    }
+    job.skip_on_stale_session
+        .then(|| format!("Skipped: {}\n", reason))
+}
+
+/// Record a run that `run_if` or a stale browser login skipped: a finished
+/// history entry marked skipped, and the job's status as it was before. No
+/// notification and no post-run hooks.
+fn record_skipped(
+    job: &Job,
+    ctx: &JobContext,
+    run_id: &str,
+    started_at: &str,
+    trigger: &str,
+    reason: &str,
+    previous_status: Option<JobStatus>,
+) {
+    log::info!("[{}] Job '{}' skipped", run_id, job.name);
+    insert_history_and_prune(job, ctx, run_id, started_at, trigger, None, None);
+    let reason = crate::secrets::scrub(reason, &secret_values(job, &ctx.secrets, &ctx.settings));
+    {
+        let h = ctx.history.lock();
+        let finished_at = Utc::now().to_rfc3339();
+        if let Err(e) = h
+            .update_finished(run_id, &finished_at, None, "", &reason)
+            .and_then(|()| h.mark_skipped(run_id))
+        {
+            log::error!("Failed to record skipped run: {}", e);
+        }
+    }
+    lifecycle::log(Event::Skipped, run_id, &job.slug, serde_json::json!({}));
+
+    let status = previous_status.unwrap_or(JobStatus::Idle);
+    ctx.job_status
+        .lock()
+        .insert(job.slug.clone(), status.clone());
+    crate::relay::push_status_update(&ctx.relay, &job.slug, &status);
+}
+
 /// Insert the new run record, then prune the per-job history to max_history,
 /// killing any tmux panes that the prune removed.
 fn insert_history_and_prune(
@@ -243,6 +524,7 @@ fn insert_history_and_prune(
     started_at: &str,
     trigger: &str,
     stream_log_path: Option<&std::path::Path>,
+    git: Option<&git_info::GitInfo>,
 ) {
     let record = RunRecord {
         id: run_id.to_string(),
@@ -255,6 +537,11 @@ fn insert_history_and_prune(
         stderr: String::new(),
         pane_id: None,
         log_path: stream_log_path.map(|p| p.to_string_lossy().into_owned()),
+        git_branch: git.and_then(|g| g.branch.clone()),
+        git_commit: git.map(|g| g.commit.clone()),
+        launch_ms: None,
+        output_truncated: false,
+        skipped: false,
     };
 
     let h = ctx.history.lock();
@@ -319,8 +606,12 @@ async fn dispatch_job(
     result_file: Option<&std::path::Path>,
     stream_log_path: Option<&std::path::Path>,
 ) -> Result<(Option<i32>, String, String, Option<TmuxHandle>), String> {
-    match job.job_type {
-        JobType::Binary => execute_binary_job(
+    let precheck_output = match run_precheck(job, ctx, run_id).await {
+        Ok(output) => output,
+        Err(failed) => return Ok((Some(failed.exit_code), failed.stdout, failed.stderr, None)),
+    };
+    let result = match job.job_type {
+        JobType::Binary | JobType::Shell => execute_binary_job(
             job,
             run_id,
             started_at,
@@ -331,14 +622,57 @@ async fn dispatch_job(
             stream_log_path,
         )
         .await
-        .map(|(code, out, err)| (code, out, err, None)),
+        .map(|out| {
+            let h = ctx.history.lock();
+            if out.truncated {
+                if let Err(e) = h.mark_output_truncated(run_id) {
+                    log::warn!("[{}] Failed to record output truncation: {}", run_id, e);
+                }
+            }
+            for item in &out.items {
+                if let Err(e) = h.record_run_item(item) {
+                    log::warn!("[{}] Failed to record batch item: {}", run_id, e);
+                }
+            }
+            drop(h);
+            (out.code, out.stdout, out.stderr, None)
+        }),
         JobType::Claude => {
             execute_claude_job(job, &ctx.secrets, &ctx.settings, params, result_file).await
         }
         JobType::Job => {
             execute_folder_job(job, &ctx.secrets, &ctx.settings, params, result_file).await
         }
+    };
+    result.map(|(code, stdout, stderr, handle)| (code, stdout, precheck_output + &stderr, handle))
+}
+
+/// Run the job's precheck. A passing one's output is stored with the run
+/// right away, so a reattached monitor keeps it too, and returned to lead
+/// the run's stderr.
+async fn run_precheck(
+    job: &Job,
+    ctx: &JobContext,
+    run_id: &str,
+) -> Result<String, precheck::PrecheckFailure> {
+    let output = precheck::run(job, ctx, run_id)
+        .await
+        .inspect_err(|failed| {
+            log::warn!(
+                "[{}] Precheck for '{}' failed with exit code {}, skipping run",
+                run_id,
+                job.name,
+                failed.exit_code
+            );
+        })?;
+    if output.is_empty() {
+        return Ok(output);
+    }
+    let output = crate::secrets::scrub(&output, &secret_values(job, &ctx.secrets, &ctx.settings));
+    if let Err(e) = ctx.history.lock().append_stderr(run_id, &output) {
+        log::warn!("[{}] Failed to record precheck output: {}", run_id, e);
     }
+    Ok(output)
 }
 
 /// Branch on the dispatcher's result: tmux jobs hand off to the monitor;
@@ -348,11 +682,13 @@ async fn handle_result(
     result: Result<(Option<i32>, String, String, Option<TmuxHandle>), String>,
     pane_tx: &mut Option<tokio::sync::oneshot::Sender<(String, String)>>,
     use_auto_yes: bool,
-) {
+) -> Option<bool> {
     match result {
-        Ok((_, _, _, Some(handle))) => {
-            // monitor owns finalization for tmux jobs; drop the unused output.
-            attach_monitor(rc, handle, pane_tx, use_auto_yes);
+        Ok((_, _, stderr, Some(handle))) => {
+            // monitor owns finalization for tmux jobs; it records the
+            // precheck's output as the run's stderr.
+            attach_monitor(rc, handle, stderr, pane_tx, use_auto_yes);
+            None
         }
         Ok((exit_code, stdout, stderr, None)) => {
             let success = exit_code == Some(0);
@@ -367,6 +703,7 @@ async fn handle_result(
                 },
             )
             .await;
+            Some(success)
         }
         Err(e) => {
             finalize_run(
@@ -380,6 +717,47 @@ async fn handle_result(
                 },
             )
             .await;
+            Some(false)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_only_follow_failures_until_the_last_attempt() {
+        assert!(retries_after(Some(false), 0, 2));
+        assert!(retries_after(Some(false), 1, 2));
+        assert!(!retries_after(Some(false), 2, 2));
+        assert!(!retries_after(Some(true), 0, 2));
+        assert!(!retries_after(None, 0, 2));
+        assert!(!retries_after(Some(false), 0, 0));
+    }
+
+    #[test]
+    fn retry_attempts_get_their_own_run_and_keep_the_prior_outcome() {
+        let previous = JobStatus::Failed {
+            last_run: String::new(),
+            exit_code: 3,
+            consecutive_failures: 2,
+        };
+        let first = Attempt::new(0, 2, Some("run-1".to_string()), "cron", Some(&previous));
+        assert_eq!(first.run_id, "run-1");
+        assert_eq!(first.trigger, "cron");
+        assert!(first.first_attempt);
+        assert!(!first.final_attempt);
+        assert_eq!(first.previous_failure, Some((3, 2)));
+
+        let last = Attempt::new(2, 2, None, "cron", Some(&previous));
+        assert_ne!(last.run_id, first.run_id);
+        assert_eq!(last.trigger, "cron (retry 2/2)");
+        assert!(last.final_attempt);
+        assert!(!last.first_attempt);
+        assert_eq!(last.previous_outcome, first.previous_outcome);
+        assert_eq!(last.previous_failure, Some((3, 2)));
+
+        assert!(Attempt::new(0, 0, None, "manual", None).final_attempt);
+    }
+}
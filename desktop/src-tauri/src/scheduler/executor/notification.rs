@@ -1,64 +1,133 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::config::jobs::{job_group, Job, NotifyTarget};
+use crate::relay::RelayHandle;
 use crate::telegram::TelegramConfig;
 
+use super::flapping::Alert;
+
 use super::super::monitor::TelegramStream;
 
-/// Build a TelegramStream for the monitor, using per-job chat_id or global chat_ids.
+/// Build a TelegramStream for the monitor, using the per-job, group or global chat.
 pub(super) fn build_telegram_stream(
     config: &Option<TelegramConfig>,
     job_chat_id: Option<i64>,
+    group_name: &str,
 ) -> Option<TelegramStream> {
     let config = config.as_ref()?;
     if !config.is_configured() {
         return None;
     }
-    let chat_id = job_chat_id.or_else(|| config.chat_ids.first().copied())?;
+    let chat_id = config.chat_for_job(job_chat_id, group_name)?;
     Some(TelegramStream {
         bot_token: config.bot_token.clone(),
         chat_id,
+        log_flush_secs: config.log_flush_secs,
+        stop_button: config.agent_enabled,
+        finish_actions: config.active_finish_actions().to_vec(),
     })
 }
 
-/// Send telegram notification, routing to per-job chat_id if set.
+/// Send telegram notification, routing to the per-job chat, then the group chat.
 pub(super) async fn send_job_notification(
     config: &TelegramConfig,
-    job_chat_id: Option<i64>,
-    group_name: &str,
-    job_id: &str,
+    job: &Job,
     exit_code: Option<i32>,
-    success: bool,
+    alert: Alert,
+    git_label: Option<&str>,
 ) {
-    if !should_notify(config, success) {
+    if !should_notify(config, alert) {
         return;
     }
 
-    let status = if success { "finished" } else { "failed" };
-    let text = crate::telegram::format_job_status_message(group_name, job_id, status, exit_code);
-    let chat_ids = resolve_chat_ids(config, job_chat_id);
-
-    for chat_id in chat_ids {
-        if let Err(e) = crate::telegram::send_message(&config.bot_token, chat_id, &text).await {
+    let group_name = job_group(job);
+    let mut text = crate::telegram::format_job_status_message(
+        group_name,
+        &job.name,
+        &alert.status_text(),
+        exit_code,
+    );
+    crate::telegram::append_git_label(&mut text, git_label);
+    let keyboard = crate::telegram::job_action_keyboard(config.active_finish_actions(), &job.slug);
+    for chat_id in config.chats_for_job(job.telegram_chat_id, group_name) {
+        if let Err(e) = crate::telegram::send_message_returning_id(
+            &config.bot_token,
+            chat_id,
+            &text,
+            crate::telegram::ParseMode::Html,
+            keyboard.as_ref(),
+        )
+        .await
+        {
             log::error!("Failed to send Telegram notification to {}: {}", chat_id, e);
         }
     }
 }
 
-/// Whether the given outcome should produce a notification under this config.
-fn should_notify(config: &TelegramConfig, success: bool) -> bool {
+/// Ask the job's chats for a new browser login. Sent whatever the success and
+/// failure notification settings are.
+pub(super) async fn send_reauth_notification(
+    config: &TelegramConfig,
+    job: &Job,
+    reason: &str,
+    skipped: bool,
+) {
     if !config.is_configured() {
-        return false;
+        return;
     }
-    if success {
-        config.notify_on_success
-    } else {
-        config.notify_on_failure
+    let group_name = job_group(job);
+    let status = format!(
+        "needs a new browser login: {}. {}",
+        crate::telegram::html_escape(reason),
+        if skipped {
+            "The run was skipped; log in again from ClawTab."
+        } else {
+            "It runs anyway; log in again from ClawTab."
+        }
+    );
+    let text = crate::telegram::format_job_status_message(group_name, &job.name, &status, None);
+    for chat_id in config.chats_for_job(job.telegram_chat_id, group_name) {
+        if let Err(e) = crate::telegram::send_message(
+            &config.bot_token,
+            chat_id,
+            &text,
+            crate::telegram::ParseMode::Html,
+        )
+        .await
+        {
+            log::error!(
+                "Failed to send Telegram re-login prompt to {}: {}",
+                chat_id,
+                e
+            );
+        }
     }
 }
 
-/// Pick the destination chat IDs: per-job override wins, else the global list.
-fn resolve_chat_ids(config: &TelegramConfig, job_chat_id: Option<i64>) -> Vec<i64> {
-    if let Some(cid) = job_chat_id {
-        vec![cid]
-    } else {
-        config.chat_ids.clone()
+/// Whether the given outcome should produce a notification under this config.
+fn should_notify(config: &TelegramConfig, alert: Alert) -> bool {
+    config.is_configured() && alert.wanted(config.notify_on_success, config.notify_on_failure)
+}
+
+/// Surface a failed run as a mobile toast. APNs delivery stays with the
+/// `JobNotification` path so App-target jobs are not pushed twice.
+pub(in crate::scheduler) fn push_failure_notice(
+    relay: &Arc<Mutex<Option<RelayHandle>>>,
+    notify_target: &NotifyTarget,
+    job_name: &str,
+    alert: Alert,
+    body: &str,
+) {
+    if *notify_target == NotifyTarget::None || !matches!(alert, Alert::Failure | Alert::Streak(_)) {
+        return;
     }
+    crate::relay::push_notice(
+        relay,
+        clawtab_protocol::NoticeLevel::Error,
+        &format!("{} {}", job_name, alert.status_text()),
+        body,
+        false,
+    );
 }
@@ -2,8 +2,10 @@ use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::config::jobs::{Job, NotifyTarget};
-use crate::config::settings::AppSettings;
+use clawtab_protocol::JobParam;
+
+use crate::config::jobs::{Job, JobType, NotifyTarget};
+use crate::config::settings::{secret_in_scope, AppSettings};
 use crate::secrets::SecretsManager;
 
 /// Fill missing entries in a runtime params HashMap from each JobParam's default value.
@@ -27,7 +29,212 @@ pub(super) fn apply_params(mut prompt: String, params: &HashMap<String, String>)
     prompt
 }
 
-/// Collect env vars from job's secret_keys as (key, value) pairs.
+const SECRET_REF_OPEN: &str = "{{secret:";
+const SECRET_REF_CLOSE: &str = "}}";
+
+/// Replace `{{secret:KEY}}` tokens in a prompt template with values from
+/// the secrets store. Run it before `apply_params` so a param value can't
+/// pull in a secret. The resolved values end up in the agent's prompt and on
+/// its screen, so they are returned for the monitor to redact from captured
+/// output. Unknown keys, and keys outside the job's `secret_scopes`, are
+/// logged and the token is left as written.
+pub(super) fn apply_secret_refs(
+    template: String,
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+) -> (String, Vec<String>) {
+    if !template.contains(SECRET_REF_OPEN) {
+        return (template, Vec::new());
+    }
+    let scopes = settings.lock().secret_scopes.clone();
+    let sm = secrets.lock();
+    substitute_secret_refs(&template, |key| {
+        if !secret_allowed(&scopes, key, job) {
+            return None;
+        }
+        let value = sm.get(key).cloned();
+        if value.is_none() {
+            warn_missing_secret(&sm, key, &job.slug);
+        }
+        value
+    })
+}
+
+/// The raw prompt template of an agent job, `None` for binary and shell jobs.
+pub(super) fn prompt_template(job: &Job) -> Result<Option<String>, String> {
+    match job.job_type {
+        JobType::Binary | JobType::Shell => Ok(None),
+        JobType::Claude => super::claude::read_prompt(job).map(Some),
+        JobType::Job => super::folder::read_job_md(job).map(Some),
+    }
+}
+
+/// Values of the `{{secret:KEY}}` refs in the job's prompt template that a
+/// run resolves, so output can be scrubbed without the run's own list (e.g.
+/// after a reattach).
+fn prompt_secret_values(
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+) -> Vec<String> {
+    let Ok(Some(template)) = prompt_template(job) else {
+        return Vec::new();
+    };
+    if !template.contains(SECRET_REF_OPEN) {
+        return Vec::new();
+    }
+    let scopes = settings.lock().secret_scopes.clone();
+    let sm = secrets.lock();
+    substitute_secret_refs(&template, |key| {
+        secret_in_scope(&scopes, key, job)
+            .then(|| sm.get(key).cloned())
+            .flatten()
+    })
+    .1
+}
+
+/// The `job.secret_keys` the job may receive under the `secret_scopes`
+/// setting. The others are logged and left out.
+pub(super) fn scoped_secret_keys(job: &Job, settings: &Arc<Mutex<AppSettings>>) -> Vec<String> {
+    let s = settings.lock();
+    job.secret_keys
+        .iter()
+        .filter(|key| secret_allowed(&s.secret_scopes, key, job))
+        .cloned()
+        .collect()
+}
+
+fn secret_allowed(scopes: &HashMap<String, Vec<String>>, key: &str, job: &Job) -> bool {
+    let allowed = secret_in_scope(scopes, key, job);
+    if !allowed {
+        log::warn!(
+            "Secret key '{}' is configured for '{}' but is not scoped to it, not passing it",
+            key,
+            job.slug
+        );
+    }
+    allowed
+}
+
+/// Values of the secrets a run of `job` receives as env vars or prompt
+/// secret refs, including the Telegram bot token, for scrubbing from its
+/// output.
+pub(crate) fn secret_values(
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+) -> Vec<String> {
+    let sm = secrets.lock();
+    let keys = if job.name == "agent" {
+        sm.list_keys()
+    } else {
+        job.secret_keys.clone()
+    };
+    let mut values: Vec<String> = keys.iter().filter_map(|k| sm.get(k).cloned()).collect();
+    drop(sm);
+    for value in prompt_secret_values(job, secrets, settings) {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    if let Some(ref tg) = settings.lock().telegram {
+        values.push(tg.bot_token.clone());
+    }
+    values
+}
+
+fn substitute_secret_refs(
+    prompt: &str,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(prompt.len());
+    let mut used: Vec<String> = Vec::new();
+    let mut rest = prompt;
+    while let Some(start) = rest.find(SECRET_REF_OPEN) {
+        let after = &rest[start + SECRET_REF_OPEN.len()..];
+        let Some(end) = after.find(SECRET_REF_CLOSE) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let token_len = SECRET_REF_OPEN.len() + end + SECRET_REF_CLOSE.len();
+        match lookup(after[..end].trim()) {
+            Some(value) => {
+                out.push_str(&value);
+                if !value.is_empty() && !used.contains(&value) {
+                    used.push(value);
+                }
+            }
+            None => out.push_str(&rest[start..start + token_len]),
+        }
+        rest = &rest[start + token_len..];
+    }
+    out.push_str(rest);
+    (out, used)
+}
+
+/// Log a declared secret that resolved to nothing, telling a denied or locked
+/// keychain apart from a key that was never stored.
+pub(super) fn warn_missing_secret(sm: &SecretsManager, key: &str, slug: &str) {
+    if sm.is_access_denied(key) {
+        log::warn!(
+            "Secret key '{}' is configured for '{}' but keychain access was denied or the keychain is locked",
+            key,
+            slug
+        );
+    } else {
+        log::warn!(
+            "Secret key '{}' is configured for '{}' but was not found",
+            key,
+            slug
+        );
+    }
+}
+
+/// Names inside single-brace `{name}` placeholders. Double-brace tokens such
+/// as `{{secret:KEY}}` are skipped.
+pub(super) fn placeholder_names(prompt: &str) -> Vec<String> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = prompt;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        if let Some(inner) = after.strip_prefix('{') {
+            rest = inner.find("}}").map_or("", |end| &inner[end + 2..]);
+            continue;
+        }
+        let len = after.find(|c: char| !is_name(c)).unwrap_or(after.len());
+        if len > 0 && after[len..].starts_with('}') && !names.iter().any(|n| n == &after[..len]) {
+            names.push(after[..len].to_string());
+        }
+        rest = after;
+    }
+    names
+}
+
+/// Params a run of `job` can take, for a run form: the declared ones with
+/// their defaults, then any other `{name}` placeholder in its prompt file.
+/// Binary and shell jobs get params as env vars, so only the declared ones apply.
+pub fn job_params(job: &Job) -> Result<Vec<JobParam>, String> {
+    let Some(prompt) = prompt_template(job)? else {
+        return Ok(job.params.clone());
+    };
+    Ok(merge_params(&job.params, placeholder_names(&prompt)))
+}
+
+fn merge_params(declared: &[JobParam], found: Vec<String>) -> Vec<JobParam> {
+    let mut params = declared.to_vec();
+    for name in found {
+        if !params.iter().any(|p| p.name == name) {
+            params.push(JobParam::new(name));
+        }
+    }
+    params
+}
+
+/// Collect env vars from job's secret_keys as (key, value) pairs, leaving
+/// out secrets not scoped to the job. The agent gets every secret scoped to
+/// it, which is all of them unless `secret_scopes` says otherwise.
 /// Also auto-injects TELEGRAM_BOT_TOKEN from global settings when the job
 /// has a telegram_chat_id but doesn't explicitly list the token in secret_keys.
 pub(super) fn collect_env_vars(
@@ -35,31 +242,32 @@ pub(super) fn collect_env_vars(
     secrets: &Arc<Mutex<SecretsManager>>,
     settings: &Arc<Mutex<AppSettings>>,
 ) -> Vec<(String, String)> {
-    let sm = secrets.lock();
     let mut vars = Vec::new();
 
     let is_agent = job.name == "agent";
 
     if is_agent {
+        let scopes = settings.lock().secret_scopes.clone();
+        let sm = secrets.lock();
         for key in sm.list_keys() {
+            if !secret_in_scope(&scopes, &key, job) {
+                continue;
+            }
             if let Some(value) = sm.get(&key) {
                 vars.push((key, value.clone()));
             }
         }
     } else {
-        for key in &job.secret_keys {
-            if let Some(value) = sm.get(key) {
-                vars.push((key.clone(), value.clone()));
+        let keys = scoped_secret_keys(job, settings);
+        let sm = secrets.lock();
+        for key in keys {
+            if let Some(value) = sm.get(&key) {
+                vars.push((key, value.clone()));
             } else {
-                log::warn!(
-                    "Secret key '{}' is configured for '{}' but was not found",
-                    key,
-                    job.slug
-                );
+                warn_missing_secret(&sm, &key, &job.slug);
             }
         }
     }
-    drop(sm);
 
     for (key, value) in &job.env {
         vars.push((key.clone(), value.clone()));
@@ -78,3 +286,55 @@ pub(super) fn collect_env_vars(
 
     vars
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{apply_params, merge_params, placeholder_names, substitute_secret_refs, JobParam};
+
+    #[test]
+    fn secret_refs_resolve_known_keys_and_keep_unknown_tokens() {
+        let (prompt, used) = substitute_secret_refs(
+            "acct {{secret:ACCOUNT}} / {{ secret:MISSING }} / {{secret:ACCOUNT}} {{secret:open",
+            |key| (key == "ACCOUNT").then(|| "12345".to_string()),
+        );
+        assert_eq!(
+            prompt,
+            "acct 12345 / {{ secret:MISSING }} / 12345 {{secret:open"
+        );
+        assert_eq!(used, vec!["12345".to_string()]);
+    }
+
+    #[test]
+    fn param_values_cannot_pull_in_secret_refs() {
+        let params = HashMap::from([("note".to_string(), "{{secret:AWS_KEY}}".to_string())]);
+        let (prompt, used) = substitute_secret_refs("acct {{secret:ACCOUNT}}: {note}", |key| {
+            Some(format!("value-of-{}", key))
+        });
+        assert_eq!(
+            apply_params(prompt, &params),
+            "acct value-of-ACCOUNT: {{secret:AWS_KEY}}"
+        );
+        assert_eq!(used, vec!["value-of-ACCOUNT".to_string()]);
+    }
+
+    #[test]
+    fn flags_single_brace_placeholders_but_not_secret_refs() {
+        let prompt = "Deploy {env} with {{secret:TOKEN}} to {env} as {user_name}. {not a param}";
+        assert_eq!(placeholder_names(prompt), vec!["env", "user_name"]);
+    }
+
+    #[test]
+    fn declared_params_come_first_and_keep_their_defaults() {
+        let declared = vec![JobParam {
+            name: "env".to_string(),
+            value: Some("staging".to_string()),
+        }];
+        let found = vec!["user".to_string(), "env".to_string()];
+        assert_eq!(
+            merge_params(&declared, found),
+            vec![declared[0].clone(), JobParam::new("user")]
+        );
+    }
+}
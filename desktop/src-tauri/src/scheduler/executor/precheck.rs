@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use crate::config::jobs::{Job, JobType};
+use crate::job_context::JobContext;
+
+/// A precheck that hangs counts as failed.
+const PRECHECK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A `login_check_url` that doesn't answer in time leaves the login unjudged.
+const LOGIN_CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Exit code and output of a failed `precheck_command`, recorded as the run's
+/// result in place of the main command.
+pub(super) struct PrecheckFailure {
+    /// -1 when it was killed, timed out or never started.
+    pub(super) exit_code: i32,
+    pub(super) stdout: String,
+    pub(super) stderr: String,
+}
+
+/// Run the job's `precheck_command` through `sh -c` in the job's work dir,
+/// with the same secrets and env the job gets. `Ok` with its output, for the
+/// run's stderr, when it exits 0; empty when there is none.
+pub(super) async fn run(
+    job: &Job,
+    ctx: &JobContext,
+    run_id: &str,
+) -> Result<String, PrecheckFailure> {
+    let Some(command) = job
+        .precheck_command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    else {
+        return Ok(String::new());
+    };
+    log::info!("[{}] Running precheck for '{}'", run_id, job.name);
+    let mut cmd = build_command(job, ctx, command);
+    let output = match tokio::time::timeout(PRECHECK_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(failure(None, "", &format!("Failed to spawn: {}", e))),
+        Err(_) => {
+            let msg = format!("Timed out after {}s", PRECHECK_TIMEOUT.as_secs());
+            return Err(failure(None, "", &msg));
+        }
+    };
+    if output.status.success() {
+        return Ok(format!(
+            "Precheck passed\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Err(failure(
+        output.status.code(),
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+    ))
+}
+
+/// Run the job's `run_if` check like a precheck. `Some(reason)` when it exits
+/// non-zero, times out or can't start: the run is then skipped, not failed.
+pub(super) async fn run_if(job: &Job, ctx: &JobContext, run_id: &str) -> Option<String> {
+    let command = job.run_if.as_deref().filter(|c| !c.trim().is_empty())?;
+    log::info!("[{}] Checking run_if for '{}'", run_id, job.name);
+    let mut cmd = build_command(job, ctx, command);
+    let output = match tokio::time::timeout(PRECHECK_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Some(format!("Skipped: run_if failed to spawn: {}\n", e)),
+        Err(_) => {
+            return Some(format!(
+                "Skipped: run_if timed out after {}s\n",
+                PRECHECK_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    if output.status.success() {
+        return None;
+    }
+    let status = output
+        .status
+        .code()
+        .map_or_else(|| "no exit code".to_string(), |c| format!("exit {}", c));
+    Some(format!(
+        "Skipped: run_if returned {}\n{}{}",
+        status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Claude and folder jobs with `session_max_age_days` or `login_check_url`:
+/// why the job's saved browser login looks stale, if it does. A login check
+/// that can't reach the URL only logs a warning.
+pub(super) async fn stale_session(job: &Job, run_id: &str) -> Option<String> {
+    if !matches!(job.job_type, JobType::Claude | JobType::Job)
+        || (job.session_max_age_days.is_none() && job.login_check_url.is_none())
+    {
+        return None;
+    }
+    let Some(age) = crate::browser::session_age(&job.slug) else {
+        return Some("no browser login is saved".to_string());
+    };
+    if let Some(max_days) = job.session_max_age_days {
+        if age > Duration::from_secs(u64::from(max_days) * 86_400) {
+            return Some(format!(
+                "the browser login is {} days old (limit {})",
+                age.as_secs() / 86_400,
+                max_days
+            ));
+        }
+    }
+    let url = job
+        .login_check_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())?;
+    log::info!("[{}] Checking browser login for '{}'", run_id, job.name);
+    match check_login(&job.slug, url).await {
+        Ok(reason) => reason,
+        Err(e) => {
+            log::warn!("[{}] Login check for '{}' failed: {}", run_id, job.name, e);
+            None
+        }
+    }
+}
+
+/// Fetch `url` with the session's cookies, without following redirects.
+async fn check_login(slug: &str, url: &str) -> Result<Option<String>, String> {
+    let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid login_check_url: {}", e))?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(LOGIN_CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut request = client.get(url.clone());
+    if let Some(cookies) = crate::browser::session_cookie_header(slug, &url) {
+        request = request.header(reqwest::header::COOKIE, cookies);
+    }
+    let status = request.send().await.map_err(|e| e.to_string())?.status();
+    let logged_out = status.is_redirection()
+        || status == reqwest::StatusCode::UNAUTHORIZED
+        || status == reqwest::StatusCode::FORBIDDEN;
+    Ok(logged_out.then(|| format!("login_check_url answered {}", status)))
+}
+
+pub(super) fn build_command(job: &Job, ctx: &JobContext, command: &str) -> Command {
+    let work_dir = job
+        .work_dir
+        .clone()
+        .unwrap_or_else(|| ctx.settings.lock().default_work_dir.clone());
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd.env_clear();
+    for var in ["PATH", "HOME"] {
+        if let Ok(value) = std::env::var(var) {
+            cmd.env(var, value);
+        }
+    }
+    {
+        let keys = super::params::scoped_secret_keys(job, &ctx.settings);
+        let sm = ctx.secrets.lock();
+        for key in &keys {
+            if let Some(value) = sm.get(key) {
+                cmd.env(key, value);
+            }
+        }
+    }
+    cmd.envs(&job.env);
+    cmd.env("CLAWTAB_JOB_SLUG", &job.slug);
+    cmd.current_dir(work_dir);
+    cmd.stdin(std::process::Stdio::null());
+    cmd.kill_on_drop(true);
+    cmd
+}
+
+fn failure(exit_code: Option<i32>, stdout: &str, stderr: &str) -> PrecheckFailure {
+    let status = exit_code.map_or_else(|| "no exit code".to_string(), |c| format!("exit {}", c));
+    PrecheckFailure {
+        exit_code: exit_code.unwrap_or(-1),
+        stdout: stdout.to_string(),
+        stderr: format!(
+            "Precheck failed ({}), main command skipped\n{}",
+            status, stderr
+        ),
+    }
+}
@@ -0,0 +1,175 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::config::jobs::{Job, JobType};
+use crate::config::settings::AppSettings;
+use crate::secrets::SecretsManager;
+
+use super::binary::param_env_key;
+use super::claude::{read_prompt, with_skill_refs};
+use super::folder::{folder_prompt, read_job_md, resolve_folder_launch};
+use super::params::{apply_param_defaults, apply_params, collect_env_vars, scoped_secret_keys};
+use super::project_window_name;
+use super::tmux_spawn::{build_send_cmd, resolve_agent_launch, AgentLaunch};
+
+/// What a run of a job would do, resolved the way the executors resolve it
+/// but without spawning anything.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobPreview {
+    pub job_type: JobType,
+    pub work_dir: String,
+    /// Env vars the job gets on top of `PATH` and `HOME`, secrets excluded.
+    pub env: BTreeMap<String, String>,
+    /// Secrets passed as env vars, by key only.
+    pub secret_keys: Vec<String>,
+    /// The binary and its args, or the shell command typed into the pane.
+    pub command: String,
+    pub tmux_session: Option<String>,
+    /// Example window name; a real run stamps its own start time into it.
+    pub window_name: Option<String>,
+    /// Prompt with `{param}` placeholders filled in. `{{secret:KEY}}` tokens
+    /// are left as written.
+    pub prompt: Option<String>,
+    pub precheck_command: Option<String>,
+    pub run_if: Option<String>,
+}
+
+/// Resolve a run of `job` with `params` (plus the param defaults).
+pub fn preview_job(
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+    params: &HashMap<String, String>,
+) -> Result<JobPreview, String> {
+    let mut params = params.clone();
+    apply_param_defaults(job, &mut params);
+    match job.job_type {
+        JobType::Binary | JobType::Shell => Ok(preview_binary(job, secrets, settings, &params)),
+        JobType::Claude => {
+            let launch = resolve_agent_launch(job, &settings.lock());
+            let prompt = with_skill_refs(job, apply_params(read_prompt(job)?, &params));
+            Ok(preview_agent(job, secrets, settings, launch, prompt))
+        }
+        JobType::Job => {
+            let launch = resolve_folder_launch(job, &settings.lock())?;
+            let raw_prompt = apply_params(read_job_md(job)?, &params);
+            let prompt = folder_prompt(job, launch.provider, raw_prompt);
+            Ok(preview_agent(job, secrets, settings, launch, prompt))
+        }
+    }
+}
+
+fn preview_binary(
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+    params: &HashMap<String, String>,
+) -> JobPreview {
+    let work_dir = job
+        .work_dir
+        .clone()
+        .unwrap_or_else(|| settings.lock().default_work_dir.clone());
+    let secret_keys = {
+        let keys = scoped_secret_keys(job, settings);
+        let sm = secrets.lock();
+        keys.into_iter()
+            .filter(|key| sm.get(key).is_some())
+            .collect()
+    };
+
+    let mut env: BTreeMap<String, String> = job.env.clone().into_iter().collect();
+    env.insert("CLAWTAB_JOB_SLUG".to_string(), job.slug.clone());
+    if let Some(job_id) = &job.job_id {
+        env.insert("CLAWTAB_JOB_ID".to_string(), job_id.clone());
+    }
+    env.extend(params.iter().map(|(k, v)| (param_env_key(k), v.clone())));
+
+    let program: &[&str] = match job.job_type {
+        JobType::Shell => &["sh", "-c", &job.path, "sh"],
+        _ => &[&job.path],
+    };
+    let command = program
+        .iter()
+        .copied()
+        .chain(job.args.iter().map(String::as_str))
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    JobPreview {
+        job_type: job.job_type.clone(),
+        work_dir,
+        env,
+        secret_keys,
+        command,
+        tmux_session: None,
+        window_name: None,
+        prompt: None,
+        precheck_command: job.precheck_command.clone(),
+        run_if: job.run_if.clone(),
+    }
+}
+
+fn preview_agent(
+    job: &Job,
+    secrets: &Arc<Mutex<SecretsManager>>,
+    settings: &Arc<Mutex<AppSettings>>,
+    launch: AgentLaunch,
+    prompt: String,
+) -> JobPreview {
+    let mut env = BTreeMap::new();
+    let mut secret_keys = Vec::new();
+    for (key, value) in collect_env_vars(job, secrets, settings) {
+        if job.env.contains_key(&key) {
+            env.insert(key, value);
+        } else {
+            secret_keys.push(key);
+        }
+    }
+
+    let command = build_send_cmd(
+        launch.provider,
+        &launch.work_dir,
+        &launch.agent_command,
+        launch.model.as_deref(),
+        &prompt,
+    );
+
+    JobPreview {
+        job_type: job.job_type.clone(),
+        work_dir: launch.work_dir,
+        env,
+        secret_keys,
+        command,
+        tmux_session: Some(launch.tmux_session),
+        window_name: Some(project_window_name(job)),
+        prompt: Some(prompt),
+        precheck_command: job.precheck_command.clone(),
+        run_if: job.run_if.clone(),
+    }
+}
+
+/// Quote an argument for display so the command can be pasted into a shell.
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shell_quote;
+
+    #[test]
+    fn quotes_only_args_that_need_it() {
+        assert_eq!(shell_quote("--out=/tmp/a.txt"), "--out=/tmp/a.txt");
+        assert_eq!(shell_quote("two words"), "'two words'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
@@ -0,0 +1,131 @@
+//! Process jobs with `pty: true` run on a pseudo-terminal instead of pipes,
+//! so programs that check for a tty keep their colors and interactive
+//! output. The terminal merges stdout and stderr into one stream.
+
+use parking_lot::Mutex;
+use std::io::{BufRead, Read};
+use std::sync::Arc;
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use tokio::process::Command;
+
+use crate::config::jobs::Job;
+
+use super::binary::{append_line, finish_capture, open_stream_log, BinaryOutput};
+use super::capture::{CappedOutput, DEFAULT_MAX_OUTPUT_BYTES, MAX_LINE_BYTES};
+
+/// Size of the terminal the job sees.
+const PTY_SIZE: PtySize = PtySize {
+    rows: 50,
+    cols: 200,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+/// Run the command built for a piped run on a PTY instead. The child leads
+/// its own session, so its pid is also the process group `stop` kills.
+pub(super) async fn execute_pty_job(
+    job: &Job,
+    run_id: &str,
+    started_at: &str,
+    cmd: &Command,
+    stream_log_path: Option<&std::path::Path>,
+) -> Result<BinaryOutput, String> {
+    let pair = native_pty_system()
+        .openpty(PTY_SIZE)
+        .map_err(|e| format!("Failed to open pty: {}", e))?;
+    let mut child = pair
+        .slave
+        .spawn_command(pty_command(cmd))
+        .map_err(|e| format!("Failed to spawn process: {}", e))?;
+    drop(pair.slave);
+    if let Some(pid) = child.process_id() {
+        super::binary_runtime::register(job, run_id, started_at, pid);
+        log::info!(
+            "[{}] Started binary job '{}' on a pty, pid={}",
+            run_id,
+            job.name,
+            pid
+        );
+    }
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to read pty: {}", e))?;
+    let log_file = open_stream_log(stream_log_path);
+    let cap = job.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+    let buf = Arc::new(Mutex::new(CappedOutput::new(cap)));
+    let read_task = {
+        let buf = Arc::clone(&buf);
+        tokio::task::spawn_blocking(move || read_lines(reader, &buf, log_file.as_deref()))
+    };
+
+    let status = tokio::task::spawn_blocking(move || child.wait())
+        .await
+        .map_err(|e| format!("Failed to wait for process: {}", e))?
+        .map_err(|e| format!("Failed to wait for process: {}", e))?;
+    super::binary_runtime::unregister(&job.slug);
+    drop(pair.master);
+    let _ = read_task.await;
+
+    let (stdout, truncated) = finish_capture(buf);
+    if truncated {
+        log::warn!(
+            "[{}] Output of '{}' exceeded {} bytes and was truncated in history",
+            run_id,
+            job.name,
+            cap
+        );
+    }
+    Ok(BinaryOutput {
+        code: Some(status.exit_code() as i32),
+        stdout,
+        stderr: String::new(),
+        truncated,
+        items: Vec::new(),
+    })
+}
+
+/// The same program, args, env and working dir as `cmd`, on a terminal.
+fn pty_command(cmd: &Command) -> CommandBuilder {
+    let cmd = cmd.as_std();
+    let mut builder = CommandBuilder::new(cmd.get_program());
+    builder.args(cmd.get_args());
+    builder.env_clear();
+    builder.env("TERM", "xterm-256color");
+    for (key, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            builder.env(key, value);
+        }
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        builder.cwd(dir);
+    }
+    builder
+}
+
+/// Copy terminal output into `buf` and the log line by line, without the
+/// escape sequences and carriage returns a terminal adds. Over-long lines are
+/// split like piped output. Ends when the terminal closes, which reads as an
+/// error on Linux and EOF elsewhere.
+fn read_lines(
+    reader: Box<dyn Read + Send>,
+    buf: &Mutex<CappedOutput>,
+    file: Option<&Mutex<std::fs::File>>,
+) {
+    let mut reader = std::io::BufReader::new(reader);
+    let mut line = Vec::new();
+    while let Ok(n) = (&mut reader)
+        .take(MAX_LINE_BYTES as u64)
+        .read_until(b'\n', &mut line)
+    {
+        if n == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        let text = crate::telegram::strip_ansi(text.trim_end_matches(['\n', '\r']));
+        append_line(buf, file, &text);
+        line.clear();
+    }
+}
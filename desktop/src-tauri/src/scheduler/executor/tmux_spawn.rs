@@ -1,7 +1,15 @@
 use crate::agent_session::ProcessProvider;
+use crate::config::jobs::Job;
+use crate::config::settings::AppSettings;
 use crate::tmux;
 
-use super::TmuxHandle;
+use super::super::monitor::EXIT_MARKER;
+use super::{resolve_agent_model, TmuxHandle};
+
+/// How long a new session or window gets to become visible before the launch
+/// is failed instead of sending keys into nothing.
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+const READY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(25);
 
 /// Args for spawning an agent pane via tmux. Shared by Claude and Folder job types.
 pub(super) struct SpawnArgs<'a> {
@@ -15,6 +23,42 @@ pub(super) struct SpawnArgs<'a> {
     pub prompt_content: String,
     pub slug: &'a str,
     pub aerospace_workspace: Option<&'a str>,
+    /// Secret values substituted into the prompt, redacted from monitor output.
+    pub redact: Vec<String>,
+}
+
+/// Where and with what an agent job launches, resolved from the job and the
+/// global settings.
+pub(super) struct AgentLaunch {
+    pub provider: ProcessProvider,
+    pub model: Option<String>,
+    pub tmux_session: String,
+    pub work_dir: String,
+    pub agent_command: String,
+}
+
+pub(super) fn resolve_agent_launch(job: &Job, s: &AppSettings) -> AgentLaunch {
+    let provider = job.agent_provider.unwrap_or(s.default_provider);
+    let agent_command = match provider {
+        ProcessProvider::Claude => s.claude_path.clone(),
+        ProcessProvider::Codex | ProcessProvider::Opencode | ProcessProvider::Antigravity => {
+            provider.binary_name().to_string()
+        }
+        ProcessProvider::Shell => String::new(),
+    };
+    AgentLaunch {
+        provider,
+        model: resolve_agent_model(job, s, provider),
+        tmux_session: job
+            .tmux_session
+            .clone()
+            .unwrap_or_else(|| s.default_tmux_session.clone()),
+        work_dir: job
+            .work_dir
+            .clone()
+            .unwrap_or_else(|| s.default_work_dir.clone()),
+        agent_command,
+    }
 }
 
 /// Create the tmux window, send the agent command, tag the pane, and optionally
@@ -34,6 +78,7 @@ pub(super) async fn spawn_agent_pane(
         prompt_content,
         slug,
         aerospace_workspace,
+        redact,
     } = args;
 
     if !tmux::is_available() {
@@ -42,12 +87,22 @@ pub(super) async fn spawn_agent_pane(
 
     if !tmux::session_exists(&tmux_session) {
         tmux::create_session(&tmux_session)?;
+        wait_until_ready(&format!("tmux session '{}'", tmux_session), || {
+            tmux::session_exists(&tmux_session)
+        })
+        .await?;
     }
 
     // Every spawn gets its own window - clawtab needs independent geometry
     // per tab, which tmux splits can't give us.
-    let pane_id =
-        tmux::create_window_with_cwd(&tmux_session, &window_name, Some(&work_dir), &env_vars)?;
+    let pane_id = tmux::retry_transient("tmux_spawn::create_window", || {
+        tmux::create_window_with_cwd(&tmux_session, &window_name, Some(&work_dir), &env_vars)
+    })
+    .await?;
+    wait_until_ready(&format!("tmux pane {}", pane_id), || {
+        tmux::pane_exists(&pane_id)
+    })
+    .await?;
 
     let send_cmd = build_send_cmd(
         provider,
@@ -56,7 +111,10 @@ pub(super) async fn spawn_agent_pane(
         model.as_deref(),
         &prompt_content,
     );
-    tmux::send_keys_to_pane(&tmux_session, &pane_id, &send_cmd)?;
+    tmux::retry_transient("tmux_spawn::send_keys", || {
+        tmux::send_keys_to_pane(&tmux_session, &pane_id, &send_cmd)
+    })
+    .await?;
 
     tag_pane(&pane_id, slug);
 
@@ -67,13 +125,47 @@ pub(super) async fn spawn_agent_pane(
     let handle = TmuxHandle {
         tmux_session,
         pane_id,
+        redact,
     };
     Ok((Some(0), String::new(), String::new(), Some(handle)))
 }
 
+/// Poll `ready` until it returns true, failing once `READY_TIMEOUT` passes.
+/// Returns as soon as tmux reports the object, so fast machines do not wait
+/// out a fixed delay and slow ones are not cut off by one.
+async fn wait_until_ready(what: &str, mut ready: impl FnMut() -> bool) -> Result<(), String> {
+    let deadline = std::time::Instant::now() + READY_TIMEOUT;
+    loop {
+        if ready() {
+            return Ok(());
+        }
+        if std::time::Instant::now() >= deadline {
+            return Err(format!(
+                "{} not ready after {}ms",
+                what,
+                READY_TIMEOUT.as_millis()
+            ));
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
 /// Compose the shell command sent to the pane: cd into the work dir, then
 /// invoke the agent (or just leave a shell prompt for ProcessProvider::Shell).
-fn build_send_cmd(
+/// Once it exits the shell prints `EXIT_MARKER` and the exit status, which the
+/// monitor reads back to record the run's real outcome.
+pub(super) fn build_send_cmd(
+    provider: ProcessProvider,
+    work_dir: &str,
+    agent_command: &str,
+    model: Option<&str>,
+    prompt_content: &str,
+) -> String {
+    let cmd = build_agent_cmd(provider, work_dir, agent_command, model, prompt_content);
+    format!("{}; echo \"{}$?\"", cmd, EXIT_MARKER)
+}
+
+fn build_agent_cmd(
     provider: ProcessProvider,
     work_dir: &str,
     agent_command: &str,
@@ -0,0 +1,72 @@
+//! One structured log line per job lifecycle transition:
+//!
+//! `lifecycle {"event":"started","job":"ops/deploy","run_id":"...","trigger":"cron"}`
+//!
+//! Every line carries the event, the job's slug and the run id, so
+//! filtering the log on a run id gives that run's timeline.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// Waiting for a `max_concurrent` slot or a mutex group.
+    Queued,
+    /// Removed from the queue before it started.
+    Cancelled,
+    /// The job's `run_if` check exited non-zero, or its browser login was
+    /// stale with `skip_on_stale_session`; nothing was launched.
+    Skipped,
+    Started,
+    /// The agent command was sent to its tmux pane.
+    PaneReady,
+    PromptDetected,
+    /// A detected prompt went away while the pane lived on.
+    Answered,
+    Completed,
+    Failed,
+    /// Stopped from Telegram, the app or the relay while running.
+    Stopped,
+}
+
+/// Log `event` for run `run_id` of job `slug`. `fields` (a JSON object)
+/// adds event-specific details.
+pub(crate) fn log(event: Event, run_id: &str, slug: &str, fields: Value) {
+    log::info!("lifecycle {}", line(event, run_id, slug, fields));
+}
+
+fn line(event: Event, run_id: &str, slug: &str, fields: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("event".to_string(), serde_json::json!(event));
+    map.insert("run_id".to_string(), Value::from(run_id));
+    map.insert("job".to_string(), Value::from(slug));
+    if let Value::Object(extra) = fields {
+        map.extend(extra);
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_merges_fields_into_one_object() {
+        let line = line(
+            Event::PaneReady,
+            "r1",
+            "ops/deploy",
+            serde_json::json!({ "pane_id": "%3" }),
+        );
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "event": "pane_ready",
+                "run_id": "r1",
+                "job": "ops/deploy",
+                "pane_id": "%3",
+            })
+        );
+    }
+}
@@ -1,15 +1,32 @@
 pub mod executor;
 pub mod monitor;
+pub mod output_sections;
 pub mod reattach;
 
+mod after_boot;
+mod concurrency;
+mod cron_expr;
+pub(crate) mod lifecycle;
+mod session_reaper;
+mod simulate;
+pub mod usage_pause;
+
+pub use concurrency::cancel_queued_run;
+pub use cron_expr::{next_run, normalize_cron, validate_cron, validate_timezone, CronZone};
+pub use simulate::{simulate_schedule, ScheduleSimulation, ScheduledFire};
+
 use parking_lot::Mutex;
 use std::sync::Arc;
 
-use chrono::{Duration, Local};
-use cron::Schedule;
+use chrono::{Duration, Utc};
 
-use crate::config::jobs::{JobStatus, JobType, JobsConfig};
+use crate::config::jobs::{Job, JobStatus, JobsConfig};
+use crate::config::settings::AppSettings;
 use crate::job_context::JobContext;
+use cron_expr::parse_cron;
+
+/// Scheduler ticks (30s each) between idle tmux session sweeps.
+const SESSION_REAP_TICKS: u32 = 20;
 
 pub async fn start(
     event_sink: Arc<dyn crate::events::EventSink>,
@@ -17,16 +34,63 @@ pub async fn start(
     ctx: JobContext,
 ) {
     log::info!("Scheduler started");
+    tokio::spawn(after_boot::run(
+        Arc::clone(&jobs_config),
+        ctx.clone(),
+        std::time::Instant::now(),
+    ));
+    let mut last_check = Utc::now();
+    if let Some(last_tick) = crate::config::scheduler_state::load_last_tick() {
+        run_catch_up_jobs(&jobs_config, &ctx, last_tick, last_check);
+    }
+    persist_tick(last_check);
     emit_missed_cron_jobs(&jobs_config, &ctx, event_sink.as_ref());
-    log_startup_cron(&jobs_config);
+    log_startup_cron(&jobs_config, &ctx.settings);
 
-    let mut last_check = Local::now();
+    let mut ticks: u32 = 0;
     loop {
         tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-        let now = Local::now();
+        usage_pause::refresh(&jobs_config, &ctx, &event_sink, ticks);
+        let now = Utc::now();
         run_due_jobs(&jobs_config, &ctx, last_check, now);
         cleanup_stale_running(&jobs_config, &ctx, event_sink.as_ref());
+        ticks = ticks.wrapping_add(1);
+        if ticks.is_multiple_of(SESSION_REAP_TICKS) {
+            session_reaper::reap_idle_sessions(&ctx);
+        }
         last_check = now;
+        persist_tick(now);
+    }
+}
+
+fn persist_tick(tick: chrono::DateTime<Utc>) {
+    if let Err(e) = crate::config::scheduler_state::save_last_tick(tick) {
+        log::warn!("Failed to persist scheduler tick: {}", e);
+    }
+}
+
+/// Start one run for each `catch_up` job with a fire time between the last
+/// tick persisted before shutdown and now.
+fn run_catch_up_jobs(
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    ctx: &JobContext,
+    last_tick: chrono::DateTime<Utc>,
+    now: chrono::DateTime<Utc>,
+) {
+    let jobs = jobs_config.lock().jobs.clone();
+    let default_tz = ctx.settings.lock().default_timezone.clone();
+    for job in jobs.iter().filter(|j| j.catch_up && j.enabled) {
+        let Some(schedules) = parse_cron(&job.cron) else {
+            continue;
+        };
+        if cron_zone(job, default_tz.as_deref()).fires_between(&schedules, last_tick, now) {
+            log::info!(
+                "Catching up job '{}' missed since {}",
+                job.name,
+                last_tick.to_rfc3339()
+            );
+            spawn_cron_job(job.clone(), ctx.clone(), "catch_up");
+        }
     }
 }
 
@@ -35,13 +99,15 @@ fn emit_missed_cron_jobs(
     ctx: &JobContext,
     event_sink: &dyn crate::events::EventSink,
 ) {
-    let now = Local::now();
+    let now = Utc::now();
     let lookback_limit = now - Duration::hours(24);
     let jobs = jobs_config.lock().jobs.clone();
+    let default_tz = ctx.settings.lock().default_timezone.clone();
     let mut missed_jobs: Vec<String> = Vec::new();
 
     for job in &jobs {
-        if !job.enabled || job.cron.is_empty() {
+        // catch_up jobs were already started by run_catch_up_jobs
+        if !job.enabled || job.cron.is_empty() || job.catch_up {
             continue;
         }
         let Some(schedules) = parse_cron(&job.cron) else {
@@ -53,7 +119,7 @@ fn emit_missed_cron_jobs(
             continue;
         };
         let since = last_run_since(&ctx.history, &job.slug, lookback_limit);
-        if has_missed_run(&schedules, since, now) {
+        if cron_zone(job, default_tz.as_deref()).fires_between(&schedules, since, now) {
             log::info!("Missed cron job detected: '{}'", job.name);
             missed_jobs.push(job.name.clone());
         }
@@ -71,30 +137,27 @@ fn emit_missed_cron_jobs(
 fn last_run_since(
     history: &Arc<Mutex<crate::history::HistoryStore>>,
     slug: &str,
-    lookback_limit: chrono::DateTime<Local>,
-) -> chrono::DateTime<Local> {
+    lookback_limit: chrono::DateTime<Utc>,
+) -> chrono::DateTime<Utc> {
     let h = history.lock();
-    h.get_by_job_id(slug, 1)
+    h.get_by_job_id(slug, 1, 0)
         .ok()
         .and_then(|runs| runs.into_iter().next())
         .and_then(|r| chrono::DateTime::parse_from_rfc3339(&r.started_at).ok())
-        .map(|t| t.with_timezone(&Local))
+        .map(|t| t.with_timezone(&Utc))
         .filter(|t| *t > lookback_limit)
         .unwrap_or(lookback_limit)
 }
 
-fn has_missed_run(
-    schedules: &[Schedule],
-    since: chrono::DateTime<Local>,
-    now: chrono::DateTime<Local>,
-) -> bool {
-    schedules
-        .iter()
-        .any(|s| s.after(&since).take_while(|t| *t <= now).next().is_some())
+/// Zone a job's schedule is evaluated in: its own `timezone`, else the
+/// settings default, else local time.
+pub fn cron_zone(job: &Job, default_tz: Option<&str>) -> CronZone {
+    CronZone::resolve(job.timezone.as_deref(), default_tz)
 }
 
-fn log_startup_cron(jobs_config: &Arc<Mutex<JobsConfig>>) {
+fn log_startup_cron(jobs_config: &Arc<Mutex<JobsConfig>>, settings: &Arc<Mutex<AppSettings>>) {
     let jobs = jobs_config.lock().jobs.clone();
+    let default_tz = settings.lock().default_timezone.clone();
     let cron_jobs: Vec<_> = jobs
         .iter()
         .filter(|j| j.enabled && !j.cron.is_empty())
@@ -102,9 +165,10 @@ fn log_startup_cron(jobs_config: &Arc<Mutex<JobsConfig>>) {
     log::info!("Scheduler tracking {} cron-enabled job(s)", cron_jobs.len());
     for job in &cron_jobs {
         if let Some(schedules) = parse_cron(&job.cron) {
+            let zone = cron_zone(job, default_tz.as_deref());
             let next: Vec<String> = schedules
                 .iter()
-                .filter_map(|s| s.upcoming(Local).next())
+                .filter_map(|s| zone.next_after(std::slice::from_ref(s), Utc::now()))
                 .map(|t| t.to_rfc3339())
                 .collect();
             log::trace!("  '{}' cron='{}' next={:?}", job.name, job.cron, next);
@@ -117,10 +181,11 @@ fn log_startup_cron(jobs_config: &Arc<Mutex<JobsConfig>>) {
 fn run_due_jobs(
     jobs_config: &Arc<Mutex<JobsConfig>>,
     ctx: &JobContext,
-    last_check: chrono::DateTime<Local>,
-    now: chrono::DateTime<Local>,
+    last_check: chrono::DateTime<Utc>,
+    now: chrono::DateTime<Utc>,
 ) {
     let jobs = jobs_config.lock().jobs.clone();
+    let default_tz = ctx.settings.lock().default_timezone.clone();
     for job in &jobs {
         if !job.enabled || job.cron.is_empty() {
             continue;
@@ -133,19 +198,22 @@ fn run_due_jobs(
             );
             continue;
         };
-        if has_missed_run(&schedules, last_check, now) {
+        if cron_zone(job, default_tz.as_deref()).fires_between(&schedules, last_check, now) {
             log::info!("Cron trigger for job '{}'", job.name);
-            spawn_cron_job(job.clone(), ctx.clone());
+            spawn_cron_job(job.clone(), ctx.clone(), "cron");
         }
     }
 }
 
-fn spawn_cron_job(job: crate::config::jobs::Job, ctx: JobContext) {
+fn spawn_cron_job(job: crate::config::jobs::Job, ctx: JobContext, trigger: &'static str) {
+    if usage_pause::defer(&job, trigger) {
+        return;
+    }
     tokio::spawn(async move {
         executor::execute_job(
             &job,
             &ctx,
-            "cron",
+            trigger,
             &std::collections::HashMap::new(),
             executor::ExecuteOpts {
                 use_auto_yes: true,
@@ -166,7 +234,7 @@ fn cleanup_stale_running(
         .lock()
         .jobs
         .iter()
-        .filter(|job| matches!(job.job_type, JobType::Binary))
+        .filter(|job| job.job_type.is_process())
         .map(|job| job.slug.clone())
         .collect();
     let stale: Vec<(String, String)> = {
@@ -208,80 +276,3 @@ fn cleanup_stale_running(
     drop(statuses);
     event_sink.emit_jobs_changed();
 }
-
-fn parse_single_cron(cron: &str) -> Option<Schedule> {
-    let parts: Vec<&str> = cron.split_whitespace().collect();
-    let expr = if parts.len() == 5 {
-        // 5-field cron: min hour dom month dow - prepend seconds
-        let dow = translate_dow(parts[4]);
-        format!(
-            "0 {} {} {} {} {}",
-            parts[0], parts[1], parts[2], parts[3], dow
-        )
-    } else if parts.len() == 6 {
-        // 6-field cron: sec min hour dom month dow
-        let dow = translate_dow(parts[5]);
-        format!(
-            "{} {} {} {} {} {}",
-            parts[0], parts[1], parts[2], parts[3], parts[4], dow
-        )
-    } else {
-        cron.to_string()
-    };
-    expr.parse().ok()
-}
-
-/// Translate day-of-week values from standard cron (0=Sun, 1-6=Mon-Sat)
-/// to the `cron` crate format (1=Sun, 2-7=Mon-Sat). Handles comma-separated
-/// lists and ranges.
-fn translate_dow(dow: &str) -> String {
-    if dow == "*" || dow == "?" {
-        return dow.to_string();
-    }
-    dow.split(',')
-        .map(|part| {
-            if part.contains('-') {
-                // Handle ranges like 0-5
-                let bounds: Vec<&str> = part.split('-').collect();
-                if bounds.len() == 2 {
-                    let lo = bounds[0]
-                        .parse::<u8>()
-                        .map(|v| if v <= 6 { v + 1 } else { v })
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|_| bounds[0].to_string());
-                    let hi = bounds[1]
-                        .parse::<u8>()
-                        .map(|v| if v <= 6 { v + 1 } else { v })
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|_| bounds[1].to_string());
-                    format!("{}-{}", lo, hi)
-                } else {
-                    part.to_string()
-                }
-            } else {
-                part.parse::<u8>()
-                    .map(|v| if v <= 6 { v + 1 } else { v })
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|_| part.to_string())
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(",")
-}
-
-fn parse_cron(cron: &str) -> Option<Vec<Schedule>> {
-    let parts: Vec<&str> = cron
-        .split('|')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-    if parts.is_empty() {
-        return None;
-    }
-    let schedules: Vec<Schedule> = parts.iter().filter_map(|p| parse_single_cron(p)).collect();
-    if schedules.is_empty() {
-        None
-    } else {
-        Some(schedules)
-    }
-}
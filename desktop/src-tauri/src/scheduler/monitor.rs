@@ -5,17 +5,44 @@ use std::sync::Arc;
 
 use chrono::Utc;
 
-use crate::config::jobs::{JobStatus, NotifyTarget, TelegramNotify};
+use crate::config::jobs::{JobStatus, NotifyTarget, TelegramLogMode, TelegramNotify};
+use crate::config::settings::FailureAlertSettings;
 use crate::history::HistoryStore;
 use crate::relay::RelayHandle;
+use crate::scheduler::executor::flapping::{self, Alert};
+use crate::scheduler::lifecycle::{self, Event};
+use crate::telegram::ParseMode;
 use crate::tmux;
 
 const POLL_INTERVAL_SECS: u64 = 2;
-const CAPTURE_LINES: u32 = 80;
+
+/// Printed by the pane's shell once the agent exits, followed by its exit
+/// status. See `build_send_cmd`.
+pub(super) const EXIT_MARKER: &str = "CWT_EXIT:";
+/// Like `EXIT_MARKER`, printed after the job's `post_command`.
+const POST_EXIT_MARKER: &str = "CWT_POST_EXIT:";
+/// How long `post_command` may run before it is interrupted with C-c.
+const POST_COMMAND_TIMEOUT_SECS: u64 = 600;
+/// Exit code recorded for a run stopped before its pane printed a marker,
+/// the shell's code for a command ended by SIGHUP.
+const STOPPED_EXIT_CODE: i32 = 129;
 
 pub struct TelegramStream {
     pub bot_token: String,
     pub chat_id: i64,
+    pub log_flush_secs: u64,
+    /// Put a Stop button on the working message. Only set when agent mode
+    /// is on, as its poller handles the button.
+    pub stop_button: bool,
+    /// Buttons on the completion message.
+    pub finish_actions: Vec<crate::telegram::JobAction>,
+}
+
+impl TelegramStream {
+    fn stop_keyboard(&self, run_id: &str) -> Option<serde_json::Value> {
+        self.stop_button
+            .then(|| crate::telegram::stop_keyboard(run_id))
+    }
 }
 
 pub struct MonitorParams {
@@ -28,12 +55,18 @@ pub struct MonitorParams {
     pub agent_group: Option<String>,
     pub agent_prompt_path: Option<std::path::PathBuf>,
     pub kill_on_end: bool,
+    /// Shell command typed into the pane once the agent exits.
+    pub post_command: Option<String>,
     pub telegram: Option<TelegramStream>,
+    /// `Always` batches new output into one message per `log_flush_secs`
+    /// instead of waiting for the pane to go quiet.
+    pub telegram_log_mode: TelegramLogMode,
     pub telegram_notify: TelegramNotify,
     pub notify_target: NotifyTarget,
     pub history: Arc<Mutex<HistoryStore>>,
     pub job_status: Arc<Mutex<HashMap<String, JobStatus>>>,
     pub notify_on_success: bool,
+    pub notify_on_failure: bool,
     pub relay: Arc<Mutex<Option<RelayHandle>>>,
     pub notifier: Option<Arc<dyn crate::notifications::Notifier>>,
     /// When true, skip the "job started" notification (used for reattach).
@@ -46,6 +79,24 @@ pub struct MonitorParams {
     /// pushes a `DesktopMessage::TriggerResult` to the relay.
     pub trigger_id: Option<String>,
     pub result_file: Option<std::path::PathBuf>,
+    /// `branch commit` captured at spawn, appended to the completion message.
+    pub git_label: Option<String>,
+    /// Recorded as the run's stderr: the output of a precheck that passed.
+    pub stderr: String,
+    /// Only notify when the outcome differs from `previous_outcome`.
+    pub notify_on_change_only: bool,
+    /// Whether the previous run succeeded, if there was one.
+    pub previous_outcome: Option<bool>,
+    /// Exit code and streak of the failures right before this run.
+    pub previous_failure: Option<(i32, u32)>,
+    /// Throttling of repeated failure notifications, as for non-tmux runs.
+    pub failure_alerts: FailureAlertSettings,
+    /// Secret values the job was given, as env vars or substituted into the
+    /// prompt via `{{secret:KEY}}`. Masked in everything the monitor
+    /// streams, stores or sends.
+    pub redact: Vec<String>,
+    /// The job's `on_success` / `on_failure`, run once the exit code is in.
+    pub post_run_hooks: Option<crate::scheduler::executor::PostRunHooks>,
 }
 
 fn format_elapsed(secs: u64) -> String {
@@ -56,14 +107,85 @@ fn format_elapsed(secs: u64) -> String {
 
 const IDLE_SEND_THRESHOLD: u32 = 5; // 5 ticks * 2s = 10 seconds
 const MAX_LOG_LINES: usize = 40;
+const MAX_TELEGRAM_BACKOFF_SECS: u64 = 300;
+
+/// Per-monitor Telegram failure tracking. Consecutive failures double the
+/// wait before the next attempt (capped at `MAX_TELEGRAM_BACKOFF_SECS`) and a
+/// repeated identical error is only logged at debug level, so an outage does
+/// not flood the log or hammer the API every tick.
+#[derive(Default)]
+struct TelegramBackoff {
+    failures: u32,
+    retry_at: Option<std::time::Instant>,
+    last_error: Option<String>,
+}
+
+impl TelegramBackoff {
+    fn ready(&self) -> bool {
+        self.retry_at
+            .is_none_or(|at| std::time::Instant::now() >= at)
+    }
+
+    fn on_success(&mut self, run_id: &str) {
+        if self.failures > 0 {
+            log::info!(
+                "[{}] Telegram sends recovered after {} failure(s)",
+                run_id,
+                self.failures
+            );
+        }
+        *self = Self::default();
+    }
+
+    fn on_failure(&mut self, run_id: &str, what: &str, error: String) {
+        self.failures += 1;
+        let secs =
+            (POLL_INTERVAL_SECS << (self.failures - 1).min(16)).min(MAX_TELEGRAM_BACKOFF_SECS);
+        let delay = std::time::Duration::from_secs(secs);
+        self.retry_at = Some(std::time::Instant::now() + delay);
+        if self.last_error.as_deref() == Some(error.as_str()) {
+            log::debug!(
+                "[{}] Failed to send {} again (failure {}, retrying in {:?})",
+                run_id,
+                what,
+                self.failures,
+                delay
+            );
+        } else {
+            log::error!(
+                "[{}] Failed to send {}: {} (retrying in {:?})",
+                run_id,
+                what,
+                error,
+                delay
+            );
+            self.last_error = Some(error);
+        }
+    }
+
+    fn record(&mut self, run_id: &str, what: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.on_success(run_id),
+            Err(e) => self.on_failure(run_id, what, e),
+        }
+    }
+}
 
 struct PollState {
     last_content: String,
+    /// Absolute pane line where the next capture starts: the top of the
+    /// screen at the previous tick, so lines that scrolled off in between are
+    /// still captured.
+    capture_from: u32,
     pending_diff: String,
+    /// Log blocks a failed send left behind, sent ahead of `pending_diff`.
+    unsent_log_blocks: Vec<String>,
     accumulated_log: String,
     stale_ticks: u32,
     idle_ticks: u32,
     tick_counter: u32,
+    telegram_backoff: TelegramBackoff,
+    last_log_flush: std::time::Instant,
 }
 
 pub async fn monitor_pane(params: MonitorParams) {
@@ -74,13 +196,18 @@ pub async fn monitor_pane(params: MonitorParams) {
     notify_start(&params, use_telegram, use_app).await;
     let working_message_id = init_working_message(&params, use_telegram).await;
 
+    let (last_content, capture_from) = capture_screen(&params.pane_id).await;
     let mut state = PollState {
-        last_content: capture_trimmed(&params.tmux_session, &params.pane_id),
+        last_content,
+        capture_from,
         pending_diff: String::new(),
+        unsent_log_blocks: Vec::new(),
         accumulated_log: String::new(),
         stale_ticks: 0,
         idle_ticks: 0,
         tick_counter: 0,
+        telegram_backoff: TelegramBackoff::default(),
+        last_log_flush: started_at,
     };
 
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -95,9 +222,42 @@ pub async fn monitor_pane(params: MonitorParams) {
         &mut state,
     )
     .await;
+    if params.telegram_log_mode == TelegramLogMode::Always {
+        flush_log_batch(&params, use_telegram, &mut state, true).await;
+    }
+    run_post_command(&params).await;
+    finish_run(
+        &params,
+        use_telegram,
+        use_app,
+        working_message_id,
+        state.accumulated_log,
+        started_at,
+    )
+    .await;
+
+    log::info!(
+        "[{}] Monitor finished for job '{}'",
+        params.run_id,
+        params.job_id
+    );
+}
 
-    finalize_telegram(&params, use_telegram, working_message_id).await;
-    let full_output = compute_full_output(&params, state.accumulated_log);
+/// Everything after the pane is done: store the output and outcome, notify,
+/// and run the trigger result and post-run hooks.
+async fn finish_run(
+    params: &MonitorParams,
+    use_telegram: bool,
+    use_app: bool,
+    working_message_id: Option<i64>,
+    accumulated_log: String,
+    started_at: std::time::Instant,
+) {
+    finalize_telegram(params, use_telegram, working_message_id).await;
+    let full_output = crate::secrets::scrub(
+        &compute_full_output(params, accumulated_log),
+        &params.redact,
+    );
     if let Some(path) = save_log_file(
         &params.slug,
         &params.run_id,
@@ -105,21 +265,121 @@ pub async fn monitor_pane(params: MonitorParams) {
         params.agent_group.as_deref(),
     ) {
         let h = params.history.lock();
+        if let Some(sections) = super::output_sections::parse(&full_output) {
+            super::output_sections::save(&path, &sections, &h);
+        }
         let _ = h.update_log_path(&params.run_id, &path.to_string_lossy());
     }
-    maybe_kill_pane(&params);
-    persist_finish(&params, &full_output);
-    notify_finish(&params, use_telegram, use_app).await;
-    push_trigger_result_if_any(&params);
+    let marker = parse_exit_marker(&full_output);
+    maybe_kill_pane(params);
+    let stopped =
+        marker.is_none() && was_stopped(params.job_status.lock().get(&params.slug), &params.run_id);
+    if stopped {
+        persist_stopped(params, &full_output, started_at);
+    } else {
+        let exit_code = marker.unwrap_or_else(|| {
+            log::debug!(
+                "[{}] No exit marker in pane {}, assuming success",
+                params.run_id,
+                params.pane_id
+            );
+            0
+        });
+        report_outcome(
+            params,
+            use_telegram,
+            use_app,
+            &full_output,
+            exit_code,
+            started_at,
+        )
+        .await;
+    }
     if let Some(path) = params.agent_prompt_path.as_deref() {
         crate::agent::remove_agent_prompt(path);
     }
+}
 
-    log::info!(
-        "[{}] Monitor finished for job '{}'",
-        params.run_id,
-        params.job_id
+/// Store the outcome of a run that exited on its own, notify, and run the
+/// trigger result and post-run hooks.
+async fn report_outcome(
+    params: &MonitorParams,
+    use_telegram: bool,
+    use_app: bool,
+    full_output: &str,
+    exit_code: i32,
+    started_at: std::time::Instant,
+) {
+    let streak = persist_finish(params, full_output, exit_code);
+    lifecycle::log(
+        if exit_code == 0 {
+            Event::Completed
+        } else {
+            Event::Failed
+        },
+        &params.run_id,
+        &params.slug,
+        serde_json::json!({
+            "exit_code": exit_code,
+            "duration_secs": started_at.elapsed().as_secs(),
+            "reattached": params.is_reattach,
+        }),
+    );
+    let alert = flapping::classify(params.previous_failure, streak, &params.failure_alerts);
+    notify_finish(params, use_telegram, use_app, exit_code, alert).await;
+    super::executor::notification::push_failure_notice(
+        &params.relay,
+        &params.notify_target,
+        &params.job_id,
+        alert,
+        &format!("Exited with code {}", exit_code),
+    );
+    push_trigger_result_if_any(params, exit_code);
+    if let Some(hooks) = &params.post_run_hooks {
+        hooks
+            .run(&params.run_id, exit_code == 0, Some(exit_code))
+            .await;
+    }
+}
+
+/// A pane without an exit marker whose job has left `Running` for this run
+/// was killed by a stop (Telegram, the app or the relay), which sets the
+/// job `Idle`. A job may already be queued or running again by then.
+fn was_stopped(status: Option<&JobStatus>, run_id: &str) -> bool {
+    match status {
+        Some(JobStatus::Idle) | Some(JobStatus::Queued { .. }) => true,
+        Some(JobStatus::Running {
+            run_id: current, ..
+        }) => current != run_id,
+        _ => false,
+    }
+}
+
+/// Record a stopped run as exiting with `STOPPED_EXIT_CODE`. The stop has
+/// already set the job's status, and a stopped run sends no finish
+/// notification and runs no post-run hooks.
+fn persist_stopped(params: &MonitorParams, full_output: &str, started_at: std::time::Instant) {
+    let finished_at = Utc::now().to_rfc3339();
+    if let Err(e) = params.history.lock().update_finished(
+        &params.run_id,
+        &finished_at,
+        Some(STOPPED_EXIT_CODE),
+        full_output,
+        &params.stderr,
+    ) {
+        log::error!("[{}] Failed to update history: {}", params.run_id, e);
+    }
+    lifecycle::log(
+        Event::Stopped,
+        &params.run_id,
+        &params.slug,
+        serde_json::json!({
+            "exit_code": STOPPED_EXIT_CODE,
+            "duration_secs": started_at.elapsed().as_secs(),
+            "reattached": params.is_reattach,
+        }),
     );
+    push_trigger_result_if_any(params, STOPPED_EXIT_CODE);
 }
 
 async fn notify_start(params: &MonitorParams, use_telegram: bool, use_app: bool) {
@@ -134,7 +394,10 @@ async fn notify_start(params: &MonitorParams, use_telegram: bool, use_app: bool)
                 "started",
                 None,
             );
-            if let Err(e) = crate::telegram::send_message(&tg.bot_token, tg.chat_id, &text).await {
+            if let Err(e) =
+                crate::telegram::send_message(&tg.bot_token, tg.chat_id, &text, ParseMode::Html)
+                    .await
+            {
                 log::error!(
                     "[{}] Failed to send start notification: {}",
                     params.run_id,
@@ -156,8 +419,14 @@ async fn init_working_message(params: &MonitorParams, use_telegram: bool) -> Opt
         return None;
     }
     let tg = params.telegram.as_ref()?;
-    match crate::telegram::send_message_returning_id(&tg.bot_token, tg.chat_id, "Working... 0:00")
-        .await
+    match crate::telegram::send_message_returning_id(
+        &tg.bot_token,
+        tg.chat_id,
+        "Working... 0:00",
+        ParseMode::None,
+        tg.stop_keyboard(&params.run_id).as_ref(),
+    )
+    .await
     {
         Ok(mid) => Some(mid),
         Err(e) => {
@@ -167,9 +436,18 @@ async fn init_working_message(params: &MonitorParams, use_telegram: bool) -> Opt
     }
 }
 
-fn capture_trimmed(session: &str, pane_id: &str) -> String {
-    tmux::capture_pane(session, pane_id, CAPTURE_LINES)
-        .unwrap_or_default()
+/// The visible screen and the absolute line of its top row.
+async fn capture_screen(pane_id: &str) -> (String, u32) {
+    let (content, history_size) = tmux::retry_transient("monitor::capture_screen", || {
+        tmux::capture_pane_since(pane_id, u32::MAX)
+    })
+    .await
+    .unwrap_or_default();
+    (trim_capture(&content), history_size)
+}
+
+fn trim_capture(content: &str) -> String {
+    content
         .lines()
         .collect::<Vec<_>>()
         .join("\n")
@@ -206,7 +484,7 @@ async fn run_poll_loop(
         tokio::time::sleep(std::time::Duration::from_secs(POLL_INTERVAL_SECS)).await;
         state.tick_counter += 1;
 
-        let Some(trimmed) = capture_or_break(params) else {
+        let Some(trimmed) = capture_or_break(params, &mut state.capture_from) else {
             break;
         };
 
@@ -216,10 +494,15 @@ async fn run_poll_loop(
             working_message_id,
             started_at,
             state.tick_counter,
+            &mut state.telegram_backoff,
         )
         .await;
         handle_capture_diff(params, use_telegram, process_exited, state, trimmed).await;
-        maybe_flush_idle_logs(params, use_telegram, state).await;
+        if params.telegram_log_mode == TelegramLogMode::Always {
+            flush_log_batch(params, use_telegram, state, false).await;
+        } else {
+            maybe_flush_idle_logs(params, use_telegram, state).await;
+        }
 
         if process_exited.load(Ordering::Acquire) {
             break;
@@ -227,9 +510,14 @@ async fn run_poll_loop(
     }
 }
 
-fn capture_or_break(params: &MonitorParams) -> Option<String> {
-    match tmux::capture_pane(&params.tmux_session, &params.pane_id, CAPTURE_LINES) {
-        Ok(c) => Some(c.lines().collect::<Vec<_>>().join("\n").trim().to_string()),
+/// Capture new pane output, or None once the pane is gone. No retry here: a
+/// missing pane is how the run ends, and the next tick polls again anyway.
+fn capture_or_break(params: &MonitorParams, capture_from: &mut u32) -> Option<String> {
+    match tmux::capture_pane_since(&params.pane_id, *capture_from) {
+        Ok((c, history_size)) => {
+            *capture_from = history_size;
+            Some(trim_capture(&c))
+        }
         Err(e) => {
             log::warn!(
                 "[{}] Failed to capture pane {}: {}",
@@ -248,8 +536,13 @@ async fn maybe_update_working_message(
     working_message_id: Option<i64>,
     started_at: std::time::Instant,
     tick_counter: u32,
+    backoff: &mut TelegramBackoff,
 ) {
-    if !params.telegram_notify.working || !use_telegram || !tick_counter.is_multiple_of(4) {
+    if !params.telegram_notify.working
+        || !use_telegram
+        || !tick_counter.is_multiple_of(4)
+        || !backoff.ready()
+    {
         return;
     }
     let Some(tg) = params.telegram.as_ref() else {
@@ -258,14 +551,18 @@ async fn maybe_update_working_message(
     let elapsed = started_at.elapsed().as_secs();
     let working_text = format!("Working... {}", format_elapsed(elapsed));
     if let Some(mid) = working_message_id {
-        if let Err(e) =
-            crate::telegram::edit_message_text(&tg.bot_token, tg.chat_id, mid, &working_text).await
-        {
-            log::warn!(
-                "[{}] Failed to update working message: {}",
-                params.run_id,
-                e
-            );
+        let result = crate::telegram::edit_message_text(
+            &tg.bot_token,
+            tg.chat_id,
+            mid,
+            &working_text,
+            ParseMode::None,
+            tg.stop_keyboard(&params.run_id).as_ref(),
+        )
+        .await;
+        backoff.record(&params.run_id, "working message update", result);
+        if !backoff.ready() {
+            return;
         }
     }
     let _ = crate::telegram::send_chat_action(&tg.bot_token, tg.chat_id, "typing").await;
@@ -279,14 +576,18 @@ async fn handle_capture_diff(
     trimmed: String,
 ) {
     if trimmed != state.last_content && !trimmed.is_empty() {
-        let new_content = diff_content(&state.last_content, &trimmed);
+        let new_content =
+            crate::secrets::scrub(&diff_content(&state.last_content, &trimmed), &params.redact);
         state.last_content = trimmed;
         state.stale_ticks = 0;
         update_idle_ticks_for_content(state, &new_content);
         accumulate_and_push_log(params, state, &new_content, use_telegram);
     } else if !process_exited.load(Ordering::Acquire) {
         state.idle_ticks += 1;
-        if params.telegram_notify.logs && use_telegram {
+        if relays_logs(params)
+            && use_telegram
+            && params.telegram_log_mode != TelegramLogMode::Always
+        {
             maybe_flush_stale_pending(params, state).await;
         }
     }
@@ -317,7 +618,7 @@ fn accumulate_and_push_log(
     }
     state.accumulated_log.push_str(new_content);
     crate::relay::push_log_chunk(&params.relay, &params.slug, new_content);
-    if params.telegram_notify.logs && use_telegram {
+    if relays_logs(params) && use_telegram {
         if state.pending_diff.is_empty() {
             state.pending_diff = new_content.to_string();
         } else {
@@ -327,16 +628,24 @@ fn accumulate_and_push_log(
     }
 }
 
+/// Whether pane output goes to Telegram at all. Independent of the start,
+/// working and finish messages, which have their own `telegram_notify` flags.
+fn relays_logs(params: &MonitorParams) -> bool {
+    params.telegram_notify.logs && params.telegram_log_mode != TelegramLogMode::Off
+}
+
 async fn maybe_flush_stale_pending(params: &MonitorParams, state: &mut PollState) {
     state.stale_ticks += 1;
-    if state.stale_ticks < 2 || state.pending_diff.is_empty() {
+    if state.stale_ticks < 2 || state.pending_diff.is_empty() || !state.telegram_backoff.ready() {
         return;
     }
     if let Some(ref tg) = params.telegram {
         let msg = format!("<pre>{}</pre>", html_escape(&state.pending_diff));
-        if let Err(e) = crate::telegram::send_message(&tg.bot_token, tg.chat_id, &msg).await {
-            log::error!("[{}] Failed to send log snapshot: {}", params.run_id, e);
-        }
+        let result =
+            crate::telegram::send_message(&tg.bot_token, tg.chat_id, &msg, ParseMode::Html).await;
+        state
+            .telegram_backoff
+            .record(&params.run_id, "log snapshot", result);
     }
     state.pending_diff.clear();
     state.stale_ticks = 0;
@@ -344,10 +653,11 @@ async fn maybe_flush_stale_pending(params: &MonitorParams, state: &mut PollState
 }
 
 async fn maybe_flush_idle_logs(params: &MonitorParams, use_telegram: bool, state: &mut PollState) {
-    if !params.telegram_notify.logs
+    if !relays_logs(params)
         || !use_telegram
         || state.idle_ticks < IDLE_SEND_THRESHOLD
         || state.pending_diff.is_empty()
+        || !state.telegram_backoff.ready()
     {
         return;
     }
@@ -357,13 +667,12 @@ async fn maybe_flush_idle_logs(params: &MonitorParams, use_telegram: bool, state
         let snippet = tail_lines[start..].join("\n");
         if !snippet.trim().is_empty() {
             let msg = format!("<pre>{}</pre>", html_escape(&snippet));
-            if let Err(e) = crate::telegram::send_message(&tg.bot_token, tg.chat_id, &msg).await {
-                log::error!(
-                    "[{}] Failed to send idle log snapshot: {}",
-                    params.run_id,
-                    e
-                );
-            }
+            let result =
+                crate::telegram::send_message(&tg.bot_token, tg.chat_id, &msg, ParseMode::Html)
+                    .await;
+            state
+                .telegram_backoff
+                .record(&params.run_id, "idle log snapshot", result);
         }
     }
     state.pending_diff.clear();
@@ -371,6 +680,89 @@ async fn maybe_flush_idle_logs(params: &MonitorParams, use_telegram: bool, state
     state.stale_ticks = 0;
 }
 
+/// Send everything buffered since the last flush, split into `<pre>` blocks
+/// that fit a message. Unless `force`d (at process exit), at most once per
+/// `log_flush_secs` and not while Telegram sends are backing off. Blocks that
+/// fail to send are kept for the next flush.
+async fn flush_log_batch(
+    params: &MonitorParams,
+    use_telegram: bool,
+    state: &mut PollState,
+    force: bool,
+) {
+    if !relays_logs(params)
+        || !use_telegram
+        || (state.pending_diff.is_empty() && state.unsent_log_blocks.is_empty())
+    {
+        return;
+    }
+    let Some(tg) = params.telegram.as_ref() else {
+        return;
+    };
+    let interval = std::time::Duration::from_secs(tg.log_flush_secs);
+    if !force && (state.last_log_flush.elapsed() < interval || !state.telegram_backoff.ready()) {
+        return;
+    }
+    let mut blocks = std::mem::take(&mut state.unsent_log_blocks);
+    blocks.extend(pre_blocks(&std::mem::take(&mut state.pending_diff)));
+    for (i, block) in blocks.iter().enumerate() {
+        let result =
+            crate::telegram::send_message(&tg.bot_token, tg.chat_id, block, ParseMode::Html).await;
+        let failed = result.is_err();
+        state
+            .telegram_backoff
+            .record(&params.run_id, "log batch", result);
+        if failed {
+            state.unsent_log_blocks = blocks.split_off(i);
+            break;
+        }
+    }
+    state.last_log_flush = std::time::Instant::now();
+}
+
+/// Escape `text` into `<pre>` blocks of at most one message each, breaking
+/// between lines, or inside a line too long for a message of its own.
+fn pre_blocks(text: &str) -> Vec<String> {
+    let limit = crate::telegram::MAX_MESSAGE_LEN - "<pre></pre>".len();
+    let mut blocks = Vec::new();
+    let mut block = String::new();
+    for line in text.lines() {
+        let line = html_escape(line);
+        if !block.is_empty() && block.len() + 1 + line.len() > limit {
+            blocks.push(std::mem::take(&mut block));
+        }
+        let mut rest = line.as_str();
+        while rest.len() > limit {
+            let mut cut = limit;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            // Don't split an escape like `&amp;`.
+            if let Some(amp) = rest[..cut].rfind('&') {
+                if !rest[amp..cut].contains(';') {
+                    cut = amp;
+                }
+            }
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+            blocks.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+        }
+        if !block.is_empty() {
+            block.push('\n');
+        }
+        block.push_str(rest);
+    }
+    if !block.trim().is_empty() {
+        blocks.push(block);
+    }
+    blocks
+        .into_iter()
+        .map(|block| format!("<pre>{}</pre>", block))
+        .collect()
+}
+
 async fn finalize_telegram(
     params: &MonitorParams,
     use_telegram: bool,
@@ -386,6 +778,16 @@ async fn finalize_telegram(
                 params.run_id,
                 e
             );
+            // At least take the Stop button off it.
+            let _ = crate::telegram::edit_message_text(
+                &tg.bot_token,
+                tg.chat_id,
+                mid,
+                "Done",
+                ParseMode::None,
+                None,
+            )
+            .await;
         }
     }
 }
@@ -428,65 +830,181 @@ fn maybe_kill_pane(params: &MonitorParams) {
     }
 }
 
-fn persist_finish(params: &MonitorParams, full_output: &str) {
+/// Type the job's `post_command` into the pane and wait for it to print
+/// `POST_EXIT_MARKER`, interrupting it after `POST_COMMAND_TIMEOUT_SECS`.
+/// Its output stays in the pane and so ends up in the run's output.
+async fn run_post_command(params: &MonitorParams) {
+    let Some(command) = params.post_command.as_deref() else {
+        return;
+    };
+    if !tmux::pane_exists(&params.pane_id) {
+        log::warn!(
+            "[{}] Pane {} is gone, skipping post command",
+            params.run_id,
+            params.pane_id
+        );
+        return;
+    }
+    let pane_output = || tmux::capture_pane_full(&params.pane_id).unwrap_or_default();
+    let seen = marker_codes(&pane_output(), POST_EXIT_MARKER).count();
+    let line = format!("{}; echo \"{}$?\"", command, POST_EXIT_MARKER);
+    if let Err(e) = tmux::send_keys_to_pane(&params.tmux_session, &params.pane_id, &line) {
+        log::warn!("[{}] Failed to send post command: {}", params.run_id, e);
+        return;
+    }
+    log::info!("[{}] Running post command: {}", params.run_id, command);
+
+    let deadline =
+        std::time::Instant::now() + std::time::Duration::from_secs(POST_COMMAND_TIMEOUT_SECS);
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        if let Some(code) = marker_codes(&pane_output(), POST_EXIT_MARKER).nth(seen) {
+            if code != 0 {
+                log::warn!("[{}] Post command exited with {}", params.run_id, code);
+            }
+            return;
+        }
+        if !tmux::pane_exists(&params.pane_id) {
+            log::warn!("[{}] Pane closed during post command", params.run_id);
+            return;
+        }
+        if std::time::Instant::now() >= deadline {
+            log::warn!(
+                "[{}] Post command still running after {}s, interrupting it",
+                params.run_id,
+                POST_COMMAND_TIMEOUT_SECS
+            );
+            let _ = tmux::send_sigint_to_pane(&params.pane_id);
+            return;
+        }
+    }
+}
+
+/// Codes printed after each `marker` in the pane output, in order. The
+/// echoed command line itself never matches, as `$?` is not a number.
+fn marker_codes<'a>(output: &'a str, marker: &'a str) -> impl Iterator<Item = i32> + 'a {
+    output
+        .lines()
+        .filter_map(move |line| line.trim().strip_prefix(marker)?.parse().ok())
+}
+
+/// Exit code from the last `CWT_EXIT:<code>` line of the pane output.
+pub(crate) fn parse_exit_marker(output: &str) -> Option<i32> {
+    marker_codes(output, EXIT_MARKER).last()
+}
+
+/// Record the finished run and publish the job's new status. Returns the
+/// failure streak it leaves, 0 for a success.
+fn persist_finish(params: &MonitorParams, full_output: &str, exit_code: i32) -> u32 {
     let finished_at = Utc::now().to_rfc3339();
     {
         let h = params.history.lock();
-        if let Err(e) = h.update_finished(&params.run_id, &finished_at, Some(0), full_output, "") {
+        if let Err(e) = h.update_finished(
+            &params.run_id,
+            &finished_at,
+            Some(exit_code),
+            full_output,
+            &params.stderr,
+        ) {
             log::error!("[{}] Failed to update history: {}", params.run_id, e);
         }
     }
-    let new_status = JobStatus::Success {
-        last_run: finished_at,
+    let streak = if exit_code == 0 {
+        0
+    } else {
+        flapping::next_streak(params.previous_failure, exit_code)
+    };
+    let new_status = if exit_code == 0 {
+        JobStatus::Success {
+            last_run: finished_at,
+        }
+    } else {
+        JobStatus::Failed {
+            last_run: finished_at,
+            exit_code,
+            consecutive_failures: streak,
+        }
     };
     let mut status = params.job_status.lock();
     status.insert(params.slug.clone(), new_status.clone());
     drop(status);
     crate::relay::push_status_update(&params.relay, &params.slug, &new_status);
+    streak
 }
 
-async fn notify_finish(params: &MonitorParams, use_telegram: bool, use_app: bool) {
-    if !params.telegram_notify.finish {
+async fn notify_finish(
+    params: &MonitorParams,
+    use_telegram: bool,
+    use_app: bool,
+    exit_code: i32,
+    alert: Alert,
+) {
+    if alert == Alert::Muted {
+        log::debug!(
+            "[{}] '{}' keeps failing, notification muted",
+            params.run_id,
+            params.job_id
+        );
         return;
     }
-    if use_telegram {
+    if !params.telegram_notify.finish
+        || (params.notify_on_change_only && params.previous_outcome == Some(exit_code == 0))
+    {
+        return;
+    }
+    if use_telegram && alert.wanted(params.notify_on_success, params.notify_on_failure) {
         if let Some(ref tg) = params.telegram {
-            if params.notify_on_success {
-                let text = crate::telegram::format_job_status_message(
-                    &params.group_name,
-                    &params.job_id,
-                    "finished",
-                    None,
+            let code = (exit_code != 0).then_some(exit_code);
+            let mut text = crate::telegram::format_job_status_message(
+                &params.group_name,
+                &params.job_id,
+                &alert.status_text(),
+                code,
+            );
+            crate::telegram::append_git_label(&mut text, params.git_label.as_deref());
+            let keyboard = crate::telegram::job_action_keyboard(&tg.finish_actions, &params.slug);
+            if let Err(e) = crate::telegram::send_message_returning_id(
+                &tg.bot_token,
+                tg.chat_id,
+                &text,
+                ParseMode::Html,
+                keyboard.as_ref(),
+            )
+            .await
+            {
+                log::error!(
+                    "[{}] Failed to send completion notification: {}",
+                    params.run_id,
+                    e
                 );
-                if let Err(e) =
-                    crate::telegram::send_message(&tg.bot_token, tg.chat_id, &text).await
-                {
-                    log::error!(
-                        "[{}] Failed to send completion notification: {}",
-                        params.run_id,
-                        e
-                    );
-                }
             }
         }
     }
     if use_app {
-        crate::relay::push_job_notification(
-            &params.relay,
-            &params.slug,
-            "completed",
-            &params.run_id,
-        );
+        let event = alert.app_event();
+        crate::relay::push_job_notification(&params.relay, &params.slug, &event, &params.run_id);
         if let Some(ref n) = params.notifier {
-            n.notify_job(&params.job_id, "completed");
+            n.notify_job(&params.job_id, &event);
         }
     }
 }
 
-fn push_trigger_result_if_any(params: &MonitorParams) {
+fn push_trigger_result_if_any(params: &MonitorParams, exit_code: i32) {
     let Some(tid) = params.trigger_id.as_ref() else {
         return;
     };
+    if exit_code != 0 {
+        let error = format!("Exited with code {}", exit_code);
+        crate::relay::push_trigger_result(
+            &params.relay,
+            tid,
+            "failed",
+            Some(exit_code),
+            None,
+            Some(error),
+        );
+        return;
+    }
     let parsed = params
         .result_file
         .as_ref()
@@ -530,6 +1048,13 @@ fn diff_content(previous: &str, current: &str) -> String {
     let prev_lines: Vec<&str> = previous.lines().collect();
     let curr_lines: Vec<&str> = current.lines().collect();
 
+    // Full-screen TUIs repaint the same layout every tick, so the anchor line
+    // (often a box border) is found in place and everything after it looks
+    // unchanged. Compare those captures line by line instead.
+    if is_screen_redraw(&prev_lines, &curr_lines) {
+        return redraw_diff(&prev_lines, &curr_lines);
+    }
+
     // Try multiple anchor candidates from the end of previous capture.
     // If the last line is a common/empty string, try earlier lines.
     for anchor in prev_lines.iter().rev().filter(|l| !l.is_empty()) {
@@ -548,9 +1073,126 @@ fn diff_content(previous: &str, current: &str) -> String {
     String::new()
 }
 
+/// Minimum share of non-empty lines that must sit at the same row in both
+/// captures for the pane to be treated as a repainted screen.
+const REDRAW_MATCH_RATIO: f64 = 0.6;
+
+/// A redraw keeps roughly the same height and leaves most rows where they
+/// were; scrolling shifts every row up, so positional matches collapse.
+fn is_screen_redraw(prev: &[&str], curr: &[&str]) -> bool {
+    if prev.len().abs_diff(curr.len()) > 2 {
+        return false;
+    }
+    let mut rows = 0usize;
+    let mut same = 0usize;
+    for (p, c) in prev.iter().zip(curr) {
+        if p.trim().is_empty() && c.trim().is_empty() {
+            continue;
+        }
+        rows += 1;
+        if p == c {
+            same += 1;
+        }
+    }
+    // Plain appends leave every shared row intact (`same == rows`), so they
+    // still go through the anchor heuristic.
+    rows > 0 && same < rows && (same as f64) >= (rows as f64) * REDRAW_MATCH_RATIO
+}
+
+/// Rows that changed in place, skipping pure box-drawing rows and rows that
+/// merely moved from elsewhere on the screen.
+fn redraw_diff(prev: &[&str], curr: &[&str]) -> String {
+    let seen: std::collections::HashSet<&str> = prev.iter().copied().collect();
+    curr.iter()
+        .enumerate()
+        .filter(|(i, line)| prev.get(*i) != Some(*line))
+        .map(|(_, line)| *line)
+        .filter(|line| !seen.contains(line) && !is_decoration(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_decoration(line: &str) -> bool {
+    crate::telegram::strip_ansi(line).chars().all(|c| {
+        c.is_whitespace() || ('\u{2500}'..='\u{257F}').contains(&c) || matches!(c, '-' | '|' | '+')
+    })
+}
+
 fn html_escape(s: &str) -> String {
     crate::telegram::strip_ansi(s)
         .replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appended_output_uses_anchor() {
+        assert_eq!(diff_content("a\nb", "a\nb\nc\nd"), "c\nd");
+    }
+
+    #[test]
+    fn scrolled_output_uses_anchor() {
+        assert_eq!(diff_content("a\nb\nc", "b\nc\nd"), "d");
+    }
+
+    #[test]
+    fn redraw_reports_only_changed_rows() {
+        let prev = "╭────╮\n│ title │\n│ step 1 │\n│ idle │\n╰────╯";
+        let curr = "╭────╮\n│ title │\n│ step 2 │\n│ idle │\n╰────╯";
+        assert_eq!(diff_content(prev, curr), "│ step 2 │");
+    }
+
+    #[test]
+    fn moved_rows_are_not_reported() {
+        let prev = "╭────╮\n│ title │\n│ a │\n│ b │\n│ footer │\n╰────╯";
+        let curr = "╭────╮\n│ title │\n│ b │\n│ a │\n│ footer │\n╰────╯";
+        assert_eq!(diff_content(prev, curr), "");
+    }
+
+    #[test]
+    fn log_batches_split_between_lines_and_fit_a_message() {
+        assert_eq!(pre_blocks("a < b\nc"), vec!["<pre>a &lt; b\nc</pre>"]);
+        let line = "x".repeat(3000);
+        let blocks = pre_blocks(&format!("{}\n{}\n&&&", line, line));
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[1].ends_with("\n&amp;&amp;&amp;</pre>"));
+        let long = "&".repeat(2000);
+        for block in pre_blocks(&long) {
+            assert!(block.len() <= crate::telegram::MAX_MESSAGE_LEN);
+            assert!(block.ends_with(";</pre>"));
+        }
+    }
+
+    #[test]
+    fn exit_marker_takes_the_last_printed_status() {
+        let output = "$ cd /w && claude 'hi'; echo \"CWT_EXIT:$?\"\nCWT_EXIT:1\n$ ";
+        assert_eq!(parse_exit_marker(output), Some(1));
+        assert_eq!(parse_exit_marker("CWT_EXIT:0\nCWT_EXIT:127"), Some(127));
+        assert_eq!(parse_exit_marker("echo \"CWT_EXIT:$?\"\nCWT_EXIT:$?"), None);
+        let with_post = "CWT_EXIT:0\n$ git commit; echo \"CWT_POST_EXIT:$?\"\nCWT_POST_EXIT:1";
+        assert_eq!(parse_exit_marker(with_post), Some(0));
+        assert_eq!(
+            marker_codes(with_post, POST_EXIT_MARKER).collect::<Vec<_>>(),
+            [1]
+        );
+    }
+
+    #[test]
+    fn only_a_run_moved_off_running_counts_as_stopped() {
+        let running = |run_id: &str| JobStatus::Running {
+            run_id: run_id.to_string(),
+            started_at: String::new(),
+            pane_id: None,
+            tmux_session: None,
+        };
+        assert!(was_stopped(Some(&JobStatus::Idle), "r1"));
+        assert!(was_stopped(Some(&running("r2")), "r1"));
+        assert!(!was_stopped(Some(&running("r1")), "r1"));
+        assert!(!was_stopped(Some(&JobStatus::Paused), "r1"));
+        assert!(!was_stopped(None, "r1"));
+    }
+}
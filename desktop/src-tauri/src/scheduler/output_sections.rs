@@ -0,0 +1,185 @@
+//! Split a Claude Code pane's scrollback into labeled sections for display.
+//! The raw log stays the source of truth; this is a best-effort view that is
+//! only produced when Claude Code's `⏺` / `⎿` markers are present.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::HistoryStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Prompt,
+    Text,
+    ToolCall,
+    ToolResult,
+    Error,
+    /// The last `Text` section of the run.
+    FinalAnswer,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputSection {
+    pub kind: SectionKind,
+    pub text: String,
+}
+
+/// Lines starting with these are input box and status chrome, not output.
+const CHROME_PREFIXES: &[&str] = &["╭", "│", "╰", "✻", "✽", "✶", "✳", "✢", "? for shortcuts"];
+
+/// Segment `output`. `None` when no Claude Code markers are found.
+pub fn parse(output: &str) -> Option<Vec<OutputSection>> {
+    let mut sections: Vec<OutputSection> = Vec::new();
+    // Indentation stripped from continuation lines of the open section
+    let mut indent = 0;
+    let mut open = false;
+    let mut saw_marker = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = line.strip_prefix("⏺ ") {
+            saw_marker = true;
+            let kind = if is_tool_call(rest) {
+                SectionKind::ToolCall
+            } else {
+                SectionKind::Text
+            };
+            sections.push(section(kind, rest));
+            (indent, open) = (2, true);
+        } else if let Some(rest) = trimmed.strip_prefix('⎿') {
+            saw_marker = true;
+            let rest = rest.trim_start();
+            let kind = if rest.starts_with("Error") {
+                SectionKind::Error
+            } else {
+                SectionKind::ToolResult
+            };
+            sections.push(section(kind, rest));
+            (indent, open) = (5, true);
+        } else if let Some(rest) = line.strip_prefix("> ") {
+            sections.push(section(SectionKind::Prompt, rest));
+            (indent, open) = (2, true);
+        } else if CHROME_PREFIXES.iter().any(|p| trimmed.starts_with(p)) {
+            open = false;
+        } else if open {
+            if let Some(last) = sections.last_mut() {
+                last.text.push('\n');
+                last.text.push_str(strip_indent(line, indent));
+            }
+        }
+    }
+
+    if !saw_marker {
+        return None;
+    }
+    for s in &mut sections {
+        s.text.truncate(s.text.trim_end().len());
+    }
+    sections.retain(|s| !s.text.is_empty());
+    if let Some(last) = sections
+        .iter_mut()
+        .rev()
+        .find(|s| s.kind == SectionKind::Text)
+    {
+        last.kind = SectionKind::FinalAnswer;
+    }
+    Some(sections)
+}
+
+fn section(kind: SectionKind, text: &str) -> OutputSection {
+    OutputSection {
+        kind,
+        text: text.to_string(),
+    }
+}
+
+/// `Name(...)` where `Name` looks like a tool (e.g. `Bash(ls)`, `mcp__x__y(...)`).
+fn is_tool_call(rest: &str) -> bool {
+    let Some((name, _)) = rest.split_once('(') else {
+        return false;
+    };
+    !name.is_empty()
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '.' | '-'))
+}
+
+fn strip_indent(line: &str, max: usize) -> &str {
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    &line[spaces.min(max)..]
+}
+
+/// Sections file kept next to a run's log.
+fn sections_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension("sections.json")
+}
+
+/// Write the sections sealed like the run's stored output, so they are
+/// encrypted whenever `encrypt_history` is on. Nothing is written when the
+/// history store drops the output for lack of a key.
+pub fn save(log_path: &Path, sections: &[OutputSection], history: &HistoryStore) {
+    let path = sections_path(log_path);
+    match serde_json::to_string(sections) {
+        Ok(json) => {
+            let sealed = history.seal(&json);
+            if sealed.is_empty() {
+                return;
+            }
+            if let Err(e) = std::fs::write(&path, sealed) {
+                log::warn!("Failed to write {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize output sections: {}", e),
+    }
+}
+
+pub fn load(log_path: &Path, history: &HistoryStore) -> Option<Vec<OutputSection>> {
+    let raw = std::fs::read_to_string(sections_path(log_path)).ok()?;
+    serde_json::from_str(&history.open_output(raw)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_claude_markers() {
+        let output = "\
+> check the build
+⏺ I'll run the tests.
+⏺ Bash(cargo test)
+  ⎿  running 3 tests
+     test ok
+⏺ Update(src/lib.rs)
+  ⎿  Error: File has not been read yet
+⏺ All tests pass.
+  Nothing else to do.
+╭──────────────╮
+│ >            │
+╰──────────────╯";
+        let kinds: Vec<SectionKind> = parse(output).unwrap().iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SectionKind::Prompt,
+                SectionKind::Text,
+                SectionKind::ToolCall,
+                SectionKind::ToolResult,
+                SectionKind::ToolCall,
+                SectionKind::Error,
+                SectionKind::FinalAnswer,
+            ]
+        );
+        let sections = parse(output).unwrap();
+        assert_eq!(sections[3].text, "running 3 tests\ntest ok");
+        assert_eq!(sections[6].text, "All tests pass.\nNothing else to do.");
+    }
+
+    #[test]
+    fn plain_output_has_no_sections() {
+        assert_eq!(parse("$ ls\nCargo.toml\nsrc\n"), None);
+    }
+}
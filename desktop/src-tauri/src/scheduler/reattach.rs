@@ -183,6 +183,7 @@ fn reattach_one_run(
         session,
         &ctx.job_status,
     );
+    super::concurrency::hold_group(job);
     restore_auto_yes(job, pane_id, &ctx.auto_yes_panes);
     insert_reattach_history(job, &run_id, &started_at, pane_id, &ctx.history);
     register_active_agent(job, &run_id, pane_id, session, ctx, telegram_config);
@@ -192,7 +193,7 @@ fn reattach_one_run(
 
 fn cleanup_stale_reattach_records(slug: &str, history: &Arc<Mutex<crate::history::HistoryStore>>) {
     let h = history.lock();
-    let Ok(old_runs) = h.get_by_job_id(slug, 20) else {
+    let Ok(old_runs) = h.get_by_job_id(slug, 20, 0) else {
         return;
     };
     let stale_ids: Vec<String> = old_runs
@@ -266,6 +267,11 @@ fn insert_reattach_history(
         stderr: String::new(),
         pane_id: Some(pane_id.to_string()),
         log_path: None,
+        git_branch: None,
+        git_commit: None,
+        launch_ms: None,
+        output_truncated: false,
+        skipped: false,
     };
     if let Err(e) = h.insert(&record) {
         log::error!("Failed to insert reattach record: {}", e);
@@ -290,9 +296,10 @@ fn register_active_agent(
     ctx: &JobContext,
     telegram_config: Option<&crate::telegram::TelegramConfig>,
 ) {
-    let chat_id = job
-        .telegram_chat_id
-        .or_else(|| telegram_config.and_then(|c| c.chat_ids.first().copied()));
+    let chat_id = match telegram_config {
+        Some(c) => c.chat_for_job(job.telegram_chat_id, crate::config::jobs::job_group(job)),
+        None => job.telegram_chat_id,
+    };
     let Some(chat_id) = chat_id else { return };
     let mut map = ctx.active_agents.lock();
     map.insert(
@@ -318,6 +325,16 @@ fn spawn_reattach_monitor(
 ) {
     let telegram = build_telegram_stream(job, telegram_config);
     let notify_on_success = telegram_config.map(|c| c.notify_on_success).unwrap_or(true);
+    let notify_on_failure = telegram_config.map(|c| c.notify_on_failure).unwrap_or(true);
+    // Holds the output of a precheck that passed before the restart.
+    let stderr = ctx
+        .history
+        .lock()
+        .get_by_id(&run_id)
+        .ok()
+        .flatten()
+        .map(|record| record.stderr)
+        .unwrap_or_default();
     let params = MonitorParams {
         tmux_session: session.to_string(),
         pane_id: pane_id.to_string(),
@@ -328,18 +345,29 @@ fn spawn_reattach_monitor(
         agent_group: (job.group == "agent").then(|| crate::agent::agent_group_from_slug(&job.slug)),
         agent_prompt_path: (job.group == "agent").then(|| std::path::PathBuf::from(&job.path)),
         kill_on_end: job.kill_on_end,
+        post_command: job.post_command.clone(),
+        telegram_log_mode: job.telegram_log_mode.clone(),
         telegram,
         telegram_notify: job.telegram_notify.clone(),
         notify_target: job.notify_target.clone(),
         history: Arc::clone(&ctx.history),
         job_status: Arc::clone(&ctx.job_status),
         notify_on_success,
+        notify_on_failure,
         relay: Arc::clone(&ctx.relay),
         notifier: None,
         is_reattach: true,
         protected_panes: Arc::clone(&ctx.protected_panes),
         trigger_id: None,
         result_file: None,
+        git_label: None,
+        stderr,
+        notify_on_change_only: false,
+        previous_outcome: None,
+        previous_failure: None,
+        failure_alerts: ctx.settings.lock().failure_alerts.clone(),
+        redact: super::executor::secret_values(job, &ctx.secrets, &ctx.settings),
+        post_run_hooks: super::executor::PostRunHooks::new(job, ctx, &[]),
     };
     tokio::spawn(super::monitor::monitor_pane(params));
 }
@@ -355,12 +383,13 @@ fn build_telegram_stream(
     if !config.is_configured() {
         return None;
     }
-    let chat_id = job
-        .telegram_chat_id
-        .or_else(|| config.chat_ids.first().copied())?;
+    let chat_id = config.chat_for_job(job.telegram_chat_id, crate::config::jobs::job_group(job))?;
     Some(TelegramStream {
         bot_token: config.bot_token.clone(),
         chat_id,
+        log_flush_secs: config.log_flush_secs,
+        stop_button: config.agent_enabled,
+        finish_actions: config.active_finish_actions().to_vec(),
     })
 }
 
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use crate::config::jobs::JobStatus;
+use crate::job_context::JobContext;
+use crate::tmux::{self, SessionSnapshot};
+
+/// A session must have seen no activity for this long before it is reaped, so
+/// the pane of a run that just finished stays around to be looked at.
+const IDLE_SECS: i64 = 60 * 60;
+
+/// Kill tmux sessions that clawtab created (named after the
+/// `default_tmux_session` prefix) once every pane in them is an idle shell.
+/// Sessions with an attached client, a pane open in the app, or a pane of a
+/// running job are left alone.
+pub(super) fn reap_idle_sessions(ctx: &JobContext) {
+    if !tmux::is_available() {
+        return;
+    }
+    let prefix = ctx.settings.lock().default_tmux_session.clone();
+    if prefix.is_empty() {
+        return;
+    }
+    let sessions = match tmux::list_sessions() {
+        Ok(s) => s,
+        Err(e) => {
+            log::debug!("reap_idle_sessions: list_sessions failed: {}", e);
+            return;
+        }
+    };
+    let keep = panes_in_use(ctx);
+    let now = chrono::Utc::now().timestamp();
+    for session in sessions.iter().filter(|s| is_candidate(s, &prefix)) {
+        let snapshot = match tmux::session_snapshot(session) {
+            Ok(s) => s,
+            Err(e) => {
+                log::debug!("reap_idle_sessions: list-panes {} failed: {}", session, e);
+                continue;
+            }
+        };
+        if !is_reapable(&snapshot, &keep, now) {
+            continue;
+        }
+        match tmux::kill_session(session) {
+            Ok(()) => log::info!("Killed idle tmux session '{}'", session),
+            Err(e) => log::warn!("Failed to kill idle tmux session '{}': {}", session, e),
+        }
+    }
+}
+
+/// Panes the reaper must not touch: open in the app or owned by a running job.
+fn panes_in_use(ctx: &JobContext) -> HashSet<String> {
+    let mut panes = ctx.protected_panes.lock().clone();
+    panes.extend(ctx.job_status.lock().values().filter_map(|s| match s {
+        JobStatus::Running {
+            pane_id: Some(pane_id),
+            ..
+        } => Some(pane_id.clone()),
+        _ => None,
+    }));
+    panes
+}
+
+/// Only the default session and its `<prefix>-N` siblings, never the app's
+/// own view sessions.
+fn is_candidate(session: &str, prefix: &str) -> bool {
+    (session == prefix || session.starts_with(&format!("{prefix}-"))) && !session.contains("-view-")
+}
+
+fn is_reapable(snapshot: &SessionSnapshot, keep: &HashSet<String>, now: i64) -> bool {
+    !snapshot.attached
+        && now - snapshot.last_activity >= IDLE_SECS
+        && snapshot
+            .panes
+            .iter()
+            .all(|(pane_id, command)| tmux::is_idle_command(command) && !keep.contains(pane_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(attached: bool, commands: &[&str]) -> SessionSnapshot {
+        SessionSnapshot {
+            attached,
+            last_activity: 0,
+            panes: commands
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (format!("%{}", i), c.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reaps_only_unattached_sessions_of_idle_shells() {
+        let none = HashSet::new();
+        let reapable = |s: SessionSnapshot| is_reapable(&s, &none, IDLE_SECS);
+        assert!(reapable(snapshot(false, &["zsh", "bash"])));
+        assert!(!reapable(snapshot(false, &["zsh", "claude"])));
+        assert!(!reapable(snapshot(true, &["zsh"])));
+        let now = IDLE_SECS - 1;
+        assert!(!is_reapable(&snapshot(false, &["zsh"]), &none, now));
+        let keep = HashSet::from(["%0".to_string()]);
+        assert!(!is_reapable(&snapshot(false, &["zsh"]), &keep, IDLE_SECS));
+    }
+
+    #[test]
+    fn only_considers_sessions_with_the_default_prefix() {
+        assert!(is_candidate("cwt", "cwt"));
+        assert!(is_candidate("cwt-2", "cwt"));
+        assert!(!is_candidate("work", "cwt"));
+        assert!(!is_candidate("cwtools", "cwt"));
+        assert!(!is_candidate("cwt-view-1", "cwt"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Dry run of the scheduler: when each enabled job's cron would fire over a
+//! window, without running anything.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::config::jobs::Job;
+
+use super::cron_expr::parse_cron;
+use super::cron_zone;
+
+/// Longest window a simulation covers.
+pub const MAX_WINDOW_DAYS: i64 = 31;
+/// Most fire times a simulation returns.
+pub const MAX_OCCURRENCES: usize = 2000;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScheduledFire {
+    /// RFC3339, UTC.
+    pub time: String,
+    pub job_name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleSimulation {
+    /// Fire times of all jobs, earliest first.
+    pub fires: Vec<ScheduledFire>,
+    /// More fire times fell in the window than `MAX_OCCURRENCES`.
+    pub truncated: bool,
+}
+
+/// Every fire time in `(from, to]` of each enabled job with a valid cron,
+/// evaluated the way the scheduler loop evaluates it.
+pub fn simulate_schedule(
+    jobs: &[Job],
+    default_tz: Option<&str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<ScheduleSimulation, String> {
+    if to <= from {
+        return Err("The end of the window must be after its start".to_string());
+    }
+    if to - from > Duration::days(MAX_WINDOW_DAYS) {
+        return Err(format!(
+            "Simulation window is limited to {} days",
+            MAX_WINDOW_DAYS
+        ));
+    }
+
+    let mut fires: Vec<(DateTime<Utc>, &Job)> = Vec::new();
+    for job in jobs.iter().filter(|j| j.enabled && !j.cron.is_empty()) {
+        let Some(schedules) = parse_cron(&job.cron) else {
+            continue;
+        };
+        let zone = cron_zone(job, default_tz);
+        fires.extend(
+            zone.fire_times(&schedules, from, to, MAX_OCCURRENCES + 1)
+                .into_iter()
+                .map(|t| (t, job)),
+        );
+    }
+    fires.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    let truncated = fires.len() > MAX_OCCURRENCES;
+    fires.truncate(MAX_OCCURRENCES);
+
+    Ok(ScheduleSimulation {
+        fires: fires
+            .into_iter()
+            .map(|(t, job)| ScheduledFire {
+                time: t.to_rfc3339(),
+                job_name: job.name.clone(),
+                slug: job.slug.clone(),
+            })
+            .collect(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn job(name: &str, cron: &str, enabled: bool) -> Job {
+        let yaml = format!(
+            "name: {}\njob_type: binary\nenabled: {}\npath: /bin/true\ncron: '{}'\ntimezone: UTC\n",
+            name, enabled, cron
+        );
+        let mut job: Job = serde_yml::from_str(&yaml).unwrap();
+        job.slug = name.to_string();
+        job
+    }
+
+    #[test]
+    fn merges_enabled_jobs_into_one_timeline() {
+        let jobs = vec![
+            job("hourly", "0 * * * *", true),
+            job("daily", "0 12 * * *", true),
+            job("off", "*/5 * * * *", false),
+            job("manual", "", true),
+        ];
+        let from = Utc.with_ymd_and_hms(2026, 7, 1, 10, 30, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2026, 7, 1, 12, 0, 0).unwrap();
+        let sim = simulate_schedule(&jobs, None, from, to).unwrap();
+        let names: Vec<&str> = sim.fires.iter().map(|f| f.job_name.as_str()).collect();
+        assert_eq!(names, vec!["hourly", "daily", "hourly"]);
+        assert_eq!(sim.fires[0].time, "2026-07-01T11:00:00+00:00");
+        assert!(!sim.truncated);
+    }
+
+    #[test]
+    fn window_and_occurrences_are_capped() {
+        let jobs = vec![job("every-minute", "* * * * *", true)];
+        let from = Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap();
+        let sim = simulate_schedule(&jobs, None, from, from + Duration::days(2)).unwrap();
+        assert_eq!(sim.fires.len(), MAX_OCCURRENCES);
+        assert!(sim.truncated);
+
+        assert!(simulate_schedule(&jobs, None, from, from).is_err());
+        assert!(simulate_schedule(&jobs, None, from, from + Duration::days(40)).is_err());
+    }
+}
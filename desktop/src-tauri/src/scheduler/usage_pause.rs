@@ -0,0 +1,244 @@
+//! `pause_when_usage_exhausted`: while the Claude session quota is used up,
+//! scheduled Claude and folder jobs are held back instead of failing, and
+//! each runs once when the quota resets. Binary jobs keep running.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use clawtab_protocol::NoticeLevel;
+
+use crate::claude_usage::{self, UsageResponse};
+use crate::config::jobs::{Job, JobType, JobsConfig};
+use crate::job_context::JobContext;
+
+/// Scheduler ticks (30s each) between usage checks.
+const CHECK_TICKS: u32 = 10;
+/// A usage check that takes longer is dropped.
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Why scheduled agent jobs are on hold.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsagePause {
+    /// Session utilization (percent) at the last check.
+    pub utilization: f64,
+    /// When the session quota resets (RFC3339), if known.
+    pub resets_at: Option<String>,
+    pub since: String,
+}
+
+#[derive(Default)]
+struct State {
+    pause: Option<UsagePause>,
+    /// Held-back triggers by job slug. Each job runs once on resume, however
+    /// many of its triggers were held back.
+    deferred: HashMap<String, &'static str>,
+    /// A usage check is in flight, so the tick doesn't start another.
+    checking: bool,
+}
+
+static STATE: OnceLock<Mutex<State>> = OnceLock::new();
+
+fn state() -> &'static Mutex<State> {
+    STATE.get_or_init(|| Mutex::new(State::default()))
+}
+
+/// The current pause, if scheduled agent jobs are on hold.
+pub fn current() -> Option<UsagePause> {
+    state().lock().pause.clone()
+}
+
+/// Hold back a scheduled trigger of `job` while paused. Returns false when
+/// the job should run now.
+pub(super) fn defer(job: &Job, trigger: &'static str) -> bool {
+    if !matches!(job.job_type, JobType::Claude | JobType::Job) {
+        return false;
+    }
+    let mut state = state().lock();
+    if state.pause.is_none() {
+        return false;
+    }
+    log::info!(
+        "Job '{}' ({}) held back until Claude usage resets",
+        job.name,
+        trigger
+    );
+    state.deferred.entry(job.slug.clone()).or_insert(trigger);
+    true
+}
+
+/// Pause applying at `threshold` percent session usage, or None.
+fn pause_for(usage: &UsageResponse, threshold: f64, now: DateTime<Utc>) -> Option<UsagePause> {
+    let session = usage.five_hour.as_ref()?;
+    if session.utilization < threshold {
+        return None;
+    }
+    Some(UsagePause {
+        utilization: session.utilization,
+        resets_at: session.resets_at.clone(),
+        since: now.to_rfc3339(),
+    })
+}
+
+/// Whether the reset time of `pause` has passed, so usage is worth checking
+/// before the next regular check.
+fn reset_passed(pause: &UsagePause, now: DateTime<Utc>) -> bool {
+    pause
+        .resets_at
+        .as_ref()
+        .and_then(|r| r.parse::<DateTime<Utc>>().ok())
+        .is_some_and(|at| at <= now)
+}
+
+/// On the scheduler's tick, start a usage check when one is due. The check
+/// runs in its own task, so a slow usage endpoint doesn't hold up the tick.
+pub(super) fn refresh(
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    ctx: &JobContext,
+    event_sink: &Arc<dyn crate::events::EventSink>,
+    tick: u32,
+) {
+    let (enabled, threshold) = {
+        let s = ctx.settings.lock();
+        (s.pause_when_usage_exhausted, s.usage_pause_percent)
+    };
+    let paused = current();
+    if !enabled {
+        if paused.is_some() {
+            resume(jobs_config, ctx, event_sink.as_ref());
+        }
+        return;
+    }
+    let due = tick.is_multiple_of(CHECK_TICKS)
+        || paused.as_ref().is_some_and(|p| reset_passed(p, Utc::now()));
+    if !due || std::mem::replace(&mut state().lock().checking, true) {
+        return;
+    }
+    let jobs_config = Arc::clone(jobs_config);
+    let ctx = ctx.clone();
+    let event_sink = Arc::clone(event_sink);
+    tokio::spawn(async move {
+        check(&jobs_config, &ctx, event_sink.as_ref(), threshold).await;
+        state().lock().checking = false;
+    });
+}
+
+/// Fetch Claude usage and pause or resume. On resume, held-back jobs that are
+/// still enabled start. A reading served from cache may be stale, so it
+/// changes nothing.
+async fn check(
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    ctx: &JobContext,
+    event_sink: &dyn crate::events::EventSink,
+    threshold: f64,
+) {
+    let usage = match tokio::time::timeout(FETCH_TIMEOUT, claude_usage::fetch_usage()).await {
+        Ok(Ok(u)) if u.cached_at.is_some() => {
+            log::debug!("Usage pause check skipped: usage served from cache");
+            return;
+        }
+        Ok(Ok(u)) => u,
+        Ok(Err(e)) => {
+            log::debug!("Usage pause check skipped: {}", e);
+            return;
+        }
+        Err(_) => {
+            log::debug!("Usage pause check timed out");
+            return;
+        }
+    };
+    match (pause_for(&usage, threshold, Utc::now()), current()) {
+        (Some(pause), None) => {
+            log::info!(
+                "Claude session usage at {:.0}%, holding back scheduled agent jobs",
+                pause.utilization
+            );
+            state().lock().pause = Some(pause.clone());
+            crate::relay::push_notice(
+                &ctx.relay,
+                NoticeLevel::Warning,
+                "Scheduler paused",
+                &format!(
+                    "Claude usage at {:.0}%. Scheduled Claude and folder jobs wait for the reset.",
+                    pause.utilization
+                ),
+                false,
+            );
+            event_sink.emit_usage_pause_changed(Some(pause));
+        }
+        (Some(pause), Some(previous)) => {
+            state().lock().pause = Some(UsagePause {
+                since: previous.since,
+                ..pause
+            });
+        }
+        (None, Some(_)) => resume(jobs_config, ctx, event_sink),
+        (None, None) => {}
+    }
+}
+
+fn resume(
+    jobs_config: &Arc<Mutex<JobsConfig>>,
+    ctx: &JobContext,
+    event_sink: &dyn crate::events::EventSink,
+) {
+    let deferred = {
+        let mut state = state().lock();
+        state.pause = None;
+        std::mem::take(&mut state.deferred)
+    };
+    log::info!(
+        "Claude usage available again, starting {} held-back job(s)",
+        deferred.len()
+    );
+    crate::relay::push_notice(
+        &ctx.relay,
+        NoticeLevel::Info,
+        "Scheduler resumed",
+        &format!(
+            "Claude usage is available again. {} held-back job(s) starting.",
+            deferred.len()
+        ),
+        false,
+    );
+    event_sink.emit_usage_pause_changed(None);
+    let jobs = jobs_config.lock().jobs.clone();
+    for (slug, trigger) in deferred {
+        if let Some(job) = jobs.iter().find(|j| j.slug == slug && j.enabled) {
+            super::spawn_cron_job(job.clone(), ctx.clone(), trigger);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude_usage::UsageBucket;
+
+    fn usage(session: f64) -> UsageResponse {
+        UsageResponse {
+            five_hour: Some(UsageBucket {
+                utilization: session,
+                resets_at: Some("2026-01-01T15:00:00Z".to_string()),
+            }),
+            seven_day: None,
+            cached_at: None,
+        }
+    }
+
+    #[test]
+    fn pauses_at_the_threshold_until_the_reset_time() {
+        let now: DateTime<Utc> = "2026-01-01T12:00:00Z".parse().unwrap();
+        assert!(pause_for(&usage(99.0), 100.0, now).is_none());
+        let pause = pause_for(&usage(100.0), 100.0, now).unwrap();
+        assert_eq!(pause.resets_at.as_deref(), Some("2026-01-01T15:00:00Z"));
+        assert!(pause_for(&usage(90.0), 90.0, now).is_some());
+
+        assert!(!reset_passed(&pause, now));
+        let later: DateTime<Utc> = "2026-01-01T15:00:30Z".parse().unwrap();
+        assert!(reset_passed(&pause, later));
+    }
+}
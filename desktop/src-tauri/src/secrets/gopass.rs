@@ -1,4 +1,5 @@
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 
 /// Stateless helper for interacting with the gopass store.
 /// Secrets selected from gopass are stored into macOS Keychain, not cached here.
@@ -60,4 +61,30 @@ impl GopassBackend {
 
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
+
+    /// Create or overwrite the entry at `path`. The value goes in on stdin so
+    /// it never shows up in the process list.
+    pub fn set(path: &str, value: &str) -> Result<(), String> {
+        let mut child = Command::new("gopass")
+            .args(["insert", "--force", "--", path])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run gopass: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(value.as_bytes())
+                .map_err(|e| format!("Failed to write to gopass: {}", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run gopass: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gopass error: {}", stderr.trim()));
+        }
+        Ok(())
+    }
 }
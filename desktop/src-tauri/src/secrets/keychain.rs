@@ -1,15 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 const SERVICE_NAME: &str = "cc.clawtab";
+/// `security` exit status for errSecItemNotFound.
+pub(crate) const ITEM_NOT_FOUND_EXIT: i32 = 44;
 
 pub struct KeychainBackend {
     cache: HashMap<String, String>,
+    /// Keys present in the keychain whose values could not be read.
+    denied: HashSet<String>,
+    /// Why the keychain itself could not be listed, if it couldn't.
+    access_error: Option<String>,
 }
 
 impl KeychainBackend {
     pub fn new() -> Self {
         let mut backend = Self {
             cache: HashMap::new(),
+            denied: HashSet::new(),
+            access_error: None,
         };
         backend.reload_all();
         backend
@@ -19,6 +27,23 @@ impl KeychainBackend {
         self.cache.get(key)
     }
 
+    /// Keys that exist but were not readable (access denied or keychain locked).
+    pub fn denied_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.denied.iter().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn access_error(&self) -> Option<&str> {
+        self.access_error.as_deref()
+    }
+
+    /// True when `key` is unavailable because of an access failure rather
+    /// than because it was never stored.
+    pub fn is_denied(&self, key: &str) -> bool {
+        self.denied.contains(key) || self.access_error.is_some()
+    }
+
     pub fn list_keys(&self) -> Vec<String> {
         let mut keys: Vec<String> = self.cache.keys().cloned().collect();
         keys.sort();
@@ -55,6 +80,7 @@ impl KeychainBackend {
         }
 
         self.cache.insert(key.to_string(), value.to_string());
+        self.denied.remove(key);
         Ok(())
     }
 
@@ -70,6 +96,7 @@ impl KeychainBackend {
         }
 
         self.cache.remove(key);
+        self.denied.remove(key);
         Ok(())
     }
 
@@ -82,11 +109,20 @@ impl KeychainBackend {
             Ok(o) => o,
             Err(e) => {
                 log::warn!("Failed to dump keychain: {}", e);
+                self.access_error = Some(format!("Failed to run security command: {}", e));
                 return;
             }
         };
 
         self.cache.clear();
+        self.denied.clear();
+        self.access_error = if output.status.success() {
+            None
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            log::warn!("Keychain is not accessible: {}", stderr);
+            Some(format!("Keychain access failed: {}", stderr))
+        };
         let text = String::from_utf8_lossy(&output.stdout);
         let mut current_is_ours = false;
         let mut current_account: Option<String> = None;
@@ -110,8 +146,15 @@ impl KeychainBackend {
 
             if current_is_ours {
                 if let Some(ref acct) = current_account {
-                    if let Some(value) = read_keychain_value(acct) {
-                        self.cache.insert(acct.clone(), value);
+                    match read_keychain_value(acct) {
+                        Ok(Some(value)) => {
+                            self.cache.insert(acct.clone(), value);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            log::warn!("Keychain denied access to '{}': {}", acct, e);
+                            self.denied.insert(acct.clone());
+                        }
                     }
                     current_is_ours = false;
                     current_account = None;
@@ -121,20 +164,20 @@ impl KeychainBackend {
     }
 }
 
-fn read_keychain_value(key: &str) -> Option<String> {
+/// Read one value. `Ok(None)` means the item is absent or empty; `Err` means
+/// it could not be read (denied, locked, or the prompt was cancelled).
+fn read_keychain_value(key: &str) -> Result<Option<String>, String> {
     let output = std::process::Command::new("security")
         .args(["find-generic-password", "-s", SERVICE_NAME, "-a", key, "-w"])
         .output()
-        .ok()?;
+        .map_err(|e| format!("Failed to run security command: {}", e))?;
 
     if output.status.success() {
         let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if value.is_empty() {
-            None
-        } else {
-            Some(value)
-        }
+        Ok(if value.is_empty() { None } else { Some(value) })
+    } else if output.status.code() == Some(ITEM_NOT_FOUND_EXIT) {
+        Ok(None)
     } else {
-        None
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
     }
 }
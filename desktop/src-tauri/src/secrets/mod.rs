@@ -1,7 +1,7 @@
 pub mod gopass;
 pub mod keychain;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use self::keychain::KeychainBackend;
 
@@ -9,12 +9,36 @@ use self::keychain::KeychainBackend;
 pub struct SecretEntry {
     pub key: String,
     pub source: SecretSource,
+    /// The key exists but its value could not be read from the keychain.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub access_denied: bool,
 }
 
+/// Whether the keychain could be read, for prompting the user to grant access.
 #[derive(Debug, Clone, Serialize)]
+pub struct KeychainStatus {
+    pub accessible: bool,
+    pub error: Option<String>,
+    pub denied_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SecretSource {
     Keychain,
+    /// Only a write target: jobs read secrets from the keychain.
+    Gopass,
+}
+
+/// Replace every exact occurrence of the given secret values in `text` with
+/// `***`. Longer values go first, so a secret containing another is masked
+/// whole rather than leaving its remainder behind.
+pub fn scrub(text: &str, values: &[String]) -> String {
+    let mut values: Vec<&String> = values.iter().filter(|v| !v.is_empty()).collect();
+    values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    values
+        .into_iter()
+        .fold(text.to_string(), |acc, v| acc.replace(v.as_str(), "***"))
 }
 
 pub struct SecretsManager {
@@ -33,6 +57,22 @@ impl SecretsManager {
         self.keychain.get(key)
     }
 
+    /// True when `key` resolves to nothing because keychain access failed,
+    /// as opposed to the key never having been stored.
+    pub fn is_access_denied(&self, key: &str) -> bool {
+        self.keychain.is_denied(key)
+    }
+
+    pub fn keychain_status(&self) -> KeychainStatus {
+        let denied_keys = self.keychain.denied_keys();
+        let error = self.keychain.access_error().map(str::to_string);
+        KeychainStatus {
+            accessible: error.is_none() && denied_keys.is_empty(),
+            error,
+            denied_keys,
+        }
+    }
+
     /// Reload the in-memory keychain cache from the OS keychain.
     /// Call this after another process writes or deletes a secret.
     pub fn reload(&mut self) {
@@ -48,16 +88,31 @@ impl SecretsManager {
             .map(|key| SecretEntry {
                 key,
                 source: SecretSource::Keychain,
+                access_denied: false,
             })
+            .chain(
+                self.keychain
+                    .denied_keys()
+                    .into_iter()
+                    .map(|key| SecretEntry {
+                        key,
+                        source: SecretSource::Keychain,
+                        access_denied: true,
+                    }),
+            )
             .collect();
 
         entries.sort_by(|a, b| a.key.cmp(&b.key));
         entries
     }
 
-    /// List just the key names
+    /// List just the names of readable keys
     pub fn list_keys(&self) -> Vec<String> {
-        self.list_entries().into_iter().map(|e| e.key).collect()
+        self.list_entries()
+            .into_iter()
+            .filter(|e| !e.access_denied)
+            .map(|e| e.key)
+            .collect()
     }
 
     /// Set a secret in keychain
@@ -65,6 +120,16 @@ impl SecretsManager {
         self.keychain.set(key, value)
     }
 
+    /// Set a secret in the given store. Writing to gopass creates the entry at
+    /// path `key` and also stores the value in the keychain, which is where
+    /// jobs read secrets from, just like an import does.
+    pub fn set_in(&mut self, source: SecretSource, key: &str, value: &str) -> Result<(), String> {
+        if source == SecretSource::Gopass {
+            gopass::GopassBackend::set(key, value)?;
+        }
+        self.keychain.set(key, value)
+    }
+
     /// Delete a secret from keychain
     pub fn delete(&mut self, key: &str) -> Result<(), String> {
         self.keychain.delete(key)
@@ -80,3 +145,18 @@ impl SecretsManager {
         gopass::GopassBackend::list_entries()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::scrub;
+
+    #[test]
+    fn scrubs_longest_secret_first() {
+        let values = vec!["abc".to_string(), "abcdef123".to_string(), String::new()];
+        assert_eq!(
+            scrub("token=abcdef123 short=abc", &values),
+            "token=*** short=***"
+        );
+        assert_eq!(scrub("nothing here", &values), "nothing here");
+    }
+}
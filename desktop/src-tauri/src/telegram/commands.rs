@@ -2,6 +2,11 @@ use std::collections::HashMap;
 
 use crate::config::jobs::{Job, JobStatus};
 
+/// Lines `/tail` replies with when no count is given.
+const DEFAULT_TAIL_LINES: usize = 40;
+/// Upper bound on the `/tail` line count.
+const MAX_TAIL_LINES: usize = 200;
+
 #[derive(Debug)]
 pub enum AgentCommand {
     Help,
@@ -10,6 +15,7 @@ pub enum AgentCommand {
     Run(String, HashMap<String, String>),
     Pause(String),
     Resume(String),
+    Tail(String, usize),
     Agent(String),
     AgentExit,
     Unknown(String),
@@ -53,6 +59,20 @@ pub fn parse_command(text: &str) -> Option<AgentCommand> {
             Some(name) => AgentCommand::Resume(name),
             None => AgentCommand::Unknown("/resume requires a job name".to_string()),
         },
+        "/tail" => match arg {
+            Some(arg_str) => {
+                let mut parts = arg_str.split_whitespace();
+                let name = parts.next().unwrap().to_string();
+                match parts.next().map(str::parse::<usize>) {
+                    None => AgentCommand::Tail(name, DEFAULT_TAIL_LINES),
+                    Some(Ok(n)) if n > 0 => AgentCommand::Tail(name, n.min(MAX_TAIL_LINES)),
+                    Some(_) => AgentCommand::Unknown(
+                        "/tail line count must be a positive number".to_string(),
+                    ),
+                }
+            }
+            None => AgentCommand::Unknown("/tail requires a job name".to_string()),
+        },
         "/agent" => AgentCommand::Agent(arg.unwrap_or_default()),
         "/exit" | "/quit" => AgentCommand::AgentExit,
         _ => AgentCommand::Unknown(format!("Unknown command: {}", cmd)),
@@ -68,6 +88,7 @@ pub fn format_help() -> String {
         "/run &lt;name&gt; [key=val ...] - Run a job",
         "/pause &lt;name&gt; - Pause a running job",
         "/resume &lt;name&gt; - Resume a paused job",
+        "/tail &lt;name&gt; [lines] - Show the last lines of a running job's pane",
         "/agent [prompt] - Start interactive Claude Code session",
         "/exit - End active agent session",
         "/help - Show this help",
@@ -87,6 +108,7 @@ pub fn format_jobs(jobs: &[Job]) -> String {
         let enabled = if job.enabled { "on" } else { "off" };
         let jt = match job.job_type {
             crate::config::jobs::JobType::Binary => "bin",
+            crate::config::jobs::JobType::Shell => "sh",
             crate::config::jobs::JobType::Claude => "claude",
             crate::config::jobs::JobType::Job => "job",
         };
@@ -112,10 +134,12 @@ pub fn format_status(statuses: &HashMap<String, JobStatus>) -> String {
         let status_str = match status {
             JobStatus::Idle => "idle".to_string(),
             JobStatus::Running { started_at, .. } => format!("running since {}", started_at),
+            JobStatus::Queued { queued_at } => format!("queued since {}", queued_at),
             JobStatus::Success { last_run } => format!("success ({})", last_run),
             JobStatus::Failed {
                 last_run,
                 exit_code,
+                ..
             } => format!("failed exit {} ({})", exit_code, last_run),
             JobStatus::Paused => "paused".to_string(),
         };
@@ -123,3 +147,59 @@ pub fn format_status(statuses: &HashMap<String, JobStatus>) -> String {
     }
     lines.join("\n")
 }
+
+/// Reply for `/tail`: the last `lines` lines of `output` in a `<pre>` block,
+/// dropping older lines until the reply fits in one message.
+pub fn format_tail(slug: &str, output: &str, lines: usize) -> String {
+    let header = format!("<b>{}</b>\n", crate::telegram::html_escape(slug));
+    let budget = crate::telegram::MAX_MESSAGE_LEN - header.len() - "<pre></pre>".len();
+    let text = crate::telegram::strip_ansi(output);
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    let mut kept: Vec<String> = Vec::new();
+    let mut len = 0;
+    for line in all.iter().rev().take(lines) {
+        let line = crate::telegram::html_escape(line.trim_end());
+        if len + line.len() + 1 > budget {
+            break;
+        }
+        len += line.len() + 1;
+        kept.push(line);
+    }
+    if kept.iter().all(|l| l.is_empty()) {
+        return format!("{}<i>(pane is empty)</i>", header);
+    }
+    kept.reverse();
+    format!("{}<pre>{}</pre>", header, kept.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_defaults_and_caps_line_count() {
+        assert!(matches!(
+            parse_command("/tail deploy"),
+            Some(AgentCommand::Tail(name, 40)) if name == "deploy"
+        ));
+        assert!(matches!(
+            parse_command("/tail deploy 500"),
+            Some(AgentCommand::Tail(_, 200))
+        ));
+        assert!(matches!(
+            parse_command("/tail deploy x"),
+            Some(AgentCommand::Unknown(_))
+        ));
+        assert!(matches!(
+            parse_command("/tail"),
+            Some(AgentCommand::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn format_tail_keeps_last_lines_escaped() {
+        let reply = format_tail("ops/deploy", "one\ntwo <b>\n\x1b[1mthree\x1b[0m\n\n", 2);
+        assert_eq!(reply, "<b>ops/deploy</b>\n<pre>two &lt;b&gt;\nthree</pre>");
+        assert!(format_tail("a", "\n\n", 40).contains("pane is empty"));
+    }
+}
@@ -16,7 +16,64 @@ pub struct ActiveAgent {
     pub job_id: String,
 }
 
-const MAX_MESSAGE_LEN: usize = 4096;
+pub(crate) const MAX_MESSAGE_LEN: usize = 4096;
+/// `callback_data` of the Stop button on a job's working message, followed
+/// by the run id.
+pub const STOP_CALLBACK_PREFIX: &str = "stop:";
+/// `callback_data` of the Re-run button on a completion message, followed by
+/// the job slug.
+pub const RERUN_CALLBACK_PREFIX: &str = "rerun:";
+/// Longest `callback_data` Telegram accepts, in bytes.
+const MAX_CALLBACK_DATA: usize = 64;
+/// Longest button label that still shares a keyboard row.
+const MAX_SHARED_ROW_LABEL: usize = 16;
+/// 429s waited out before a send gives up.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+
+/// How Telegram should parse a message's text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    #[default]
+    Html,
+    MarkdownV2,
+    /// Plain text: no `parse_mode` is sent, so `<`, `>` and `&` in raw
+    /// output can't make Telegram reject the message.
+    None,
+}
+
+impl ParseMode {
+    fn as_api_str(self) -> Option<&'static str> {
+        match self {
+            ParseMode::Html => Some("HTML"),
+            ParseMode::MarkdownV2 => Some("MarkdownV2"),
+            ParseMode::None => None,
+        }
+    }
+
+    /// Add `parse_mode` to a request body, unless the mode is `None`.
+    fn apply(self, body: &mut serde_json::Value) {
+        if let Some(mode) = self.as_api_str() {
+            body["parse_mode"] = mode.into();
+        }
+    }
+}
+
+impl std::str::FromStr for ParseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" => Ok(ParseMode::Html),
+            "markdownv2" | "markdown" => Ok(ParseMode::MarkdownV2),
+            "none" | "plain" => Ok(ParseMode::None),
+            other => Err(format!(
+                "Unknown parse mode '{}' (expected html, markdownv2 or none)",
+                other
+            )),
+        }
+    }
+}
 
 /// Describe Telegram transport failures without formatting reqwest's URL,
 /// which contains the bot token in its path.
@@ -39,6 +96,55 @@ pub(crate) fn telegram_request_error(operation: &str, error: &reqwest::Error) ->
     }
 }
 
+/// Seconds to wait before retrying, from the `parameters.retry_after` of a
+/// 429 response body, capped at `MAX_RETRY_AFTER_SECS`.
+fn retry_after_secs(body: &str) -> u64 {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["parameters"]["retry_after"].as_u64())
+        .unwrap_or(1)
+        .clamp(1, MAX_RETRY_AFTER_SECS)
+}
+
+/// POST `body` to the Bot API `method`, sleeping out up to
+/// `MAX_RATE_LIMIT_RETRIES` rate-limit responses. Returns the final status
+/// and response body.
+async fn post_json(
+    client: &reqwest::Client,
+    bot_token: &str,
+    method: &str,
+    body: &serde_json::Value,
+) -> Result<(reqwest::StatusCode, String), String> {
+    let url = format!("https://api.telegram.org/bot{}/{}", bot_token, method);
+    let mut retries = 0;
+    loop {
+        let resp = client
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| telegram_request_error(method, &e))?;
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .map_err(|e| telegram_request_error(&format!("read {} response", method), &e))?;
+        if status != reqwest::StatusCode::TOO_MANY_REQUESTS || retries == MAX_RATE_LIMIT_RETRIES {
+            return Ok((status, text));
+        }
+        retries += 1;
+        let secs = retry_after_secs(&text);
+        log::warn!(
+            "Telegram {} rate limited, retrying in {}s ({}/{})",
+            method,
+            secs,
+            retries,
+            MAX_RATE_LIMIT_RETRIES
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(secs)).await;
+    }
+}
+
 /// When true, the agent poller yields to the setup poller so they don't compete
 /// for getUpdates from the same bot.
 static SETUP_POLLING_ACTIVE: AtomicBool = AtomicBool::new(false);
@@ -57,9 +163,40 @@ pub struct TelegramConfig {
     pub bot_token: String,
     pub chat_ids: Vec<i64>,
     pub chat_names: HashMap<String, String>,
+    /// Default chat per job group, used when a job has no chat of its own.
+    pub group_chats: HashMap<String, i64>,
     pub notify_on_success: bool,
     pub notify_on_failure: bool,
     pub agent_enabled: bool,
+    /// Jobs with `telegram_log_mode: always`: seconds between batched log
+    /// messages.
+    pub log_flush_secs: u64,
+    /// Buttons on job completion messages. Only shown with agent mode on, as
+    /// its poller handles the taps.
+    pub finish_actions: Vec<JobAction>,
+}
+
+/// A job control button on completion messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobAction {
+    /// Start the job again with its param defaults.
+    Rerun,
+    /// Stop the job's current run.
+    Stop,
+}
+
+impl JobAction {
+    fn button(self, slug: &str) -> InlineButton {
+        let (text, prefix) = match self {
+            JobAction::Rerun => ("\u{1f501} Re-run", RERUN_CALLBACK_PREFIX),
+            JobAction::Stop => ("\u{23f9} Stop", STOP_CALLBACK_PREFIX),
+        };
+        InlineButton {
+            text: text.to_string(),
+            callback_data: format!("{}{}", prefix, slug),
+        }
+    }
 }
 
 impl Default for TelegramConfig {
@@ -68,9 +205,12 @@ impl Default for TelegramConfig {
             bot_token: String::new(),
             chat_ids: Vec::new(),
             chat_names: HashMap::new(),
+            group_chats: HashMap::new(),
             notify_on_success: true,
             notify_on_failure: true,
             agent_enabled: false,
+            log_flush_secs: 5,
+            finish_actions: Vec::new(),
         }
     }
 }
@@ -79,10 +219,39 @@ impl TelegramConfig {
     pub fn is_configured(&self) -> bool {
         !self.bot_token.is_empty() && !self.chat_ids.is_empty()
     }
+
+    /// Chats a job's notifications go to: its own chat, else its group's
+    /// chat, else every global chat.
+    pub fn chats_for_job(&self, job_chat_id: Option<i64>, group: &str) -> Vec<i64> {
+        match job_chat_id.or_else(|| self.group_chats.get(group).copied()) {
+            Some(cid) => vec![cid],
+            None => self.chat_ids.clone(),
+        }
+    }
+
+    /// Single chat for streams and replies, resolved in the same order as
+    /// [`chats_for_job`](Self::chats_for_job).
+    pub fn chat_for_job(&self, job_chat_id: Option<i64>, group: &str) -> Option<i64> {
+        self.chats_for_job(job_chat_id, group).first().copied()
+    }
+
+    /// `finish_actions`, or none while agent mode is off.
+    pub fn active_finish_actions(&self) -> &[JobAction] {
+        if self.agent_enabled {
+            &self.finish_actions
+        } else {
+            &[]
+        }
+    }
 }
 
 /// Send a message to a specific chat. Splits long messages into chunks.
-pub async fn send_message(bot_token: &str, chat_id: i64, text: &str) -> Result<(), String> {
+pub async fn send_message(
+    bot_token: &str,
+    chat_id: i64,
+    text: &str,
+    parse_mode: ParseMode,
+) -> Result<(), String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
@@ -92,21 +261,14 @@ pub async fn send_message(bot_token: &str, chat_id: i64, text: &str) -> Result<(
     let chunks = split_message(text);
 
     for chunk in chunks {
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
-
-        let resp = client
-            .post(&url)
-            .json(&serde_json::json!({
-                "chat_id": chat_id,
-                "text": chunk,
-                "parse_mode": "HTML",
-            }))
-            .send()
-            .await
-            .map_err(|e| telegram_request_error("sendMessage", &e))?;
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": chunk,
+        });
+        parse_mode.apply(&mut body);
 
-        if !resp.status().is_success() {
-            let body = resp.text().await.unwrap_or_default();
+        let (status, body) = post_json(&client, bot_token, "sendMessage", &body).await?;
+        if !status.is_success() {
             return Err(format!("Telegram API error: {}", body));
         }
     }
@@ -121,7 +283,7 @@ pub async fn notify(config: &TelegramConfig, text: &str) {
     }
 
     for &chat_id in &config.chat_ids {
-        if let Err(e) = send_message(&config.bot_token, chat_id, text).await {
+        if let Err(e) = send_message(&config.bot_token, chat_id, text, ParseMode::Html).await {
             log::error!("Failed to send Telegram notification to {}: {}", chat_id, e);
         }
     }
@@ -145,6 +307,13 @@ pub(crate) fn format_job_status_message(
     )
 }
 
+/// Append ` @ <label>` (the run's git branch/commit) to a status message.
+pub(crate) fn append_git_label(text: &mut String, label: Option<&str>) {
+    if let Some(label) = label {
+        text.push_str(&format!(" @ <code>{}</code>", html_escape(label)));
+    }
+}
+
 /// Send a job completion notification
 pub async fn notify_job_result(
     config: &TelegramConfig,
@@ -176,6 +345,7 @@ pub async fn test_connection(bot_token: &str, chat_id: i64) -> Result<(), String
         bot_token,
         chat_id,
         "ClawTab test message - connection successful.",
+        ParseMode::Html,
     )
     .await
 }
@@ -217,34 +387,89 @@ pub async fn can_read_group_messages(bot_token: &str) -> bool {
     }
 }
 
+/// A button on an inline keyboard.
+pub struct InlineButton {
+    pub text: String,
+    pub callback_data: String,
+}
+
+/// Lay `buttons` out `columns` to a row, so short options like "1. Yes" and
+/// "2. No" sit side by side. Falls back to one per row when any label is
+/// longer than `MAX_SHARED_ROW_LABEL` chars.
+pub fn inline_keyboard(buttons: &[InlineButton], columns: usize) -> serde_json::Value {
+    let columns = if buttons
+        .iter()
+        .any(|b| b.text.chars().count() > MAX_SHARED_ROW_LABEL)
+    {
+        1
+    } else {
+        columns.max(1)
+    };
+    let rows: Vec<Vec<serde_json::Value>> = buttons
+        .chunks(columns)
+        .map(|row| {
+            row.iter()
+                .map(|b| serde_json::json!({ "text": b.text, "callback_data": b.callback_data }))
+                .collect()
+        })
+        .collect();
+    serde_json::json!({ "inline_keyboard": rows })
+}
+
+/// Inline keyboard with a single Stop button for `run_id`.
+pub fn stop_keyboard(run_id: &str) -> serde_json::Value {
+    inline_keyboard(
+        &[InlineButton {
+            text: "\u{23f9} Stop".to_string(),
+            callback_data: format!("{}{}", STOP_CALLBACK_PREFIX, run_id),
+        }],
+        1,
+    )
+}
+
+/// Inline keyboard with `actions` for the job `slug`, or None when there are
+/// no actions. A slug too long for Telegram's `callback_data` gets no buttons.
+pub fn job_action_keyboard(actions: &[JobAction], slug: &str) -> Option<serde_json::Value> {
+    let buttons: Vec<InlineButton> = actions.iter().map(|a| a.button(slug)).collect();
+    if buttons.is_empty() {
+        return None;
+    }
+    if buttons
+        .iter()
+        .any(|b| b.callback_data.len() > MAX_CALLBACK_DATA)
+    {
+        log::warn!(
+            "Job slug '{}' is too long for Telegram action buttons",
+            slug
+        );
+        return None;
+    }
+    Some(inline_keyboard(&buttons, buttons.len()))
+}
+
 /// Send a message and return its message_id for later editing/deletion.
 pub async fn send_message_returning_id(
     bot_token: &str,
     chat_id: i64,
     text: &str,
+    parse_mode: ParseMode,
+    reply_markup: Option<&serde_json::Value>,
 ) -> Result<i64, String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
 
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
-
-    let resp = client
-        .post(&url)
-        .json(&serde_json::json!({
-            "chat_id": chat_id,
-            "text": text,
-            "parse_mode": "HTML",
-        }))
-        .send()
-        .await
-        .map_err(|e| telegram_request_error("sendMessage", &e))?;
+    let mut body = serde_json::json!({
+        "chat_id": chat_id,
+        "text": text,
+    });
+    parse_mode.apply(&mut body);
+    if let Some(markup) = reply_markup {
+        body["reply_markup"] = markup.clone();
+    }
 
-    let body = resp
-        .text()
-        .await
-        .map_err(|e| telegram_request_error("read sendMessage response", &e))?;
+    let (_, body) = post_json(&client, bot_token, "sendMessage", &body).await?;
 
     let parsed: serde_json::Value =
         serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))?;
@@ -254,34 +479,73 @@ pub async fn send_message_returning_id(
         .ok_or_else(|| format!("No message_id in response: {}", body))
 }
 
-/// Edit an existing message's text.
-pub async fn edit_message_text(
+/// Upload `content` as a file attachment with an optional HTML caption.
+pub async fn send_document(
     bot_token: &str,
     chat_id: i64,
-    message_id: i64,
-    text: &str,
+    file_name: &str,
+    content: Vec<u8>,
+    caption: Option<&str>,
 ) -> Result<(), String> {
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(std::time::Duration::from_secs(60))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
 
-    let url = format!("https://api.telegram.org/bot{}/editMessageText", bot_token);
+    let url = format!("https://api.telegram.org/bot{}/sendDocument", bot_token);
+
+    let part = reqwest::multipart::Part::bytes(content).file_name(file_name.to_string());
+    let mut form = reqwest::multipart::Form::new()
+        .text("chat_id", chat_id.to_string())
+        .part("document", part);
+    if let Some(caption) = caption {
+        form = form
+            .text("caption", caption.to_string())
+            .text("parse_mode", "HTML");
+    }
 
     let resp = client
         .post(&url)
-        .json(&serde_json::json!({
-            "chat_id": chat_id,
-            "message_id": message_id,
-            "text": text,
-            "parse_mode": "HTML",
-        }))
+        .multipart(form)
         .send()
         .await
-        .map_err(|e| telegram_request_error("editMessageText", &e))?;
+        .map_err(|e| telegram_request_error("sendDocument", &e))?;
 
     if !resp.status().is_success() {
         let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Telegram sendDocument error: {}", body));
+    }
+
+    Ok(())
+}
+
+/// Edit an existing message's text. The message keeps an inline keyboard
+/// only if `reply_markup` is given again.
+pub async fn edit_message_text(
+    bot_token: &str,
+    chat_id: i64,
+    message_id: i64,
+    text: &str,
+    parse_mode: ParseMode,
+    reply_markup: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let mut body = serde_json::json!({
+        "chat_id": chat_id,
+        "message_id": message_id,
+        "text": text,
+    });
+    parse_mode.apply(&mut body);
+    if let Some(markup) = reply_markup {
+        body["reply_markup"] = markup.clone();
+    }
+
+    let (status, body) = post_json(&client, bot_token, "editMessageText", &body).await?;
+    if !status.is_success() {
         return Err(format!("Telegram editMessageText error: {}", body));
     }
 
@@ -395,7 +659,7 @@ pub fn strip_ansi(s: &str) -> String {
     out
 }
 
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -429,7 +693,62 @@ fn split_message(text: &str) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::format_job_status_message;
+    use super::{
+        append_git_label, format_job_status_message, inline_keyboard, job_action_keyboard,
+        retry_after_secs, InlineButton, JobAction, ParseMode, TelegramConfig,
+    };
+
+    fn buttons(labels: &[&str]) -> Vec<InlineButton> {
+        labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| InlineButton {
+                text: label.to_string(),
+                callback_data: (i + 1).to_string(),
+            })
+            .collect()
+    }
+
+    fn row_lengths(keyboard: &serde_json::Value) -> Vec<usize> {
+        keyboard["inline_keyboard"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row.as_array().unwrap().len())
+            .collect()
+    }
+
+    #[test]
+    fn short_labels_share_rows_and_long_ones_do_not() {
+        let short = buttons(&["1. Yes", "2. No", "3. Always", "4. Never", "5. Skip"]);
+        assert_eq!(row_lengths(&inline_keyboard(&short, 2)), vec![2, 2, 1]);
+        assert_eq!(row_lengths(&inline_keyboard(&short, 0)), vec![1; 5]);
+        let long = buttons(&["1. Yes", "2. Yes, and don't ask again"]);
+        assert_eq!(row_lengths(&inline_keyboard(&long, 3)), vec![1, 1]);
+    }
+
+    #[test]
+    fn retry_after_is_read_from_the_error_body_and_capped() {
+        let body = r#"{"ok":false,"error_code":429,"parameters":{"retry_after":7}}"#;
+        assert_eq!(retry_after_secs(body), 7);
+        assert_eq!(
+            retry_after_secs(r#"{"parameters":{"retry_after":120}}"#),
+            30
+        );
+        assert_eq!(retry_after_secs("Too Many Requests"), 1);
+    }
+
+    #[test]
+    fn parse_mode_none_omits_the_field() {
+        let mut body = serde_json::json!({ "text": "a < b" });
+        ParseMode::None.apply(&mut body);
+        assert!(body.get("parse_mode").is_none());
+        ParseMode::MarkdownV2.apply(&mut body);
+        assert_eq!(body["parse_mode"], "MarkdownV2");
+        assert_eq!("none".parse::<ParseMode>(), Ok(ParseMode::None));
+        assert_eq!("HTML".parse::<ParseMode>(), Ok(ParseMode::Html));
+        assert!("bbcode".parse::<ParseMode>().is_err());
+    }
 
     #[test]
     fn job_status_message_uses_group_prefix() {
@@ -446,4 +765,52 @@ mod tests {
             "<b>api &amp; web</b>: Job <code>deploy &lt;prod&gt;</code> failed (exit 1)"
         );
     }
+
+    #[test]
+    fn git_label_is_appended_only_when_present() {
+        let mut text = format_job_status_message("ops", "deploy", "finished", None);
+        append_git_label(&mut text, None);
+        assert_eq!(text, "<b>ops</b>: Job <code>deploy</code> finished");
+        append_git_label(&mut text, Some("main a1b2c3d"));
+        assert_eq!(
+            text,
+            "<b>ops</b>: Job <code>deploy</code> finished @ <code>main a1b2c3d</code>"
+        );
+    }
+
+    #[test]
+    fn chats_resolve_job_then_group_then_global() {
+        let config = TelegramConfig {
+            chat_ids: vec![1, 2],
+            group_chats: [("prod".to_string(), 10)].into_iter().collect(),
+            ..TelegramConfig::default()
+        };
+        assert_eq!(config.chats_for_job(Some(5), "prod"), vec![5]);
+        assert_eq!(config.chats_for_job(None, "prod"), vec![10]);
+        assert_eq!(config.chats_for_job(None, "default"), vec![1, 2]);
+        assert_eq!(config.chat_for_job(None, "default"), Some(1));
+    }
+
+    #[test]
+    fn finish_actions_need_agent_mode_and_a_short_slug() {
+        let mut config = TelegramConfig {
+            finish_actions: vec![JobAction::Rerun, JobAction::Stop],
+            ..TelegramConfig::default()
+        };
+        assert!(config.active_finish_actions().is_empty());
+        config.agent_enabled = true;
+
+        let keyboard = job_action_keyboard(config.active_finish_actions(), "ops/backup").unwrap();
+        assert_eq!(row_lengths(&keyboard), vec![2]);
+        assert_eq!(
+            keyboard["inline_keyboard"][0][0]["callback_data"],
+            "rerun:ops/backup"
+        );
+        assert_eq!(
+            keyboard["inline_keyboard"][0][1]["callback_data"],
+            "stop:ops/backup"
+        );
+        assert!(job_action_keyboard(&[], "ops/backup").is_none());
+        assert!(job_action_keyboard(&[JobAction::Rerun], &"x".repeat(60)).is_none());
+    }
 }
@@ -5,8 +5,8 @@ use crate::config::jobs::{Job, JobStatus};
 use crate::telegram::{
     self,
     commands::{self, AgentCommand},
-    types::Update,
-    TelegramConfig,
+    types::{CallbackQuery, Update},
+    ParseMode, TelegramConfig,
 };
 
 use super::{agent, lock_or_log, AgentState};
@@ -26,8 +26,13 @@ pub(super) async fn handle_update(update: &Update, config: &TelegramConfig, stat
             );
             if let Some(reply) = handle_message(text, config, state, message.chat.id).await {
                 log::info!("Sending reply: {}", &reply[..reply.len().min(100)]);
-                if let Err(e) =
-                    telegram::send_message(&config.bot_token, message.chat.id, &reply).await
+                if let Err(e) = telegram::send_message(
+                    &config.bot_token,
+                    message.chat.id,
+                    &reply,
+                    ParseMode::Html,
+                )
+                .await
                 {
                     log::error!("Failed to send reply: {}", e);
                 }
@@ -36,19 +41,33 @@ pub(super) async fn handle_update(update: &Update, config: &TelegramConfig, stat
     }
 
     if let Some(ref cq) = update.callback_query {
-        let _ = telegram::answer_callback_query(&config.bot_token, &cq.id).await;
-        let Some(ref data) = cq.data else { return };
-        let Some(chat_id) = cq.message.as_ref().map(|m| m.chat.id) else {
-            return;
-        };
-        if !config.chat_ids.contains(&chat_id) {
-            return;
-        }
-        log::info!("Callback query from chat {}: {}", chat_id, data);
-        if let Some(reply) = handle_message(data, config, state, chat_id).await {
-            if let Err(e) = telegram::send_message(&config.bot_token, chat_id, &reply).await {
-                log::error!("Failed to send callback reply: {}", e);
-            }
+        handle_callback_query(cq, config, state).await;
+    }
+}
+
+/// Inline keyboard button press: Stop and Rerun buttons, or a command.
+async fn handle_callback_query(cq: &CallbackQuery, config: &TelegramConfig, state: &AgentState) {
+    let _ = telegram::answer_callback_query(&config.bot_token, &cq.id).await;
+    let Some(ref data) = cq.data else { return };
+    let Some(chat_id) = cq.message.as_ref().map(|m| m.chat.id) else {
+        return;
+    };
+    if !config.chat_ids.contains(&chat_id) {
+        return;
+    }
+    log::info!("Callback query from chat {}: {}", chat_id, data);
+    let reply = if let Some(target) = data.strip_prefix(telegram::STOP_CALLBACK_PREFIX) {
+        Some(stop_run(state, target))
+    } else if let Some(slug) = data.strip_prefix(telegram::RERUN_CALLBACK_PREFIX) {
+        Some(rerun_job(state, slug))
+    } else {
+        handle_message(data, config, state, chat_id).await
+    };
+    if let Some(reply) = reply {
+        if let Err(e) =
+            telegram::send_message(&config.bot_token, chat_id, &reply, ParseMode::Html).await
+        {
+            log::error!("Failed to send callback reply: {}", e);
         }
     }
 }
@@ -96,6 +115,7 @@ pub(super) async fn handle_message(
                 },
                 None => "Internal error".to_string(),
             },
+            AgentCommand::Tail(name, lines) => handle_tail(state, &name, lines),
             AgentCommand::Agent(prompt) => {
                 agent::handle_agent_command(&prompt, config, state, chat_id).await
             }
@@ -107,6 +127,99 @@ pub(super) async fn handle_message(
     agent::relay_to_agent(text, state, chat_id).await
 }
 
+/// Stop button. `target` is a run id (working message) or a job slug
+/// (completion message). Kills the run's pane, or its process for binary and
+/// shell jobs.
+fn stop_run(state: &AgentState, target: &str) -> String {
+    let Some(mut status) = lock_or_log(&state.job_status, "job_status") else {
+        return "Internal error".to_string();
+    };
+    let running = status.iter().find_map(|(slug, s)| match s {
+        JobStatus::Running {
+            run_id, pane_id, ..
+        } if run_id == target || slug == target => {
+            Some((slug.clone(), run_id.clone(), pane_id.clone()))
+        }
+        _ => None,
+    });
+    let Some((slug, run_id, pane_id)) = running else {
+        return "That run is no longer running".to_string();
+    };
+    let stopped = match &pane_id {
+        Some(pane_id) => crate::tmux::kill_pane(pane_id),
+        None => match crate::scheduler::executor::binary_runtime::stop(&slug) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("no tracked process".to_string()),
+            Err(e) => Err(e),
+        },
+    };
+    if let Err(e) = stopped {
+        log::warn!("Failed to stop run {} of {}: {}", run_id, slug, e);
+        return format!("Failed to stop <code>{}</code>", slug);
+    }
+    status.insert(slug.clone(), JobStatus::Idle);
+    drop(status);
+    state.active_agents.lock().retain(|_, a| a.run_id != run_id);
+    crate::relay::push_status_update(&state.ctx.relay, &slug, &JobStatus::Idle);
+    log::info!("Stopped run {} of {} from Telegram", run_id, slug);
+    format!("Stopped job <code>{}</code>", slug)
+}
+
+/// `/tail`: the last lines of a running job's pane.
+fn handle_tail(state: &AgentState, name: &str, lines: usize) -> String {
+    let job = match lock_or_log(&state.jobs_config, "jobs_config") {
+        Some(config) => match crate::config::jobs::find_job(&config.jobs, name) {
+            Ok(job) => job.clone(),
+            Err(e) => return e,
+        },
+        None => return "Internal error".to_string(),
+    };
+    let slug = job.slug.clone();
+    let status = lock_or_log(&state.job_status, "job_status").and_then(|s| s.get(&slug).cloned());
+    let (session, pane_id) = match status {
+        Some(JobStatus::Running {
+            pane_id: Some(pane_id),
+            tmux_session: Some(session),
+            ..
+        }) => (session, pane_id),
+        Some(JobStatus::Running { .. }) => {
+            return format!("Job <code>{}</code> is not running in a tmux pane", slug)
+        }
+        _ => return format!("Job <code>{}</code> is not running", slug),
+    };
+    match crate::tmux::capture_pane(&session, &pane_id, lines as u32) {
+        Ok(output) => {
+            let redact = crate::scheduler::executor::secret_values(
+                &job,
+                &state.ctx.secrets,
+                &state.ctx.settings,
+            );
+            commands::format_tail(&slug, &crate::secrets::scrub(&output, &redact), lines)
+        }
+        Err(e) => {
+            log::warn!("Failed to capture pane {} for {}: {}", pane_id, slug, e);
+            format!("Failed to read the pane of <code>{}</code>", slug)
+        }
+    }
+}
+
+/// Re-run button on a completion message: start the job again with its
+/// param defaults.
+fn rerun_job(state: &AgentState, slug: &str) -> String {
+    let job = lock_or_log(&state.jobs_config, "jobs_config")
+        .and_then(|c| c.jobs.iter().find(|j| j.slug == slug).cloned());
+    let Some(job) = job else {
+        return format!("Job not found: {}", slug);
+    };
+    let params = std::collections::HashMap::new();
+    if let Some(msg) = missing_params_message(&job, &params) {
+        return msg;
+    }
+    let name = job.name.clone();
+    spawn_job(job, state.ctx.clone(), params);
+    format!("Started job <code>{}</code>", name)
+}
+
 fn handle_run(
     state: &AgentState,
     name: String,
@@ -14,6 +14,53 @@ fn run(args: &[&str], callsite: &'static str) -> std::io::Result<Output> {
     debug_spawn::run_logged("tmux", args, callsite)
 }
 
+/// Extra attempts for commands that race tmux's own bookkeeping.
+const TRANSIENT_RETRIES: u32 = 2;
+const TRANSIENT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Error fragments tmux prints while a just-created server, window or pane is
+/// not yet visible to other clients.
+const TRANSIENT_ERRORS: &[&str] = &[
+    "no server running",
+    "error connecting to",
+    "server exited unexpectedly",
+    "lost server",
+    "can't find pane",
+    "can't find window",
+    "can't find session",
+];
+
+fn is_transient_error(error: &str) -> bool {
+    TRANSIENT_ERRORS.iter().any(|e| error.contains(e))
+}
+
+/// Run `op`, a tmux operation issued right after a session, window or pane
+/// was created. A failure whose error looks like a timing race is retried
+/// after a short sleep; any other failure is returned as-is on the first
+/// attempt.
+pub async fn retry_transient<T>(
+    callsite: &'static str,
+    mut op: impl FnMut() -> Result<T, String>,
+) -> Result<T, String> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if attempt < TRANSIENT_RETRIES && is_transient_error(&e) => {
+                attempt += 1;
+                log::debug!(
+                    "{}: transient tmux error, retrying ({}/{}): {}",
+                    callsite,
+                    attempt,
+                    TRANSIENT_RETRIES,
+                    e
+                );
+                tokio::time::sleep(TRANSIENT_RETRY_DELAY).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 pub fn is_available() -> bool {
     run(&["-V"], "tmux::is_available")
         .map(|o| o.status.success())
@@ -416,22 +463,54 @@ pub fn list_panes_by_slug(slug: &str) -> Result<Vec<(String, u64)>, String> {
     Ok(panes)
 }
 
-/// Send keys to a specific pane by its ID (e.g. "%42").
+/// Send keys to a specific pane by its ID (e.g. "%42"), then press Enter.
 /// Pane IDs starting with '%' are global tmux targets and used directly.
 pub fn send_keys_to_pane(_session: &str, pane_id: &str, keys: &str) -> Result<(), String> {
-    let output = run(
-        &["send-keys", "-t", pane_id, keys, "Enter"],
-        "tmux::send_keys_to_pane",
-    )
-    .map_err(|e| format!("Failed to send keys to pane: {}", e))?;
+    send_keys_raw(pane_id, keys, true)
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("tmux error: {}", stderr.trim()));
+/// Type `keys` into a pane literally (`send-keys -l`, so key names such as
+/// `Enter` or `C-c` are not interpreted), pressing Enter afterwards only when
+/// `send_enter` is set. Empty `keys` with `send_enter` just presses Enter.
+pub fn send_keys_raw(pane_id: &str, keys: &str, send_enter: bool) -> Result<(), String> {
+    if !keys.is_empty() {
+        let output = run(
+            &["send-keys", "-t", pane_id, "-l", keys],
+            "tmux::send_keys_raw::text",
+        )
+        .map_err(|e| format!("Failed to send keys to pane: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("tmux error: {}", stderr.trim()));
+        }
+    }
+    if send_enter {
+        let output = run(
+            &["send-keys", "-t", pane_id, "Enter"],
+            "tmux::send_keys_raw::enter",
+        )
+        .map_err(|e| format!("Failed to send Enter to pane: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("tmux error: {}", stderr.trim()));
+        }
     }
     Ok(())
 }
 
+/// Pause between typing a prompt answer and submitting it, so the TUI has
+/// registered the selection before Enter arrives.
+const ANSWER_ENTER_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Answer a TUI prompt: type `answer`, wait briefly, then press Enter as a
+/// separate keystroke. More reliable than sending both at once, which some
+/// prompts read before their selection state has updated.
+pub async fn send_answer_to_pane(pane_id: &str, answer: &str) -> Result<(), String> {
+    send_keys_raw(pane_id, answer, false)?;
+    tokio::time::sleep(ANSWER_ENTER_DELAY).await;
+    send_keys_raw(pane_id, "", true)
+}
+
 /// Enter tmux copy-mode for a specific pane.
 pub fn enter_copy_mode(pane_id: &str) -> Result<(), String> {
     let output = run(&["copy-mode", "-t", pane_id], "tmux::enter_copy_mode")
@@ -474,30 +553,7 @@ pub fn send_copy_mode_command_to_pane(pane_id: &str, command: &str) -> Result<()
 /// Send text to a TUI pane (like Claude Code) that uses vim-style input.
 /// Types the text literally, then presses Enter to submit.
 pub fn send_keys_to_tui_pane(pane_id: &str, text: &str) -> Result<(), String> {
-    // Send text literally (prevents tmux from interpreting special keys)
-    let output = run(
-        &["send-keys", "-t", pane_id, "-l", text],
-        "tmux::send_keys_to_tui_pane::text",
-    )
-    .map_err(|e| format!("Failed to send text to pane: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("tmux error: {}", stderr.trim()));
-    }
-
-    // Press Enter to submit
-    let output = run(
-        &["send-keys", "-t", pane_id, "Enter"],
-        "tmux::send_keys_to_tui_pane::enter",
-    )
-    .map_err(|e| format!("Failed to send Enter to pane: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("tmux error: {}", stderr.trim()));
-    }
-    Ok(())
+    send_keys_raw(pane_id, text, true)
 }
 
 /// Send a keystroke to select a "Type something" option, then type freetext and press Enter.
@@ -518,29 +574,7 @@ pub fn send_keys_to_tui_pane_freetext(
         return Err(format!("tmux error: {}", stderr.trim()));
     }
 
-    // Type the freetext literally
-    let output = run(
-        &["send-keys", "-t", pane_id, "-l", freetext],
-        "tmux::send_keys_to_tui_pane_freetext::text",
-    )
-    .map_err(|e| format!("Failed to send freetext to pane: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("tmux error: {}", stderr.trim()));
-    }
-
-    // Press Enter to submit
-    let output = run(
-        &["send-keys", "-t", pane_id, "Enter"],
-        "tmux::send_keys_to_tui_pane_freetext::enter",
-    )
-    .map_err(|e| format!("Failed to send Enter to pane: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("tmux error: {}", stderr.trim()));
-    }
-
-    Ok(())
+    send_keys_raw(pane_id, freetext, true)
 }
 
 /// Capture the full visible area of a pane with ANSI escape sequences preserved.
@@ -636,6 +670,64 @@ pub fn capture_pane(_session: &str, pane_id: &str, lines: u32) -> Result<String,
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Capture a pane from absolute line `last_line` (0 is the oldest scrollback
+/// line) down to the bottom of the visible screen. Returns the text and the
+/// pane's current `history_size`, which is the absolute index of the top
+/// visible row; passing that back on the next call captures the screen as it
+/// was plus everything that scrolled since, so no output is skipped between
+/// polls. A `last_line` past the scrollback captures just the visible screen.
+///
+/// Once the scrollback reaches tmux's `history-limit`, each new line drops
+/// the oldest one, so absolute indices shift by an unknown amount and
+/// `history_size` stops growing. Captures then start at the oldest retained
+/// line, and the caller's diff finds where it left off.
+pub fn capture_pane_since(pane_id: &str, last_line: u32) -> Result<(String, u32), String> {
+    let size_output = run(
+        &[
+            "display",
+            "-t",
+            pane_id,
+            "-p",
+            "#{history_size} #{history_limit}",
+        ],
+        "tmux::capture_pane_since::history_size",
+    )
+    .map_err(|e| format!("Failed to get pane history size: {}", e))?;
+    if !size_output.status.success() {
+        let stderr = String::from_utf8_lossy(&size_output.stderr);
+        return Err(format!("tmux error: {}", stderr.trim()));
+    }
+    let (history_size, history_limit) =
+        parse_history_sizes(&String::from_utf8_lossy(&size_output.stdout))
+            .ok_or("Failed to parse pane history size")?;
+
+    let wrapped = history_size >= history_limit && last_line <= history_size;
+    let start = if wrapped {
+        "-".to_string()
+    } else {
+        (i64::from(last_line.min(history_size)) - i64::from(history_size)).to_string()
+    };
+    let output = run(
+        &["capture-pane", "-t", pane_id, "-p", "-e", "-S", &start],
+        "tmux::capture_pane_since",
+    )
+    .map_err(|e| format!("Failed to capture pane: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("tmux error: {}", stderr.trim()));
+    }
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        history_size,
+    ))
+}
+
+/// `history_size` and `history_limit` from `display -p`.
+fn parse_history_sizes(output: &str) -> Option<(u32, u32)> {
+    let mut parts = output.split_whitespace().map(str::parse::<u32>);
+    Some((parts.next()?.ok()?, parts.next()?.ok()?))
+}
+
 /// Capture only recent scrollback, excluding the currently visible screen.
 /// Visible-screen changes include echoed input and TUI redraws, so this is the
 /// stable activity signal used by the tmux status indicator.
@@ -807,9 +899,7 @@ pub fn is_pane_busy(_session: &str, pane_id: &str) -> bool {
             let stdout = String::from_utf8_lossy(&o.stdout);
             stdout.lines().any(|line| {
                 if let Some((id, cmd)) = line.split_once(':') {
-                    id == pane_id
-                        && !cmd.trim().is_empty()
-                        && !matches!(cmd.trim(), "bash" | "zsh" | "fish" | "sh" | "dash")
+                    id == pane_id && !is_idle_command(cmd)
                 } else {
                     false
                 }
@@ -819,6 +909,55 @@ pub fn is_pane_busy(_session: &str, pane_id: &str) -> bool {
     }
 }
 
+/// Whether a `pane_current_command` is an idle shell (or nothing at all).
+pub fn is_idle_command(cmd: &str) -> bool {
+    let cmd = cmd.trim();
+    cmd.is_empty() || matches!(cmd, "bash" | "zsh" | "fish" | "sh" | "dash")
+}
+
+/// A session's attach state and panes, for the idle-session reaper.
+pub struct SessionSnapshot {
+    /// A client is attached to the session or to one grouped with it.
+    pub attached: bool,
+    /// Unix time of the session's last activity.
+    pub last_activity: i64,
+    /// `(pane_id, pane_current_command)` for every pane in every window.
+    pub panes: Vec<(String, String)>,
+}
+
+/// `list-panes -s -t <session>` with attach state and activity time.
+pub fn session_snapshot(session: &str) -> Result<SessionSnapshot, String> {
+    let raw = run_capture(
+        &[
+            "list-panes",
+            "-s",
+            "-t",
+            session,
+            "-F",
+            "#{session_attached}|#{session_group_attached}|#{session_activity}|#{pane_id}|#{pane_current_command}",
+        ],
+        "tmux::session_snapshot",
+    )?;
+    let mut snapshot = SessionSnapshot {
+        attached: false,
+        last_activity: 0,
+        panes: Vec::new(),
+    };
+    for line in raw.lines() {
+        let parts: Vec<&str> = line.splitn(5, '|').collect();
+        let [attached, group_attached, activity, pane_id, command] = parts[..] else {
+            continue;
+        };
+        let count = |s: &str| s.parse::<u32>().unwrap_or(0);
+        snapshot.attached |= count(attached) > 0 || count(group_attached) > 0;
+        snapshot.last_activity = snapshot.last_activity.max(activity.parse().unwrap_or(0));
+        snapshot
+            .panes
+            .push((pane_id.to_string(), command.to_string()));
+    }
+    Ok(snapshot)
+}
+
 /// Capture the entire scrollback from a pane.
 pub fn capture_pane_full(pane_id: &str) -> Result<String, String> {
     let output = run(
@@ -921,6 +1060,28 @@ pub fn focus_window(session: &str, window: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Exit status of a pane whose process exited while tmux kept the pane open
+/// (`remain-on-exit`). `None` while the process is still running.
+pub fn pane_dead_status(pane_id: &str) -> Option<i32> {
+    let output = run(
+        &[
+            "display-message",
+            "-t",
+            pane_id,
+            "-p",
+            "#{pane_dead} #{pane_dead_status}",
+        ],
+        "tmux::pane_dead_status",
+    )
+    .ok()
+    .filter(|o| o.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.trim().split_once(' ')? {
+        ("1", status) => status.parse().ok(),
+        _ => None,
+    }
+}
+
 /// Get the working directory of a pane.
 pub fn get_pane_path(pane_id: &str) -> Result<String, String> {
     let output = run(
@@ -1378,3 +1539,18 @@ pub fn is_active_attached_pane(pane_id: &str) -> Result<bool, String> {
     }
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_transient_error;
+
+    #[test]
+    fn only_race_errors_are_transient() {
+        assert!(is_transient_error("can't find pane: %42"));
+        assert!(is_transient_error(
+            "no server running on /private/tmp/tmux-501/default"
+        ));
+        assert!(!is_transient_error("unknown command: send-kes"));
+        assert!(!is_transient_error("duplicate session: main"));
+    }
+}
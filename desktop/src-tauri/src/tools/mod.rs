@@ -1,6 +1,9 @@
-use serde::Serialize;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
+use std::sync::OnceLock;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ToolInfo {
@@ -14,17 +17,18 @@ pub struct ToolInfo {
     pub brew_formula: Option<String>,
 }
 
-struct ToolSpec {
-    name: &'static str,
-    binary: &'static str,
-    version_flag: &'static str,
-    category: &'static str,
+#[derive(Clone, Copy)]
+struct ToolSpec<'a> {
+    name: &'a str,
+    binary: &'a str,
+    version_flag: &'a str,
+    category: &'a str,
     required: bool,
-    group: Option<&'static str>,
-    brew_formula: Option<&'static str>,
+    group: Option<&'a str>,
+    brew_formula: Option<&'a str>,
 }
 
-const TOOLS: &[ToolSpec] = &[
+const TOOLS: &[ToolSpec<'static>] = &[
     // AI Agent
     ToolSpec {
         name: "claude",
@@ -212,6 +216,101 @@ const TOOLS: &[ToolSpec] = &[
     },
 ];
 
+/// A tool declared in `~/.config/clawtab/tools.toml`, with the `ToolSpec`
+/// fields. Only `name` is required.
+#[derive(Debug, Deserialize)]
+struct UserToolSpec {
+    name: String,
+    /// Defaults to `name`.
+    binary: Option<String>,
+    #[serde(default = "default_version_flag")]
+    version_flag: String,
+    #[serde(default = "default_category")]
+    category: String,
+    #[serde(default)]
+    required: bool,
+    group: Option<String>,
+    brew_formula: Option<String>,
+}
+
+impl UserToolSpec {
+    fn spec(&self) -> ToolSpec<'_> {
+        ToolSpec {
+            name: &self.name,
+            binary: self.binary.as_deref().unwrap_or(&self.name),
+            version_flag: &self.version_flag,
+            category: &self.category,
+            required: self.required,
+            group: self.group.as_deref(),
+            brew_formula: self.brew_formula.as_deref(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ToolsFile {
+    #[serde(default)]
+    tools: Vec<UserToolSpec>,
+}
+
+fn default_version_flag() -> String {
+    "--version".to_string()
+}
+
+fn default_category() -> String {
+    "Optional".to_string()
+}
+
+fn parse_tools_file(contents: &str) -> Result<Vec<UserToolSpec>, String> {
+    toml::from_str::<ToolsFile>(contents)
+        .map(|file| file.tools)
+        .map_err(|e| e.to_string())
+}
+
+/// Tools from `tools.toml`, read on first use. A missing file means none; an
+/// unreadable one is logged and ignored.
+fn user_tools() -> &'static [UserToolSpec] {
+    static USER_TOOLS: OnceLock<Vec<UserToolSpec>> = OnceLock::new();
+    USER_TOOLS.get_or_init(|| {
+        let Some(path) = crate::config::config_dir().map(|d| d.join("tools.toml")) else {
+            return Vec::new();
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                log::warn!("Failed to read {}: {}", path.display(), e);
+                return Vec::new();
+            }
+        };
+        parse_tools_file(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring {}: {}", path.display(), e);
+            Vec::new()
+        })
+    })
+}
+
+/// The built-in `TOOLS` followed by `extra`. An extra tool named like a
+/// built-in one replaces it in place.
+fn merge_specs(extra: &[UserToolSpec]) -> Vec<ToolSpec<'_>> {
+    let mut specs: Vec<ToolSpec> = TOOLS
+        .iter()
+        .map(|t| {
+            extra
+                .iter()
+                .find(|u| u.name == t.name)
+                .map_or(*t, UserToolSpec::spec)
+        })
+        .collect();
+    specs.extend(
+        extra
+            .iter()
+            .filter(|u| !TOOLS.iter().any(|t| t.name == u.name))
+            .map(UserToolSpec::spec),
+    );
+    specs
+}
+
 pub fn which(binary: &str) -> Option<String> {
     let output = Command::new("which").arg(binary).output().ok()?;
     if output.status.success() {
@@ -267,7 +366,7 @@ fn resolve_binary(spec: &ToolSpec, custom_paths: &HashMap<String, String>) -> Op
 
 fn get_version_from(binary_path: &str, spec: &ToolSpec) -> Option<String> {
     let output = Command::new(binary_path)
-        .arg(spec.version_flag)
+        .args(spec.version_flag.split_whitespace())
         .output()
         .ok()?;
 
@@ -375,6 +474,104 @@ fn detect_tool(spec: &ToolSpec, custom_paths: &HashMap<String, String>) -> ToolI
     }
 }
 
+/// Detect the built-in tools and those declared in `tools.toml`.
 pub fn detect_tools(custom_paths: &HashMap<String, String>) -> Vec<ToolInfo> {
-    TOOLS.iter().map(|s| detect_tool(s, custom_paths)).collect()
+    merge_specs(user_tools())
+        .iter()
+        .map(|s| detect_tool(s, custom_paths))
+        .collect()
+}
+
+/// Last `detect_tools` result with what it was computed from.
+struct DetectCache {
+    custom_paths: HashMap<String, String>,
+    /// Modification time of each detected tool's binary.
+    mtimes: Vec<(String, Option<SystemTime>)>,
+    tools: Vec<ToolInfo>,
+}
+
+static DETECT_CACHE: Mutex<Option<DetectCache>> = Mutex::new(None);
+
+fn binary_mtimes(tools: &[ToolInfo]) -> Vec<(String, Option<SystemTime>)> {
+    tools
+        .iter()
+        .filter_map(|t| t.path.as_ref())
+        .map(|p| {
+            let mtime = std::fs::metadata(p).and_then(|m| m.modified()).ok();
+            (p.clone(), mtime)
+        })
+        .collect()
+}
+
+/// `detect_tools`, reusing the previous result while the custom paths are
+/// the same and every detected binary is unchanged on disk. Tools that were
+/// missing are only picked up again after `invalidate_detect_cache` or a
+/// `force_refresh`.
+pub fn detect_tools_cached(
+    custom_paths: &HashMap<String, String>,
+    force_refresh: bool,
+) -> Vec<ToolInfo> {
+    if !force_refresh {
+        if let Some(cache) = DETECT_CACHE.lock().as_ref() {
+            if &cache.custom_paths == custom_paths && binary_mtimes(&cache.tools) == cache.mtimes {
+                return cache.tools.clone();
+            }
+        }
+    }
+    let tools = detect_tools(custom_paths);
+    *DETECT_CACHE.lock() = Some(DetectCache {
+        custom_paths: custom_paths.clone(),
+        mtimes: binary_mtimes(&tools),
+        tools: tools.clone(),
+    });
+    tools
+}
+
+/// Drop the cached detection result, after a tool path was set or a tool
+/// installed.
+pub fn invalidate_detect_cache() {
+    *DETECT_CACHE.lock() = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tools_file_adds_tools_and_overrides_builtins_by_name() {
+        let extra = parse_tools_file(
+            r#"
+[[tools]]
+name = "gh"
+brew_formula = "gh"
+
+[[tools]]
+name = "kubectl"
+version_flag = "version --client"
+category = "Cluster"
+
+[[tools]]
+name = "tmux"
+binary = "/opt/tmux/bin/tmux"
+version_flag = "-V"
+category = "Required"
+required = true
+"#,
+        )
+        .unwrap();
+        let specs = merge_specs(&extra);
+        assert_eq!(specs.len(), TOOLS.len() + 2);
+
+        let tmux = specs.iter().find(|s| s.name == "tmux").unwrap();
+        assert_eq!(tmux.binary, "/opt/tmux/bin/tmux");
+        let gh = specs.iter().find(|s| s.name == "gh").unwrap();
+        assert_eq!(
+            (gh.binary, gh.version_flag, gh.category, gh.required),
+            ("gh", "--version", "Optional", false)
+        );
+        assert_eq!(specs.last().unwrap().category, "Cluster");
+
+        assert!(parse_tools_file("").unwrap().is_empty());
+        assert!(parse_tools_file("[[tools]]\nbinary = \"x\"").is_err());
+    }
 }
@@ -16,6 +16,9 @@ use crate::claude_usage;
 pub struct UsageEntry {
     pub label: String,
     pub value: String,
+    /// Raw reset time (RFC3339) behind `value`, for a precise countdown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resets_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -303,6 +306,7 @@ fn parse_antigravity_usage_snapshot(text: &str) -> Result<ProviderUsageSnapshot,
         entries.push(UsageEntry {
             label: "Account".to_string(),
             value: email.clone(),
+            resets_at: None,
         });
     }
 
@@ -310,6 +314,7 @@ fn parse_antigravity_usage_snapshot(text: &str) -> Result<ProviderUsageSnapshot,
         entries.push(UsageEntry {
             label: "Session".to_string(),
             value: s.display_text(),
+            resets_at: None,
         });
     }
 
@@ -317,6 +322,7 @@ fn parse_antigravity_usage_snapshot(text: &str) -> Result<ProviderUsageSnapshot,
         entries.push(UsageEntry {
             label: "Week".to_string(),
             value: w.display_text(),
+            resets_at: None,
         });
     }
 
@@ -354,19 +360,33 @@ async fn fetch_claude_snapshot() -> ProviderUsageSnapshot {
                 ),
                 None => format!("Session {}, Week {}", session_pct, week_pct),
             };
+            let note = usage.cached_at.as_deref().map(|at| {
+                let at = at
+                    .parse::<DateTime<Utc>>()
+                    .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+                    .unwrap_or_else(|_| at.to_string());
+                format!("Usage API unavailable, showing the reading from {}.", at)
+            });
+            let status = if note.is_some() {
+                "partial"
+            } else {
+                "available"
+            };
             ProviderUsageSnapshot {
                 provider: "claude".to_string(),
-                status: "available".to_string(),
+                status: status.to_string(),
                 summary,
-                note: None,
+                note,
                 entries: vec![
                     UsageEntry {
                         label: "Session".to_string(),
                         value: usage_bucket_text(usage.five_hour.as_ref()),
+                        resets_at: usage.five_hour.as_ref().and_then(|b| b.resets_at.clone()),
                     },
                     UsageEntry {
                         label: "Week".to_string(),
                         value: usage_bucket_text(usage.seven_day.as_ref()),
+                        resets_at: usage.seven_day.as_ref().and_then(|b| b.resets_at.clone()),
                     },
                 ],
             }
@@ -380,10 +400,12 @@ async fn fetch_claude_snapshot() -> ProviderUsageSnapshot {
                 UsageEntry {
                     label: "Session".to_string(),
                     value: "n/a".to_string(),
+                    resets_at: None,
                 },
                 UsageEntry {
                     label: "Week".to_string(),
                     value: "n/a".to_string(),
+                    resets_at: None,
                 },
             ],
         },
@@ -630,14 +652,17 @@ fn build_codex_snapshot(
             UsageEntry {
                 label: "Plan".to_string(),
                 value: title_case_words(&plan_text),
+                resets_at: None,
             },
             UsageEntry {
                 label: "Session".to_string(),
                 value: primary_text,
+                resets_at: None,
             },
             UsageEntry {
                 label: "Week".to_string(),
                 value: secondary_text,
+                resets_at: None,
             },
         ],
     }
@@ -718,24 +743,28 @@ async fn read_zai_quota(token: &str) -> Result<ProviderUsageSnapshot, String> {
         entries.push(UsageEntry {
             label: "Plan".to_string(),
             value: plan,
+            resets_at: None,
         });
     }
     if let Some(limit) = session_token {
         entries.push(UsageEntry {
             label: zai_limit_label(limit, "Session").to_string(),
             value: zai_limit_text(limit),
+            resets_at: None,
         });
     }
     if let Some(limit) = token_limit {
         entries.push(UsageEntry {
             label: zai_limit_label(limit, "Tokens").to_string(),
             value: zai_limit_text(limit),
+            resets_at: None,
         });
     }
     if let Some(limit) = time_limit {
         entries.push(UsageEntry {
             label: "MCP".to_string(),
             value: zai_limit_text(limit),
+            resets_at: None,
         });
     }
     for detail in data.usage_details.unwrap_or_default().into_iter().take(3) {
@@ -743,6 +772,7 @@ async fn read_zai_quota(token: &str) -> Result<ProviderUsageSnapshot, String> {
         entries.push(UsageEntry {
             label,
             value: detail.summary(),
+            resets_at: None,
         });
     }
 
@@ -1062,6 +1092,7 @@ fn parse_codex_status_snapshot(text: &str) -> Result<ProviderUsageSnapshot, Stri
         entries.push(UsageEntry {
             label: "Plan".to_string(),
             value: title_case_words(&plan),
+            resets_at: None,
         });
     }
     entries.push(UsageEntry {
@@ -1070,6 +1101,7 @@ fn parse_codex_status_snapshot(text: &str) -> Result<ProviderUsageSnapshot, Stri
             .as_ref()
             .map(CodexCliLimit::display_text)
             .unwrap_or_else(|| "n/a".to_string()),
+        resets_at: None,
     });
     entries.push(UsageEntry {
         label: "Week".to_string(),
@@ -1077,6 +1109,7 @@ fn parse_codex_status_snapshot(text: &str) -> Result<ProviderUsageSnapshot, Stri
             .as_ref()
             .map(CodexCliLimit::display_text)
             .unwrap_or_else(|| "n/a".to_string()),
+        resets_at: None,
     });
 
     Ok(ProviderUsageSnapshot {
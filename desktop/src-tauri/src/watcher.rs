@@ -103,7 +103,7 @@ fn is_job_file(path: &Path) -> bool {
         return false;
     }
     match path.file_name().and_then(|n| n.to_str()) {
-        Some("job.yaml") | Some("job.md") | Some("context.md") => true,
+        Some("job.yaml") | Some("job.md") | Some("context.md") | Some("order.yaml") => true,
         _ => false,
     }
 }
@@ -1,5 +1,12 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import type { CheckStatus, SelfTestReport } from "../types";
+
+const CHECK_COLORS: Record<CheckStatus, string> = {
+  pass: "var(--success-color)",
+  warn: "var(--warning-color)",
+  fail: "var(--danger-color)",
+};
 
 interface DaemonStatus {
   installed: boolean;
@@ -14,6 +21,8 @@ export function DaemonPanel() {
   const [error, setError] = useState<string | null>(null);
   const [logs, setLogs] = useState<string>("");
   const [showLogs, setShowLogs] = useState(false);
+  const [selfTest, setSelfTest] = useState<SelfTestReport | null>(null);
+  const [testing, setTesting] = useState(false);
   const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const logsRef = useRef<HTMLPreElement>(null);
 
@@ -76,6 +85,18 @@ export function DaemonPanel() {
     }
   };
 
+  const handleSelfTest = async () => {
+    setTesting(true);
+    setError(null);
+    try {
+      setSelfTest(await invoke<SelfTestReport>("self_test"));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setTesting(false);
+    }
+  };
+
   if (!status) {
     return (
       <div className="settings-section">
@@ -167,6 +188,43 @@ export function DaemonPanel() {
         </div>
       )}
 
+      <div className="field-group">
+        <span className="field-group-title">Self-test</span>
+        <div className="form-group" style={{ marginBottom: selfTest ? 8 : 0 }}>
+          <button className="btn" disabled={testing} onClick={handleSelfTest}>
+            {testing ? "Checking..." : "Run Self-test"}
+          </button>
+          <span className="hint">
+            Checks tmux, the agent CLIs, Telegram, the relay, keychain access and Playwright.
+          </span>
+        </div>
+        {selfTest &&
+          selfTest.checks.map((check) => (
+            <div key={check.id} className="form-group" style={{ marginBottom: 6 }}>
+              <div style={{ display: "flex", alignItems: "baseline", gap: 8, fontSize: 13 }}>
+                <span
+                  style={{
+                    color: CHECK_COLORS[check.status],
+                    fontWeight: 600,
+                    width: 36,
+                    textTransform: "uppercase",
+                    fontSize: 11,
+                  }}
+                >
+                  {check.status}
+                </span>
+                <span style={{ color: "var(--text-primary)" }}>{check.label}</span>
+                <span style={{ color: "var(--text-secondary)" }}>{check.detail}</span>
+              </div>
+              {check.hint && (
+                <span className="hint" style={{ marginLeft: 44 }}>
+                  {check.hint}
+                </span>
+              )}
+            </div>
+          ))}
+      </div>
+
       <div className="field-group">
         <span className="field-group-title">Logs</span>
         <div className="form-group" style={{ marginBottom: showLogs ? 8 : 0 }}>
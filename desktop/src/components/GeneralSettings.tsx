@@ -277,7 +277,7 @@ function GeneralSettingsContent() {
 
       <div className="field-group">
         <span className="field-group-title">Behavior</span>
-        <div className="form-group" style={{ marginBottom: 0 }}>
+        <div className="form-group">
           <label className="checkbox-label">
             <input
               type="checkbox"
@@ -290,6 +290,19 @@ function GeneralSettingsContent() {
             Returns panes to their original tmux windows on blur, re-captures on focus. 3 second debounce.
           </span>
         </div>
+        <div className="form-group" style={{ marginBottom: 0 }}>
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.encrypt_history ?? false}
+              onChange={(e) => update({ encrypt_history: e.target.checked })}
+            />
+            Encrypt run history output
+          </label>
+          <span className="hint">
+            Stores job output in the history database encrypted with a key kept in the keychain. Existing runs stay as they are.
+          </span>
+        </div>
       </div>
 
       <div className="field-group">
@@ -305,7 +318,7 @@ function GeneralSettingsContent() {
           </label>
           <span className="hint">Show local macOS notifications when an agent asks a question</span>
         </div>
-        <div className="form-group" style={{ marginBottom: 0 }}>
+        <div className="form-group">
           <label className="checkbox-label">
             <input
               type="checkbox"
@@ -316,6 +329,18 @@ function GeneralSettingsContent() {
           </label>
           <span className="hint">Send question notifications to connected remote clients</span>
         </div>
+        <div className="form-group" style={{ marginBottom: 0 }}>
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.question_screenshots ?? false}
+              disabled={!settings.notify_questions_remote}
+              onChange={(e) => update({ question_screenshots: e.target.checked })}
+            />
+            Question screenshots
+          </label>
+          <span className="hint">Attach an image of the question's terminal text to phone notifications. Needs a relay with a public URL.</span>
+        </div>
       </div>
 
       <div className="field-group">
@@ -343,6 +343,9 @@ export function DesktopDetailSections({
             {job.job_type === "binary" && (
               <DetailRow label="Path" value={job.path} mono />
             )}
+            {job.job_type === "shell" && (
+              <DetailRow label="Script" value={job.path} mono />
+            )}
             {job.args.length > 0 && (
               <DetailRow label="Args" value={job.args.join(" ")} mono />
             )}
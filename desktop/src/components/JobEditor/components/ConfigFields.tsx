@@ -82,7 +82,7 @@ export function ConfigFields({ form, setForm, isShellJob, envText, setEnvText, s
         <span className="hint">How many recent runs to keep in history. Older runs are pruned after each new run.</span>
       </div>
 
-      {form.job_type === "binary" && (
+      {(form.job_type === "binary" || form.job_type === "shell") && (
         <div className="form-group">
           <label>Environment Variables</label>
           <textarea
@@ -26,6 +26,7 @@ export function IdentityFields({ form, setForm, isNew, argsText, setArgsText, pi
             <option value="job">Job</option>
             <option value="claude">Claude</option>
             <option value="binary">Binary</option>
+            <option value="shell">Shell</option>
           </select>
         </div>
       )}
@@ -91,18 +92,32 @@ export function IdentityFields({ form, setForm, isNew, argsText, setArgsText, pi
             />
           </div>
 
-          <div className="form-group">
-            <label>{form.job_type === "binary" ? "Binary Path" : "Prompt File Path"}</label>
-            <input
-              type="text"
-              value={form.path}
-              onChange={(e) => setForm((prev) => ({ ...prev, path: e.target.value }))}
-              placeholder=""
-              style={{ maxWidth: "100%" }}
-            />
-          </div>
+          {form.job_type === "shell" ? (
+            <div className="form-group">
+              <label>Script</label>
+              <textarea
+                value={form.path}
+                onChange={(e) => setForm((prev) => ({ ...prev, path: e.target.value }))}
+                placeholder=""
+                rows={5}
+                style={{ maxWidth: "100%", fontFamily: "monospace" }}
+              />
+              <span className="hint">Run with sh -c in the work directory</span>
+            </div>
+          ) : (
+            <div className="form-group">
+              <label>{form.job_type === "binary" ? "Binary Path" : "Prompt File Path"}</label>
+              <input
+                type="text"
+                value={form.path}
+                onChange={(e) => setForm((prev) => ({ ...prev, path: e.target.value }))}
+                placeholder=""
+                style={{ maxWidth: "100%" }}
+              />
+            </div>
+          )}
 
-          {form.job_type === "binary" && (
+          {(form.job_type === "binary" || form.job_type === "shell") && (
             <div className="form-group">
               <label>Arguments</label>
               <input
@@ -112,7 +127,11 @@ export function IdentityFields({ form, setForm, isNew, argsText, setArgsText, pi
                 placeholder=""
                 style={{ maxWidth: "100%" }}
               />
-              <span className="hint">Space-separated arguments</span>
+              <span className="hint">
+                {form.job_type === "shell"
+                  ? "Space-separated, available to the script as $1, $2, ..."
+                  : "Space-separated arguments"}
+              </span>
             </div>
           )}
         </>
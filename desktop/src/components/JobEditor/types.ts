@@ -51,6 +51,17 @@ export const emptyJob: Job = {
   agent_provider: null,
   aerospace_workspace: null,
   max_history: 3,
+  run_on_enable: false,
+  include_git_info: false,
+  notify_on_change_only: false,
+  allowed_dirs: [],
+  skip_claude_settings: false,
+  timezone: null,
+  catch_up: false,
+  manage_project_files: true,
+  retries: 0,
+  retry_delay_secs: 0,
+  min_interval_secs: 0,
 };
 
 export interface JobEditorProps {
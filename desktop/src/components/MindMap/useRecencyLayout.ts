@@ -9,7 +9,7 @@ export interface MindItem {
   group: string;
   score: number;
   running: boolean;
-  state: "running" | "idle" | "success" | "failed" | "paused";
+  state: JobStatus["state"];
   asking?: boolean;
   working?: boolean;
   provider?: ProcessProvider | null;
@@ -26,6 +26,7 @@ interface RelayStatus {
   configured: boolean;
   server_url: string;
   device_name: string;
+  update_required: boolean;
 }
 
 const UNAUTHORIZED_PREFIX = "UNAUTHORIZED:";
@@ -546,6 +547,21 @@ export function RelayPanel({ externalAccessToken, externalRefreshToken, onExtern
                       : "Disconnected"}
                 </span>
               </div>
+              {status?.update_required && (
+                <div
+                  style={{
+                    marginTop: 12,
+                    background: "var(--warning-bg, rgba(217, 119, 6, 0.12))",
+                    border: "1px solid var(--warning-color, #d97706)",
+                    borderRadius: 6,
+                    padding: "10px 12px",
+                    fontSize: 12,
+                    color: "var(--warning-color, #d97706)",
+                  }}
+                >
+                  The relay server no longer supports this version of ClawTab. Update the app to keep remote access working.
+                </div>
+              )}
               {settings.enabled && !status?.connected && !status?.subscription_required && (
                 <div
                   style={{
@@ -1,7 +1,7 @@
 import { useEffect, useMemo, useRef, useState } from "react";
 import { createPortal } from "react-dom";
 import { invoke } from "@tauri-apps/api/core";
-import type { SecretEntry } from "../types";
+import type { SecretEntry, SecretSource } from "../types";
 import { ConfirmDialog, DeleteButton } from "./ConfirmDialog";
 
 interface TreeNode {
@@ -213,6 +213,7 @@ export function SecretsPanel() {
   const [search, setSearch] = useState("");
   const [newKey, setNewKey] = useState("");
   const [newValue, setNewValue] = useState("");
+  const [newSource, setNewSource] = useState<SecretSource>("keychain");
   const [editingKey, setEditingKey] = useState<string | null>(null);
   const [editValue, setEditValue] = useState("");
   const [confirmDeleteKey, setConfirmDeleteKey] = useState<string | null>(null);
@@ -251,7 +252,7 @@ export function SecretsPanel() {
   const handleAdd = async () => {
     if (!newKey.trim() || !newValue.trim()) return;
     try {
-      await invoke("set_secret", { key: newKey.trim(), value: newValue.trim() });
+      await invoke("set_secret", { key: newKey.trim(), value: newValue.trim(), source: newSource });
       setNewKey("");
       setNewValue("");
       await loadSecrets();
@@ -399,8 +400,21 @@ export function SecretsPanel() {
               )}
             </div>
           </div>
+          {gopassAvailable && (
+            <div className="form-group">
+              <label>Store</label>
+              <select
+                value={newSource}
+                onChange={(e) => setNewSource(e.target.value as SecretSource)}
+                title="gopass entries are created at the key's path and also kept in Keychain for jobs"
+              >
+                <option value="keychain">Keychain</option>
+                <option value="gopass">gopass + Keychain</option>
+              </select>
+            </div>
+          )}
           <button className="btn btn-primary" onClick={handleAdd} style={{ alignSelf: "flex-end", marginBottom: 19 }}>
-            Add to Keychain
+            {newSource === "gopass" ? "Add to gopass" : "Add to Keychain"}
           </button>
         </div>
       </div>
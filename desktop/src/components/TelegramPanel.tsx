@@ -1,6 +1,6 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { TelegramConfig } from "../types";
+import type { TelegramConfig, TelegramJobAction } from "../types";
 import { TelegramSetup } from "./TelegramSetup";
 import { ConfirmDialog } from "./ConfirmDialog";
 
@@ -42,6 +42,12 @@ export function TelegramPanel() {
     }
   };
 
+  const finishActions = config?.finish_actions ?? [];
+  const toggleFinishAction = (action: TelegramJobAction, on: boolean) => {
+    const rest = finishActions.filter((a) => a !== action);
+    saveSettings({ finish_actions: on ? [...rest, action] : rest });
+  };
+
   const handleDisable = async () => {
     try {
       await invoke("set_telegram_config", { config: null });
@@ -147,6 +153,32 @@ export function TelegramPanel() {
               </label>
             </div>
 
+            {agentEnabled && (
+              <>
+                <div className="form-group">
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={finishActions.includes("rerun")}
+                      onChange={(e) => toggleFinishAction("rerun", e.target.checked)}
+                    />{" "}
+                    Re-run button on completion messages
+                  </label>
+                </div>
+
+                <div className="form-group">
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={finishActions.includes("stop")}
+                      onChange={(e) => toggleFinishAction("stop", e.target.checked)}
+                    />{" "}
+                    Stop button on completion messages
+                  </label>
+                </div>
+              </>
+            )}
+
             {agentEnabled && (
               <div className="terminal-preview">
                 <div className="terminal-header">
@@ -20,7 +20,12 @@ interface ToolGroup {
 }
 
 function buildGroups(tools: ToolInfo[]): ToolGroup[] {
-  const categoryOrder = ["AI Agent", "Required", "Terminal", "Editor", "Optional", "Browser"];
+  const builtIn = ["AI Agent", "Required", "Terminal", "Editor", "Optional", "Browser"];
+  // Categories from tools.toml come after the built-in ones
+  const categoryOrder = [
+    ...builtIn,
+    ...new Set(tools.map((t) => t.category).filter((c) => !builtIn.includes(c))),
+  ];
   const groups: ToolGroup[] = [];
   const seen = new Set<string>();
 
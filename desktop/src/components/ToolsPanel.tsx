@@ -8,10 +8,10 @@ export function ToolsPanel() {
   const [loading, setLoading] = useState(true);
   const [settings, setSettings] = useState<AppSettings | null>(null);
 
-  const loadTools = async () => {
+  const loadTools = async (forceRefresh = false) => {
     setLoading(true);
     try {
-      const detected = await invoke<ToolInfo[]>("detect_tools");
+      const detected = await invoke<ToolInfo[]>("detect_tools", { forceRefresh });
       setTools(detected);
     } catch (e) {
       console.error("Failed to detect tools:", e);
@@ -54,7 +54,7 @@ export function ToolsPanel() {
         <h2>Tools</h2>
         <button
           className="btn btn-sm"
-          onClick={loadTools}
+          onClick={() => loadTools(true)}
           disabled={loading}
         >
           {loading ? "Scanning..." : "Rescan"}
@@ -1,7 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import type { Transport } from "@clawtab/shared";
-import type { RemoteJob, JobStatus, RunRecord, RunDetail } from "@clawtab/shared";
+import type { RemoteJob, JobStatus, JobParam, RunRecord, RunDetail } from "@clawtab/shared";
 import type { DetectedProcess, ProcessProvider, ShellPane } from "@clawtab/shared";
 
 export function createTauriTransport(): Transport {
@@ -28,10 +28,18 @@ export function createTauriTransport(): Transport {
       return await invoke<{ pane_id: string; tmux_session: string } | null>("run_job_now", { name, params });
     },
 
+    async getJobParams(name: string) {
+      return invoke<JobParam[]>("get_job_params", { name });
+    },
+
     async stopJob(name: string) {
       await invoke("stop_job", { name });
     },
 
+    async cancelQueuedRun(name: string) {
+      await invoke("cancel_queued_run", { name });
+    },
+
     async pauseJob(name: string) {
       await invoke("pause_job", { name });
     },
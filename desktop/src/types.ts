@@ -1,6 +1,6 @@
-import type { ProcessProvider } from "@clawtab/shared";
+import type { ProcessProvider, QuestionOption } from "@clawtab/shared";
 
-export type JobType = "binary" | "claude" | "job";
+export type JobType = "binary" | "shell" | "claude" | "job";
 export type TelegramLogMode = "off" | "on_prompt" | "always";
 export type NotifyTarget = "none" | "telegram" | "app";
 
@@ -44,6 +44,57 @@ export interface Job {
   agent_model?: string | null;
   added_at?: string;
   max_history: number;
+  run_on_enable?: boolean;
+  history_retention_days?: number | null;
+  include_git_info?: boolean;
+  notify_on_change_only?: boolean;
+  allowed_dirs?: string[];
+  skip_claude_settings?: boolean;
+  timezone?: string | null;
+  catch_up?: boolean;
+  after_boot_secs?: number | null;
+  manage_project_files?: boolean;
+  retries?: number;
+  retry_delay_secs?: number;
+  min_interval_secs?: number;
+  max_output_bytes?: number | null;
+  pty?: boolean;
+  precheck_command?: string | null;
+  run_if?: string | null;
+  session_max_age_days?: number | null;
+  login_check_url?: string | null;
+  skip_on_stale_session?: boolean;
+  post_command?: string | null;
+  mutex_group?: string | null;
+  auto_answer?: AutoAnswerRule[];
+  items?: string[];
+  items_file?: string | null;
+  parallelism?: number | null;
+  on_success?: string | null;
+  on_failure?: string | null;
+  triggers?: string[];
+}
+
+export interface AutoAnswerRule {
+  pattern: string;
+  answer: string;
+}
+
+export interface AutoAnswerRecord {
+  run_id: string;
+  answered_at: string;
+  pattern: string;
+  answer: string;
+  prompt: string;
+}
+
+export interface RunItemRecord {
+  run_id: string;
+  item_index: number;
+  item: string;
+  started_at: string;
+  finished_at: string;
+  exit_code: number | null;
 }
 
 export interface AerospaceWorkspace {
@@ -59,6 +110,38 @@ export interface RunRecord {
   trigger: string;
   stdout: string;
   stderr: string;
+  git_branch?: string;
+  git_commit?: string;
+  launch_ms?: number;
+  output_truncated?: boolean;
+  skipped?: boolean;
+}
+
+export interface HistoryPage {
+  records: RunRecord[];
+  total: number;
+}
+
+export interface JobStats {
+  runs: number;
+  succeeded: number;
+  failed: number;
+  avg_duration_ms: number | null;
+  max_duration_ms: number | null;
+  last_exit_code: number | null;
+}
+
+export interface LaunchStats {
+  runs: number;
+  avg_ms: number | null;
+  min_ms: number | null;
+  max_ms: number | null;
+}
+
+export interface DiscoveredJob {
+  folder_path: string;
+  job_name: string;
+  source: string;
 }
 
 export interface ExistingPaneInfo {
@@ -70,28 +153,149 @@ export interface ExistingPaneInfo {
   matched_group?: string | null;
 }
 
-export type SecretSource = "keychain";
+export type SecretSource = "keychain" | "gopass";
 
 export interface SecretEntry {
   key: string;
   source: SecretSource;
+  access_denied?: boolean;
+}
+
+export interface KeychainStatus {
+  accessible: boolean;
+  error: string | null;
+  denied_keys: string[];
+}
+
+export type CheckStatus = "pass" | "warn" | "fail";
+
+export interface SelfTestCheck {
+  id: string;
+  label: string;
+  status: CheckStatus;
+  detail: string;
+  hint?: string;
+}
+
+export interface SelfTestReport {
+  status: CheckStatus;
+  checks: SelfTestCheck[];
+  ran_at: string;
 }
 
 export interface TelegramConfig {
   bot_token: string;
   chat_ids: number[];
   chat_names: Record<string, string>;
+  group_chats?: Record<string, number>;
   notify_on_success: boolean;
   notify_on_failure: boolean;
   agent_enabled: boolean;
+  log_flush_secs?: number;
+  finish_actions?: TelegramJobAction[];
 }
 
+export type TelegramJobAction = "rerun" | "stop";
+
 export interface RelaySettings {
   enabled: boolean;
   server_url: string;
   device_token: string;
   device_id: string;
   device_name: string;
+  heartbeat_secs?: number;
+  log_batch_ms?: number | null;
+}
+
+export interface PromptDetectionSettings {
+  option_markers: string;
+  number_separators: string[];
+  prompt_indicators: string[];
+}
+
+export interface PromptDetection {
+  candidates: QuestionOption[];
+  has_prompt_indicator: boolean;
+  options: QuestionOption[];
+}
+
+export interface RelayPingResult {
+  latency_ms: number;
+  mobile_clients: number;
+}
+
+export type RelayProbeError =
+  | "invalid_url"
+  | "dns"
+  | "refused"
+  | "timeout"
+  | "certificate"
+  | "http"
+  | "connect";
+
+export interface RelayProbe {
+  reachable: boolean;
+  tls_verified: boolean;
+  server_version: string | null;
+  latency_ms: number | null;
+  error_kind: RelayProbeError | null;
+  error: string | null;
+}
+
+export interface PromptReference {
+  reference: string;
+  path: string;
+  exists: boolean;
+}
+
+export interface FolderPromptReport {
+  prompt: string;
+  references: PromptReference[];
+  unresolved_params: string[];
+}
+
+export interface JobPreview {
+  job_type: JobType;
+  work_dir: string;
+  env: Record<string, string>;
+  secret_keys: string[];
+  command: string;
+  tmux_session: string | null;
+  window_name: string | null;
+  prompt: string | null;
+  precheck_command: string | null;
+  run_if: string | null;
+}
+
+export interface ScheduledFire {
+  time: string;
+  job_name: string;
+  slug: string;
+}
+
+export interface ScheduleSimulation {
+  fires: ScheduledFire[];
+  truncated: boolean;
+}
+
+export interface SlugChange {
+  old_slug: string;
+  new_slug: string;
+  reason: "malformed" | "duplicate";
+}
+
+export interface LayoutFix {
+  path: string;
+  issue: "generated_claude_md" | "legacy_cwt_dir";
+  applied: boolean;
+}
+
+export interface DroppedRelayMessage {
+  dropped_at: string;
+  kind: string;
+  reason: string;
+  payload: string | null;
+  replayed: boolean;
 }
 
 export interface DetectedProcessOverride {
@@ -142,6 +346,7 @@ export interface AppSettings {
   setup_completed: boolean;
   telegram: TelegramConfig | null;
   secrets_backend: string;
+  secret_scopes?: Record<string, string[]>;
   preferred_browser: string;
   auto_update_enabled: boolean;
   tool_paths: Record<string, string>;
@@ -155,7 +360,57 @@ export interface AppSettings {
   shortcuts: ShortcutSettings;
   notify_questions_local: boolean;
   notify_questions_remote: boolean;
+  question_screenshots?: boolean;
   auto_release_on_blur: boolean;
+  notify_usage_reset?: boolean;
+  usage_alerts?: UsageAlertSettings;
+  pause_when_usage_exhausted?: boolean;
+  usage_pause_percent?: number;
+  default_timezone?: string | null;
+  max_concurrent?: number;
+  history_retention_days?: number;
+  encrypt_history?: boolean;
+  playwright_version?: string;
+  playwright_cache_dir?: string | null;
+  answer_timeout?: AnswerTimeoutSettings;
+  failure_alerts?: FailureAlertSettings;
+  host_profiles?: Record<string, HostProfile>;
+  prompt_detection?: PromptDetectionSettings;
+}
+
+export interface AnswerTimeoutSettings {
+  reminder_secs: number;
+  action: "none" | "decline" | "stop";
+}
+
+export interface UsageAlertSettings {
+  enabled: boolean;
+  warn_percent: number;
+  critical_percent: number;
+}
+
+export interface UsagePause {
+  utilization: number;
+  resets_at: string | null;
+  since: string;
+}
+
+export interface FailureAlertSettings {
+  flap_threshold: number;
+  remind_every: number;
+}
+
+export interface HostProfile {
+  default_tmux_session?: string;
+  default_work_dir?: string;
+  default_provider?: ProcessProvider;
+  default_model?: string;
+  claude_path?: string;
+  preferred_editor?: string;
+  preferred_terminal?: string;
+  preferred_browser?: string;
+  default_timezone?: string;
+  max_concurrent?: number;
 }
 
 export interface ToolInfo {
@@ -172,6 +427,7 @@ export interface ToolInfo {
 export interface UsageEntry {
   label: string;
   value: string;
+  resets_at?: string;
 }
 
 export interface ProviderUsageSnapshot {
@@ -210,6 +466,18 @@ export interface DetectedProcess {
 export type JobStatus =
   | { state: "idle" }
   | { state: "running"; run_id: string; started_at: string; pane_id?: string; tmux_session?: string }
+  | { state: "queued"; queued_at: string }
   | { state: "success"; last_run: string }
-  | { state: "failed"; last_run: string; exit_code: number }
+  | { state: "failed"; last_run: string; exit_code: number; consecutive_failures?: number }
   | { state: "paused" };
+
+export interface GroupStatus {
+  group: string;
+  running: number;
+  queued: number;
+  idle: number;
+  success: number;
+  failed: number;
+  paused: number;
+  last_run: string | null;
+}
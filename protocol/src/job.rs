@@ -92,6 +92,10 @@ pub enum JobStatus {
         run_id: String,
         started_at: String,
     },
+    /// Waiting for a free slot under the desktop's concurrency limit.
+    Queued {
+        queued_at: String,
+    },
     Success {
         last_run: String,
     },
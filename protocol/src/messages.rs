@@ -6,6 +6,38 @@ use crate::job::{
     AgentActivity, ClaudeQuestion, DetectedProcess, JobStatus, RemoteJob, RunDetail, RunRecord,
 };
 
+/// Version of this message set, announced by the relay in
+/// `ServerMessage::Welcome`. Bump it when a side starts relying on messages
+/// or fields that older peers do not understand.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest client `PROTOCOL_VERSION` the relay still serves. Clients below it
+/// should ask the user to update.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Optional relay features, listed in `ServerMessage::Welcome::capabilities`
+/// so clients only use the ones the relay they talk to supports.
+pub mod capabilities {
+    /// Answers `DesktopMessage::Ping` with `ServerMessage::Pong`.
+    pub const PING: &str = "ping";
+    /// Fans `DesktopMessage::Notice` out to mobiles and APNs.
+    pub const NOTICE: &str = "notice";
+    /// Hands `DesktopMessage::TriggerResult` back to webhook callers.
+    pub const TRIGGER_RESULT: &str = "trigger_result";
+    /// Serves `DesktopMessage::QuestionImage` to the iOS notification
+    /// extension.
+    pub const QUESTION_IMAGE: &str = "question_image";
+}
+
+/// Optional desktop features, listed in `DesktopMessage::Hello::capabilities`
+/// so the relay only routes to a desktop what it can serve.
+pub mod desktop_capabilities {
+    /// Streams tmux panes over `SubscribePty`, `PtyInput` and `PtyResize`.
+    pub const PTY: &str = "pty";
+    /// Reports `DesktopMessage::TriggerResult` for dispatched runs.
+    pub const TRIGGER_RESULT: &str = "trigger_result";
+}
+
 /// Messages sent by mobile/web clients to the relay server.
 /// The relay forwards these to the appropriate desktop app.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -338,6 +370,46 @@ pub enum DesktopMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         error: Option<String>,
     },
+    /// Round-trip probe. The relay answers with `ServerMessage::Pong` carrying
+    /// the same `id` and does not forward it to mobiles.
+    Ping {
+        id: String,
+    },
+    /// First message on every connection, answering `ServerMessage::Welcome`
+    /// with the desktop's own protocol version and `desktop_capabilities`.
+    /// The relay keeps them on the connection and does not forward it.
+    Hello {
+        protocol_version: u32,
+        #[serde(default)]
+        capabilities: Vec<String>,
+    },
+    /// Desktop-originated toast for the owner's mobiles (job failures,
+    /// reconnects, usage warnings). With `push` set the relay also sends an
+    /// APNs alert.
+    Notice {
+        level: NoticeLevel,
+        title: String,
+        body: String,
+        #[serde(default)]
+        push: bool,
+    },
+    /// PNG render of a question's pane text, sent just before the
+    /// `ClaudeQuestions` push that first carries `question_id` so the APNs
+    /// alert can show it.
+    QuestionImage {
+        question_id: String,
+        /// Base64-encoded PNG.
+        png: String,
+    },
+}
+
+/// Severity of a `DesktopMessage::Notice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeLevel {
+    Info,
+    Warning,
+    Error,
 }
 
 /// Messages sent by the relay server to connected clients.
@@ -347,6 +419,16 @@ pub enum ServerMessage {
     Welcome {
         connection_id: String,
         server_version: String,
+        /// The relay's `PROTOCOL_VERSION`, 0 for relays that predate
+        /// negotiation.
+        #[serde(default)]
+        protocol_version: u32,
+        /// Oldest client protocol the relay serves.
+        #[serde(default)]
+        min_protocol_version: u32,
+        /// Supported optional features, see `capabilities`.
+        #[serde(default)]
+        capabilities: Vec<String>,
     },
     Error {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -359,6 +441,12 @@ pub enum ServerMessage {
         device_name: String,
         online: bool,
     },
+    /// Reply to `DesktopMessage::Ping`.
+    Pong {
+        id: String,
+        /// Mobile clients currently connected for the desktop's user.
+        mobile_clients: u32,
+    },
 }
 
 /// Error codes used in ServerMessage::Error
@@ -370,4 +458,5 @@ pub mod error_codes {
     pub const RATE_LIMITED: &str = "RATE_LIMITED";
     pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
     pub const INVALID_MESSAGE: &str = "INVALID_MESSAGE";
+    pub const UNSUPPORTED: &str = "UNSUPPORTED";
 }
@@ -0,0 +1,8 @@
+-- Last job list each desktop sent, so mobiles can show it while the
+-- desktop is offline.
+CREATE TABLE IF NOT EXISTS job_snapshots (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    jobs JSONB NOT NULL,
+    statuses JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -21,6 +21,19 @@ struct QuestionPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     matched_job: Option<String>,
     options: Vec<PayloadOption>,
+    /// Rendered pane text the notification extension attaches to the alert.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_url: Option<String>,
+}
+
+/// The question a push asks, as the app's notification actions need it.
+pub struct QuestionPush<'a> {
+    pub question_id: &'a str,
+    pub pane_id: &'a str,
+    pub matched_job: Option<&'a str>,
+    /// (number, label) of every option, including ones past the 4 buttons.
+    pub options: &'a [(String, String)],
+    pub image_url: Option<&'a str>,
 }
 
 #[derive(Serialize)]
@@ -35,6 +48,11 @@ struct JobPayload {
     run_id: String,
 }
 
+#[derive(Serialize)]
+struct NoticePayload<'a> {
+    notice_level: &'a str,
+}
+
 /// Result of a single APNs send attempt.
 enum SendResult {
     Ok,
@@ -76,10 +94,7 @@ impl ApnsClient {
             .as_ref()
             .ok_or("APNS_KEY_PATH not set")?;
         let key_id = config.apns_key_id.as_ref().ok_or("APNS_KEY_ID not set")?;
-        let team_id = config
-            .apns_team_id
-            .as_ref()
-            .ok_or("APNS_TEAM_ID not set")?;
+        let team_id = config.apns_team_id.as_ref().ok_or("APNS_TEAM_ID not set")?;
 
         let topic = config
             .apns_topic
@@ -141,44 +156,30 @@ impl ApnsClient {
         let custom_json =
             serde_json::to_value(&custom_data).map_err(|e| format!("json error: {e}"))?;
 
-        let build_payload = || {
-            let builder = DefaultNotificationBuilder::new()
-                .set_title(title)
-                .set_body(&body)
-                .set_sound("default");
-
-            let options_obj = NotificationOptions {
-                apns_id: None,
-                apns_expiration: None,
-                apns_priority: Some(Priority::High),
-                apns_topic: Some(&self.topic),
-                apns_collapse_id: None,
-                apns_push_type: Some(PushType::Alert),
-            };
-
-            let mut payload = builder.build(device_token, options_obj);
-            payload.add_custom_data("clawtab", &custom_json).ok();
-            payload
-        };
+        let builder = DefaultNotificationBuilder::new()
+            .set_title(title)
+            .set_body(&body)
+            .set_sound("default");
+        self.send_alert(device_token, builder, &custom_json).await
+    }
 
-        // Try production first
-        match classify_send_result(self.production.send(build_payload()).await) {
-            SendResult::Ok => return Ok(()),
-            SendResult::BadToken => {
-                tracing::debug!("production rejected token, trying sandbox: {device_token}");
-            }
-            SendResult::Fatal(e) => return Err(e),
-        }
+    pub async fn send_notice(
+        &self,
+        device_token: &str,
+        level: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<(), String> {
+        let custom_json = serde_json::to_value(NoticePayload {
+            notice_level: level,
+        })
+        .map_err(|e| format!("json error: {e}"))?;
 
-        // Retry on sandbox
-        match classify_send_result(self.sandbox.send(build_payload()).await) {
-            SendResult::Ok => {
-                tracing::debug!("push delivered via sandbox: {device_token}");
-                Ok(())
-            }
-            SendResult::BadToken => Err("invalid_token:both".to_string()),
-            SendResult::Fatal(e) => Err(e),
-        }
+        let builder = DefaultNotificationBuilder::new()
+            .set_title(title)
+            .set_body(body)
+            .set_sound("default");
+        self.send_alert(device_token, builder, &custom_json).await
     }
 
     pub async fn send_question_notification(
@@ -186,12 +187,10 @@ impl ApnsClient {
         device_token: &str,
         title: &str,
         body: &str,
-        question_id: &str,
-        pane_id: &str,
-        matched_job: Option<&str>,
-        options: &[(String, String)],
+        question: &QuestionPush<'_>,
     ) -> Result<(), String> {
-        let payload_options: Vec<PayloadOption> = options
+        let payload_options: Vec<PayloadOption> = question
+            .options
             .iter()
             .map(|(n, l)| PayloadOption {
                 number: n.clone(),
@@ -200,30 +199,42 @@ impl ApnsClient {
             .collect();
 
         let custom_data = QuestionPayload {
-            question_id: question_id.to_string(),
-            pane_id: pane_id.to_string(),
-            matched_job: matched_job.map(|s| s.to_string()),
+            question_id: question.question_id.to_string(),
+            pane_id: question.pane_id.to_string(),
+            matched_job: question.matched_job.map(str::to_string),
             options: payload_options,
+            image_url: question.image_url.map(str::to_string),
         };
 
         let custom_json =
             serde_json::to_value(&custom_data).map_err(|e| format!("json error: {e}"))?;
 
         // Pick category based on option count (pre-registered in the iOS app)
-        let category = match options.len().min(4) {
+        let category = match question.options.len().min(4) {
             2 => "CLAUDE_Q2",
             3 => "CLAUDE_Q3",
             _ => "CLAUDE_Q4",
         };
 
-        let build_payload = || {
-            let builder = DefaultNotificationBuilder::new()
-                .set_title(title)
-                .set_body(body)
-                .set_mutable_content()
-                .set_category(category)
-                .set_sound("default");
+        let builder = DefaultNotificationBuilder::new()
+            .set_title(title)
+            .set_body(body)
+            .set_mutable_content()
+            .set_category(category)
+            .set_sound("default");
+        self.send_alert(device_token, builder, &custom_json).await
+    }
 
+    /// Send a high-priority alert with `custom_json` under the `clawtab` key.
+    /// Tokens are tried on production first and, when production rejects
+    /// them, on sandbox (development builds register sandbox tokens).
+    async fn send_alert(
+        &self,
+        device_token: &str,
+        builder: DefaultNotificationBuilder<'_>,
+        custom_json: &serde_json::Value,
+    ) -> Result<(), String> {
+        let build_payload = || {
             let options_obj = NotificationOptions {
                 apns_id: None,
                 apns_expiration: None,
@@ -233,8 +244,8 @@ impl ApnsClient {
                 apns_push_type: Some(PushType::Alert),
             };
 
-            let mut payload = builder.build(device_token, options_obj);
-            payload.add_custom_data("clawtab", &custom_json).ok();
+            let mut payload = builder.clone().build(device_token, options_obj);
+            payload.add_custom_data("clawtab", custom_json).ok();
             payload
         };
 
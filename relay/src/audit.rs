@@ -0,0 +1,41 @@
+use uuid::Uuid;
+
+/// A remote-controlled action worth recording.
+pub enum AuditAction<'a> {
+    AnswerQuestion {
+        question_id: &'a str,
+        answer: &'a str,
+    },
+    RunJob {
+        name: &'a str,
+    },
+}
+
+/// Record an action in `audit_log` without holding up the forward.
+pub fn spawn_record(pool: &sqlx::PgPool, owner_id: Uuid, actor_id: Uuid, action: &AuditAction<'_>) {
+    let (action, target, detail) = match *action {
+        AuditAction::AnswerQuestion {
+            question_id,
+            answer,
+        } => ("answer_question", question_id, Some(answer.to_string())),
+        AuditAction::RunJob { name } => ("run_job", name, None),
+    };
+    let target = target.to_string();
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        let res = sqlx::query(
+            "INSERT INTO audit_log (owner_id, actor_id, action, target, detail)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(owner_id)
+        .bind(actor_id)
+        .bind(action)
+        .bind(&target)
+        .bind(detail)
+        .execute(&pool)
+        .await;
+        if let Err(e) = res {
+            tracing::warn!(%owner_id, %actor_id, action, %target, "failed to write audit log: {e}");
+        }
+    });
+}
@@ -30,6 +30,15 @@ pub struct Config {
     /// Shared secret for the /_internal/* endpoints called by the triggers service.
     /// When None, internal endpoints reject all requests.
     pub relay_internal_secret: Option<String>,
+
+    /// Externally reachable base URL of this relay (e.g. https://relay.example.com).
+    /// Needed to link question images from push notifications; without it the
+    /// relay doesn't accept them.
+    pub public_url: Option<String>,
+
+    /// Path the routes are mounted under when the relay sits behind a reverse
+    /// proxy at a subpath, e.g. `/clawtab`. Empty mounts them at the root.
+    pub route_prefix: String,
 }
 
 impl Config {
@@ -62,6 +71,36 @@ impl Config {
             apns_topic: env::var("APNS_TOPIC").ok(),
             redis_url: env::var("REDIS_URL").ok(),
             relay_internal_secret: env::var("RELAY_INTERNAL_SECRET").ok(),
+            public_url: env::var("PUBLIC_URL")
+                .ok()
+                .map(|v| v.trim_end_matches('/').to_string())
+                .filter(|v| !v.is_empty()),
+            route_prefix: normalize_route_prefix(&env::var("ROUTE_PREFIX").unwrap_or_default()),
         }
     }
 }
+
+/// `clawtab`, `/clawtab/` and `/clawtab` all become `/clawtab`; an empty or
+/// `/` prefix becomes empty.
+fn normalize_route_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim().trim_matches('/');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("/{prefix}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_route_prefix;
+
+    #[test]
+    fn route_prefix_is_normalized() {
+        assert_eq!(normalize_route_prefix(""), "");
+        assert_eq!(normalize_route_prefix(" / "), "");
+        assert_eq!(normalize_route_prefix("clawtab"), "/clawtab");
+        assert_eq!(normalize_route_prefix("/clawtab/"), "/clawtab");
+        assert_eq!(normalize_route_prefix("/apps/clawtab"), "/apps/clawtab");
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use clawtab_protocol::{JobStatus, RemoteJob};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+enum Write {
+    Replace {
+        user_id: Uuid,
+        jobs: Value,
+        statuses: Value,
+    },
+    Status {
+        user_id: Uuid,
+        name: String,
+        status: Value,
+    },
+}
+
+/// Caches each desktop's job list. Writes go through one task in the order
+/// the desktop sent them, so an older snapshot never lands last.
+#[derive(Clone)]
+pub struct JobSnapshotStore {
+    tx: mpsc::UnboundedSender<Write>,
+}
+
+impl JobSnapshotStore {
+    pub fn spawn(pool: sqlx::PgPool) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(write) = rx.recv().await {
+                apply(&pool, write).await;
+            }
+        });
+        Self { tx }
+    }
+
+    /// Replace the user's cached job list without holding up the fanout.
+    pub fn store(&self, user_id: Uuid, jobs: &[RemoteJob], statuses: &HashMap<String, JobStatus>) {
+        let (jobs, statuses) = match (serde_json::to_value(jobs), serde_json::to_value(statuses)) {
+            (Ok(jobs), Ok(statuses)) => (jobs, statuses),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!(%user_id, "failed to serialize job snapshot: {e}");
+                return;
+            }
+        };
+        self.send(Write::Replace {
+            user_id,
+            jobs,
+            statuses,
+        });
+    }
+
+    /// Set one job's status in the user's cached snapshot, if there is one.
+    pub fn update_status(&self, user_id: Uuid, name: &str, status: &JobStatus) {
+        let status = match serde_json::to_value(status) {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!(%user_id, "failed to serialize job status: {e}");
+                return;
+            }
+        };
+        self.send(Write::Status {
+            user_id,
+            name: name.to_string(),
+            status,
+        });
+    }
+
+    fn send(&self, write: Write) {
+        if self.tx.send(write).is_err() {
+            tracing::warn!("job snapshot writer has stopped");
+        }
+    }
+}
+
+async fn apply(pool: &sqlx::PgPool, write: Write) {
+    let (user_id, res) = match write {
+        Write::Replace {
+            user_id,
+            jobs,
+            statuses,
+        } => {
+            let res = sqlx::query(
+                "INSERT INTO job_snapshots (user_id, jobs, statuses, updated_at)
+                 VALUES ($1, $2, $3, now())
+                 ON CONFLICT (user_id) DO UPDATE
+                 SET jobs = EXCLUDED.jobs, statuses = EXCLUDED.statuses, updated_at = now()",
+            )
+            .bind(user_id)
+            .bind(jobs)
+            .bind(statuses)
+            .execute(pool)
+            .await;
+            (user_id, res)
+        }
+        Write::Status {
+            user_id,
+            name,
+            status,
+        } => {
+            let res = sqlx::query(
+                "UPDATE job_snapshots
+                 SET statuses = jsonb_set(statuses, ARRAY[$2], $3), updated_at = now()
+                 WHERE user_id = $1",
+            )
+            .bind(user_id)
+            .bind(name)
+            .bind(status)
+            .execute(pool)
+            .await;
+            (user_id, res)
+        }
+    };
+    if let Err(e) = res {
+        tracing::warn!(%user_id, "failed to store job snapshot: {e}");
+    }
+}
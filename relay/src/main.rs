@@ -9,14 +9,17 @@ use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod apns;
+mod audit;
 mod auth;
 pub mod auth_session;
 mod billing;
 mod config;
 mod db;
 mod error;
+mod job_snapshots;
 mod notification_fmt;
 mod push_limiter;
+pub mod question_images;
 mod routes;
 mod ws;
 
@@ -28,6 +31,8 @@ pub struct AppState {
     pub apns: Option<Arc<apns::ApnsClient>>,
     pub redis: Option<redis::aio::ConnectionManager>,
     pub auth_sessions: Arc<auth_session::AuthSessionStore>,
+    pub question_images: Arc<question_images::QuestionImageStore>,
+    pub job_snapshots: job_snapshots::JobSnapshotStore,
 }
 
 #[tokio::main]
@@ -46,6 +51,7 @@ async fn main() -> anyhow::Result<()> {
     let pool = db::create_pool(&config.database_url).await?;
     let hub = Arc::new(RwLock::new(ws::Hub::new()));
     let listen_addr = config.listen_addr.clone();
+    let route_prefix = config.route_prefix.clone();
 
     // Initialize APNs client (optional)
     let apns_client = if config.apns_key_path.is_some() {
@@ -87,6 +93,10 @@ async fn main() -> anyhow::Result<()> {
 
     let auth_sessions = Arc::new(auth_session::AuthSessionStore::new());
     auth_session::spawn_cleanup(Arc::clone(&auth_sessions));
+    let question_images = Arc::new(question_images::QuestionImageStore::new());
+    question_images::spawn_cleanup(Arc::clone(&question_images));
+
+    let job_snapshots = job_snapshots::JobSnapshotStore::spawn(pool.clone());
 
     let state = AppState {
         config: Arc::new(config),
@@ -95,6 +105,8 @@ async fn main() -> anyhow::Result<()> {
         apns: apns_client,
         redis: redis_conn,
         auth_sessions,
+        question_images,
+        job_snapshots,
     };
 
     let cors = CorsLayer::new()
@@ -102,10 +114,17 @@ async fn main() -> anyhow::Result<()> {
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let app = Router::new()
+    let routes = Router::new()
         .route("/ws", get(ws::ws_handler))
         .merge(routes::router(state.clone()))
-        .with_state(state)
+        .with_state(state);
+    let app = if route_prefix.is_empty() {
+        routes
+    } else {
+        tracing::info!("serving routes under {route_prefix}");
+        Router::new().nest(&route_prefix, routes)
+    };
+    let app = app
         .layer(cors)
         .layer(TraceLayer::new_for_http());
 
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Long enough for the notification extension to fetch the image after the
+/// push goes out.
+const IMAGE_TTL: Duration = Duration::from_secs(10 * 60);
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+/// Larger uploads are dropped; a rendered question is a few KB.
+pub const MAX_IMAGE_BYTES: usize = 512 * 1024;
+const MAX_IMAGES_PER_USER: usize = 20;
+pub const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+struct StoredImage {
+    user_id: Uuid,
+    question_id: String,
+    png: Vec<u8>,
+    created: Instant,
+}
+
+/// Question images from desktops, served by an unguessable token so the
+/// notification extension can fetch them without credentials.
+#[derive(Default)]
+pub struct QuestionImageStore {
+    images: RwLock<HashMap<String, StoredImage>>,
+}
+
+impl QuestionImageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `png` for the user's question, replacing an earlier image of
+    /// the same question. The user's oldest image goes once they hold
+    /// `MAX_IMAGES_PER_USER`.
+    pub async fn insert(&self, user_id: Uuid, question_id: &str, png: Vec<u8>) {
+        let mut images = self.images.write().await;
+        images.retain(|_, i| !(i.user_id == user_id && i.question_id == question_id));
+        let owned: Vec<(&String, Instant)> = images
+            .iter()
+            .filter(|(_, i)| i.user_id == user_id)
+            .map(|(token, i)| (token, i.created))
+            .collect();
+        if owned.len() >= MAX_IMAGES_PER_USER {
+            if let Some(oldest) = owned.iter().min_by_key(|(_, created)| *created) {
+                let oldest = oldest.0.clone();
+                images.remove(&oldest);
+            }
+        }
+        images.insert(
+            Uuid::new_v4().simple().to_string(),
+            StoredImage {
+                user_id,
+                question_id: question_id.to_string(),
+                png,
+                created: Instant::now(),
+            },
+        );
+    }
+
+    /// Token of the stored image for the user's question, if any.
+    pub async fn token_for(&self, user_id: Uuid, question_id: &str) -> Option<String> {
+        let images = self.images.read().await;
+        images
+            .iter()
+            .find(|(_, i)| i.user_id == user_id && i.question_id == question_id)
+            .map(|(token, _)| token.clone())
+    }
+
+    pub async fn get(&self, token: &str) -> Option<Vec<u8>> {
+        let images = self.images.read().await;
+        images
+            .get(token)
+            .filter(|i| i.created.elapsed() < IMAGE_TTL)
+            .map(|i| i.png.clone())
+    }
+
+    async fn cleanup(&self) {
+        let mut images = self.images.write().await;
+        images.retain(|_, i| i.created.elapsed() < IMAGE_TTL);
+    }
+}
+
+pub fn spawn_cleanup(store: Arc<QuestionImageStore>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+            store.cleanup().await;
+        }
+    });
+}
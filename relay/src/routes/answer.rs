@@ -53,6 +53,16 @@ pub async fn answer(
         "answer via HTTP forwarded"
     );
 
+    crate::audit::spawn_record(
+        &state.pool,
+        claims.sub,
+        claims.sub,
+        &crate::audit::AuditAction::AnswerQuestion {
+            question_id: &req.question_id,
+            answer: &req.answer,
+        },
+    );
+
     // Mark answered in DB (fire and forget)
     let pool = state.pool.clone();
     let qid = req.question_id;
@@ -0,0 +1,67 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::error::AppError;
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    limit: Option<i64>,
+}
+
+/// owner_id, actor_id, actor email, action, target, detail, created_at
+type AuditRow = (
+    Uuid,
+    Uuid,
+    String,
+    String,
+    String,
+    Option<String>,
+    chrono::DateTime<chrono::Utc>,
+);
+
+/// Recent remote actions in the caller's workspace, plus those the caller
+/// took in workspaces shared with them.
+pub async fn list(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Value>, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let rows: Vec<AuditRow> = sqlx::query_as(
+        "SELECT a.owner_id, a.actor_id, u.email, a.action, a.target, a.detail, a.created_at
+         FROM audit_log a
+         JOIN users u ON u.id = a.actor_id
+         WHERE a.owner_id = $1 OR a.actor_id = $1
+         ORDER BY a.created_at DESC
+         LIMIT $2",
+    )
+    .bind(claims.sub)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let entries: Vec<Value> = rows
+        .into_iter()
+        .map(
+            |(owner_id, actor_id, actor_email, action, target, detail, created_at)| {
+                json!({
+                    "owner_id": owner_id,
+                    "actor_id": actor_id,
+                    "actor_email": actor_email,
+                    "action": action,
+                    "target": target,
+                    "detail": detail,
+                    "created_at": created_at.to_rfc3339(),
+                })
+            },
+        )
+        .collect();
+
+    Ok(Json(json!({ "entries": entries })))
+}
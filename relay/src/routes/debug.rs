@@ -3,6 +3,7 @@ use axum::Json;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use crate::apns::QuestionPush;
 use crate::auth::Claims;
 use crate::error::AppError;
 use crate::notification_fmt;
@@ -47,10 +48,13 @@ pub async fn test_push(
                 &req.device_token,
                 &title,
                 &body,
-                &question_id,
-                "test-pane",
-                None,
-                &push_options,
+                &QuestionPush {
+                    question_id: &question_id,
+                    pane_id: "test-pane",
+                    matched_job: None,
+                    options: &push_options,
+                    image_url: None,
+                },
             )
             .await;
 
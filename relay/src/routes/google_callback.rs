@@ -114,7 +114,8 @@ pub async fn google_callback(
         .get("host")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("relay.clawtab.cc");
-    let redirect_uri = format!("https://{host}/auth/google/callback");
+    let prefix = &state.config.route_prefix;
+    let redirect_uri = format!("https://{host}{prefix}/auth/google/callback");
 
     // Exchange the authorization code for tokens
     let token_resp = reqwest::Client::new()
@@ -0,0 +1,30 @@
+use axum::extract::State;
+use axum::Json;
+use serde_json::{json, Value};
+
+use crate::auth::Claims;
+use crate::error::AppError;
+use crate::AppState;
+
+/// The last job list the caller's desktop sent, for a read-only view while
+/// the desktop is offline.
+pub async fn cached(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<Json<Value>, AppError> {
+    let row: Option<(Value, Value, chrono::DateTime<chrono::Utc>)> =
+        sqlx::query_as("SELECT jobs, statuses, updated_at FROM job_snapshots WHERE user_id = $1")
+            .bind(claims.sub)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    let Some((jobs, statuses, updated_at)) = row else {
+        return Err(AppError::NotFound("no cached jobs".to_string()));
+    };
+
+    Ok(Json(json!({
+        "jobs": jobs,
+        "statuses": statuses,
+        "updated_at": updated_at.to_rfc3339(),
+    })))
+}
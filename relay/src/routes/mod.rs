@@ -1,5 +1,6 @@
 mod account;
 mod answer;
+mod audit;
 mod auth_session;
 mod health;
 mod register;
@@ -13,7 +14,9 @@ mod apple_auth;
 mod apple_callback;
 mod iap;
 mod internal;
+mod jobs;
 mod notifications;
+mod question_image;
 mod share;
 mod subscription;
 
@@ -62,7 +65,8 @@ pub fn router(state: AppState) -> Router<AppState> {
     );
 
     let public = Router::new()
-        .route("/health", get(health::health));
+        .route("/health", get(health::health))
+        .route("/question-images/{token}", get(question_image::get));
 
     let rate_limited_auth = Router::new()
         .route("/auth/register", post(register::register))
@@ -93,6 +97,8 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/shares/{id}", delete(share::remove))
         .route("/shares/{id}", patch(share::update))
         .route("/account", delete(account::delete_account))
+        .route("/audit", get(audit::list))
+        .route("/jobs/cached", get(jobs::cached))
         .layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
 
     let internal = Router::new()
@@ -0,0 +1,21 @@
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::AppState;
+
+/// Serve a question image by its token. Unauthenticated: the token is the
+/// credential, and it expires with the image.
+pub async fn get(State(state): State<AppState>, Path(token): Path<String>) -> Response {
+    match state.question_images.get(&token).await {
+        Some(png) => (
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "private, no-store"),
+            ],
+            png,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
@@ -1,15 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
 use axum::extract::ws::WebSocket;
+use base64::Engine;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use clawtab_protocol::{DesktopMessage, JobStatus, RemoteJob, ServerMessage};
+use clawtab_protocol::{DesktopMessage, JobStatus, RemoteJob, ServerMessage, MIN_PROTOCOL_VERSION};
 
-use crate::ws::handler::{run_session_loop, LoopExit};
-use crate::ws::hub::DesktopConnection;
+use crate::config::Config;
+use crate::question_images::{MAX_IMAGE_BYTES, PNG_SIGNATURE};
+use crate::ws::handler::{run_session_loop, welcome, LoopExit};
+use crate::ws::hub::{DesktopConnection, DesktopPeer};
 use crate::ws::push::{
-    handle_claude_questions_push, handle_job_notification_push, handle_trigger_result,
+    handle_claude_questions_push, handle_job_notification_push, handle_notice_push,
+    handle_trigger_result,
 };
 use crate::ws::shared::{filter_questions_for_groups, get_shared_guests, SharedGuest};
 use crate::AppState;
@@ -37,10 +41,10 @@ pub(super) async fn run(
         &guest_ids,
     )
     .await;
-    send_welcome(&tx, device_id);
+    send_welcome(&tx, device_id, &state.config);
     tracing::info!(%user_id, %device_id, %connection_id, %device_name, "desktop connected");
 
-    let exit = drive_session(state.clone(), socket, rx, user_id).await;
+    let exit = drive_session(state.clone(), socket, rx, tx, user_id, connection_id).await;
 
     unregister(
         &state,
@@ -59,12 +63,15 @@ async fn drive_session(
     state: AppState,
     socket: WebSocket,
     rx: mpsc::UnboundedReceiver<String>,
+    tx: mpsc::UnboundedSender<String>,
     user_id: Uuid,
+    connection_id: Uuid,
 ) -> LoopExit {
     run_session_loop(socket, rx, move |text| {
         let state = state.clone();
+        let tx = tx.clone();
         async move {
-            handle_message(&state, user_id, &text).await;
+            handle_message(&state, &tx, user_id, connection_id, &text).await;
         }
     })
     .await
@@ -103,6 +110,7 @@ async fn register(
             device_id,
             device_name: device_name.to_string(),
             tx,
+            peer: None,
         },
     );
     for &gid in guest_ids {
@@ -140,142 +148,293 @@ async fn unregister(
     }
 }
 
-fn send_welcome(tx: &mpsc::UnboundedSender<String>, device_id: Uuid) {
-    if let Ok(json) = serde_json::to_string(&ServerMessage::Welcome {
-        connection_id: device_id.to_string(),
-        server_version: env!("CARGO_PKG_VERSION").to_string(),
+fn send_welcome(tx: &mpsc::UnboundedSender<String>, device_id: Uuid, config: &Config) {
+    if let Ok(json) = serde_json::to_string(&welcome(device_id.to_string(), config)) {
+        let _ = tx.send(json);
+    }
+}
+
+fn send_pong(tx: &mpsc::UnboundedSender<String>, id: String, mobile_clients: usize) {
+    if let Ok(json) = serde_json::to_string(&ServerMessage::Pong {
+        id,
+        mobile_clients: u32::try_from(mobile_clients).unwrap_or(u32::MAX),
     }) {
         let _ = tx.send(json);
     }
 }
 
-async fn handle_message(state: &AppState, user_id: Uuid, text: &str) {
+async fn handle_message(
+    state: &AppState,
+    tx: &mpsc::UnboundedSender<String>,
+    user_id: Uuid,
+    connection_id: Uuid,
+    text: &str,
+) {
     let Ok(msg) = serde_json::from_str::<DesktopMessage>(text) else {
         tracing::warn!(%user_id, "invalid message from desktop: {text}");
         return;
     };
 
-    let guests = get_shared_guests(&state.pool, user_id).await;
+    match msg {
+        DesktopMessage::Ping { id } => {
+            let mobile_clients = state.hub.read().await.mobile_count(user_id);
+            send_pong(tx, id, mobile_clients);
+        }
+        DesktopMessage::Hello {
+            protocol_version,
+            capabilities,
+        } => {
+            record_hello(
+                state,
+                user_id,
+                connection_id,
+                protocol_version,
+                capabilities,
+            )
+            .await;
+        }
+        DesktopMessage::TriggerResult {
+            trigger_id,
+            status,
+            exit_code,
+            result,
+            error,
+        } => {
+            // Internal-only channel for the triggers service. Do NOT fan out to mobiles.
+            handle_trigger_result(
+                state,
+                user_id,
+                &trigger_id,
+                &status,
+                exit_code,
+                &result,
+                &error,
+            )
+            .await;
+        }
+        DesktopMessage::QuestionImage { question_id, png } => {
+            // Only the push pipeline uses these; mobiles render the text.
+            store_question_image(state, user_id, &question_id, &png).await;
+        }
+        msg => {
+            let guests = get_shared_guests(&state.pool, user_id).await;
+            fanout_message(state, user_id, msg, text, &guests).await;
+        }
+    }
+}
 
-    match &msg {
+/// Forward a desktop update to the owner's and guests' mobiles, narrowing
+/// it to each guest's allowed groups, and trigger the pushes it implies.
+async fn fanout_message(
+    state: &AppState,
+    user_id: Uuid,
+    msg: DesktopMessage,
+    text: &str,
+    guests: &[SharedGuest],
+) {
+    match msg {
         DesktopMessage::ClaudeQuestions {
             questions,
             apns_questions,
         } => {
-            tracing::info!(
-                %user_id,
-                questions = questions.len(),
-                apns_questions = apns_questions.as_ref().map_or(questions.len(), Vec::len),
-                "claude questions from desktop"
-            );
-            fanout_claude_questions(state, user_id, questions, text, &guests).await;
-            let push_questions = apns_questions.as_ref().unwrap_or(questions);
-            if !push_questions.is_empty() {
-                spawn_push(state.clone(), user_id, push_questions.clone());
-            }
+            handle_claude_questions(state, user_id, questions, apns_questions, text, guests).await;
         }
         DesktopMessage::AutoYesPanes { pane_ids } => {
-            fanout_auto_yes_panes(state, user_id, pane_ids, text, &guests).await;
+            fanout_auto_yes_panes(state, user_id, &pane_ids, text, guests).await;
         }
         DesktopMessage::JobsList { jobs, statuses, id } => {
+            state.job_snapshots.store(user_id, &jobs, &statuses);
             let hub = state.hub.read().await;
-            hub.send_raw_to_mobiles(user_id, text);
-            for guest in &guests {
-                let Some((filtered_jobs, filtered_statuses)) =
-                    filter_jobs_by_group(guest, jobs, statuses)
-                else {
-                    hub.send_raw_to_mobiles(guest.guest_id, text);
-                    continue;
-                };
-                hub.broadcast_to_mobiles(
-                    guest.guest_id,
-                    &DesktopMessage::JobsList {
-                        id: id.clone(),
-                        jobs: filtered_jobs,
-                        statuses: filtered_statuses,
-                    },
-                );
-            }
+            fanout_jobs(
+                &hub,
+                user_id,
+                &jobs,
+                &statuses,
+                text,
+                guests,
+                |jobs, statuses| DesktopMessage::JobsList {
+                    id: id.clone(),
+                    jobs,
+                    statuses,
+                },
+            );
         }
         DesktopMessage::JobsChanged { jobs, statuses } => {
+            state.job_snapshots.store(user_id, &jobs, &statuses);
             let hub = state.hub.read().await;
-            hub.send_raw_to_mobiles(user_id, text);
-            for guest in &guests {
-                let Some((filtered_jobs, filtered_statuses)) =
-                    filter_jobs_by_group(guest, jobs, statuses)
-                else {
-                    hub.send_raw_to_mobiles(guest.guest_id, text);
-                    continue;
-                };
-                hub.broadcast_to_mobiles(
-                    guest.guest_id,
-                    &DesktopMessage::JobsChanged {
-                        jobs: filtered_jobs,
-                        statuses: filtered_statuses,
-                    },
-                );
-            }
+            fanout_jobs(
+                &hub,
+                user_id,
+                &jobs,
+                &statuses,
+                text,
+                guests,
+                |jobs, statuses| DesktopMessage::JobsChanged { jobs, statuses },
+            );
         }
         DesktopMessage::DetectedProcesses { id, processes } => {
             let mut hub = state.hub.write().await;
-            hub.set_cached_detected_processes(user_id, processes.clone());
             hub.send_raw_to_mobiles(user_id, text);
-            for guest in &guests {
-                forward_detected_processes(&hub, guest, text, id, processes);
+            for guest in guests {
+                forward_detected_processes(&hub, guest, text, &id, &processes);
             }
+            hub.set_cached_detected_processes(user_id, processes);
         }
         DesktopMessage::AgentActivity { activity } => {
-            let mut hub = state.hub.write().await;
-            hub.set_cached_agent_activity(user_id, activity.clone());
-            hub.send_raw_to_mobiles(user_id, text);
-            for guest in &guests {
-                let Some(groups) = guest.allowed_groups.as_deref() else {
-                    hub.send_raw_to_mobiles(guest.guest_id, text);
-                    continue;
-                };
-                let activity = hub.cached_agent_activity(user_id, Some(groups));
-                hub.broadcast_to_mobiles(
-                    guest.guest_id,
-                    &DesktopMessage::AgentActivity { activity },
-                );
-            }
+            fanout_agent_activity(state, user_id, activity, text, guests).await;
         }
-        DesktopMessage::TriggerResult {
-            trigger_id,
-            status,
-            exit_code,
-            result,
-            error,
+        DesktopMessage::Notice {
+            level,
+            title,
+            body,
+            push,
         } => {
-            // Internal-only channel for the triggers service. Do NOT fan out to mobiles.
-            handle_trigger_result(
-                state, user_id, trigger_id, status, *exit_code, result, error,
-            )
-            .await;
+            // Notices can name jobs outside a guest's allowed groups, so they
+            // only go to the owner's devices.
+            state.hub.read().await.send_raw_to_mobiles(user_id, text);
+            if push {
+                spawn_notice_push(state.clone(), user_id, level, title, body);
+            }
         }
-        _ => {
-            let hub = state.hub.read().await;
-            hub.send_raw_to_mobiles(user_id, text);
-            for guest in &guests {
-                hub.send_raw_to_mobiles(guest.guest_id, text);
+        msg => {
+            fanout_raw(state, user_id, text, guests).await;
+            match msg {
+                DesktopMessage::StatusUpdate { name, status } => {
+                    state.job_snapshots.update_status(user_id, &name, &status);
+                }
+                DesktopMessage::JobNotification {
+                    name,
+                    event,
+                    run_id,
+                } => {
+                    spawn_job_notification(state.clone(), user_id, name, event, run_id);
+                }
+                _ => {}
             }
         }
     }
+}
+
+async fn fanout_raw(state: &AppState, user_id: Uuid, text: &str, guests: &[SharedGuest]) {
+    let hub = state.hub.read().await;
+    hub.send_raw_to_mobiles(user_id, text);
+    for guest in guests {
+        hub.send_raw_to_mobiles(guest.guest_id, text);
+    }
+}
+
+async fn handle_claude_questions(
+    state: &AppState,
+    user_id: Uuid,
+    questions: Vec<clawtab_protocol::ClaudeQuestion>,
+    apns_questions: Option<Vec<clawtab_protocol::ClaudeQuestion>>,
+    text: &str,
+    guests: &[SharedGuest],
+) {
+    tracing::info!(
+        %user_id,
+        questions = questions.len(),
+        apns_questions = apns_questions.as_ref().map_or(questions.len(), Vec::len),
+        "claude questions from desktop"
+    );
+    fanout_claude_questions(state, user_id, &questions, text, guests).await;
+    let push_questions = apns_questions.unwrap_or(questions);
+    if !push_questions.is_empty() {
+        spawn_push(state.clone(), user_id, push_questions);
+    }
+}
+
+/// Send a job list to the owner as-is and to each guest filtered to their
+/// groups, rebuilt with `rebuild` when a filter applies.
+fn fanout_jobs(
+    hub: &super::Hub,
+    user_id: Uuid,
+    jobs: &[RemoteJob],
+    statuses: &HashMap<String, JobStatus>,
+    text: &str,
+    guests: &[SharedGuest],
+    rebuild: impl Fn(Vec<RemoteJob>, HashMap<String, JobStatus>) -> DesktopMessage,
+) {
+    hub.send_raw_to_mobiles(user_id, text);
+    for guest in guests {
+        let Some((filtered_jobs, filtered_statuses)) = filter_jobs_by_group(guest, jobs, statuses)
+        else {
+            hub.send_raw_to_mobiles(guest.guest_id, text);
+            continue;
+        };
+        hub.broadcast_to_mobiles(guest.guest_id, &rebuild(filtered_jobs, filtered_statuses));
+    }
+}
+
+async fn fanout_agent_activity(
+    state: &AppState,
+    user_id: Uuid,
+    activity: Vec<clawtab_protocol::AgentActivity>,
+    text: &str,
+    guests: &[SharedGuest],
+) {
+    let mut hub = state.hub.write().await;
+    hub.set_cached_agent_activity(user_id, activity);
+    hub.send_raw_to_mobiles(user_id, text);
+    for guest in guests {
+        let Some(groups) = guest.allowed_groups.as_deref() else {
+            hub.send_raw_to_mobiles(guest.guest_id, text);
+            continue;
+        };
+        let activity = hub.cached_agent_activity(user_id, Some(groups));
+        hub.broadcast_to_mobiles(guest.guest_id, &DesktopMessage::AgentActivity { activity });
+    }
+}
 
-    if let DesktopMessage::JobNotification {
-        name,
-        event,
-        run_id,
-    } = &msg
-    {
-        spawn_job_notification(
-            state.clone(),
-            user_id,
-            name.clone(),
-            event.clone(),
-            run_id.clone(),
+/// Keep the desktop's announced protocol on its connection so mobile
+/// requests it cannot serve are refused at the relay.
+async fn record_hello(
+    state: &AppState,
+    user_id: Uuid,
+    connection_id: Uuid,
+    protocol_version: u32,
+    capabilities: Vec<String>,
+) {
+    if protocol_version < MIN_PROTOCOL_VERSION {
+        tracing::warn!(
+            %user_id,
+            %connection_id,
+            protocol_version,
+            min = MIN_PROTOCOL_VERSION,
+            "desktop protocol is older than the relay serves"
         );
+    } else {
+        tracing::info!(%user_id, %connection_id, protocol_version, ?capabilities, "desktop hello");
+    }
+    state.hub.write().await.set_desktop_peer(
+        user_id,
+        connection_id,
+        DesktopPeer {
+            protocol_version,
+            capabilities,
+        },
+    );
+}
+
+async fn store_question_image(state: &AppState, user_id: Uuid, question_id: &str, png: &str) {
+    if state.config.public_url.is_none() {
+        return;
+    }
+    let bytes = match base64::engine::general_purpose::STANDARD.decode(png) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(%user_id, "invalid question image: {e}");
+            return;
+        }
+    };
+    if bytes.len() > MAX_IMAGE_BYTES || !bytes.starts_with(PNG_SIGNATURE) {
+        tracing::warn!(%user_id, len = bytes.len(), "question image rejected");
+        return;
     }
+    state
+        .question_images
+        .insert(user_id, question_id, bytes)
+        .await;
 }
 
 async fn fanout_claude_questions(
@@ -372,6 +531,18 @@ fn spawn_push(state: AppState, user_id: Uuid, questions: Vec<clawtab_protocol::C
     });
 }
 
+fn spawn_notice_push(
+    state: AppState,
+    user_id: Uuid,
+    level: clawtab_protocol::NoticeLevel,
+    title: String,
+    body: String,
+) {
+    tokio::spawn(async move {
+        handle_notice_push(&state, user_id, level, &title, &body).await;
+    });
+}
+
 fn spawn_job_notification(
     state: AppState,
     user_id: Uuid,
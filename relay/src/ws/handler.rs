@@ -9,6 +9,9 @@ use tokio::sync::mpsc;
 use tokio::time::interval;
 use uuid::Uuid;
 
+use clawtab_protocol::{capabilities, ServerMessage, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+
+use crate::config::Config;
 use crate::error::AppError;
 use crate::ws::{desktop, mobile};
 use crate::AppState;
@@ -92,6 +95,26 @@ async fn handle_socket(state: AppState, socket: WebSocket, auth: AuthResult) {
     }
 }
 
+/// First message on every connection: the relay's version and the optional
+/// protocol features it supports.
+pub(super) fn welcome(connection_id: String, config: &Config) -> ServerMessage {
+    let mut features = vec![
+        capabilities::PING.to_string(),
+        capabilities::NOTICE.to_string(),
+        capabilities::TRIGGER_RESULT.to_string(),
+    ];
+    if config.public_url.is_some() {
+        features.push(capabilities::QUESTION_IMAGE.to_string());
+    }
+    ServerMessage::Welcome {
+        connection_id,
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: PROTOCOL_VERSION,
+        min_protocol_version: MIN_PROTOCOL_VERSION,
+        capabilities: features,
+    }
+}
+
 /// Reason the per-connection loop ended. The caller decides what to log.
 #[derive(Clone, Copy)]
 pub(super) enum LoopExit {
@@ -133,7 +156,9 @@ where
                     Ok(Message::Text(text)) => {
                         on_text(text.to_string()).await;
                     }
-                    Ok(Message::Pong(_)) => {
+                    // Client pings count as liveness too: a desktop on a slow
+                    // link may miss our ping window while still pinging us.
+                    Ok(Message::Pong(_) | Message::Ping(_)) => {
                         last_pong = tokio::time::Instant::now();
                     }
                     Ok(Message::Close(_)) | Err(_) => return LoopExit::Closed,
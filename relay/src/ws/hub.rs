@@ -13,6 +13,15 @@ pub struct DesktopConnection {
     pub device_id: Uuid,
     pub device_name: String,
     pub tx: mpsc::UnboundedSender<String>,
+    /// What the desktop announced in `DesktopMessage::Hello`; `None` until
+    /// then, and for desktops that predate negotiation.
+    pub peer: Option<DesktopPeer>,
+}
+
+/// A desktop's protocol version and `desktop_capabilities`.
+pub struct DesktopPeer {
+    pub protocol_version: u32,
+    pub capabilities: Vec<String>,
 }
 
 pub struct MobileConnection {
@@ -247,12 +256,40 @@ impl Hub {
         }
     }
 
+    pub fn mobile_count(&self, user_id: Uuid) -> usize {
+        self.mobiles.get(&user_id).map_or(0, Vec::len)
+    }
+
     pub fn has_desktop(&self, user_id: Uuid) -> bool {
         self.desktops
             .get(&user_id)
             .is_some_and(|conns| !conns.is_empty())
     }
 
+    /// Record what a desktop announced in its `Hello`.
+    pub fn set_desktop_peer(&mut self, user_id: Uuid, connection_id: Uuid, peer: DesktopPeer) {
+        if let Some(conn) = self
+            .desktops
+            .get_mut(&user_id)
+            .and_then(|conns| conns.iter_mut().find(|c| c.connection_id == connection_id))
+        {
+            conn.peer = Some(peer);
+        }
+    }
+
+    /// Whether any of the user's desktops serves `capability`. Desktops that
+    /// have not sent a `Hello` are treated as supporting everything, like
+    /// relays that predate negotiation are on the desktop side.
+    pub fn desktop_supports(&self, user_id: Uuid, capability: &str) -> bool {
+        self.desktops.get(&user_id).is_some_and(|conns| {
+            conns.iter().any(|c| {
+                c.peer
+                    .as_ref()
+                    .is_none_or(|peer| peer.capabilities.iter().any(|cap| cap == capability))
+            })
+        })
+    }
+
     pub fn is_desktop_online(&self, user_id: Uuid, device_id: Uuid) -> bool {
         self.desktops
             .get(&user_id)
@@ -459,6 +496,7 @@ mod tests {
                 device_id: device,
                 device_name: "laptop".into(),
                 tx,
+                peer: None,
             },
         );
         assert!(hub.has_desktop(user));
@@ -482,6 +520,7 @@ mod tests {
                 device_id: device,
                 device_name: "laptop".into(),
                 tx: desktop_tx,
+                peer: None,
             },
         );
         hub.set_cached_questions(user, vec![mk_question("pane-1")]);
@@ -514,6 +553,7 @@ mod tests {
                 device_id: device,
                 device_name: "laptop".into(),
                 tx: desktop_tx,
+                peer: None,
             },
         );
 
@@ -587,6 +627,7 @@ mod tests {
                 device_id: Uuid::new_v4(),
                 device_name: "laptop".into(),
                 tx,
+                peer: None,
             },
         );
 
@@ -613,6 +654,7 @@ mod tests {
                 device_id: device,
                 device_name: "laptop".into(),
                 tx: old_tx,
+                peer: None,
             },
         );
         hub.add_desktop(
@@ -622,6 +664,7 @@ mod tests {
                 device_id: device,
                 device_name: "laptop".into(),
                 tx: new_tx,
+                peer: None,
             },
         );
         hub.remove_desktop(user, old_connection);
@@ -647,6 +690,7 @@ mod tests {
                 device_id: Uuid::new_v4(),
                 device_name: "laptop-1".into(),
                 tx: tx1,
+                peer: None,
             },
         );
         hub.add_desktop(
@@ -656,6 +700,7 @@ mod tests {
                 device_id: Uuid::new_v4(),
                 device_name: "laptop-2".into(),
                 tx: tx2,
+                peer: None,
             },
         );
 
@@ -665,6 +710,38 @@ mod tests {
         assert!(rx2.try_recv().unwrap_or_default().contains("list_jobs"));
     }
 
+    #[test]
+    fn desktop_supports_follows_hello_capabilities() {
+        let mut hub = Hub::new();
+        let user = Uuid::new_v4();
+        let connection = Uuid::new_v4();
+        let (tx, _rx) = mk_channel();
+
+        assert!(!hub.desktop_supports(user, "pty"));
+        hub.add_desktop(
+            user,
+            DesktopConnection {
+                connection_id: connection,
+                device_id: Uuid::new_v4(),
+                device_name: "laptop".into(),
+                tx,
+                peer: None,
+            },
+        );
+        assert!(hub.desktop_supports(user, "pty"));
+
+        hub.set_desktop_peer(
+            user,
+            connection,
+            DesktopPeer {
+                protocol_version: 1,
+                capabilities: vec!["trigger_result".into()],
+            },
+        );
+        assert!(!hub.desktop_supports(user, "pty"));
+        assert!(hub.desktop_supports(user, "trigger_result"));
+    }
+
     #[test]
     fn auto_yes_panes_set_and_clear() {
         let mut hub = Hub::new();
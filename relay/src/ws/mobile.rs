@@ -5,10 +5,12 @@ use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use clawtab_protocol::{
-    error_codes, ClientMessage, DesktopMessage, DetectedProcess, ServerMessage,
+    desktop_capabilities, error_codes, ClientMessage, DesktopMessage, DetectedProcess,
+    ServerMessage,
 };
 
-use crate::ws::handler::{run_session_loop, LoopExit};
+use crate::config::Config;
+use crate::ws::handler::{run_session_loop, welcome, LoopExit};
 use crate::ws::hub::MobileConnection;
 use crate::ws::shared::get_shared_owner_ids;
 use crate::AppState;
@@ -19,7 +21,7 @@ pub(super) async fn run(state: AppState, socket: WebSocket, user_id: Uuid) {
     let pty_subscriptions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
 
     register(&state, user_id, connection_id, tx.clone()).await;
-    send_welcome(&tx, connection_id);
+    send_welcome(&tx, connection_id, &state.config);
     tracing::info!(%user_id, %connection_id, "mobile connected");
 
     let exit = drive_session(
@@ -131,11 +133,8 @@ fn send_desktop_message(tx: &mpsc::UnboundedSender<String>, msg: &DesktopMessage
     }
 }
 
-fn send_welcome(tx: &mpsc::UnboundedSender<String>, connection_id: Uuid) {
-    if let Ok(json) = serde_json::to_string(&ServerMessage::Welcome {
-        connection_id: connection_id.to_string(),
-        server_version: env!("CARGO_PKG_VERSION").to_string(),
-    }) {
+fn send_welcome(tx: &mpsc::UnboundedSender<String>, connection_id: Uuid, config: &Config) {
+    if let Ok(json) = serde_json::to_string(&welcome(connection_id.to_string(), config)) {
         let _ = tx.send(json);
     }
 }
@@ -152,86 +151,112 @@ async fn handle_message(
         return;
     };
 
-    // Relay-intercepted messages (not forwarded to desktop)
-    match &msg {
+    if handle_relay_message(state, user_id, connection_id, &msg, &pty_subscriptions).await {
+        return;
+    }
+
+    let Some(target) = resolve_target_user(state, user_id).await else {
+        reply_desktop_offline(state, user_id, text, &msg).await;
+        return;
+    };
+
+    if let ClientMessage::DetectProcesses { id } = &msg {
+        reply_detected_processes(state, user_id, target, id).await;
+        return;
+    }
+
+    if reject_unsupported(state, user_id, target, &msg).await {
+        return;
+    }
+
+    track_pty_subscription(state, target, connection_id, &msg, pty_subscriptions).await;
+    forward_to_target(state, user_id, target, &msg).await;
+}
+
+/// Messages the relay handles itself or routes only to the user's own
+/// desktop. Returns false for everything else.
+async fn handle_relay_message(
+    state: &AppState,
+    user_id: Uuid,
+    connection_id: Uuid,
+    msg: &ClientMessage,
+    pty_subscriptions: &Arc<tokio::sync::Mutex<HashMap<String, Uuid>>>,
+) -> bool {
+    match msg {
         ClientMessage::RegisterPushToken {
             id,
             push_token,
             platform,
         } => {
             handle_register_push_token(state, user_id, id, push_token, platform).await;
-            return;
         }
         ClientMessage::GetNotificationHistory { id, limit } => {
             handle_get_notification_history(state, user_id, id, *limit).await;
-            return;
         }
         ClientMessage::SetAutoYesPanes { .. } => {
             let hub = state.hub.read().await;
-            hub.forward_to_desktop(user_id, &msg);
-            return;
+            hub.forward_to_desktop(user_id, msg);
         }
         ClientMessage::UnsubscribePty { pane_id } => {
             handle_pty_unsubscribe(
                 state,
                 connection_id,
                 pane_id,
-                &msg,
-                Arc::clone(&pty_subscriptions),
+                msg,
+                Arc::clone(pty_subscriptions),
             )
             .await;
-            return;
         }
-        _ => {}
+        _ => return false,
     }
+    true
+}
 
-    let target = resolve_target_user(state, user_id).await;
-
-    let Some(target) = target else {
-        let preview = &text[..text.len().min(80)];
-        tracing::warn!(%user_id, msg_preview = %preview, "no desktop online");
-        let error = ServerMessage::Error {
-            id: extract_id(&msg),
-            code: error_codes::DESKTOP_OFFLINE.into(),
-            message: "no desktop app is connected".into(),
-        };
-        let hub = state.hub.read().await;
-        hub.broadcast_to_mobiles(user_id, &error);
-        return;
+async fn reply_desktop_offline(state: &AppState, user_id: Uuid, text: &str, msg: &ClientMessage) {
+    let preview = &text[..text.len().min(80)];
+    tracing::warn!(%user_id, msg_preview = %preview, "no desktop online");
+    let error = ServerMessage::Error {
+        id: extract_id(msg),
+        code: error_codes::DESKTOP_OFFLINE.into(),
+        message: "no desktop app is connected".into(),
     };
+    let hub = state.hub.read().await;
+    hub.broadcast_to_mobiles(user_id, &error);
+}
 
-    if let ClientMessage::DetectProcesses { id } = &msg {
-        let cached = {
-            let hub = state.hub.read().await;
-            hub.cached_detected_processes(target)
-        };
-        let processes = filter_detected_processes_for_mobile(state, user_id, target, cached).await;
+/// Answer process detection from the relay's cache instead of asking the
+/// desktop to rescan.
+async fn reply_detected_processes(state: &AppState, user_id: Uuid, target: Uuid, id: &str) {
+    let cached = {
         let hub = state.hub.read().await;
-        hub.broadcast_to_mobiles(
-            user_id,
-            &DesktopMessage::DetectedProcesses {
-                id: id.clone(),
-                processes,
-            },
-        );
-        return;
-    }
-
-    track_pty_subscription(state, target, connection_id, &msg, pty_subscriptions).await;
+        hub.cached_detected_processes(target)
+    };
+    let processes = filter_detected_processes_for_mobile(state, user_id, target, cached).await;
+    let hub = state.hub.read().await;
+    hub.broadcast_to_mobiles(
+        user_id,
+        &DesktopMessage::DetectedProcesses {
+            id: id.to_string(),
+            processes,
+        },
+    );
+}
 
+async fn forward_to_target(state: &AppState, user_id: Uuid, target: Uuid, msg: &ClientMessage) {
     let hub = state.hub.read().await;
     if let ClientMessage::AnswerQuestion {
         question_id,
         pane_id,
         answer,
         ..
-    } = &msg
+    } = msg
     {
         forward_answer(
             &hub,
             &state.pool,
+            user_id,
             target,
-            &msg,
+            msg,
             question_id,
             pane_id,
             answer,
@@ -239,7 +264,15 @@ async fn handle_message(
         return;
     }
 
-    hub.forward_to_desktop(target, &msg);
+    if let ClientMessage::RunJob { name, .. } = msg {
+        crate::audit::spawn_record(
+            &state.pool,
+            target,
+            user_id,
+            &crate::audit::AuditAction::RunJob { name },
+        );
+    }
+    hub.forward_to_desktop(target, msg);
 }
 
 async fn handle_pty_unsubscribe(
@@ -258,6 +291,32 @@ async fn handle_pty_unsubscribe(
     }
 }
 
+/// Refuse a PTY subscription when the target's desktops announced they
+/// cannot stream panes, rather than leaving the mobile waiting for an ack.
+async fn reject_unsupported(
+    state: &AppState,
+    user_id: Uuid,
+    target: Uuid,
+    msg: &ClientMessage,
+) -> bool {
+    let ClientMessage::SubscribePty { id, .. } = msg else {
+        return false;
+    };
+    let hub = state.hub.read().await;
+    if hub.desktop_supports(target, desktop_capabilities::PTY) {
+        return false;
+    }
+    hub.broadcast_to_mobiles(
+        user_id,
+        &ServerMessage::Error {
+            id: Some(id.clone()),
+            code: error_codes::UNSUPPORTED.into(),
+            message: "the desktop app does not support terminal streaming".into(),
+        },
+    );
+    true
+}
+
 async fn track_pty_subscription(
     state: &AppState,
     target: Uuid,
@@ -325,9 +384,11 @@ fn filter_detected_processes_by_groups(
         .collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn forward_answer(
     hub: &super::Hub,
     pool: &sqlx::PgPool,
+    user_id: Uuid,
     target: Uuid,
     msg: &ClientMessage,
     question_id: &str,
@@ -338,6 +399,15 @@ fn forward_answer(
     let sent = hub.forward_to_desktop(target, msg);
     tracing::info!(%question_id, %answer, sent, "answer via WS forwarded");
     spawn_mark_answered(pool.clone(), question_id.to_string(), answer.to_string());
+    crate::audit::spawn_record(
+        pool,
+        target,
+        user_id,
+        &crate::audit::AuditAction::AnswerQuestion {
+            question_id,
+            answer,
+        },
+    );
 }
 
 async fn resolve_target_user(state: &AppState, user_id: Uuid) -> Option<Uuid> {
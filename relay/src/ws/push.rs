@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use clawtab_protocol::ClaudeQuestion;
+use clawtab_protocol::{ClaudeQuestion, NoticeLevel};
 
 use crate::AppState;
 
@@ -52,31 +52,41 @@ pub(super) async fn handle_claude_questions_push(
         .map(|o| (o.number.clone(), o.label.clone()))
         .collect();
 
-    let invalid = send_question_to_tokens(apns, user_id, q, &title, &body, &options, &tokens).await;
+    let image_url = question_image_url(state, user_id, &q.question_id).await;
+    let question = crate::apns::QuestionPush {
+        question_id: &q.question_id,
+        pane_id: &q.pane_id,
+        matched_job: q.matched_job.as_deref(),
+        options: &options,
+        image_url: image_url.as_deref(),
+    };
+    let invalid = send_question_to_tokens(apns, user_id, &title, &body, &question, &tokens).await;
     delete_invalid_tokens(state, &invalid).await;
 }
 
+/// Public link to the image the desktop rendered for this question, if it
+/// sent one.
+async fn question_image_url(state: &AppState, user_id: Uuid, question_id: &str) -> Option<String> {
+    let base = state.config.public_url.as_ref()?;
+    let token = state
+        .question_images
+        .token_for(user_id, question_id)
+        .await?;
+    Some(format!("{base}/question-images/{token}"))
+}
+
 async fn send_question_to_tokens(
     apns: &crate::apns::ApnsClient,
     user_id: Uuid,
-    q: &ClaudeQuestion,
     title: &str,
     body: &str,
-    options: &[(String, String)],
+    question: &crate::apns::QuestionPush<'_>,
     tokens: &[(Uuid, String)],
 ) -> Vec<Uuid> {
     let mut invalid = Vec::new();
     for (token_id, device_token) in tokens {
         let res = apns
-            .send_question_notification(
-                device_token,
-                title,
-                body,
-                &q.question_id,
-                &q.pane_id,
-                q.matched_job.as_deref(),
-                options,
-            )
+            .send_question_notification(device_token, title, body, question)
             .await;
         classify_push_result(res, *token_id, user_id, "push", &mut invalid);
     }
@@ -208,6 +218,36 @@ pub(super) async fn handle_job_notification_push(
     delete_invalid_tokens(state, &invalid).await;
 }
 
+/// Deliver a desktop `Notice` as an APNs alert to the user's iOS devices.
+pub(super) async fn handle_notice_push(
+    state: &AppState,
+    user_id: Uuid,
+    level: NoticeLevel,
+    title: &str,
+    body: &str,
+) {
+    let Some(ref apns) = state.apns else {
+        return;
+    };
+
+    let tokens = fetch_ios_push_tokens(state, user_id).await;
+    if tokens.is_empty() {
+        return;
+    }
+
+    let level = match level {
+        NoticeLevel::Info => "info",
+        NoticeLevel::Warning => "warning",
+        NoticeLevel::Error => "error",
+    };
+    let mut invalid = Vec::new();
+    for (token_id, device_token) in &tokens {
+        let res = apns.send_notice(device_token, level, title, body).await;
+        classify_push_result(res, *token_id, user_id, "notice push", &mut invalid);
+    }
+    delete_invalid_tokens(state, &invalid).await;
+}
+
 /// Per-job dedup via Redis SET NX with a 30s TTL. Returns true if this caller
 /// won the slot; false if a duplicate fired recently.
 async fn claim_job_push_slot(state: &AppState, user_id: Uuid, job_id: &str, event: &str) -> bool {
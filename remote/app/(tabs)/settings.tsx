@@ -30,6 +30,7 @@ export default function SettingsScreen({ inModal = false }: { inModal?: boolean
   const logout = useAuthStore((s) => s.logout)
   const connected = useWsStore((s) => s.connected)
   const desktopOnline = useWsStore((s) => s.desktopOnline)
+  const updateRequired = useWsStore((s) => s.updateRequired)
   const desktopDeviceName = useWsStore((s) => s.desktopDeviceName)
   const { isIosPadPortrait, isWide } = useResponsive()
 
@@ -276,6 +277,14 @@ export default function SettingsScreen({ inModal = false }: { inModal?: boolean
                   </View>
                 </View>
               </View>
+              {updateRequired && (
+                <View style={styles.offlineCard}>
+                  <Text style={styles.offlineTitle}>Update required</Text>
+                  <Text style={styles.offlineText}>
+                    The relay server no longer supports this version of ClawTab. Update the app to keep remote access working.
+                  </Text>
+                </View>
+              )}
               {!desktopOnline && (
                 <View style={styles.offlineCard}>
                   <Text style={styles.offlineTitle}>Desktop not connected</Text>
@@ -22,28 +22,60 @@ class NotificationService: UNNotificationServiceExtension {
         //
         // Instead, append the option labels to the body so the user can see them
         // in the notification itself (long press or expanded view).
-        guard let clawtab = content.userInfo["clawtab"] as? [String: Any],
-              let options = clawtab["options"] as? [[String: Any]],
-              !options.isEmpty else {
+        guard let clawtab = content.userInfo["clawtab"] as? [String: Any] else {
             contentHandler(content)
             return
         }
 
-        let buttonCount = min(options.count, 4)
-        var labels: [String] = []
-        for i in 0..<buttonCount {
-            let opt = options[i]
-            guard let number = opt["number"] as? String,
-                  let label = opt["label"] as? String else { continue }
-            labels.append("\(number). \(label)")
+        if let options = clawtab["options"] as? [[String: Any]], !options.isEmpty {
+            let buttonCount = min(options.count, 4)
+            var labels: [String] = []
+            for i in 0..<buttonCount {
+                let opt = options[i]
+                guard let number = opt["number"] as? String,
+                      let label = opt["label"] as? String else { continue }
+                labels.append("\(number). \(label)")
+            }
+
+            if !labels.isEmpty {
+                let existing = content.body
+                content.body = existing + "\n" + labels.joined(separator: "  |  ")
+            }
         }
 
-        if !labels.isEmpty {
-            let existing = content.body
-            content.body = existing + "\n" + labels.joined(separator: "  |  ")
+        // Desktops with question screenshots on send a render of the pane.
+        guard let imageURLString = clawtab["image_url"] as? String,
+              let imageURL = URL(string: imageURLString) else {
+            contentHandler(content)
+            return
+        }
+        attachImage(from: imageURL, to: content) {
+            contentHandler(content)
         }
+    }
 
-        contentHandler(content)
+    private func attachImage(
+        from url: URL,
+        to content: UNMutableNotificationContent,
+        completion: @escaping () -> Void
+    ) {
+        let task = URLSession.shared.downloadTask(with: url) { location, response, _ in
+            defer { completion() }
+            guard let location = location,
+                  let http = response as? HTTPURLResponse,
+                  http.statusCode == 200 else { return }
+            let file = FileManager.default.temporaryDirectory
+                .appendingPathComponent(UUID().uuidString)
+                .appendingPathExtension("png")
+            do {
+                try FileManager.default.moveItem(at: location, to: file)
+                let attachment = try UNNotificationAttachment(identifier: "question", url: file)
+                content.attachments = [attachment]
+            } catch {
+                // Fall back to the text-only alert.
+            }
+        }
+        task.resume()
     }
 
     override func serviceExtensionTimeWillExpire() {
@@ -12,6 +12,7 @@ import { dispatchTransportLogChunk } from "../transport/wsTransport";
 import { resolveRequest } from "../lib/useRequestMap";
 import { saveJobsCache, saveQuestionsCache } from "../lib/jobCache";
 import { flushPendingAnswers, clearRegisteredSend } from "../lib/pendingAnswers";
+import { PROTOCOL_VERSION } from "../types/messages";
 import type { ClientMessage, IncomingMessage } from "../types/messages";
 import { getWs, getWsSend, nextId, setWs, setWsSend } from "../lib/wsRuntime";
 
@@ -127,8 +128,14 @@ export function useWebSocket() {
       }
 
       switch (msg.type) {
-        case "welcome":
+        case "welcome": {
+          const updateRequired = PROTOCOL_VERSION < (msg.min_protocol_version ?? 0);
+          if (updateRequired) {
+            console.warn("[ws] relay", msg.server_version, "requires a newer app version");
+          }
+          useWsStore.setState({ updateRequired });
           break;
+        }
         case "jobs_list":
         case "jobs_changed":
           setJobs(msg.jobs, msg.statuses);
@@ -3,6 +3,8 @@ import { create } from "zustand";
 interface WsState {
   connected: boolean;
   subscriptionRequired: boolean;
+  // The relay no longer serves this app's protocol version
+  updateRequired: boolean;
   desktopOnline: boolean;
   desktopDeviceId: string | null;
   desktopDeviceName: string | null;
@@ -20,6 +22,7 @@ interface WsState {
 export const useWsStore = create<WsState>((set) => ({
   connected: false,
   subscriptionRequired: false,
+  updateRequired: false,
   desktopOnline: false,
   desktopDeviceId: null,
   desktopDeviceName: null,
@@ -38,6 +41,7 @@ export const useWsStore = create<WsState>((set) => ({
     set({
       connected: false,
       subscriptionRequired: false,
+      updateRequired: false,
       desktopOnline: false,
       desktopDeviceId: null,
       desktopDeviceName: null,
@@ -1,6 +1,10 @@
 import type { AgentActivity } from "@clawtab/shared";
 import type { DetectedProcess, ClaudeQuestion, JobStatus, NotificationHistoryItem, RemoteJob, RunDetail, RunRecord } from "./job";
 
+// Protocol version this client speaks; the relay's welcome carries the
+// oldest version it still serves.
+export const PROTOCOL_VERSION = 1;
+
 // Messages sent by this client to the relay server
 export type ClientMessage =
   | { type: "list_jobs"; id: string }
@@ -94,6 +98,10 @@ export type ServerMessage =
       type: "welcome";
       connection_id: string;
       server_version: string;
+      // Absent on relays that predate protocol negotiation
+      protocol_version?: number;
+      min_protocol_version?: number;
+      capabilities?: string[];
     }
   | { type: "error"; id?: string; code: string; message: string }
   | {
@@ -11,7 +11,7 @@ import { PopupMenu } from "../PopupMenu";
 
 const isWeb = Platform.OS === "web";
 import type { Transport } from "../../transport";
-import type { RemoteJob, JobStatus, RunRecord } from "../../types/job";
+import type { RemoteJob, JobStatus, JobParam, RunRecord } from "../../types/job";
 import type { ProcessProvider, ShellPane } from "../../types/process";
 import { StatusBadge } from "../StatusBadge";
 import { ReadOnlyXterm } from "../ReadOnlyXterm";
@@ -200,6 +200,7 @@ export function JobDetailView({
   const [outputCollapsed, setOutputCollapsed] = useState(false);
   const [runsCollapsed, setRunsCollapsed] = useState(false);
   const [showParamsModal, setShowParamsModal] = useState(false);
+  const [runParams, setRunParams] = useState<JobParam[]>([]);
   const [showDuplicateMenu, setShowDuplicateMenu] = useState(false);
   const [showSettingsMenu, setShowSettingsMenu] = useState(false);
   const [menuPos, setMenuPos] = useState<{ top: number; left: number } | null>(null);
@@ -219,7 +220,7 @@ export function JobDetailView({
   // Clear run-pending spinner once the job actually starts (or timeout after 1s)
   useEffect(() => {
     if (!runPending) return;
-    if (state === "running") { setRunPending(false); return; }
+    if (state === "running" || state === "queued") { setRunPending(false); return; }
     const timer = setTimeout(() => setRunPending(false), 1000);
     return () => clearTimeout(timer);
   }, [runPending, state]);
@@ -250,10 +251,17 @@ export function JobDetailView({
   }, [logsHeight]);
 
   const handleAction = useCallback(
-    async (action: "run" | "stop" | "sigint" | "pause" | "resume" | "restart") => {
-      if ((action === "run" || action === "restart") && job.params && job.params.length > 0) {
-        setShowParamsModal(true);
-        return;
+    async (action: "run" | "stop" | "sigint" | "pause" | "resume" | "restart" | "cancelQueued") => {
+      if (action === "run" || action === "restart") {
+        // Desktop also lists {placeholders} found in the prompt file.
+        const params = transport.getJobParams
+          ? await transport.getJobParams(job.slug).catch(() => job.params ?? [])
+          : job.params ?? [];
+        if (params.length > 0) {
+          setRunParams(params);
+          setShowParamsModal(true);
+          return;
+        }
       }
       try {
         if (action === "run" || action === "restart") setRunPending(true);
@@ -285,6 +293,9 @@ export function JobDetailView({
           case "pause":
             await transport.pauseJob(job.slug);
             break;
+          case "cancelQueued":
+            await transport.cancelQueuedRun?.(job.slug);
+            break;
           case "resume":
             await transport.resumeJob(job.slug);
             break;
@@ -460,6 +471,9 @@ export function JobDetailView({
           {!runPending && state === "success" && (
             <ActionButton label="Run Again" color={colors.accent} filled onPress={() => handleAction("run")} compact icon="run" />
           )}
+          {state === "queued" && transport.cancelQueuedRun && (
+            <ActionButton label="Cancel Queued" color={colors.danger} onPress={() => handleAction("cancelQueued")} compact />
+          )}
           {!runPending && state === "idle" && (
             <ActionButton label="Run" color={colors.accent} filled onPress={() => handleAction("run")} compact icon="run" />
           )}
@@ -816,10 +830,10 @@ export function JobDetailView({
       )}
 
       {/* Params modal */}
-      {job.params && job.params.length > 0 && (
+      {showParamsModal && runParams.length > 0 && (
         <ParamsDialog
           jobName={job.name}
-          params={job.params}
+          params={runParams}
           visible={showParamsModal}
           onRun={handleRunWithParams}
           onCancel={() => setShowParamsModal(false)}
@@ -7,6 +7,7 @@ import {
   ScrollView,
   StyleSheet,
   Text,
+  TextInput,
   TouchableOpacity,
   useWindowDimensions,
   View,
@@ -89,6 +90,7 @@ export function NotificationCard({
 }: NotificationCardProps) {
   const { width } = useWindowDimensions();
   const [answered, setAnswered] = useState(false);
+  const [reply, setReply] = useState("");
   const prevQuestionId = useRef(question.question_id);
   const flyAnim = useRef(new Animated.Value(0)).current;
   const webCardHeight = useRef(0);
@@ -99,6 +101,7 @@ export function NotificationCard({
     if (question.question_id !== prevQuestionId.current) {
       prevQuestionId.current = question.question_id;
       setAnswered(false);
+      setReply("");
       setFlying(false);
       flyAnim.setValue(0);
     }
@@ -134,13 +137,7 @@ export function NotificationCard({
     return () => clearTimeout(timer);
   }, [answered]); // eslint-disable-line react-hooks/exhaustive-deps
 
-  const handleOptionPress = (optionNumber: string, label: string) => {
-    // "Type something" options need freetext input - navigate to detail view
-    if (isFreetextOption(label)) {
-      onNavigate(question, resolvedJob);
-      return;
-    }
-    onSendOption(question, resolvedJob, optionNumber);
+  const markAnswered = () => {
     setAnswered(true);
     setFlying(false);
     if (!isWeb && !isLast) {
@@ -153,7 +150,26 @@ export function NotificationCard({
     }
   };
 
+  const handleOptionPress = (optionNumber: string, label: string) => {
+    // "Type something" options need freetext input - navigate to detail view
+    if (isFreetextOption(label)) {
+      onNavigate(question, resolvedJob);
+      return;
+    }
+    onSendOption(question, resolvedJob, optionNumber);
+    markAnswered();
+  };
+
+  // Free-text questions: the reply is typed into the pane, followed by Enter
+  const handleReplySubmit = () => {
+    if (!reply.trim()) return;
+    onSendOption(question, resolvedJob, reply);
+    setReply("");
+    markAnswered();
+  };
+
   const showAnswered = answered || autoAnswered;
+  const isTextQuestion = question.input_mode === "text";
 
   const fittedPath = useFittedNotificationPath(question.cwd);
   const title = resolvedJob ? resolvedJob : fittedPath.text;
@@ -246,6 +262,29 @@ export function NotificationCard({
       )}
     </View>
   );
+  const replyArea = (
+    <View style={[styles.optionRow, styles.replyRow]}>
+      <TextInput
+        style={[styles.replyInput, !isWeb && styles.replyInputNative]}
+        value={reply}
+        onChangeText={setReply}
+        onSubmitEditing={handleReplySubmit}
+        placeholder="Type a reply"
+        placeholderTextColor={colors.textMuted}
+        returnKeyType="send"
+        autoCapitalize="none"
+        autoCorrect={false}
+      />
+      <TouchableOpacity
+        style={[styles.optionBtn, !isWeb && styles.optionBtnNative, !reply.trim() && styles.replyBtnDisabled]}
+        onPress={handleReplySubmit}
+        disabled={!reply.trim()}
+        activeOpacity={0.6}
+      >
+        <Text style={[styles.optionBtnText, !isWeb && styles.optionBtnTextNative]}>Send</Text>
+      </TouchableOpacity>
+    </View>
+  );
   const previewContent = hasAnsi(preview) ? (
     <AnsiText content={preview} style={styles.logText} />
   ) : (
@@ -287,12 +326,14 @@ export function NotificationCard({
         ) : null}
       </TouchableOpacity>
 
-      {question.options.length > 0 && (
+      {(question.options.length > 0 || isTextQuestion) && (
         showAnswered ? (
           <View style={styles.sentRow}>
             <ActivityIndicator size="small" color={autoAnswered ? colors.warning : colors.accent} />
             <Text style={styles.sentText}>{autoAnswered ? "Auto-accepted" : "Sent"}</Text>
           </View>
+        ) : isTextQuestion ? (
+          replyArea
         ) : (
           optionArea
         )
@@ -490,6 +531,33 @@ const styles = StyleSheet.create({
     lineHeight: 19,
     fontWeight: "700",
   },
+  replyRow: {
+    flexDirection: "row",
+    alignItems: "center",
+    gap: 6,
+    paddingHorizontal: spacing.md,
+    paddingVertical: 6,
+  },
+  replyInput: {
+    flex: 1,
+    minWidth: 0,
+    color: colors.text,
+    fontSize: 12,
+    paddingHorizontal: spacing.sm,
+    paddingVertical: 4,
+    borderRadius: radius.sm,
+    borderWidth: 1,
+    borderColor: colors.border,
+  },
+  replyInputNative: {
+    minHeight: 44,
+    fontSize: 15,
+    paddingHorizontal: spacing.md,
+    borderRadius: 999,
+  },
+  replyBtnDisabled: {
+    opacity: 0.4,
+  },
   sentRow: {
     flexDirection: "row",
     alignItems: "center",
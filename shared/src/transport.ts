@@ -1,4 +1,4 @@
-import type { RemoteJob, JobStatus, RunRecord, RunDetail } from "./types/job";
+import type { RemoteJob, JobStatus, JobParam, RunRecord, RunDetail } from "./types/job";
 import type { DetectedProcess, ProcessProvider, ShellPane } from "./types/process";
 
 export interface Transport {
@@ -24,6 +24,8 @@ export interface Transport {
   focusJobWindow?(name: string): Promise<void>;
   saveJob?(job: RemoteJob): Promise<void>;
   restartJob?(name: string, params?: Record<string, string>): Promise<void>;
+  getJobParams?(name: string): Promise<JobParam[]>;
   sigintJob?(name: string): Promise<void>;
+  cancelQueuedRun?(name: string): Promise<void>;
   getExistingPaneInfo?(paneId: string): Promise<ShellPane | null>;
 }
@@ -53,6 +53,7 @@ export interface DesktopJob extends RemoteJob {
 export type JobStatus =
   | { state: "idle" }
   | { state: "running"; run_id: string; started_at: string; pane_id?: string; tmux_session?: string }
+  | { state: "queued"; queued_at: string }
   | { state: "success"; last_run: string }
   | { state: "failed"; last_run: string; exit_code: number }
   | { state: "paused" };
@@ -68,6 +69,7 @@ export interface RunRecord {
   stderr?: string;
   pane_id?: string | null;
   log_path?: string | null;
+  skipped?: boolean;
 }
 
 export interface RunDetail {
@@ -81,4 +83,18 @@ export interface RunDetail {
   stderr: string;
   pane_id?: string | null;
   log_path?: string | null;
+  sections?: OutputSection[];
+}
+
+export type OutputSectionKind =
+  | "prompt"
+  | "text"
+  | "tool_call"
+  | "tool_result"
+  | "error"
+  | "final_answer";
+
+export interface OutputSection {
+  kind: OutputSectionKind;
+  text: string;
 }
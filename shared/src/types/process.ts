@@ -70,7 +70,7 @@ export interface ClaudeQuestion {
   question_id: string;
   context_lines: string;
   options: QuestionOption[];
-  input_mode?: "numbered" | "select";
+  input_mode?: "numbered" | "select" | "text";
   button_row?: number;
   matched_group?: string | null;
   matched_job?: string | null;
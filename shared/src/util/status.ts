@@ -7,6 +7,8 @@ export function statusLabel(status: JobStatus): string {
       return "Idle";
     case "running":
       return "Running";
+    case "queued":
+      return "Queued";
     case "success":
       return "Success";
     case "failed":
@@ -21,6 +23,7 @@ export function statusColor(status: JobStatus): string {
     case "idle":
       return colors.statusIdle;
     case "running":
+    case "queued":
       return colors.statusRunning;
     case "success":
       return colors.statusSuccess;
@@ -36,6 +39,7 @@ export function statusBg(status: JobStatus): string {
     case "idle":
       return "transparent";
     case "running":
+    case "queued":
       return colors.accentBg;
     case "success":
       return colors.successBg;
@@ -47,6 +51,7 @@ export function statusBg(status: JobStatus): string {
 }
 
 export function runStatusColor(run: RunRecord, currentState: string): string {
+  if (run.skipped) return colors.statusIdle;
   if (run.exit_code == null) {
     if (run.finished_at || currentState !== "running") return colors.danger;
     return colors.statusRunning;
@@ -56,6 +61,7 @@ export function runStatusColor(run: RunRecord, currentState: string): string {
 }
 
 export function runStatusLabel(run: RunRecord, currentState: string): string {
+  if (run.skipped) return "skipped";
   if (run.exit_code == null) {
     if (run.finished_at || currentState !== "running") return "interrupted";
     return "running";
@@ -72,6 +78,8 @@ export function availableActions(status: JobStatus): AvailableAction[] {
       return ["run"];
     case "running":
       return ["pause", "stop"];
+    case "queued":
+      return [];
     case "success":
       return ["run"];
     case "failed":